@@ -1,13 +1,28 @@
 use clap::{Parser, Subcommand};
 use anyhow::{Result, anyhow};
+use borsh::BorshDeserialize;
+use bridge_vault::{
+    instruction::BridgeInstruction,
+    processor::create_unlock_message,
+    state::{bytes32_to_eth_address, UserBridgeState, CHAIN_ETHEREUM},
+    BridgeError,
+};
+use solana_client::{client_error::ClientError, nonblocking::rpc_client::RpcClient};
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{Instruction, InstructionError},
     pubkey::Pubkey,
-    transaction::Transaction,
+    signature::{read_keypair_file, Signature, Signer},
+    transaction::{Transaction, TransactionError},
 };
+use relayer::token_accounts::{derive_user_ata, derive_vault_ata};
+use relayer::types::Chain;
+use relayer::validator_client::{verify_transfer_signature, SignatureCheck};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use dialoguer::{Confirm, Input};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Bridge program ID (hardcoded from programs/bridge-vault/src/lib.rs)
 const BRIDGE_PROGRAM_ID: &str = "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH";
@@ -24,8 +39,33 @@ struct BridgeConfig {
     relayer_url: String,
     /// Path to the Solana keypair file
     keypair_path: String,
-    /// Bridge program ID (Solana pubkey)
+    /// Bridge program ID (Solana pubkey); differs from `BRIDGE_PROGRAM_ID` on a localnet deployment
     program_id: String,
+    /// Refuse to build instructions unless `program_id` is the id compiled into bridge-vault.
+    /// Set this against production clusters.
+    #[serde(default)]
+    require_canonical_program_id: bool,
+    /// Solana RPC endpoint for commands that read or write on-chain state
+    #[serde(default = "default_rpc_url")]
+    rpc_url: String,
+    /// Ethereum confirmations the relayer waits for (should match its ETHEREUM_CONFIRMATIONS)
+    #[serde(default = "default_ethereum_confirmations")]
+    ethereum_confirmations: u64,
+    /// Relayer polling interval (should match its POLL_INTERVAL_MS)
+    #[serde(default = "default_poll_interval_ms")]
+    poll_interval_ms: u64,
+}
+
+fn default_rpc_url() -> String {
+    "https://api.devnet.solana.com".to_string()
+}
+
+fn default_ethereum_confirmations() -> u64 {
+    12
+}
+
+fn default_poll_interval_ms() -> u64 {
+    5000
 }
 
 impl Default for BridgeConfig {
@@ -34,12 +74,36 @@ impl Default for BridgeConfig {
             relayer_url: "http://localhost:8080".to_string(),
             keypair_path: "~/.config/solana/id.json".to_string(),
             program_id: BRIDGE_PROGRAM_ID.to_string(),
+            require_canonical_program_id: false,
+            rpc_url: default_rpc_url(),
+            ethereum_confirmations: default_ethereum_confirmations(),
+            poll_interval_ms: default_poll_interval_ms(),
+        }
+    }
+}
+
+impl BridgeConfig {
+    /// The program every instruction is built for, checked against the compiled-in id
+    /// when `require_canonical_program_id` is set
+    fn program_id(&self) -> Result<Pubkey> {
+        let program_id = self
+            .program_id
+            .parse::<Pubkey>()
+            .map_err(|e| anyhow!("Invalid program ID {}: {}", self.program_id, e))?;
+        if self.require_canonical_program_id && program_id != bridge_vault::ID {
+            return Err(anyhow!(
+                "Configured program ID {} is not the bridge program ({}).\n\
+                 Unset require_canonical_program_id to use another deployment.",
+                program_id,
+                bridge_vault::ID
+            ));
         }
+        Ok(program_id)
     }
 }
 
 /// Transaction status from relayer API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 enum TxStatus {
     Pending,
@@ -47,6 +111,7 @@ enum TxStatus {
     Submitted,
     Confirmed,
     Failed,
+    Refunded,
 }
 
 impl std::fmt::Display for TxStatus {
@@ -57,11 +122,12 @@ impl std::fmt::Display for TxStatus {
             TxStatus::Submitted => write!(f, "Submitted"),
             TxStatus::Confirmed => write!(f, "Confirmed"),
             TxStatus::Failed => write!(f, "Failed"),
+            TxStatus::Refunded => write!(f, "Refunded"),
         }
     }
 }
 
-/// Transaction details from relayer API (GET /tx/:nonce)
+/// Transaction details from relayer API (GET /tx/:nonce?from_chain=)
 #[derive(Debug, Deserialize)]
 struct TxResponse {
     nonce: u64,
@@ -72,6 +138,11 @@ struct TxResponse {
     sender: String,
     recipient: String,
     amount: u64,
+    /// Amount before the bridge fee (absent from older relayers)
+    #[serde(default)]
+    gross_amount: u64,
+    #[serde(default)]
+    fee_amount: u64,
     status: TxStatus,
     error_message: Option<String>,
 }
@@ -93,6 +164,15 @@ struct TxSummary {
     status: TxStatus,
 }
 
+/// One entry of the signature file read by `bridge relay unlock`
+#[derive(Debug, Deserialize)]
+struct SignatureEntry {
+    /// Validator Solana pubkey (base58)
+    validator: String,
+    /// Ed25519 signature over the unlock message, hex (0x optional) or base58
+    signature: String,
+}
+
 /// Load config from ~/.bridge/config.toml
 fn load_config() -> Result<BridgeConfig> {
     let config_path = get_config_path()?;
@@ -133,6 +213,16 @@ fn format_amount(amount: u64) -> String {
     }
 }
 
+/// Convert a user-entered amount to base units. Fractional or small values are
+/// taken as whole tokens with 9 decimals; anything else is already raw units.
+fn amount_to_base_units(amount: f64) -> u64 {
+    if amount.fract() != 0.0 || amount < 1_000_000.0 {
+        (amount * 1_000_000_000.0) as u64
+    } else {
+        amount as u64
+    }
+}
+
 /// Get the config file path (~/.bridge/config.toml)
 fn get_config_path() -> Result<PathBuf> {
     let home = dirs::home_dir()
@@ -196,11 +286,19 @@ async fn init_config() -> Result<()> {
         .interact_text()
         .map_err(|e| anyhow!("Failed to read program ID: {}", e))?;
 
+    let rpc_url: String = Input::new()
+        .with_prompt("Solana RPC URL")
+        .default(default_rpc_url())
+        .interact_text()
+        .map_err(|e| anyhow!("Failed to read RPC URL: {}", e))?;
+
     // Create config struct
     let config = BridgeConfig {
         relayer_url,
         keypair_path,
         program_id,
+        rpc_url,
+        ..BridgeConfig::default()
     };
 
     // Serialize to TOML
@@ -216,6 +314,7 @@ async fn init_config() -> Result<()> {
     println!("  Relayer URL:   {}", config.relayer_url);
     println!("  Keypair path:  {}", config.keypair_path);
     println!("  Program ID:    {}", config.program_id);
+    println!("  RPC URL:       {}", config.rpc_url);
 
     Ok(())
 }
@@ -277,6 +376,854 @@ fn parse_destination_address(dest: &str, chain_id: u8) -> Result<[u8; 32]> {
     Ok(addr)
 }
 
+/// Read the JSON array of `{validator, signature}` entries for a manual unlock
+fn load_signature_file(path: &Path) -> Result<Vec<SignatureEntry>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read signature file {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow!("Failed to parse signature file {}: {}", path.display(), e))
+}
+
+/// Parse a 64-byte Ed25519 signature given as hex (0x optional) or base58
+fn parse_signature(signature: &str) -> Result<[u8; 64]> {
+    let hex_part = signature.strip_prefix("0x").unwrap_or(signature);
+    if let Ok(bytes) = hex::decode(hex_part) {
+        return bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| anyhow!("Signature must be 64 bytes, got {}", bytes.len()));
+    }
+
+    let signature = signature
+        .parse::<Signature>()
+        .map_err(|e| anyhow!("Invalid signature {}: {}", signature, e))?;
+    Ok(signature.into())
+}
+
+/// Check every entry locally before spending a transaction on it: the signer must be a
+/// configured validator, sign only once, and its signature must verify over `message`.
+fn validate_signatures(
+    entries: &[SignatureEntry],
+    message: &[u8; 32],
+    validators: &[Pubkey],
+) -> Result<Vec<(Pubkey, [u8; 64])>> {
+    let mut signers: Vec<(Pubkey, [u8; 64])> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let validator = entry
+            .validator
+            .parse::<Pubkey>()
+            .map_err(|e| anyhow!("Invalid validator pubkey {}: {}", entry.validator, e))?;
+
+        if !validators.contains(&validator) {
+            return Err(anyhow!("{} is not a configured validator", validator));
+        }
+
+        if signers.iter().any(|(signer, _)| *signer == validator) {
+            return Err(anyhow!("Duplicate signature from validator {}", validator));
+        }
+
+        let signature = parse_signature(&entry.signature)?;
+        if !Signature::from(signature).verify(validator.as_ref(), message) {
+            return Err(anyhow!(
+                "Signature from {} does not verify over the unlock message",
+                validator
+            ));
+        }
+
+        signers.push((validator, signature));
+    }
+
+    Ok(signers)
+}
+
+/// Ed25519 verification and `UnlockTokens` instructions releasing `state` to its user.
+/// Token accounts are the associated token accounts of the user and the vault PDA.
+fn build_relay_unlock_instructions(
+    program_id: &Pubkey,
+    relayer: &Pubkey,
+    bridge_config: &Pubkey,
+    state: &UserBridgeState,
+    signers: &[(Pubkey, [u8; 64])],
+) -> Vec<Instruction> {
+    let pdas = derive_pdas(program_id, bridge_config, &state.user, state.nonce);
+    let (vault_pda, _) = pdas.vault;
+    let (user_bridge_state, _) = pdas.user_bridge_state;
+    let message = create_unlock_message(state.nonce, &state.user, &state.token_mint, state.locked_amount);
+
+    vec![
+        BridgeInstruction::create_ed25519_verify_instruction(&message, signers),
+        BridgeInstruction::create_unlock_tokens_instruction(
+            program_id,
+            relayer,
+            &state.user,
+            &derive_user_ata(&state.user, &state.token_mint),
+            &derive_vault_ata(program_id, bridge_config, &state.token_mint),
+            &vault_pda,
+            &user_bridge_state,
+            bridge_config,
+            state.nonce,
+            signers.iter().map(|(_, signature)| *signature).collect(),
+        ),
+    ]
+}
+
+/// Program-derived addresses for one lock, with the bump seeds the program finds
+#[derive(Debug, PartialEq, Eq)]
+struct DerivedPdas {
+    vault: (Pubkey, u8),
+    user_bridge_state: (Pubkey, u8),
+}
+
+/// Derive the vault and user bridge state PDAs with the seeds `processor.rs` uses:
+/// `["vault", bridge_config]` and `["bridge", user, nonce.to_le_bytes()]`.
+fn derive_pdas(program_id: &Pubkey, bridge_config: &Pubkey, user: &Pubkey, nonce: u64) -> DerivedPdas {
+    DerivedPdas {
+        vault: Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], program_id),
+        user_bridge_state: Pubkey::find_program_address(
+            &[b"bridge", user.as_ref(), &nonce.to_le_bytes()],
+            program_id,
+        ),
+    }
+}
+
+/// Report printed by `bridge debug pda`
+fn format_pdas(program_id: &Pubkey, bridge_config: &Pubkey, user: &Pubkey, nonce: u64, pdas: &DerivedPdas) -> String {
+    let (vault, vault_bump) = pdas.vault;
+    let (user_bridge_state, user_bridge_bump) = pdas.user_bridge_state;
+    [
+        format!("Program ID:         {}", program_id),
+        format!("Bridge config:      {}", bridge_config),
+        format!("User:               {}", user),
+        format!("Nonce:              {}", nonce),
+        String::new(),
+        format!("Vault PDA:          {} (bump {})", vault, vault_bump),
+        format!("  seeds: [\"vault\", {}]", bridge_config),
+        format!("User bridge state:  {} (bump {})", user_bridge_state, user_bridge_bump),
+        format!(
+            "  seeds: [\"bridge\", {}, {} (u64 LE 0x{})]",
+            user,
+            nonce,
+            hex::encode(nonce.to_le_bytes())
+        ),
+    ]
+    .join("\n")
+}
+
+/// Seconds for a Solana transaction to finalize (~32 slots)
+const SOLANA_FINALITY_SECS: u64 = 13;
+/// Ethereum block time, used for confirmation waits on EVM chains
+const ETHEREUM_BLOCK_TIME_SECS: u64 = 12;
+
+/// Fee and arrival-time breakdown printed by `bridge estimate`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Estimate {
+    from_chain_id: u8,
+    to_chain_id: u8,
+    amount: u64,
+    fee_basis_points: u16,
+    fee: u64,
+    net_amount: u64,
+    eta_secs: u64,
+}
+
+/// Work out what a transfer costs and roughly how long it takes.
+///
+/// The Solana program takes `fee_basis_points` when tokens are locked, so the fee
+/// only applies to transfers leaving Solana. The ETA adds source-chain finality
+/// (`ethereum_confirmations` blocks on EVM chains), two relayer polls (event
+/// pickup and submission) and one block on the destination.
+fn estimate_transfer(
+    from_chain_id: u8,
+    to_chain_id: u8,
+    amount: u64,
+    fee_basis_points: u16,
+    ethereum_confirmations: u64,
+    poll_interval_ms: u64,
+) -> Result<Estimate> {
+    const SOLANA: u8 = 8;
+
+    if (from_chain_id == SOLANA) == (to_chain_id == SOLANA) {
+        return Err(anyhow!("Transfers must go to or from Solana"));
+    }
+
+    let fee = if from_chain_id == SOLANA {
+        amount
+            .checked_mul(fee_basis_points as u64)
+            .ok_or_else(|| anyhow!("Amount too large: {}", amount))?
+            / 10000
+    } else {
+        0
+    };
+
+    let block_secs = |chain_id: u8| {
+        if chain_id == SOLANA {
+            SOLANA_FINALITY_SECS
+        } else {
+            ETHEREUM_BLOCK_TIME_SECS
+        }
+    };
+    let source_secs = if from_chain_id == SOLANA {
+        SOLANA_FINALITY_SECS
+    } else {
+        ethereum_confirmations * ETHEREUM_BLOCK_TIME_SECS
+    };
+    let relayer_secs = (2 * poll_interval_ms).div_ceil(1000);
+
+    Ok(Estimate {
+        from_chain_id,
+        to_chain_id,
+        amount,
+        fee_basis_points: if from_chain_id == SOLANA { fee_basis_points } else { 0 },
+        fee,
+        net_amount: amount - fee,
+        eta_secs: source_secs + relayer_secs + block_secs(to_chain_id),
+    })
+}
+
+/// Format seconds as e.g. "2m 15s"
+fn format_duration(secs: u64) -> String {
+    match (secs / 60, secs % 60) {
+        (0, s) => format!("{}s", s),
+        (m, 0) => format!("{}m", m),
+        (m, s) => format!("{}m {}s", m, s),
+    }
+}
+
+/// Fetch and decode the on-chain bridge config
+async fn fetch_bridge_config(
+    rpc: &RpcClient,
+    bridge_config: &Pubkey,
+) -> Result<bridge_vault::state::BridgeConfig> {
+    let config_data = rpc
+        .get_account_data(bridge_config)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch bridge config: {}", e))?;
+    bridge_vault::state::BridgeConfig::load(&config_data)
+        .map_err(|e| anyhow!("Invalid bridge config account: {}", e))
+}
+
+/// One entry of the relayer's `GET /validators`
+#[derive(Debug, Deserialize)]
+struct ValidatorHealth {
+    name: String,
+    sol_public_key: String,
+    healthy: bool,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+/// Per-validator health as the relayer last probed it; `None` if the relayer can't be reached
+async fn fetch_validator_health(relayer_url: &str) -> Option<Vec<ValidatorHealth>> {
+    let url = format!("{}/validators", relayer_url.trim_end_matches('/'));
+    let response = reqwest::Client::new()
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .ok()?;
+    response.json().await.ok()
+}
+
+/// Table printed by `bridge validators list`. Validators the relayer knows but the
+/// program doesn't are listed after it, since they will never count toward the threshold.
+fn format_validator_table(
+    config: &bridge_vault::state::BridgeConfig,
+    health: Option<&[ValidatorHealth]>,
+) -> String {
+    let mut lines = vec![
+        format!(
+            "Validators: {} on-chain, threshold {}",
+            config.validators.len(),
+            config.validator_threshold
+        ),
+        String::new(),
+        "┌────┬──────────────────────────────────────────────┬──────────────────┬──────────┬──────────┐".to_string(),
+        format!(
+            "│ {:<2} │ {:<44} │ {:<16} │ {:<8} │ {:<8} │",
+            "#", "Pubkey", "Relayer name", "Health", "Response"
+        ),
+        "├────┼──────────────────────────────────────────────┼──────────────────┼──────────┼──────────┤".to_string(),
+    ];
+
+    for (index, validator) in config.validators.iter().enumerate() {
+        let key = validator.to_string();
+        let entry = health.and_then(|health| health.iter().find(|h| h.sol_public_key == key));
+        let (name, status, latency) = match (health, entry) {
+            (None, _) => ("-".to_string(), "unknown".to_string(), "-".to_string()),
+            (Some(_), None) => ("-".to_string(), "missing".to_string(), "-".to_string()),
+            (Some(_), Some(entry)) => (
+                entry.name.clone(),
+                if entry.healthy { "up" } else { "down" }.to_string(),
+                entry
+                    .latency_ms
+                    .map(|ms| format!("{} ms", ms))
+                    .unwrap_or_else(|| "-".to_string()),
+            ),
+        };
+        lines.push(format!(
+            "│ {:<2} │ {:<44} │ {:<16} │ {:<8} │ {:<8} │",
+            index + 1,
+            key,
+            truncate(&name, 16),
+            status,
+            latency
+        ));
+    }
+    lines.push(
+        "└────┴──────────────────────────────────────────────┴──────────────────┴──────────┴──────────┘".to_string(),
+    );
+
+    match health {
+        None => {
+            lines.push(String::new());
+            lines.push("Relayer API unreachable; health not shown.".to_string());
+        }
+        Some(health) => {
+            let on_chain: Vec<String> = config.validators.iter().map(|v| v.to_string()).collect();
+            for entry in health.iter().filter(|h| !on_chain.contains(&h.sol_public_key)) {
+                lines.push(format!(
+                    "⚠ Relayer validator {} ({}) is not in the on-chain set",
+                    entry.name, entry.sol_public_key
+                ));
+            }
+            for entry in health.iter().filter(|h| !h.healthy) {
+                if let Some(ref error) = entry.error {
+                    lines.push(format!("  {}: {}", entry.name, error));
+                }
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Chain a validator signature can be checked for, from a `--chain` name
+fn signing_chain(name: &str) -> Result<Chain> {
+    match chain_name_to_id(name)? {
+        1 => Ok(Chain::Ethereum),
+        8 => Ok(Chain::Solana),
+        _ => Err(anyhow!("Validators only sign for ethereum or solana, not {}", name)),
+    }
+}
+
+/// Report printed by `bridge verify-signature`
+fn format_signature_check(chain: Chain, validator: &str, check: &SignatureCheck) -> String {
+    let mut lines = vec![
+        format!("Message hash:     0x{}", hex::encode(check.message_hash)),
+        format!("Expected signer:  {}", validator),
+    ];
+    if chain == Chain::Ethereum {
+        lines.push(format!("Recovered signer: {}", check.signer));
+    }
+    lines.push(format!("Signature:        {}", if check.valid { "valid" } else { "INVALID" }));
+    lines.join("\n")
+}
+
+/// Instruction data given as hex (0x optional) or base64
+fn decode_instruction_data(data: &str) -> Result<Vec<u8>> {
+    let data = data.trim();
+    if let Some(hex_part) = data.strip_prefix("0x") {
+        return hex::decode(hex_part).map_err(|e| anyhow!("Invalid hex instruction data: {}", e));
+    }
+    if let Ok(bytes) = hex::decode(data) {
+        return Ok(bytes);
+    }
+    BASE64
+        .decode(data)
+        .map_err(|e| anyhow!("Instruction data is neither hex nor base64: {}", e))
+}
+
+/// First and last 8 characters of a long key or signature, enough to match it up
+fn abbreviate(value: &str) -> String {
+    if value.len() <= 19 {
+        return value.to_string();
+    }
+    format!("{}...{}", &value[..8], &value[value.len() - 8..])
+}
+
+fn format_key_list(keys: &[Pubkey]) -> String {
+    let keys: Vec<String> = keys.iter().map(|key| abbreviate(&key.to_string())).collect();
+    format!("{} [{}]", keys.len(), keys.join(", "))
+}
+
+fn format_signature_list(signatures: &[[u8; 64]]) -> String {
+    let signatures: Vec<String> = signatures.iter().map(|sig| abbreviate(&hex::encode(sig))).collect();
+    format!("{} [{}]", signatures.len(), signatures.join(", "))
+}
+
+fn format_update<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value.as_ref().map_or_else(|| "unchanged".to_string(), T::to_string)
+}
+
+/// Report printed by `bridge decode-instruction`: the variant, then one line per field
+fn format_instruction(instruction: &BridgeInstruction) -> String {
+    let (name, fields): (&str, Vec<(&str, String)>) = match instruction {
+        BridgeInstruction::Initialize {
+            admin,
+            relayer_authority,
+            fee_basis_points,
+            validators,
+            validator_threshold,
+        } => (
+            "Initialize",
+            vec![
+                ("admin", admin.to_string()),
+                ("relayer_authority", relayer_authority.to_string()),
+                ("fee_basis_points", fee_basis_points.to_string()),
+                ("validators", format_key_list(validators)),
+                ("validator_threshold", validator_threshold.to_string()),
+            ],
+        ),
+        BridgeInstruction::LockTokens {
+            amount,
+            destination_chain,
+            destination_address,
+        } => {
+            let destination = if *destination_chain == CHAIN_ETHEREUM {
+                format!("0x{}", hex::encode(bytes32_to_eth_address(destination_address)))
+            } else {
+                format!("0x{}", hex::encode(destination_address))
+            };
+            (
+                "LockTokens",
+                vec![
+                    ("amount", amount.to_string()),
+                    ("destination_chain", destination_chain.to_string()),
+                    ("destination_address", destination),
+                ],
+            )
+        }
+        BridgeInstruction::UnlockTokens { nonce, signatures } => (
+            "UnlockTokens",
+            vec![("nonce", nonce.to_string()), ("signatures", format_signature_list(signatures))],
+        ),
+        BridgeInstruction::UpdateConfig {
+            new_admin,
+            new_relayer,
+            new_fee,
+            new_supported_chains,
+            new_transfer_ttl,
+            new_validator_fee_bps,
+            new_max_total_locked,
+            new_signature_scheme,
+        } => (
+            "UpdateConfig",
+            vec![
+                ("new_admin", format_update(new_admin)),
+                ("new_relayer", format_update(new_relayer)),
+                ("new_fee", format_update(new_fee)),
+                ("new_supported_chains", format_update(&new_supported_chains.map(|c| format!("{:#06x}", c)))),
+                ("new_transfer_ttl", format_update(new_transfer_ttl)),
+                ("new_validator_fee_bps", format_update(new_validator_fee_bps)),
+                ("new_max_total_locked", format_update(new_max_total_locked)),
+                ("new_signature_scheme", format_update(new_signature_scheme)),
+            ],
+        ),
+        BridgeInstruction::Pause => ("Pause", vec![]),
+        BridgeInstruction::Unpause => ("Unpause", vec![]),
+        BridgeInstruction::AddAllowedMint { mint } => ("AddAllowedMint", vec![("mint", mint.to_string())]),
+        BridgeInstruction::RemoveAllowedMint { mint } => ("RemoveAllowedMint", vec![("mint", mint.to_string())]),
+        BridgeInstruction::RefundLock { nonce } => ("RefundLock", vec![("nonce", nonce.to_string())]),
+        BridgeInstruction::UnlockTokensBatch { items } => {
+            let mut fields = vec![("items", items.len().to_string())];
+            fields.extend(items.iter().map(|(nonce, amount, signatures)| {
+                (
+                    "  item",
+                    format!(
+                        "nonce {}, amount {}, signatures {}",
+                        nonce,
+                        amount,
+                        format_signature_list(signatures)
+                    ),
+                )
+            }));
+            ("UnlockTokensBatch", fields)
+        }
+        BridgeInstruction::CancelLock { nonce } => ("CancelLock", vec![("nonce", nonce.to_string())]),
+        BridgeInstruction::ClaimValidatorFees => ("ClaimValidatorFees", vec![]),
+        BridgeInstruction::UnlockTokensPartial {
+            nonce,
+            amount,
+            signatures,
+        } => (
+            "UnlockTokensPartial",
+            vec![
+                ("nonce", nonce.to_string()),
+                ("amount", amount.to_string()),
+                ("signatures", format_signature_list(signatures)),
+            ],
+        ),
+        BridgeInstruction::CloseBridgeState { nonce } => ("CloseBridgeState", vec![("nonce", nonce.to_string())]),
+        BridgeInstruction::AddRelayerAuthority { relayer } => {
+            ("AddRelayerAuthority", vec![("relayer", relayer.to_string())])
+        }
+        BridgeInstruction::RemoveRelayerAuthority { relayer } => {
+            ("RemoveRelayerAuthority", vec![("relayer", relayer.to_string())])
+        }
+        BridgeInstruction::SetChainFee {
+            chain,
+            fee_basis_points,
+        } => (
+            "SetChainFee",
+            vec![
+                ("chain", chain.to_string()),
+                ("fee_basis_points", fee_basis_points.map_or_else(|| "base fee".to_string(), |fee| fee.to_string())),
+            ],
+        ),
+        BridgeInstruction::ClaimValidatorFeesSecp256k1 { validator, signature } => (
+            "ClaimValidatorFeesSecp256k1",
+            vec![
+                ("validator", validator.to_string()),
+                ("signature", abbreviate(&hex::encode(signature))),
+            ],
+        ),
+    };
+
+    let mut lines = vec![format!("Instruction: {}", name)];
+    lines.extend(fields.into_iter().map(|(field, value)| format!("  {:<23}{}", format!("{}:", field), value)));
+    lines.join("\n")
+}
+
+/// Describe a failed submission, pointing at the program error when there is one
+fn describe_submit_error(err: &ClientError) -> String {
+    match err.get_transaction_error() {
+        Some(TransactionError::InstructionError(index, InstructionError::Custom(code))) => {
+            match BridgeError::from_u32(code) {
+                Some(bridge_error) => {
+                    format!("instruction {} failed: {:?} ({})", index, bridge_error, bridge_error)
+                }
+                None => format!("instruction {} failed with custom program error {}", index, code),
+            }
+        }
+        Some(TransactionError::InstructionError(index, error)) => {
+            format!("instruction {} failed: {}", index, error)
+        }
+        Some(error) => error.to_string(),
+        None => err.to_string(),
+    }
+}
+
+/// Transfers requested per `GET /txs` page by `bridge history export`
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Columns of a `bridge history export` CSV, in the order of `ExportRecord`'s fields
+const EXPORT_CSV_HEADER: &str =
+    "nonce,created_at,from_chain,to_chain,sender,recipient,gross_amount,fee_amount,net_amount,status,from_tx_hash,to_tx_hash";
+
+/// File format written by `bridge history export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// One transfer as exported. Amounts are raw base units.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportRecord {
+    nonce: u64,
+    #[serde(default)]
+    created_at: String,
+    from_chain: String,
+    to_chain: String,
+    sender: String,
+    recipient: String,
+    #[serde(default)]
+    gross_amount: u64,
+    #[serde(default)]
+    fee_amount: u64,
+    /// The relayer's `amount`: what the recipient receives
+    #[serde(alias = "amount")]
+    net_amount: u64,
+    status: String,
+    from_tx_hash: String,
+    to_tx_hash: Option<String>,
+}
+
+impl ExportRecord {
+    fn csv_row(&self) -> String {
+        let nonce = self.nonce.to_string();
+        let gross_amount = self.gross_amount.to_string();
+        let fee_amount = self.fee_amount.to_string();
+        let net_amount = self.net_amount.to_string();
+        let fields: [&str; 12] = [
+            &nonce,
+            &self.created_at,
+            &self.from_chain,
+            &self.to_chain,
+            &self.sender,
+            &self.recipient,
+            &gross_amount,
+            &fee_amount,
+            &net_amount,
+            &self.status,
+            &self.from_tx_hash,
+            self.to_tx_hash.as_deref().unwrap_or_default(),
+        ];
+        fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// One page of the relayer's `GET /txs`, read for export
+#[derive(Debug, Deserialize)]
+struct ExportPage {
+    transactions: Vec<ExportRecord>,
+    #[serde(default)]
+    next_before: Option<i64>,
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// `--since` as the RFC 3339 time the relayer expects: either a date (midnight UTC) or a full timestamp
+fn parse_since(since: &str) -> Result<String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(since) {
+        return Ok(time.with_timezone(&chrono::Utc).to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    }
+    let date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .map_err(|_| anyhow!("Invalid --since '{}': expected YYYY-MM-DD or an RFC 3339 time", since))?;
+    Ok(format!("{}T00:00:00Z", date))
+}
+
+/// Writes exported transfers as they arrive, so a large history is never held in memory
+struct ExportWriter<W: std::io::Write> {
+    out: W,
+    format: ExportFormat,
+    written: usize,
+}
+
+impl<W: std::io::Write> ExportWriter<W> {
+    fn new(mut out: W, format: ExportFormat) -> Result<Self> {
+        match format {
+            ExportFormat::Csv => writeln!(out, "{}", EXPORT_CSV_HEADER)?,
+            ExportFormat::Json => write!(out, "[")?,
+        }
+        Ok(Self { out, format, written: 0 })
+    }
+
+    fn write(&mut self, record: &ExportRecord) -> Result<()> {
+        match self.format {
+            ExportFormat::Csv => writeln!(self.out, "{}", record.csv_row())?,
+            ExportFormat::Json => {
+                let separator = if self.written == 0 { "\n  " } else { ",\n  " };
+                write!(self.out, "{}{}", separator, serde_json::to_string(record)?)?;
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the output and return how many transfers were written
+    fn finish(mut self) -> Result<usize> {
+        if self.format == ExportFormat::Json {
+            let close = if self.written == 0 { "]" } else { "\n]" };
+            writeln!(self.out, "{}", close)?;
+        }
+        self.out.flush()?;
+        Ok(self.written)
+    }
+}
+
+/// Page through the relayer's `GET /txs`, newest first, handing every transfer to `writer`
+async fn export_history<W: std::io::Write>(
+    client: &reqwest::Client,
+    relayer_url: &str,
+    user: Option<&str>,
+    since: Option<&str>,
+    writer: &mut ExportWriter<W>,
+) -> Result<()> {
+    let url = format!("{}/txs", relayer_url.trim_end_matches('/'));
+    let mut before: Option<i64> = None;
+
+    loop {
+        let mut query = vec![("limit", EXPORT_PAGE_SIZE.to_string())];
+        if let Some(user) = user {
+            query.push(("user", user.to_string()));
+        }
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+        if let Some(before) = before {
+            query.push(("before", before.to_string()));
+        }
+
+        let response = client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to relayer: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Relayer returned error {}: {}", status, body));
+        }
+        let page: ExportPage = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse relayer response: {}", e))?;
+
+        for record in &page.transactions {
+            writer.write(record)?;
+        }
+
+        match page.next_before {
+            // Cursors only move to older transfers; anything else would loop forever
+            Some(next) if before.map_or(true, |before| next < before) => before = Some(next),
+            Some(next) => return Err(anyhow!("Relayer returned a non-advancing page cursor {}", next)),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Recent transfers `bridge watch` asks the relayer for on each poll
+const WATCH_WINDOW: u32 = 100;
+
+/// `name` as one of the chains the relayer records transfers for
+fn relayer_chain(name: &str) -> Result<Chain> {
+    match chain_name_to_id(name)? {
+        1 => Ok(Chain::Ethereum),
+        8 => Ok(Chain::Solana),
+        9 => Ok(Chain::Sui),
+        _ => Err(anyhow!("The relayer does not bridge {}", name)),
+    }
+}
+
+/// Relayer URL of transfer `nonce` from chain `from_chain`, a Solana lock when it isn't given
+fn transfer_url(relayer_url: &str, nonce: u64, from_chain: Option<&str>) -> Result<String> {
+    let from_chain = from_chain.map(relayer_chain).transpose()?.unwrap_or(Chain::Solana);
+    Ok(format!("{}/tx/{}?from_chain={}", relayer_url.trim_end_matches('/'), nonce, from_chain))
+}
+
+/// `status` in the color `bridge watch` shows it in
+fn colored_status(status: TxStatus) -> colored::ColoredString {
+    use colored::Colorize;
+
+    let name = status.to_string();
+    match status {
+        TxStatus::Pending => name.yellow(),
+        TxStatus::Signaturescollected => name.cyan(),
+        TxStatus::Submitted => name.blue(),
+        TxStatus::Confirmed => name.green(),
+        TxStatus::Failed => name.red(),
+        TxStatus::Refunded => name.magenta(),
+    }
+}
+
+/// What `bridge watch` has already shown: the last status printed for each nonce
+struct TransferTail {
+    chain: Option<Chain>,
+    seen: HashMap<u64, TxStatus>,
+    primed: bool,
+}
+
+impl TransferTail {
+    fn new(chain: Option<Chain>) -> Self {
+        Self {
+            chain,
+            seen: HashMap::new(),
+            primed: false,
+        }
+    }
+
+    /// Lines for the transfers in `txs` (a `GET /txs` page, newest first) that are new or
+    /// have changed status since the last call. The first call only records where things
+    /// stand, so starting a watch doesn't replay the whole window.
+    fn update(&mut self, txs: &[TxSummary]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for tx in txs.iter().rev().filter(|tx| self.involves_chain(tx)) {
+            match self.seen.insert(tx.nonce, tx.status) {
+                None if self.primed => lines.push(format!(
+                    "#{} new {} → {} transfer of {}: {}",
+                    tx.nonce,
+                    tx.from_chain,
+                    tx.to_chain,
+                    format_amount(tx.amount),
+                    colored_status(tx.status)
+                )),
+                Some(previous) if previous != tx.status => lines.push(format!(
+                    "#{} {} → {}",
+                    tx.nonce,
+                    colored_status(previous),
+                    colored_status(tx.status)
+                )),
+                _ => {}
+            }
+        }
+        self.primed = true;
+        lines
+    }
+
+    fn involves_chain(&self, tx: &TxSummary) -> bool {
+        self.chain.map_or(true, |chain| {
+            let name = chain.to_string();
+            tx.from_chain == name || tx.to_chain == name
+        })
+    }
+}
+
+/// The relayer's most recent transfers, newest first
+async fn fetch_recent_transfers(client: &reqwest::Client, relayer_url: &str) -> Result<Vec<TxSummary>> {
+    let url = format!("{}/txs", relayer_url.trim_end_matches('/'));
+    let response = client
+        .get(&url)
+        .query(&[("limit", WATCH_WINDOW)])
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to connect to relayer: {}", e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Relayer returned error {}", response.status()));
+    }
+    let list: TxsListResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("Failed to parse relayer response: {}", e))?;
+    Ok(list.transactions)
+}
+
+/// Poll the relayer every `interval` and print new transfers and status changes until
+/// interrupted. Polls keep going while the relayer is unreachable; changes made in the
+/// meantime are printed once it answers again.
+async fn tail_transfers(relayer_url: &str, chain: Option<Chain>, interval: u64) -> Result<()> {
+    let client = reqwest::Client::new();
+    let mut tail = TransferTail::new(chain);
+    let mut connected = true;
+    let poll_duration = tokio::time::Duration::from_secs(interval);
+
+    println!("Watching bridge transfers on {} (Ctrl-C to stop)...", relayer_url);
+    if let Some(chain) = chain {
+        println!("Only transfers to or from {}", chain);
+    }
+    println!();
+
+    loop {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        match fetch_recent_transfers(&client, relayer_url).await {
+            Ok(txs) => {
+                if !connected {
+                    println!("[{}] Reconnected to relayer", timestamp);
+                    connected = true;
+                }
+                for line in tail.update(&txs) {
+                    println!("[{}] {}", timestamp, line);
+                }
+            }
+            Err(e) if connected => {
+                eprintln!("[{}] {}; retrying every {} seconds", timestamp, e, interval);
+                connected = false;
+            }
+            Err(_) => {}
+        }
+        tokio::time::sleep(poll_duration).await;
+    }
+}
+
 // Bridge-vault types are used via bridge_vault::instruction::BridgeInstruction::create_lock_tokens_instruction
 
 #[derive(Parser)]
@@ -317,50 +1264,254 @@ enum Commands {
 
         /// User's token account (SPL token account)
         #[arg(long)]
-        user_token_account: String,
+        user_token_account: String,
+
+        /// Vault token account (where locked tokens go)
+        #[arg(long)]
+        vault_token_account: String,
+
+        /// User pubkey (or keypair file for signing)
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Current bridge nonce (for user_bridge_state PDA derivation)
+        #[arg(long)]
+        nonce: Option<u64>,
+
+        /// Output format: base64 (default) or hex
+        #[arg(long, default_value = "base64")]
+        output: String,
+
+        /// Optional recent blockhash (otherwise uses zeros for offline building)
+        #[arg(long)]
+        blockhash: Option<String>,
+    },
+
+    /// Estimate the fee, net amount and arrival time of a transfer
+    Estimate {
+        #[arg(long)]
+        from: String,
+
+        #[arg(long)]
+        to: String,
+
+        #[arg(long)]
+        amount: f64,
+
+        /// Bridge config account address (fee is read from it)
+        #[arg(long)]
+        bridge_config: String,
+
+        /// Print JSON instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check bridge transaction status
+    Status {
+        #[arg(long)]
+        nonce: u64,
+
+        /// Chain the transfer started on (default: solana); each chain numbers its own transfers
+        #[arg(long)]
+        from_chain: Option<String>,
+    },
+
+    /// View transaction history
+    History {
+        #[arg(long)]
+        user: Option<String>,
+
+        #[command(subcommand)]
+        command: Option<HistoryCommand>,
+    },
+
+    /// Watch a transaction for status changes (polls until Confirmed or Failed), or
+    /// without --nonce, tail new transfers and status changes as they happen
+    Watch {
+        /// Transaction nonce to watch
+        #[arg(long)]
+        nonce: Option<u64>,
+
+        /// Chain the watched transfer started on (default: solana)
+        #[arg(long, requires = "nonce")]
+        from_chain: Option<String>,
+
+        /// When tailing, only show transfers to or from this chain (solana or ethereum)
+        #[arg(long, conflicts_with = "nonce")]
+        chain: Option<String>,
+
+        /// Polling interval in seconds (default: 5)
+        #[arg(long, default_value = "5")]
+        interval: u64,
+    },
+
+    /// Operator commands for driving bridge transfers by hand
+    Relay {
+        #[command(subcommand)]
+        command: RelayCommand,
+    },
+
+    /// Inspect the bridge's validator set
+    Validators {
+        #[command(subcommand)]
+        command: ValidatorsCommand,
+    },
+
+    /// Inspection helpers for debugging account and PDA mismatches
+    Debug {
+        #[command(subcommand)]
+        command: DebugCommand,
+    },
+
+    /// Maintenance of the relayer's own database
+    Admin {
+        #[command(subcommand)]
+        command: AdminCommand,
+    },
+
+    /// Decode raw BridgeInstruction data, as found in a failed transaction, and print its fields
+    DecodeInstruction {
+        /// Instruction data as hex (0x optional) or base64
+        #[arg(long)]
+        data: String,
+    },
+
+    /// Check a validator's signature over a transfer without contacting any chain
+    VerifySignature {
+        /// Chain the signature releases tokens on: ethereum or solana
+        #[arg(long)]
+        chain: String,
+
+        /// Transfer nonce
+        #[arg(long)]
+        nonce: u64,
+
+        /// Address receiving the tokens on `--chain`
+        #[arg(long)]
+        recipient: String,
+
+        /// Net amount in base units, as the validator was asked to sign it
+        #[arg(long)]
+        amount: u64,
+
+        /// Address that locked the tokens on Solana (ethereum only)
+        #[arg(long)]
+        sender: Option<String>,
+
+        /// Token mint the unlock releases, which the unlock message names (solana only)
+        #[arg(long)]
+        mint: Option<String>,
+
+        /// Validator's Ethereum address (for ethereum) or Solana pubkey (for solana)
+        #[arg(long)]
+        validator: String,
+
+        /// 0x-hex signature: 65-byte ECDSA for ethereum, 64-byte Ed25519 for solana
+        #[arg(long)]
+        signature: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RelayCommand {
+    /// Submit UnlockTokens for a pending lock using validator signatures from a file
+    Unlock {
+        /// Nonce of the lock to release
+        #[arg(long)]
+        nonce: u64,
+
+        /// JSON file containing an array of {"validator", "signature"} entries
+        #[arg(long)]
+        signatures: PathBuf,
+
+        /// User who made the lock (for user_bridge_state PDA derivation)
+        #[arg(long)]
+        user: String,
+
+        /// Bridge config account address
+        #[arg(long)]
+        bridge_config: String,
+
+        /// Relayer keypair file (defaults to the configured keypair)
+        #[arg(long)]
+        keypair: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Write every transfer the relayer knows to a CSV or JSON file
+    Export {
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+
+        /// File to write
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Only transfers this address sent or received
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Only transfers recorded on or after this date (YYYY-MM-DD) or RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ValidatorsCommand {
+    /// List the on-chain validators and threshold, with health from the relayer when reachable
+    List {
+        /// Bridge config account address
+        #[arg(long)]
+        bridge_config: String,
+    },
+}
 
-        /// Vault token account (where locked tokens go)
+#[derive(Subcommand)]
+enum AdminCommand {
+    /// Rebuild the relayer database from chain history: Ethereum burns, then Solana program
+    /// transactions, up to what is confirmed now. Events already recorded are skipped.
+    Replay {
+        /// First Solana slot to read
         #[arg(long)]
-        vault_token_account: String,
+        from_slot: u64,
 
-        /// User pubkey (or keypair file for signing)
+        /// First Ethereum block to read
         #[arg(long)]
-        user: Option<String>,
+        from_block: u64,
 
-        /// Current bridge nonce (for user_bridge_state PDA derivation)
+        /// Relayer config file (defaults to RELAYER_CONFIG, then relayer.toml, then the environment)
         #[arg(long)]
-        nonce: Option<u64>,
-
-        /// Output format: base64 (default) or hex
-        #[arg(long, default_value = "base64")]
-        output: String,
+        relayer_config: Option<PathBuf>,
 
-        /// Optional recent blockhash (otherwise uses zeros for offline building)
+        /// Database to rebuild into instead of the configured one, e.g. sqlite://rebuilt.db
         #[arg(long)]
-        blockhash: Option<String>,
+        database: Option<String>,
     },
+}
 
-    /// Check bridge transaction status
-    Status {
+#[derive(Subcommand)]
+enum DebugCommand {
+    /// Print the vault and user bridge state PDAs (and bumps) the program derives
+    Pda {
+        /// Bridge config account address
         #[arg(long)]
-        nonce: u64,
-    },
+        config: String,
 
-    /// View transaction history
-    History {
+        /// User who made the lock
         #[arg(long)]
-        user: Option<String>,
-    },
+        user: String,
 
-    /// Watch a transaction for status changes (polls until Confirmed or Failed)
-    Watch {
-        /// Transaction nonce to watch
+        /// Nonce of the lock
         #[arg(long)]
         nonce: u64,
 
-        /// Polling interval in seconds (default: 5)
-        #[arg(long, default_value = "5")]
-        interval: u64,
+        /// Program ID to derive under (defaults to the configured program)
+        #[arg(long)]
+        program_id: Option<String>,
     },
 }
 
@@ -418,8 +1569,10 @@ async fn main() -> Result<()> {
             
             let dest_address_bytes = parse_destination_address(&dest, dest_chain_id)?;
 
+            let config = load_config()?;
+
             // Parse pubkeys
-            let program_id = BRIDGE_PROGRAM_ID.parse::<Pubkey>()?;
+            let program_id = config.program_id()?;
             let bridge_config_pk = bridge_config.parse::<Pubkey>()?;
             let token_mint_pk = token_mint.parse::<Pubkey>()?;
             let user_token_account_pk = user_token_account.parse::<Pubkey>()?;
@@ -442,21 +1595,7 @@ async fn main() -> Result<()> {
                 &program_id,
             );
 
-            // Convert amount to u64 (assuming 9 decimals for SOL-like tokens, user passes raw u64 or we need more info)
-            // For simplicity, treat amount as a raw u64 in base units. In a real CLI, you'd want decimals config.
-            // Here we interpret amount as the raw integer amount (no decimal conversion for safety).
-            // If user passes 1.5 and wants 1.5 tokens with 9 decimals, they should pass 1500000000.
-            // To make it friendlier, let's support both: if amount has decimals, multiply by 10^9.
-            // Actually, simplest: just cast f64 to u64 after multiplying by 1e9 if it looks like a float.
-            // For safety and clarity, require integer amount in base units. But the CLI uses f64.
-            // Let's interpret: if amount < 1e12, treat as human units and multiply by 1e9 (9 decimals).
-            // Otherwise treat as raw. This is a heuristic.
-            let amount_u64: u64 = if amount.fract() != 0.0 || amount < 1_000_000.0 {
-                // Likely human-readable, assume 9 decimals
-                (amount * 1_000_000_000.0) as u64
-            } else {
-                amount as u64
-            };
+            let amount_u64 = amount_to_base_units(amount);
 
             // Validate amount is not zero
             if amount_u64 == 0 {
@@ -468,7 +1607,6 @@ async fn main() -> Result<()> {
             }
 
             // Check keypair file exists (from config)
-            let config = load_config()?;
             let keypair_path = shellexpand::tilde(&config.keypair_path);
             if !std::path::Path::new(&*keypair_path).exists() {
                 return Err(anyhow!(
@@ -530,12 +1668,57 @@ async fn main() -> Result<()> {
             println!("  1. Sign this transaction with your keypair (e.g., using solana-cli or a wallet)");
             println!("  2. Submit the signed transaction to the Solana network");
         }
-        Commands::Status { nonce } => {
+        Commands::Estimate {
+            from,
+            to,
+            amount,
+            bridge_config,
+            json,
+        } => {
+            let config = load_config()?;
+            let from_chain_id = chain_name_to_id(&from)?;
+            let to_chain_id = chain_name_to_id(&to)?;
+            let bridge_config_pk = bridge_config
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid bridge config address: {}", e))?;
+
+            let amount_u64 = amount_to_base_units(amount);
+            if amount_u64 == 0 {
+                return Err(anyhow!("Amount must be greater than 0"));
+            }
+
+            let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+            let on_chain_config = fetch_bridge_config(&rpc, &bridge_config_pk).await?;
+
+            let estimate = estimate_transfer(
+                from_chain_id,
+                to_chain_id,
+                amount_u64,
+                on_chain_config.lock_fee_basis_points(to_chain_id),
+                config.ethereum_confirmations,
+                config.poll_interval_ms,
+            )?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&estimate)?);
+            } else {
+                println!("Estimate: {} → {}", from, to);
+                println!("  Amount:       {}", format_amount(estimate.amount));
+                println!(
+                    "  Fee:          {} ({} bps)",
+                    format_amount(estimate.fee),
+                    estimate.fee_basis_points
+                );
+                println!("  You receive:  {}", format_amount(estimate.net_amount));
+                println!("  Arrives in:   ~{}", format_duration(estimate.eta_secs));
+            }
+        }
+        Commands::Status { nonce, from_chain } => {
             // Load config to get relayer URL
             let config = load_config()?;
 
             // Build URL
-            let url = format!("{}/tx/{}", config.relayer_url.trim_end_matches('/'), nonce);
+            let url = transfer_url(&config.relayer_url, nonce, from_chain.as_deref())?;
 
             println!("Querying relayer at {}...", config.relayer_url);
             println!();
@@ -578,6 +1761,9 @@ async fn main() -> Result<()> {
             println!("║  To Chain:   {:<45} ║", tx.to_chain);
             println!("╠════════════════════════════════════════════════════════════╣");
             println!("║  Amount:     {:<45} ║", tx.amount);
+            if tx.fee_amount > 0 {
+                println!("║  Fee:        {:<45} ║", format!("{} (of {} sent)", tx.fee_amount, tx.gross_amount));
+            }
             println!("╠════════════════════════════════════════════════════════════╣");
             println!("║  Sender:     {:<45} ║", tx.sender);
             println!("║  Recipient:  {:<45} ║", tx.recipient);
@@ -614,10 +1800,31 @@ async fn main() -> Result<()> {
                 "Failed" => {
                     println!("✗ Transaction failed. See error message above.");
                 }
+                "Refunded" => {
+                    println!("↩ Lock was refunded to the sender on the source chain.");
+                }
                 _ => {}
             }
         }
-        Commands::History { user } => {
+        Commands::History {
+            command: Some(HistoryCommand::Export { format, out, user, since }),
+            ..
+        } => {
+            let config = load_config()?;
+            let since = since.as_deref().map(parse_since).transpose()?;
+
+            let file = std::fs::File::create(&out)
+                .map_err(|e| anyhow!("Failed to create {}: {}", out.display(), e))?;
+            let mut writer = ExportWriter::new(std::io::BufWriter::new(file), format)?;
+
+            println!("Exporting transaction history from {}...", config.relayer_url);
+            let client = reqwest::Client::new();
+            export_history(&client, &config.relayer_url, user.as_deref(), since.as_deref(), &mut writer).await?;
+            let written = writer.finish()?;
+
+            println!("✓ Wrote {} transactions to {}", written, out.display());
+        }
+        Commands::History { user, command: None } => {
             // Load config to get relayer URL
             let config = load_config()?;
 
@@ -688,12 +1895,17 @@ async fn main() -> Result<()> {
             println!();
             println!("Showing {} of {} transactions", list.transactions.len(), list.total);
         }
-        Commands::Watch { nonce, interval } => {
+        Commands::Watch { nonce: None, chain, interval, .. } => {
+            let config = load_config()?;
+            let chain = chain.as_deref().map(relayer_chain).transpose()?;
+            tail_transfers(&config.relayer_url, chain, interval).await?;
+        }
+        Commands::Watch { nonce: Some(nonce), from_chain, interval, .. } => {
             // Load config to get relayer URL
             let config = load_config()?;
 
             // Build URL
-            let url = format!("{}/tx/{}", config.relayer_url.trim_end_matches('/'), nonce);
+            let url = transfer_url(&config.relayer_url, nonce, from_chain.as_deref())?;
 
             println!("Watching transaction nonce {}...", nonce);
             println!("Relayer: {}", config.relayer_url);
@@ -788,6 +2000,12 @@ async fn main() -> Result<()> {
                             println!("Watch complete. Transaction failed.");
                             break;
                         }
+                        TxStatus::Refunded => {
+                            println!("  ↩ Lock refunded to the sender");
+                            println!();
+                            println!("Watch complete. Transaction refunded.");
+                            break;
+                        }
                     }
 
                     last_status = Some(current_status);
@@ -798,18 +2016,248 @@ async fn main() -> Result<()> {
                 }
 
                 // Check if we should stop
-                if matches!(tx.status, TxStatus::Confirmed | TxStatus::Failed) {
+                if matches!(tx.status, TxStatus::Confirmed | TxStatus::Failed | TxStatus::Refunded) {
                     break;
                 }
 
                 tokio::time::sleep(poll_duration).await;
             }
         }
+        Commands::Relay {
+            command:
+                RelayCommand::Unlock {
+                    nonce,
+                    signatures,
+                    user,
+                    bridge_config,
+                    keypair,
+                },
+        } => {
+            let config = load_config()?;
+            let program_id = config.program_id()?;
+            let bridge_config_pk = bridge_config
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid bridge config address: {}", e))?;
+            let user_pk = user
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid user pubkey: {}", e))?;
+
+            let keypair_path = shellexpand::tilde(keypair.as_deref().unwrap_or(&config.keypair_path)).to_string();
+            let relayer = read_keypair_file(&keypair_path)
+                .map_err(|e| anyhow!("Failed to read relayer keypair {}: {}", keypair_path, e))?;
+
+            let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+
+            let on_chain_config = fetch_bridge_config(&rpc, &bridge_config_pk).await?;
+
+            let (user_bridge_state_pk, _) = Pubkey::find_program_address(
+                &[b"bridge", user_pk.as_ref(), &nonce.to_le_bytes()],
+                &program_id,
+            );
+            let state_data = rpc
+                .get_account_data(&user_bridge_state_pk)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch bridge state {}: {}", user_bridge_state_pk, e))?;
+            let state = UserBridgeState::deserialize(&mut state_data.as_slice())
+                .map_err(|e| anyhow!("Invalid bridge state account: {}", e))?;
+
+            if state.unlocked {
+                return Err(anyhow!("Nonce {} has already been unlocked", nonce));
+            }
+            if state.is_expired(chrono::Utc::now().timestamp()) {
+                return Err(anyhow!(
+                    "Nonce {} expired at {}; it can only be refunded",
+                    nonce,
+                    state.expiry
+                ));
+            }
+
+            let message = create_unlock_message(state.nonce, &state.user, &state.token_mint, state.locked_amount);
+            let entries = load_signature_file(&signatures)?;
+            let signers = validate_signatures(&entries, &message, &on_chain_config.validators)?;
+
+            if signers.len() < on_chain_config.validator_threshold as usize {
+                return Err(anyhow!(
+                    "Not enough signatures: {} valid, threshold is {}",
+                    signers.len(),
+                    on_chain_config.validator_threshold
+                ));
+            }
+
+            println!("✓ {} valid signatures (threshold {})", signers.len(), on_chain_config.validator_threshold);
+            println!("  Unlocking {} to {}", state.remaining_amount, state.user);
+
+            let instructions = build_relay_unlock_instructions(
+                &program_id,
+                &relayer.pubkey(),
+                &bridge_config_pk,
+                &state,
+                &signers,
+            );
+            let recent_blockhash = rpc
+                .get_latest_blockhash()
+                .await
+                .map_err(|e| anyhow!("Failed to get recent blockhash: {}", e))?;
+            let tx = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&relayer.pubkey()),
+                &[&relayer],
+                recent_blockhash,
+            );
+
+            match rpc.send_and_confirm_transaction(&tx).await {
+                Ok(signature) => {
+                    println!();
+                    println!("✓ Unlock confirmed: {}", signature);
+                }
+                Err(e) => return Err(anyhow!("Unlock failed: {}", describe_submit_error(&e))),
+            }
+        }
+        Commands::Validators {
+            command: ValidatorsCommand::List { bridge_config },
+        } => {
+            let config = load_config()?;
+            let bridge_config_pk = bridge_config
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid bridge config address: {}", e))?;
+
+            let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+            let on_chain_config = fetch_bridge_config(&rpc, &bridge_config_pk).await?;
+            let health = fetch_validator_health(&config.relayer_url).await;
+
+            println!("{}", format_validator_table(&on_chain_config, health.as_deref()));
+        }
+        Commands::Debug {
+            command:
+                DebugCommand::Pda {
+                    config,
+                    user,
+                    nonce,
+                    program_id,
+                },
+        } => {
+            let program_id = match program_id {
+                Some(id) => id,
+                None => load_config()
+                    .map(|c| c.program_id)
+                    .unwrap_or_else(|_| BRIDGE_PROGRAM_ID.to_string()),
+            };
+            let program_id = program_id
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid program ID: {}", e))?;
+            let bridge_config_pk = config
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid bridge config address: {}", e))?;
+            let user_pk = user
+                .parse::<Pubkey>()
+                .map_err(|e| anyhow!("Invalid user pubkey: {}", e))?;
+
+            let pdas = derive_pdas(&program_id, &bridge_config_pk, &user_pk, nonce);
+            println!("{}", format_pdas(&program_id, &bridge_config_pk, &user_pk, nonce, &pdas));
+        }
+        Commands::DecodeInstruction { data } => {
+            let bytes = decode_instruction_data(&data)?;
+            let instruction = BridgeInstruction::unpack(&bytes)
+                .map_err(|e| anyhow!("Not a bridge instruction ({} bytes): {}", bytes.len(), e))?;
+            println!("{}", format_instruction(&instruction));
+        }
+        Commands::VerifySignature {
+            chain,
+            nonce,
+            recipient,
+            amount,
+            sender,
+            mint,
+            validator,
+            signature,
+        } => {
+            let chain = signing_chain(&chain)?;
+            if chain == Chain::Ethereum && sender.is_none() {
+                return Err(anyhow!("--sender is required to check an Ethereum mint signature"));
+            }
+            let check = verify_transfer_signature(
+                chain,
+                nonce,
+                &recipient,
+                amount,
+                sender.as_deref().unwrap_or_default(),
+                mint.as_deref(),
+                &validator,
+                &signature,
+            )?;
+            println!("{}", format_signature_check(chain, &validator, &check));
+            if !check.valid {
+                return Err(anyhow!("Signature was not made by {}", validator));
+            }
+        }
+        Commands::Admin {
+            command:
+                AdminCommand::Replay {
+                    from_slot,
+                    from_block,
+                    relayer_config,
+                    database,
+                },
+        } => {
+            let config_path = relayer_config.unwrap_or_else(|| {
+                PathBuf::from(std::env::var("RELAYER_CONFIG").unwrap_or_else(|_| "relayer.toml".to_string()))
+            });
+            let config = relayer::config::Config::load(Some(&config_path))?;
+            let database_url = database.unwrap_or(config.database.url);
+
+            let db = relayer::db::Database::new(&database_url, config.database.max_connections).await?;
+            let existing = db.get_stats().await?.total;
+            if existing > 0 {
+                println!("{} already holds {} transfers; recorded events will be skipped", database_url, existing);
+            }
+
+            println!(
+                "Replaying Ethereum from block {} and Solana from slot {} into {}",
+                from_block, from_slot, database_url
+            );
+            let summary = relayer::replay::replay(
+                &config.solana,
+                &config.ethereum,
+                relayer::retry::RetryPolicy::from_config(&config.relayer),
+                db,
+                from_slot,
+                from_block,
+                print_replay_progress,
+            )
+            .await?;
+
+            println!();
+            println!(
+                "✓ Replayed Ethereum through block {} and {} Solana transactions; {} transfers recorded ({} new)",
+                summary.ethereum_to_block,
+                summary.solana_transactions,
+                summary.transfers,
+                summary.transfers - existing
+            );
+        }
     }
 
     Ok(())
 }
 
+/// One status line per chain, rewritten in place as the replay advances
+fn print_replay_progress(progress: relayer::replay::ReplayProgress) {
+    use std::io::Write;
+
+    match progress {
+        relayer::replay::ReplayProgress::Ethereum { block, to_block } => {
+            print!("\r  Ethereum: block {}/{}", block, to_block);
+            if block == to_block {
+                println!();
+            }
+        }
+        relayer::replay::ReplayProgress::Solana { done, total } => {
+            print!("\r  Solana: {}/{} transactions", done, total);
+        }
+    }
+    let _ = std::io::stdout().flush();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -933,6 +2381,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_instruction_prints_lock_tokens_fields() {
+        // LockTokens of 1_000_000_000 to 0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0 on Ethereum
+        let hex_data = "0100ca9a3b0000000001000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb0";
+        let base64_data = "AQDKmjsAAAAAAQAAAAAAAAAAAAAAAHQtNcxmNMBTKSWjuES8nnWV8L6w";
+        let bytes = decode_instruction_data(hex_data).unwrap();
+        assert_eq!(decode_instruction_data(&format!("0x{}", hex_data)).unwrap(), bytes);
+        assert_eq!(decode_instruction_data(base64_data).unwrap(), bytes);
+
+        let instruction = BridgeInstruction::unpack(&bytes).unwrap();
+        assert_eq!(
+            format_instruction(&instruction),
+            [
+                "Instruction: LockTokens",
+                "  amount:                1000000000",
+                "  destination_chain:     1",
+                "  destination_address:   0x742d35cc6634c0532925a3b844bc9e7595f0beb0",
+            ]
+            .join("\n")
+        );
+
+        // Signature vectors are shortened to their ends
+        let unlock = BridgeInstruction::UnlockTokens {
+            nonce: 3,
+            signatures: vec![[0xab; 64], [0xcd; 64]],
+        };
+        let decoded = format_instruction(&BridgeInstruction::unpack(&unlock.pack()).unwrap());
+        let signatures = "  signatures:            2 [abababab...abababab, cdcdcdcd...cdcdcdcd]";
+        assert!(decoded.contains(signatures), "{}", decoded);
+
+        assert!(decode_instruction_data("not instruction data!").is_err());
+    }
+
     /// Test that the instruction has the correct accounts in the correct order.
     /// The on-chain program expects accounts in a specific order (see processor.rs line 208-219).
     #[test]
@@ -1139,6 +2620,10 @@ mod tests {
             relayer_url: "http://localhost:8080".to_string(),
             keypair_path: "~/.config/solana/id.json".to_string(),
             program_id: BRIDGE_PROGRAM_ID.to_string(),
+            require_canonical_program_id: false,
+            rpc_url: default_rpc_url(),
+            ethereum_confirmations: default_ethereum_confirmations(),
+            poll_interval_ms: default_poll_interval_ms(),
         };
 
         let toml_str = toml::to_string_pretty(&config).expect("Should serialize to TOML");
@@ -1174,6 +2659,60 @@ mod tests {
         assert!(pubkey.is_ok(), "Default program ID should be a valid Solana pubkey");
     }
 
+    /// A localnet deployment lives under another program ID; instructions must follow the config
+    #[test]
+    fn test_instructions_use_the_configured_program_id() {
+        let localnet = Pubkey::new_unique();
+        let mut config = BridgeConfig {
+            program_id: localnet.to_string(),
+            ..BridgeConfig::default()
+        };
+        assert_eq!(BRIDGE_PROGRAM_ID.parse::<Pubkey>().unwrap(), bridge_vault::ID);
+        let program_id = config.program_id().unwrap();
+        assert_eq!(program_id, localnet);
+
+        let user = Pubkey::new_unique();
+        let bridge_config = Pubkey::new_unique();
+        let pdas = derive_pdas(&program_id, &bridge_config, &user, 3);
+        let lock = BridgeInstruction::create_lock_tokens_instruction(
+            &program_id,
+            &user,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &pdas.user_bridge_state.0,
+            &bridge_config,
+            &Pubkey::new_unique(),
+            1_000,
+            CHAIN_ETHEREUM,
+            [0u8; 32],
+        );
+        assert_eq!(lock.program_id, localnet);
+
+        let state = UserBridgeState {
+            user,
+            locked_amount: 1_000,
+            token_mint: Pubkey::new_unique(),
+            destination_chain: CHAIN_ETHEREUM,
+            destination_address: [0; 32],
+            status: bridge_vault::state::BridgeStatus::Pending,
+            nonce: 3,
+            timestamp: 0,
+            unlocked: false,
+            expiry: i64::MAX,
+            remaining_amount: 1_000,
+        };
+        let unlock =
+            build_relay_unlock_instructions(&program_id, &Pubkey::new_unique(), &bridge_config, &state, &[]);
+        assert_eq!(unlock[1].program_id, localnet);
+        assert!(unlock[1].accounts.iter().any(|meta| meta.pubkey == pdas.user_bridge_state.0));
+
+        // Against production the compiled-in ID is required
+        config.require_canonical_program_id = true;
+        assert!(config.program_id().unwrap_err().to_string().contains(&localnet.to_string()));
+        config.program_id = BRIDGE_PROGRAM_ID.to_string();
+        assert_eq!(config.program_id().unwrap(), bridge_vault::ID);
+    }
+
     /// Test truncate helper function
     #[test]
     fn test_truncate() {
@@ -1194,6 +2733,7 @@ mod tests {
         assert_eq!(TxStatus::Submitted.to_string(), "Submitted");
         assert_eq!(TxStatus::Confirmed.to_string(), "Confirmed");
         assert_eq!(TxStatus::Failed.to_string(), "Failed");
+        assert_eq!(TxStatus::Refunded.to_string(), "Refunded");
     }
 
     /// Test format_amount helper function
@@ -1302,4 +2842,653 @@ mod tests {
         // Verify it's non-zero
         assert!(amount_u64 > 0);
     }
+
+    /// 50 bps on a Solana lock of 1 token, with the relayer's default timings
+    #[test]
+    fn test_estimate_fee_breakdown() {
+        let estimate = estimate_transfer(8, 1, 1_000_000_000, 50, 12, 5000).unwrap();
+        assert_eq!(estimate.fee, 5_000_000);
+        assert_eq!(estimate.net_amount, 995_000_000);
+        assert_eq!(estimate.fee_basis_points, 50);
+        // Solana finality + two polls + one Ethereum block
+        assert_eq!(estimate.eta_secs, 13 + 10 + 12);
+
+        // Unlocks on Solana carry no program fee, but wait for Ethereum confirmations
+        let estimate = estimate_transfer(1, 8, 1_000_000_000, 50, 12, 5000).unwrap();
+        assert_eq!(estimate.fee, 0);
+        assert_eq!(estimate.net_amount, 1_000_000_000);
+        assert_eq!(estimate.eta_secs, 12 * 12 + 10 + 13);
+
+        assert!(estimate_transfer(1, 3, 1_000_000_000, 50, 12, 5000).is_err());
+        assert_eq!(format_duration(167), "2m 47s");
+    }
+
+    fn unlock_state(user: Pubkey, token_mint: Pubkey, nonce: u64, amount: u64) -> UserBridgeState {
+        UserBridgeState {
+            user,
+            locked_amount: amount,
+            token_mint,
+            destination_chain: 1,
+            destination_address: [0u8; 32],
+            status: bridge_vault::state::BridgeStatus::Pending,
+            nonce,
+            timestamp: 0,
+            unlocked: false,
+            expiry: i64::MAX,
+            remaining_amount: amount,
+        }
+    }
+
+    fn signature_entries(signers: &[(&solana_sdk::signature::Keypair, [u8; 64])]) -> Vec<SignatureEntry> {
+        signers
+            .iter()
+            .map(|(validator, signature)| SignatureEntry {
+                validator: validator.pubkey().to_string(),
+                signature: format!("0x{}", hex::encode(signature)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_signatures_rejects_unknown_and_invalid_signers() {
+        use solana_sdk::signature::Keypair;
+
+        let validator = Keypair::new();
+        let outsider = Keypair::new();
+        let message = create_unlock_message(3, &Pubkey::new_unique(), &Pubkey::new_unique(), 1_000);
+        let valid: [u8; 64] = validator.sign_message(&message).into();
+
+        let signers = validate_signatures(&signature_entries(&[(&validator, valid)]), &message, &[validator.pubkey()]).unwrap();
+        assert_eq!(signers, vec![(validator.pubkey(), valid)]);
+
+        let from_outsider: [u8; 64] = outsider.sign_message(&message).into();
+        let entries = signature_entries(&[(&outsider, from_outsider)]);
+        assert!(validate_signatures(&entries, &message, &[validator.pubkey()]).is_err());
+
+        let wrong_message: [u8; 64] = validator.sign_message(&[0u8; 32]).into();
+        let entries = signature_entries(&[(&validator, wrong_message)]);
+        assert!(validate_signatures(&entries, &message, &[validator.pubkey()]).is_err());
+
+        let entries = signature_entries(&[(&validator, valid), (&validator, valid)]);
+        assert!(validate_signatures(&entries, &message, &[validator.pubkey()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_signature_accepts_hex_and_base58() {
+        let bytes = [7u8; 64];
+        assert_eq!(parse_signature(&hex::encode(bytes)).unwrap(), bytes);
+        assert_eq!(parse_signature(&format!("0x{}", hex::encode(bytes))).unwrap(), bytes);
+        assert_eq!(parse_signature(&Signature::from(bytes).to_string()).unwrap(), bytes);
+        assert!(parse_signature("0x1234").is_err());
+    }
+
+    /// Drive a manual unlock end to end: signature file -> local validation -> program
+    #[tokio::test]
+    async fn test_relay_unlock_with_valid_signature_set() {
+        use solana_program_test::{processor, ProgramTest};
+        use solana_sdk::{account::Account, program_pack::Pack, signature::Keypair};
+
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "bridge_vault",
+            program_id,
+            processor!(bridge_vault::process_instruction),
+        );
+
+        let relayer = Keypair::new();
+        let validators: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let bridge_config = Pubkey::new_unique();
+        let nonce = 4u64;
+        let amount = 2_500_000u64;
+
+        let (vault_pda, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+        let (user_bridge_state, _) =
+            Pubkey::find_program_address(&[b"bridge", user.as_ref(), &nonce.to_le_bytes()], &program_id);
+        let state = unlock_state(user, mint, nonce, amount);
+
+        let mut add_account = |address: Pubkey, data: Vec<u8>, owner: Pubkey| {
+            program_test.add_account(
+                address,
+                Account {
+                    lamports: 1_000_000_000,
+                    data,
+                    owner,
+                    ..Account::default()
+                },
+            );
+        };
+
+        let token_account = |owner: Pubkey, amount: u64| {
+            let mut data = vec![0u8; spl_token::state::Account::LEN];
+            spl_token::state::Account {
+                mint,
+                owner,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+            data
+        };
+
+        let mut mint_data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 9,
+            is_initialized: true,
+            supply: amount,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut mint_data);
+
+        add_account(mint, mint_data, spl_token::id());
+        add_account(derive_vault_ata(&program_id, &bridge_config, &mint), token_account(vault_pda, amount), spl_token::id());
+        add_account(derive_user_ata(&user, &mint), token_account(user, 0), spl_token::id());
+        add_account(
+            bridge_config,
+            borsh::to_vec(&bridge_vault::state::BridgeConfig {
+                admin: Pubkey::new_unique(),
+                vault_pda_bump: vault_bump,
+                relayer_authority: relayer.pubkey(),
+                fee_basis_points: 0,
+                is_paused: false,
+                total_locked: amount,
+                nonce: nonce + 1,
+                validators: validators.iter().map(|v| v.pubkey()).collect(),
+                validator_threshold: 2,
+                supported_chains: bridge_vault::state::BridgeConfig::ALL_CHAINS,
+                transfer_ttl: bridge_vault::state::BridgeConfig::DEFAULT_TRANSFER_TTL,
+                validator_fee_bps: 0,
+                fees_per_validator: 0,
+                relayer_authorities: vec![],
+                max_total_locked: 0,
+                signature_scheme: bridge_vault::state::SIGNATURE_SCHEME_ED25519,
+                chain_fee_basis_points: [
+                    bridge_vault::state::BridgeConfig::CHAIN_FEE_UNSET;
+                    bridge_vault::state::BridgeConfig::CHAIN_FEE_SLOTS
+                ],
+            })
+            .unwrap(),
+            program_id,
+        );
+        add_account(user_bridge_state, borsh::to_vec(&state).unwrap(), program_id);
+
+        // Operator-supplied file with two of the three validators
+        let message = create_unlock_message(nonce, &user, &mint, amount);
+        let signed: Vec<(&Keypair, [u8; 64])> = validators[..2]
+            .iter()
+            .map(|v| (v, v.sign_message(&message).into()))
+            .collect();
+        let file_contents: Vec<serde_json::Value> = signature_entries(&signed)
+            .iter()
+            .map(|e| serde_json::json!({ "validator": e.validator, "signature": e.signature }))
+            .collect();
+        let path = std::env::temp_dir().join(format!("bridge-relay-unlock-{}.json", Pubkey::new_unique()));
+        std::fs::write(&path, serde_json::to_string(&file_contents).unwrap()).unwrap();
+
+        let entries = load_signature_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let validator_keys: Vec<Pubkey> = validators.iter().map(|v| v.pubkey()).collect();
+        let signers = validate_signatures(&entries, &message, &validator_keys).unwrap();
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let instructions = build_relay_unlock_instructions(&program_id, &relayer.pubkey(), &bridge_config, &state, &signers);
+        let mut tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+        tx.sign(&[&payer, &relayer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        let account = banks_client.get_account(user_bridge_state).await.unwrap().unwrap();
+        let unlocked = UserBridgeState::try_from_slice(&account.data).unwrap();
+        assert!(unlocked.unlocked);
+
+        let account = banks_client
+            .get_account(derive_user_ata(&user, &mint))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, amount);
+    }
+
+    #[test]
+    fn test_debug_pda_parses_flags() {
+        let cli = Cli::try_parse_from([
+            "bridge",
+            "debug",
+            "pda",
+            "--config",
+            BRIDGE_PROGRAM_ID,
+            "--user",
+            BRIDGE_PROGRAM_ID,
+            "--nonce",
+            "7",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Debug {
+                command: DebugCommand::Pda { nonce, program_id, .. },
+            } => {
+                assert_eq!(nonce, 7);
+                assert!(program_id.is_none());
+            }
+            _ => panic!("Expected debug pda command"),
+        }
+    }
+
+    /// The addresses and bumps `bridge debug pda` prints must be the ones the program
+    /// derives: Initialize only accepts the vault PDA it finds itself and stores its bump,
+    /// and LockTokens signs for the user bridge state with `[b"bridge", user, nonce, bump]`.
+    #[tokio::test]
+    async fn test_debug_pda_matches_program_derivation() {
+        use solana_program_test::{processor, ProgramTest};
+        use solana_sdk::signature::Keypair;
+
+        let program_id = BRIDGE_PROGRAM_ID.parse::<Pubkey>().unwrap();
+        let program_test = ProgramTest::new(
+            "bridge_vault",
+            program_id,
+            processor!(bridge_vault::process_instruction),
+        );
+
+        let bridge_config = Keypair::new();
+        let user = Pubkey::new_from_array([2u8; 32]);
+        let nonce = 7u64;
+
+        let pdas = derive_pdas(&program_id, &bridge_config.pubkey(), &user, nonce);
+        let output = format_pdas(&program_id, &bridge_config.pubkey(), &user, nonce, &pdas);
+        assert!(output.contains(&format!("Vault PDA:          {} (bump {})", pdas.vault.0, pdas.vault.1)));
+        assert!(output.contains(&format!(
+            "User bridge state:  {} (bump {})",
+            pdas.user_bridge_state.0, pdas.user_bridge_state.1
+        )));
+        assert!(output.contains("(u64 LE 0x0700000000000000)"));
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+        let ix = BridgeInstruction::create_initialize_instruction(
+            &program_id,
+            &payer.pubkey(),
+            &bridge_config.pubkey(),
+            &pdas.vault.0,
+            &payer.pubkey(),
+            0,
+            vec![Pubkey::new_unique()],
+            1,
+        );
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &bridge_config], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        let account = banks_client.get_account(bridge_config.pubkey()).await.unwrap().unwrap();
+        let config = bridge_vault::state::BridgeConfig::load(&account.data).unwrap();
+        assert_eq!(config.vault_pda_bump, pdas.vault.1);
+
+        let (state, bump) = pdas.user_bridge_state;
+        assert_eq!(
+            Pubkey::create_program_address(&[b"bridge", user.as_ref(), &nonce.to_le_bytes(), &[bump]], &program_id)
+                .unwrap(),
+            state
+        );
+    }
+
+    /// One server playing both the Solana RPC (holding `config` at any address) and
+    /// the relayer API (reporting `health` from `GET /validators`)
+    async fn mock_rpc_and_relayer(config: &bridge_vault::state::BridgeConfig, health: serde_json::Value) -> String {
+        use axum::{routing::{get, post}, Json, Router};
+        use serde_json::{json, Value};
+
+        let mut data = borsh::to_vec(config).unwrap();
+        data.resize(bridge_vault::state::BridgeConfig::LEN, 0);
+        let account = json!({
+            "data": [BASE64.encode(&data), "base64"],
+            "executable": false,
+            "lamports": 1_000_000,
+            "owner": BRIDGE_PROGRAM_ID,
+            "rentEpoch": 0,
+            "space": data.len(),
+        });
+
+        let app = Router::new()
+            .route(
+                "/",
+                post(move |Json(body): Json<Value>| {
+                    let account = account.clone();
+                    async move {
+                        let result = match body["method"].as_str() {
+                            Some("getAccountInfo") => json!({ "context": { "slot": 1 }, "value": account }),
+                            _ => Value::Null,
+                        };
+                        Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+                    }
+                }),
+            )
+            .route("/validators", get(move || async move { Json(health) }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_validators_list_shows_on_chain_set_with_health() {
+        let validators: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let config = bridge_vault::state::BridgeConfig {
+            admin: Pubkey::new_unique(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: validators.clone(),
+            validator_threshold: 2,
+            supported_chains: bridge_vault::state::BridgeConfig::ALL_CHAINS,
+            transfer_ttl: bridge_vault::state::BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: bridge_vault::state::SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [
+                bridge_vault::state::BridgeConfig::CHAIN_FEE_UNSET;
+                bridge_vault::state::BridgeConfig::CHAIN_FEE_SLOTS
+            ],
+        };
+        let stray = Pubkey::new_unique();
+        let health = serde_json::json!([
+            { "name": "alpha", "sol_public_key": validators[0].to_string(), "healthy": true, "latency_ms": 12, "error": null },
+            { "name": "beta", "sol_public_key": validators[1].to_string(), "healthy": false, "latency_ms": 5000, "error": "timed out" },
+            { "name": "stray", "sol_public_key": stray.to_string(), "healthy": true, "latency_ms": 3, "error": null },
+        ]);
+        let url = mock_rpc_and_relayer(&config, health).await;
+
+        let rpc = RpcClient::new(url.clone());
+        let fetched = fetch_bridge_config(&rpc, &Pubkey::new_unique()).await.unwrap();
+        let health = fetch_validator_health(&url).await.unwrap();
+        let table = format_validator_table(&fetched, Some(&health));
+
+        assert!(table.starts_with("Validators: 3 on-chain, threshold 2"), "{}", table);
+        let rows: Vec<&str> = table.lines().filter(|line| line.starts_with("│ ") && !line.contains("Pubkey")).collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].contains(&validators[0].to_string()) && rows[0].contains("alpha") && rows[0].contains("12 ms"));
+        assert!(rows[1].contains("beta") && rows[1].contains("down"));
+        assert!(rows[2].contains("missing"));
+        assert!(table.contains(&format!("Relayer validator stray ({}) is not in the on-chain set", stray)));
+
+        // Without a relayer the on-chain set is still listed
+        let table = format_validator_table(&fetched, None);
+        assert!(table.starts_with("Validators: 3 on-chain, threshold 2"));
+        assert!(table.contains("Relayer API unreachable"));
+    }
+
+    /// Relayer `GET /txs` serving `records` (`(id, transfer)`, newest first) two per page,
+    /// keyed by id like the real one, and checking the export's filters are forwarded
+    async fn mock_history_relayer(records: Vec<(i64, serde_json::Value)>) -> String {
+        use axum::{extract::Query, routing::get, Json, Router};
+        use serde_json::json;
+        use std::collections::HashMap;
+
+        let app = Router::new().route(
+            "/txs",
+            get(move |Query(query): Query<HashMap<String, String>>| {
+                let records = records.clone();
+                async move {
+                    assert_eq!(query.get("user").map(String::as_str), Some("alice"));
+                    assert_eq!(query.get("since").map(String::as_str), Some("2024-03-01T00:00:00Z"));
+                    let before = query.get("before").map(|before| before.parse::<i64>().unwrap());
+                    let page: Vec<&(i64, serde_json::Value)> = records
+                        .iter()
+                        .filter(|(id, _)| before.map_or(true, |before| *id < before))
+                        .take(2)
+                        .collect();
+                    let next_before = if page.len() == 2 { page.last().map(|(id, _)| *id) } else { None };
+                    let transactions: Vec<&serde_json::Value> = page.iter().map(|(_, tx)| tx).collect();
+                    Json(json!({ "transactions": transactions, "total": page.len(), "next_before": next_before }))
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_history_export_writes_every_page() {
+        let records = (1..=3i64)
+            .rev()
+            .map(|nonce| {
+                let gross = nonce as u64 * 1_000;
+                let tx = serde_json::json!({
+                    "nonce": nonce,
+                    "from_chain": "Solana",
+                    "to_chain": "Ethereum",
+                    "from_tx_hash": format!("sol_tx_{}", nonce),
+                    "to_tx_hash": if nonce == 1 { None } else { Some(format!("0xeth_{}", nonce)) },
+                    "sender": "alice",
+                    "recipient": "0xbob",
+                    "amount": gross - gross / 100,
+                    "gross_amount": gross,
+                    "fee_amount": gross / 100,
+                    "status": if nonce == 1 { "pending" } else { "confirmed" },
+                    "error_message": null,
+                    "created_at": format!("2024-03-0{}T00:00:00Z", nonce),
+                });
+                (nonce, tx)
+            })
+            .collect();
+        let url = mock_history_relayer(records).await;
+        let client = reqwest::Client::new();
+        let since = parse_since("2024-03-01").unwrap();
+
+        let export = |format: ExportFormat, extension: &str| {
+            let path =
+                std::env::temp_dir().join(format!("bridge-history-{}.{}", Pubkey::new_unique(), extension));
+            let file = std::fs::File::create(&path).unwrap();
+            (path, ExportWriter::new(std::io::BufWriter::new(file), format).unwrap())
+        };
+
+        let (path, mut writer) = export(ExportFormat::Csv, "csv");
+        export_history(&client, &url, Some("alice"), Some(&since), &mut writer).await.unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            vec![
+                EXPORT_CSV_HEADER,
+                "3,2024-03-03T00:00:00Z,Solana,Ethereum,alice,0xbob,3000,30,2970,confirmed,sol_tx_3,0xeth_3",
+                "2,2024-03-02T00:00:00Z,Solana,Ethereum,alice,0xbob,2000,20,1980,confirmed,sol_tx_2,0xeth_2",
+                "1,2024-03-01T00:00:00Z,Solana,Ethereum,alice,0xbob,1000,10,990,pending,sol_tx_1,",
+            ]
+        );
+
+        let (path, mut writer) = export(ExportFormat::Json, "json");
+        export_history(&client, &url, Some("alice"), Some(&since), &mut writer).await.unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+        let json: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let nonces: Vec<u64> = json.iter().map(|tx| tx["nonce"].as_u64().unwrap()).collect();
+        assert_eq!(nonces, vec![3, 2, 1]);
+        assert_eq!(json[0]["gross_amount"], 3000);
+        assert_eq!(json[0]["fee_amount"], 30);
+        assert_eq!(json[0]["net_amount"], 2970);
+        assert_eq!(json[0]["to_tx_hash"], "0xeth_3");
+        assert!(json[2]["to_tx_hash"].is_null());
+    }
+
+    #[test]
+    fn test_history_export_field_formatting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+
+        assert_eq!(parse_since("2024-03-01").unwrap(), "2024-03-01T00:00:00Z");
+        assert_eq!(parse_since("2024-03-01T12:30:00+02:00").unwrap(), "2024-03-01T10:30:00Z");
+        assert!(parse_since("March 1st").is_err());
+
+        let cli = Cli::try_parse_from(["bridge", "history", "export", "--format", "csv", "--out", "history.csv"]).unwrap();
+        match cli.command {
+            Commands::History {
+                command: Some(HistoryCommand::Export { format, user, since, .. }),
+                ..
+            } => {
+                assert_eq!(format, ExportFormat::Csv);
+                assert!(user.is_none() && since.is_none());
+            }
+            _ => panic!("Expected history export command"),
+        }
+    }
+
+    #[test]
+    fn test_verify_signature_command() {
+        use solana_sdk::signature::Keypair;
+
+        let validator = Keypair::new();
+        let validator_key = validator.pubkey().to_string();
+        let recipient = Pubkey::new_unique().to_string();
+        let mint = Pubkey::new_unique().to_string();
+        let unsigned = format!("0x{}", "00".repeat(64));
+        let message_hash =
+            verify_transfer_signature(Chain::Solana, 3, &recipient, 500, "", Some(&mint), &validator_key, &unsigned)
+                .unwrap()
+                .message_hash;
+        let signature = format!("0x{}", hex::encode(validator.sign_message(&message_hash).as_ref()));
+
+        let check = |amount: &str| {
+            let cli = Cli::try_parse_from([
+                "bridge",
+                "verify-signature",
+                "--chain",
+                "sol",
+                "--nonce",
+                "3",
+                "--recipient",
+                &recipient,
+                "--amount",
+                amount,
+                "--mint",
+                &mint,
+                "--validator",
+                &validator_key,
+                "--signature",
+                &signature,
+            ])
+            .unwrap();
+            let Commands::VerifySignature {
+                chain,
+                nonce,
+                recipient,
+                amount,
+                sender,
+                mint,
+                validator,
+                signature,
+            } = cli.command
+            else {
+                panic!("Expected verify-signature command");
+            };
+            assert!(sender.is_none());
+            let chain = signing_chain(&chain).unwrap();
+            let result = verify_transfer_signature(
+                chain,
+                nonce,
+                &recipient,
+                amount,
+                "",
+                mint.as_deref(),
+                &validator,
+                &signature,
+            )
+            .unwrap();
+            format_signature_check(chain, &validator, &result)
+        };
+
+        let valid = check("500");
+        assert!(valid.contains(&format!("Message hash:     0x{}", hex::encode(message_hash))));
+        assert!(valid.contains(&format!("Expected signer:  {}", validator_key)));
+        assert!(valid.ends_with("Signature:        valid"));
+        assert!(!valid.contains("Recovered signer"), "Ed25519 signatures don't recover a signer");
+        assert!(check("5000").ends_with("Signature:        INVALID"));
+
+        assert!(signing_chain("sui").is_err());
+        assert!(signing_chain("polygon").is_err());
+    }
+
+    /// Relayer `GET /txs` serving `snapshots` one poll at a time, the last one from then on;
+    /// `None` answers that poll with a 503, as a relayer that is restarting would
+    async fn mock_tailed_relayer(snapshots: Vec<Option<Vec<serde_json::Value>>>) -> String {
+        use axum::{http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+        use serde_json::json;
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route(
+            "/txs",
+            get(move || {
+                let poll = polls.fetch_add(1, Ordering::SeqCst).min(snapshots.len() - 1);
+                let snapshot = snapshots[poll].clone();
+                async move {
+                    match snapshot {
+                        Some(txs) => Json(json!({ "transactions": txs, "total": txs.len() })).into_response(),
+                        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+                    }
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_watch_tails_new_transfers_and_status_changes() {
+        colored::control::set_override(false);
+        let tx = |nonce: u64, from: &str, to: &str, status: &str| {
+            serde_json::json!({
+                "nonce": nonce,
+                "from_chain": from,
+                "to_chain": to,
+                "amount": 1_500_000_000u64,
+                "status": status,
+            })
+        };
+        let url = mock_tailed_relayer(vec![
+            Some(vec![tx(1, "Solana", "Ethereum", "confirmed")]),
+            Some(vec![tx(2, "Ethereum", "Solana", "pending"), tx(1, "Solana", "Ethereum", "confirmed")]),
+            None,
+            Some(vec![tx(3, "Sui", "Ethereum", "pending"), tx(2, "Ethereum", "Solana", "submitted")]),
+            Some(vec![tx(3, "Sui", "Ethereum", "pending"), tx(2, "Ethereum", "Solana", "confirmed")]),
+        ])
+        .await;
+
+        let client = reqwest::Client::new();
+        let mut tail = TransferTail::new(Some(relayer_chain("solana").unwrap()));
+        let mut printed = Vec::new();
+        for _ in 0..5 {
+            match fetch_recent_transfers(&client, &url).await {
+                Ok(txs) => printed.push(tail.update(&txs)),
+                Err(e) => assert!(e.to_string().contains("503"), "{}", e),
+            }
+        }
+
+        // What was there at start isn't replayed, the outage costs nothing, and the
+        // Sui transfer is filtered out
+        let expected: Vec<Vec<&str>> = vec![
+            vec![],
+            vec!["#2 new Ethereum → Solana transfer of 1.5: Pending"],
+            vec!["#2 Pending → Submitted"],
+            vec!["#2 Submitted → Confirmed"],
+        ];
+        assert_eq!(printed, expected);
+    }
 }
@@ -0,0 +1,16 @@
+//! `BridgeInstruction::unpack` reads untrusted instruction data. It may reject any input,
+//! but must never panic, and whatever it accepts has to pack back to the exact bytes it
+//! was read from, so one instruction has one encoding.
+//!
+//! Run from `programs/bridge-vault` with `cargo +nightly fuzz run unpack_instruction`.
+
+#![no_main]
+
+use bridge_vault::instruction::BridgeInstruction;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(instruction) = BridgeInstruction::unpack(data) {
+        assert_eq!(instruction.pack(), data, "{:?} did not re-pack to its input", instruction);
+    }
+});
@@ -1,52 +1,96 @@
 use solana_program::program_error::ProgramError;
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone)]
+/// Errors returned by the bridge program as `ProgramError::Custom(code)`.
+///
+/// Codes are part of the wire contract with clients (relayer, CLI, frontends),
+/// so every variant has an explicit discriminant. Never renumber or reuse a
+/// code; append new variants with the next free value.
+///
+/// | Code | Error |
+/// |------|-------|
+/// | 0 | `Unauthorized` |
+/// | 1 | `BridgePaused` |
+/// | 2 | `InvalidNonce` |
+/// | 3 | `ThresholdNotMet` |
+/// | 4 | `AlreadyInitialized` |
+/// | 5 | `Overflow` |
+/// | 6 | `IncorrectOwner` |
+/// | 7 | `AccountNotWritable` |
+/// | 8 | `MissingRequiredSignature` |
+/// | 9 | `InvalidFee` |
+/// | 10 | `InvalidStatus` |
+/// | 11 | `InsufficientFunds` |
+/// | 12 | `InvalidDestination` |
+/// | 13 | `InvalidPDA` |
+/// | 14 | `AlreadyUnlocked` |
+/// | 15 | `UnsupportedMint` |
+/// | 16 | `TransferExpired` |
+/// | 17 | `NoFeesToClaim` |
+/// | 18 | `ExceedsRemainingAmount` |
+/// | 19 | `VaultCapExceeded` |
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
 pub enum BridgeError {
     #[error("Unauthorized: Caller is not authorized to perform this action")]
-    Unauthorized,
+    Unauthorized = 0,
 
     #[error("Bridge is currently paused")]
-    BridgePaused,
+    BridgePaused = 1,
 
     #[error("Invalid nonce provided")]
-    InvalidNonce,
+    InvalidNonce = 2,
 
     #[error("Signature threshold not met")]
-    ThresholdNotMet,
+    ThresholdNotMet = 3,
 
     #[error("Account already initialized")]
-    AlreadyInitialized,
+    AlreadyInitialized = 4,
 
     #[error("Arithmetic overflow")]
-    Overflow,
+    Overflow = 5,
 
     #[error("Account has incorrect owner")]
-    IncorrectOwner,
+    IncorrectOwner = 6,
 
     #[error("Account is not writable")]
-    AccountNotWritable,
+    AccountNotWritable = 7,
 
     #[error("Missing required signature")]
-    MissingRequiredSignature,
+    MissingRequiredSignature = 8,
 
     #[error("Invalid fee basis point (must be <= 10000)")]
-    InvalidFee,
+    InvalidFee = 9,
 
     #[error("Invalid bridge status for this operation")]
-    InvalidStatus,
+    InvalidStatus = 10,
 
     #[error("Insufficient funds")]
-    InsufficientFunds,
+    InsufficientFunds = 11,
 
     #[error("Invalid destination chain")]
-    InvalidDestination,
+    InvalidDestination = 12,
 
     #[error("Invalid PDA")]
-    InvalidPDA,
+    InvalidPDA = 13,
 
     #[error("Tokens already unlocked")]
-    AlreadyUnlocked,
+    AlreadyUnlocked = 14,
+
+    #[error("Token mint is not on the bridge allowlist")]
+    UnsupportedMint = 15,
+
+    #[error("Transfer has expired; it can only be refunded")]
+    TransferExpired = 16,
+
+    #[error("Validator has no accrued fees to claim")]
+    NoFeesToClaim = 17,
+
+    #[error("Partial unlock exceeds the amount still locked")]
+    ExceedsRemainingAmount = 18,
+
+    #[error("Lock would push total_locked over the bridge's cap")]
+    VaultCapExceeded = 19,
 }
 
 impl From<BridgeError> for ProgramError {
@@ -54,3 +98,96 @@ impl From<BridgeError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl BridgeError {
+    /// Inverse of the `ProgramError::Custom` code, for clients decoding failed transactions
+    pub fn from_u32(code: u32) -> Option<Self> {
+        let error = match code {
+            0 => BridgeError::Unauthorized,
+            1 => BridgeError::BridgePaused,
+            2 => BridgeError::InvalidNonce,
+            3 => BridgeError::ThresholdNotMet,
+            4 => BridgeError::AlreadyInitialized,
+            5 => BridgeError::Overflow,
+            6 => BridgeError::IncorrectOwner,
+            7 => BridgeError::AccountNotWritable,
+            8 => BridgeError::MissingRequiredSignature,
+            9 => BridgeError::InvalidFee,
+            10 => BridgeError::InvalidStatus,
+            11 => BridgeError::InsufficientFunds,
+            12 => BridgeError::InvalidDestination,
+            13 => BridgeError::InvalidPDA,
+            14 => BridgeError::AlreadyUnlocked,
+            15 => BridgeError::UnsupportedMint,
+            16 => BridgeError::TransferExpired,
+            17 => BridgeError::NoFeesToClaim,
+            18 => BridgeError::ExceedsRemainingAmount,
+            19 => BridgeError::VaultCapExceeded,
+            _ => return None,
+        };
+        Some(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_map_back_to_variants() {
+        let expected = [
+            (0, "Unauthorized"),
+            (1, "BridgePaused"),
+            (2, "InvalidNonce"),
+            (3, "ThresholdNotMet"),
+            (4, "AlreadyInitialized"),
+            (5, "Overflow"),
+            (6, "IncorrectOwner"),
+            (7, "AccountNotWritable"),
+            (8, "MissingRequiredSignature"),
+            (9, "InvalidFee"),
+            (10, "InvalidStatus"),
+            (11, "InsufficientFunds"),
+            (12, "InvalidDestination"),
+            (13, "InvalidPDA"),
+            (14, "AlreadyUnlocked"),
+            (15, "UnsupportedMint"),
+            (16, "TransferExpired"),
+            (17, "NoFeesToClaim"),
+            (18, "ExceedsRemainingAmount"),
+            (19, "VaultCapExceeded"),
+        ];
+
+        for (code, name) in expected {
+            let error = BridgeError::from_u32(code).expect("code should map to a variant");
+            assert_eq!(format!("{:?}", error), name);
+            assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        }
+
+        assert_eq!(BridgeError::from_u32(expected.len() as u32), None);
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(BridgeError::Unauthorized as u32, 0);
+        assert_eq!(BridgeError::BridgePaused as u32, 1);
+        assert_eq!(BridgeError::InvalidNonce as u32, 2);
+        assert_eq!(BridgeError::ThresholdNotMet as u32, 3);
+        assert_eq!(BridgeError::AlreadyInitialized as u32, 4);
+        assert_eq!(BridgeError::Overflow as u32, 5);
+        assert_eq!(BridgeError::IncorrectOwner as u32, 6);
+        assert_eq!(BridgeError::AccountNotWritable as u32, 7);
+        assert_eq!(BridgeError::MissingRequiredSignature as u32, 8);
+        assert_eq!(BridgeError::InvalidFee as u32, 9);
+        assert_eq!(BridgeError::InvalidStatus as u32, 10);
+        assert_eq!(BridgeError::InsufficientFunds as u32, 11);
+        assert_eq!(BridgeError::InvalidDestination as u32, 12);
+        assert_eq!(BridgeError::InvalidPDA as u32, 13);
+        assert_eq!(BridgeError::AlreadyUnlocked as u32, 14);
+        assert_eq!(BridgeError::UnsupportedMint as u32, 15);
+        assert_eq!(BridgeError::TransferExpired as u32, 16);
+        assert_eq!(BridgeError::NoFeesToClaim as u32, 17);
+        assert_eq!(BridgeError::ExceedsRemainingAmount as u32, 18);
+        assert_eq!(BridgeError::VaultCapExceeded as u32, 19);
+    }
+}
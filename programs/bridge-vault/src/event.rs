@@ -0,0 +1,104 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Emitted by `UnlockTokens` through `sol_log_data` as `[DISCRIMINATOR, borsh(event)]`,
+/// which shows up as a `Program data: <base64> <base64>` log line.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TokensUnlockedEvent {
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+impl TokensUnlockedEvent {
+    pub const DISCRIMINATOR: &'static [u8] = b"tokens_unlocked";
+
+    pub fn emit(&self) {
+        emit(Self::DISCRIMINATOR, self);
+    }
+
+    /// Decode the already base64-decoded fields of a `Program data:` line;
+    /// `None` if they belong to some other event
+    pub fn from_log_data(fields: &[Vec<u8>]) -> Option<Self> {
+        decode(Self::DISCRIMINATOR, fields)
+    }
+}
+
+/// Emitted by `RefundLock` when a pending transfer is returned to the user
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TokensRefundedEvent {
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+impl TokensRefundedEvent {
+    pub const DISCRIMINATOR: &'static [u8] = b"tokens_refunded";
+
+    pub fn emit(&self) {
+        emit(Self::DISCRIMINATOR, self);
+    }
+
+    pub fn from_log_data(fields: &[Vec<u8>]) -> Option<Self> {
+        decode(Self::DISCRIMINATOR, fields)
+    }
+}
+
+/// Emitted by `CancelLock` when the admin returns a pending transfer to the user
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TokensCancelledEvent {
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+impl TokensCancelledEvent {
+    pub const DISCRIMINATOR: &'static [u8] = b"tokens_cancelled";
+
+    pub fn emit(&self) {
+        emit(Self::DISCRIMINATOR, self);
+    }
+
+    pub fn from_log_data(fields: &[Vec<u8>]) -> Option<Self> {
+        decode(Self::DISCRIMINATOR, fields)
+    }
+}
+
+fn emit<T: BorshSerialize>(discriminator: &[u8], event: &T) {
+    let data = borsh::to_vec(event).unwrap();
+    sol_log_data(&[discriminator, &data]);
+}
+
+fn decode<T: BorshDeserialize>(expected: &[u8], fields: &[Vec<u8>]) -> Option<T> {
+    match fields {
+        [discriminator, data] if discriminator.as_slice() == expected => T::try_from_slice(data).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_unlocked_event_round_trip() {
+        let event = TokensUnlockedEvent {
+            user: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            amount: 1_000_000,
+            nonce: 7,
+        };
+        let fields = vec![
+            TokensUnlockedEvent::DISCRIMINATOR.to_vec(),
+            borsh::to_vec(&event).unwrap(),
+        ];
+        assert_eq!(TokensUnlockedEvent::from_log_data(&fields), Some(event));
+        assert_eq!(TokensRefundedEvent::from_log_data(&fields), None);
+
+        let other = vec![b"tokens_locked".to_vec(), fields[1].clone()];
+        assert_eq!(TokensUnlockedEvent::from_log_data(&other), None);
+    }
+}
@@ -1,12 +1,34 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    ed25519_program,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     sysvar,
 };
 
+use crate::state::{AllowedMints, BridgeConfig, MintFees, ValidatorFees};
+
 const SYSTEM_PROGRAM_ID: Pubkey = solana_program::pubkey!("11111111111111111111111111111111");
 
+/// Ed25519Program data layout: `[count: u8, padding: u8]` followed by one
+/// 14-byte offsets entry (seven little-endian u16s) per signature
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Instruction index meaning "data lives in this Ed25519 instruction"
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Most unlocks one `UnlockTokensBatch` may carry. Each item costs a token
+/// transfer CPI plus signature matching; with the 1232-byte transaction limit,
+/// higher validator thresholds fit fewer items than this.
+pub const MAX_UNLOCK_BATCH: usize = 4;
+
+/// Borsh variant tags of the instructions that carry vectors. Variants are only ever
+/// appended, so these never change.
+const INITIALIZE_TAG: u8 = 0;
+const UNLOCK_TOKENS_TAG: u8 = 2;
+const UNLOCK_TOKENS_BATCH_TAG: u8 = 9;
+const UNLOCK_TOKENS_PARTIAL_TAG: u8 = 12;
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum BridgeInstruction {
     Initialize {
@@ -29,9 +51,64 @@ pub enum BridgeInstruction {
         new_admin: Option<Pubkey>,
         new_relayer: Option<Pubkey>,
         new_fee: Option<u16>,
+        new_supported_chains: Option<u16>,
+        new_transfer_ttl: Option<i64>,
+        new_validator_fee_bps: Option<u16>,
+        new_max_total_locked: Option<u64>,
+        new_signature_scheme: Option<u8>,
     },
     Pause,
     Unpause,
+    AddAllowedMint {
+        mint: Pubkey,
+    },
+    RemoveAllowedMint {
+        mint: Pubkey,
+    },
+    RefundLock {
+        nonce: u64,
+    },
+    /// `(nonce, amount, signatures)` per unlock; all succeed or none do
+    UnlockTokensBatch {
+        items: Vec<(u64, u64, Vec<[u8; 64]>)>,
+    },
+    /// Admin returns a still-pending lock to the user without waiting for the refund timeout
+    CancelLock {
+        nonce: u64,
+    },
+    /// Validator withdraws its accrued share of one mint's lock fees from the vault
+    ClaimValidatorFees,
+    /// Release `amount` of a pending lock, signed over `create_partial_unlock_message`;
+    /// the transfer completes once nothing remains
+    UnlockTokensPartial {
+        nonce: u64,
+        amount: u64,
+        signatures: Vec<[u8; 64]>,
+    },
+    /// User closes a finished transfer's `UserBridgeState` and gets its rent back
+    CloseBridgeState {
+        nonce: u64,
+    },
+    /// Admin authorizes another relayer alongside `relayer_authority`
+    AddRelayerAuthority {
+        relayer: Pubkey,
+    },
+    /// Admin revokes a relayer added with `AddRelayerAuthority`
+    RemoveRelayerAuthority {
+        relayer: Pubkey,
+    },
+    /// Admin sets the lock fee for one destination chain; `None` returns it to the base fee
+    SetChainFee {
+        chain: u8,
+        fee_basis_points: Option<u16>,
+    },
+    /// `ClaimValidatorFees` for a secp256k1 validator, which can't sign a Solana
+    /// transaction: `signature` by its Ethereum key over `create_claim_fees_message`
+    /// authorizes the claim, and anyone may submit it
+    ClaimValidatorFeesSecp256k1 {
+        validator: Pubkey,
+        signature: [u8; 64],
+    },
 }
 
 impl BridgeInstruction {
@@ -46,6 +123,7 @@ impl BridgeInstruction {
                 "Empty instruction data",
             ));
         }
+        check_declared_lengths(input)?;
         Self::try_from_slice(input)
     }
 
@@ -104,6 +182,8 @@ impl BridgeInstruction {
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(AllowedMints::find_address(program_id, bridge_config).0, false),
+            AccountMeta::new(MintFees::find_address(program_id, bridge_config, token_mint).0, false),
         ];
 
         Instruction {
@@ -139,6 +219,7 @@ impl BridgeInstruction {
             AccountMeta::new(*user_bridge_state, false),
             AccountMeta::new(*bridge_config, false),
             AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
         ];
 
         Instruction {
@@ -148,6 +229,117 @@ impl BridgeInstruction {
         }
     }
 
+    /// Same accounts as `create_unlock_tokens_instruction`
+    pub fn create_unlock_partial_instruction(
+        program_id: &Pubkey,
+        relayer: &Pubkey,
+        user: &Pubkey,
+        user_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        user_bridge_state: &Pubkey,
+        bridge_config: &Pubkey,
+        nonce: u64,
+        amount: u64,
+        signatures: Vec<[u8; 64]>,
+    ) -> Instruction {
+        let mut instruction = Self::create_unlock_tokens_instruction(
+            program_id,
+            relayer,
+            user,
+            user_token_account,
+            vault_token_account,
+            vault_pda,
+            user_bridge_state,
+            bridge_config,
+            nonce,
+            vec![],
+        );
+        instruction.data = Self::UnlockTokensPartial {
+            nonce,
+            amount,
+            signatures,
+        }
+        .pack();
+        instruction
+    }
+
+    /// `recipients` holds `(user, user_token_account, user_bridge_state)` for
+    /// each entry of `items`, in the same order.
+    pub fn create_unlock_batch_instruction(
+        program_id: &Pubkey,
+        relayer: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        bridge_config: &Pubkey,
+        recipients: &[(Pubkey, Pubkey, Pubkey)],
+        items: Vec<(u64, u64, Vec<[u8; 64]>)>,
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*relayer, true),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new_readonly(*vault_pda, false),
+            AccountMeta::new(*bridge_config, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ];
+
+        for (user, user_token_account, user_bridge_state) in recipients {
+            accounts.push(AccountMeta::new_readonly(*user, false));
+            accounts.push(AccountMeta::new(*user_token_account, false));
+            accounts.push(AccountMeta::new(*user_bridge_state, false));
+        }
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::UnlockTokensBatch { items }.pack(),
+        }
+    }
+
+    /// Ed25519Program instruction verifying each validator's signature over the
+    /// unlock message. It must precede `UnlockTokens` in the same transaction.
+    pub fn create_ed25519_verify_instruction(
+        message: &[u8; 32],
+        signers: &[(Pubkey, [u8; 64])],
+    ) -> Instruction {
+        let entries_end = ED25519_OFFSETS_START + signers.len() * ED25519_OFFSETS_LEN;
+        let message_offset = entries_end + signers.len() * (32 + 64);
+
+        let mut data = Vec::with_capacity(message_offset + message.len());
+        data.push(signers.len() as u8);
+        data.push(0);
+
+        for i in 0..signers.len() {
+            let public_key_offset = entries_end + i * (32 + 64);
+            let signature_offset = public_key_offset + 32;
+
+            for field in [
+                signature_offset as u16,
+                ED25519_CURRENT_INSTRUCTION,
+                public_key_offset as u16,
+                ED25519_CURRENT_INSTRUCTION,
+                message_offset as u16,
+                message.len() as u16,
+                ED25519_CURRENT_INSTRUCTION,
+            ] {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+
+        for (pubkey, signature) in signers {
+            data.extend_from_slice(pubkey.as_ref());
+            data.extend_from_slice(signature);
+        }
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::id(),
+            accounts: vec![],
+            data,
+        }
+    }
+
     pub fn create_update_config_instruction(
         program_id: &Pubkey,
         admin: &Pubkey,
@@ -155,6 +347,11 @@ impl BridgeInstruction {
         new_admin: Option<Pubkey>,
         new_relayer: Option<Pubkey>,
         new_fee: Option<u16>,
+        new_supported_chains: Option<u16>,
+        new_transfer_ttl: Option<i64>,
+        new_validator_fee_bps: Option<u16>,
+        new_max_total_locked: Option<u64>,
+        new_signature_scheme: Option<u8>,
     ) -> Instruction {
         let accounts = vec![
             AccountMeta::new_readonly(*admin, true),
@@ -168,6 +365,11 @@ impl BridgeInstruction {
                 new_admin,
                 new_relayer,
                 new_fee,
+                new_supported_chains,
+                new_transfer_ttl,
+                new_validator_fee_bps,
+                new_max_total_locked,
+                new_signature_scheme,
             }
             .pack(),
         }
@@ -206,6 +408,387 @@ impl BridgeInstruction {
             data: Self::Unpause.pack(),
         }
     }
+
+    pub fn create_refund_lock_instruction(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        relayer: &Pubkey,
+        user_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        user_bridge_state: &Pubkey,
+        bridge_config: &Pubkey,
+        nonce: u64,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new_readonly(*relayer, true),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new_readonly(*vault_pda, false),
+            AccountMeta::new(*user_bridge_state, false),
+            AccountMeta::new(*bridge_config, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::RefundLock { nonce }.pack(),
+        }
+    }
+
+    pub fn create_cancel_lock_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        user: &Pubkey,
+        user_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        user_bridge_state: &Pubkey,
+        bridge_config: &Pubkey,
+        nonce: u64,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new_readonly(*vault_pda, false),
+            AccountMeta::new(*user_bridge_state, false),
+            AccountMeta::new(*bridge_config, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::CancelLock { nonce }.pack(),
+        }
+    }
+
+    pub fn create_claim_validator_fees_instruction(
+        program_id: &Pubkey,
+        validator: &Pubkey,
+        validator_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        bridge_config: &Pubkey,
+        token_mint: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: Self::claim_validator_fees_accounts(
+                program_id,
+                validator,
+                validator,
+                validator_token_account,
+                vault_token_account,
+                vault_pda,
+                bridge_config,
+                token_mint,
+            ),
+            data: Self::ClaimValidatorFees.pack(),
+        }
+    }
+
+    /// Claim for secp256k1 `validator` (its padded Ethereum address), submitted and paid
+    /// for by `payer` with the validator's `signature` over `create_claim_fees_message`
+    pub fn create_claim_validator_fees_secp256k1_instruction(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        validator: &Pubkey,
+        signature: [u8; 64],
+        validator_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        bridge_config: &Pubkey,
+        token_mint: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: Self::claim_validator_fees_accounts(
+                program_id,
+                payer,
+                validator,
+                validator_token_account,
+                vault_token_account,
+                vault_pda,
+                bridge_config,
+                token_mint,
+            ),
+            data: Self::ClaimValidatorFeesSecp256k1 {
+                validator: *validator,
+                signature,
+            }
+            .pack(),
+        }
+    }
+
+    fn claim_validator_fees_accounts(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        validator: &Pubkey,
+        validator_token_account: &Pubkey,
+        vault_token_account: &Pubkey,
+        vault_pda: &Pubkey,
+        bridge_config: &Pubkey,
+        token_mint: &Pubkey,
+    ) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*validator_token_account, false),
+            AccountMeta::new(*vault_token_account, false),
+            AccountMeta::new_readonly(*vault_pda, false),
+            AccountMeta::new_readonly(*bridge_config, false),
+            AccountMeta::new_readonly(MintFees::find_address(program_id, bridge_config, token_mint).0, false),
+            AccountMeta::new(
+                ValidatorFees::find_address(program_id, bridge_config, validator, token_mint).0,
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_mint, false),
+        ]
+    }
+
+    pub fn create_close_bridge_state_instruction(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        user_bridge_state: &Pubkey,
+        nonce: u64,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*user_bridge_state, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::CloseBridgeState { nonce }.pack(),
+        }
+    }
+
+    pub fn create_add_allowed_mint_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        bridge_config: &Pubkey,
+        mint: &Pubkey,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(*bridge_config, false),
+            AccountMeta::new(AllowedMints::find_address(program_id, bridge_config).0, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(MintFees::find_address(program_id, bridge_config, mint).0, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::AddAllowedMint { mint: *mint }.pack(),
+        }
+    }
+
+    pub fn create_remove_allowed_mint_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        bridge_config: &Pubkey,
+        mint: &Pubkey,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*bridge_config, false),
+            AccountMeta::new(AllowedMints::find_address(program_id, bridge_config).0, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::RemoveAllowedMint { mint: *mint }.pack(),
+        }
+    }
+
+    pub fn create_add_relayer_authority_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        bridge_config: &Pubkey,
+        relayer: &Pubkey,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(*bridge_config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::AddRelayerAuthority { relayer: *relayer }.pack(),
+        }
+    }
+
+    pub fn create_remove_relayer_authority_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        bridge_config: &Pubkey,
+        relayer: &Pubkey,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(*bridge_config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::RemoveRelayerAuthority { relayer: *relayer }.pack(),
+        }
+    }
+
+    pub fn create_set_chain_fee_instruction(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        bridge_config: &Pubkey,
+        chain: u8,
+        fee_basis_points: Option<u16>,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(*bridge_config, false),
+        ];
+
+        Instruction {
+            program_id: *program_id,
+            accounts,
+            data: Self::SetChainFee {
+                chain,
+                fee_basis_points,
+            }
+            .pack(),
+        }
+    }
+
+    /// Point a token-moving instruction from the helpers above at `token_program`
+    /// instead of SPL Token. Instructions paying out of the vault take the mint as an
+    /// optional trailing account, which Token-2022 needs to transfer; pass it for those.
+    /// `LockTokens` already carries its mint.
+    pub fn with_token_program(
+        mut instruction: Instruction,
+        token_program: &Pubkey,
+        token_mint: Option<&Pubkey>,
+    ) -> Instruction {
+        for account in &mut instruction.accounts {
+            if account.pubkey == spl_token::id() {
+                account.pubkey = *token_program;
+            }
+        }
+        if let Some(token_mint) = token_mint {
+            instruction.accounts.push(AccountMeta::new_readonly(*token_mint, false));
+        }
+        instruction
+    }
+}
+
+/// Reject instruction data whose vectors declare more entries than the program ever
+/// accepts, before borsh allocates for them: validators and signatures are bounded by
+/// `BridgeConfig::MAX_VALIDATORS`, batch items by `MAX_UNLOCK_BATCH`.
+fn check_declared_lengths(input: &[u8]) -> Result<(), std::io::Error> {
+    let max_signatures = BridgeConfig::MAX_VALIDATORS;
+
+    match input[0] {
+        // admin, relayer_authority, fee_basis_points, then validators
+        INITIALIZE_TAG => declared_len(input, 1 + 32 + 32 + 2, BridgeConfig::MAX_VALIDATORS, "validators")?,
+        // nonce, then signatures
+        UNLOCK_TOKENS_TAG => declared_len(input, 1 + 8, max_signatures, "signatures")?,
+        // nonce, amount, then signatures
+        UNLOCK_TOKENS_PARTIAL_TAG => declared_len(input, 1 + 8 + 8, max_signatures, "signatures")?,
+        UNLOCK_TOKENS_BATCH_TAG => {
+            let items = declared_len(input, 1, MAX_UNLOCK_BATCH, "batch items")?;
+            let mut offset = 1 + 4;
+            for _ in 0..items {
+                // nonce, amount, then signatures
+                let signatures = declared_len(input, offset + 16, max_signatures, "signatures")?;
+                offset += 16 + 4 + signatures * 64;
+            }
+            items
+        }
+        _ => 0,
+    };
+
+    Ok(())
+}
+
+/// The `u32` length prefix borsh wrote at `offset`, rejected above `max`. A prefix cut
+/// short is left for borsh to report.
+fn declared_len(input: &[u8], offset: usize, max: usize, what: &str) -> Result<usize, std::io::Error> {
+    let Some(prefix) = input.get(offset..offset + 4) else {
+        return Ok(0);
+    };
+    let len = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+    if len > max {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} declares {} entries, max {}", what, len, max),
+        ));
+    }
+    Ok(len)
+}
+
+/// `(pubkey, signature)` pairs in an Ed25519Program instruction that sign `message`.
+///
+/// Only entries whose signature, key and message all live in the instruction
+/// itself are returned; offsets pointing into other instructions are ignored so
+/// a crafted entry can't borrow data from elsewhere in the transaction.
+pub fn parse_ed25519_instruction(data: &[u8], message: &[u8; 32]) -> Vec<(Pubkey, [u8; 64])> {
+    let read_u16 = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+
+    let count = data.first().copied().unwrap_or(0) as usize;
+    let mut pairs = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_LEN;
+        let fields: Option<Vec<u16>> = (0..7).map(|field| read_u16(start + field * 2)).collect();
+        let Some(
+            &[signature_offset, signature_ix, public_key_offset, public_key_ix, message_offset, message_size, message_ix],
+        ) = fields.as_deref()
+        else {
+            break;
+        };
+
+        if [signature_ix, public_key_ix, message_ix]
+            .iter()
+            .any(|ix| *ix != ED25519_CURRENT_INSTRUCTION)
+        {
+            continue;
+        }
+
+        let signature_offset = signature_offset as usize;
+        let public_key_offset = public_key_offset as usize;
+        let message_offset = message_offset as usize;
+
+        let signature = data
+            .get(signature_offset..signature_offset + 64)
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok());
+        let public_key = data
+            .get(public_key_offset..public_key_offset + 32)
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok());
+        let signed_message = data.get(message_offset..message_offset + message_size as usize);
+
+        if let (Some(signature), Some(public_key), Some(signed_message)) = (signature, public_key, signed_message) {
+            if signed_message == message {
+                pairs.push((Pubkey::new_from_array(public_key), signature));
+            }
+        }
+    }
+
+    pairs
 }
 
 #[cfg(test)]
@@ -260,4 +843,132 @@ mod tests {
             _ => panic!("Wrong instruction type"),
         }
     }
+
+    #[test]
+    fn test_vector_variant_tags_match_borsh() {
+        let unlock = BridgeInstruction::UnlockTokens { nonce: 0, signatures: vec![] };
+        let batch = BridgeInstruction::UnlockTokensBatch { items: vec![] };
+        let partial = BridgeInstruction::UnlockTokensPartial { nonce: 0, amount: 0, signatures: vec![] };
+        assert_eq!(unlock.pack()[0], UNLOCK_TOKENS_TAG);
+        assert_eq!(batch.pack()[0], UNLOCK_TOKENS_BATCH_TAG);
+        assert_eq!(partial.pack()[0], UNLOCK_TOKENS_PARTIAL_TAG);
+        let init = BridgeInstruction::Initialize {
+            admin: Pubkey::new_unique(),
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            validators: vec![],
+            validator_threshold: 1,
+        };
+        assert_eq!(init.pack()[0], INITIALIZE_TAG);
+    }
+
+    #[test]
+    fn test_unpack_rejects_oversized_length_prefix() {
+        // UnlockTokens declaring u32::MAX signatures with none behind it
+        let mut data = vec![UNLOCK_TOKENS_TAG];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+        let err = BridgeInstruction::unpack(&data).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("signatures declares 4294967295 entries"), "{}", err);
+
+        // The same inside the second item of a batch
+        let signatures = vec![[1u8; 64]; BridgeConfig::MAX_VALIDATORS];
+        let mut data = BridgeInstruction::UnlockTokensBatch {
+            items: vec![(1, 10, signatures.clone()), (2, 20, signatures)],
+        }
+        .pack();
+        let second_prefix = 1 + 4 + (16 + 4 + BridgeConfig::MAX_VALIDATORS * 64) + 16;
+        data[second_prefix..second_prefix + 4].copy_from_slice(&(1u32 << 30).to_le_bytes());
+        assert!(BridgeInstruction::unpack(&data).is_err());
+
+        let validators = vec![Pubkey::new_unique(); BridgeConfig::MAX_VALIDATORS + 1];
+        let init = BridgeInstruction::Initialize {
+            admin: Pubkey::new_unique(),
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            validators,
+            validator_threshold: 1,
+        };
+        assert!(BridgeInstruction::unpack(&init.pack()).is_err());
+
+        // Lengths at the limit still unpack
+        let full = BridgeInstruction::UnlockTokens {
+            nonce: 7,
+            signatures: vec![[1u8; 64]; BridgeConfig::MAX_VALIDATORS],
+        };
+        assert!(BridgeInstruction::unpack(&full.pack()).is_ok());
+    }
+
+    /// The properties `fuzz/fuzz_targets/unpack_instruction.rs` checks, over every
+    /// truncation and single-byte change of a few vector-carrying instructions
+    #[test]
+    fn test_unpack_of_damaged_input_errors_or_round_trips() {
+        let samples = [
+            BridgeInstruction::Initialize {
+                admin: Pubkey::new_unique(),
+                relayer_authority: Pubkey::new_unique(),
+                fee_basis_points: 50,
+                validators: vec![Pubkey::new_unique(); 2],
+                validator_threshold: 2,
+            },
+            BridgeInstruction::UpdateConfig {
+                new_admin: None,
+                new_relayer: Some(Pubkey::new_unique()),
+                new_fee: Some(10),
+                new_supported_chains: None,
+                new_transfer_ttl: Some(3_600),
+                new_validator_fee_bps: None,
+                new_max_total_locked: Some(1),
+                new_signature_scheme: None,
+            },
+            BridgeInstruction::UnlockTokensBatch {
+                items: vec![(1, 10, vec![[1u8; 64]]), (2, 20, vec![[2u8; 64]; 2])],
+            },
+        ];
+
+        for packed in samples.iter().map(BridgeInstruction::pack) {
+            let mut inputs: Vec<Vec<u8>> = (0..packed.len()).map(|len| packed[..len].to_vec()).collect();
+            for index in 0..packed.len() {
+                for value in [0x00, 0x01, 0x02, 0x80, 0xff] {
+                    let mut changed = packed.clone();
+                    changed[index] = value;
+                    inputs.push(changed);
+                }
+            }
+
+            for input in inputs {
+                if let Ok(instruction) = BridgeInstruction::unpack(&input) {
+                    assert_eq!(instruction.pack(), input, "{:?}", instruction);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ed25519_verify_instruction_layout() {
+        let message = [9u8; 32];
+        let signers = vec![(Pubkey::new_unique(), [1u8; 64]), (Pubkey::new_unique(), [2u8; 64])];
+
+        let ix = BridgeInstruction::create_ed25519_verify_instruction(&message, &signers);
+        assert_eq!(ix.program_id, ed25519_program::id());
+        assert_eq!(ix.data[0], 2);
+
+        assert_eq!(parse_ed25519_instruction(&ix.data, &message), signers);
+        assert!(parse_ed25519_instruction(&ix.data, &[0u8; 32]).is_empty());
+    }
+
+    #[test]
+    fn test_ed25519_entries_referencing_other_instructions_are_ignored() {
+        let message = [9u8; 32];
+        let mut data = BridgeInstruction::create_ed25519_verify_instruction(
+            &message,
+            &[(Pubkey::new_unique(), [1u8; 64])],
+        )
+        .data;
+
+        // signature_instruction_index -> instruction 0
+        data[ED25519_OFFSETS_START + 2..ED25519_OFFSETS_START + 4].copy_from_slice(&0u16.to_le_bytes());
+        assert!(parse_ed25519_instruction(&data, &message).is_empty());
+    }
 }
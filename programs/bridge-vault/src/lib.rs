@@ -1,12 +1,14 @@
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+pub mod token;
 
 pub use error::BridgeError;
 pub use instruction::BridgeInstruction;
 pub use processor::process_instruction;
-pub use state::{BridgeConfig, BridgeStatus, UserBridgeState};
+pub use state::{AllowedMints, BridgeConfig, BridgeStatus, UserBridgeState};
 
 #[cfg(all(target_arch = "bpf", not(feature = "no-entrypoint")))]
 use solana_program::entrypoint;
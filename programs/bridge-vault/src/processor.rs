@@ -3,24 +3,35 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     clock::Clock,
     entrypoint::ProgramResult,
+    keccak,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
-    program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
+    secp256k1_recover::secp256k1_recover,
     system_instruction,
     sysvar::Sysvar,
 };
-use spl_token::state::Account as TokenAccount;
 use sha2::{Sha256, Digest};
+#[cfg(not(feature = "in-program-ed25519"))]
+use solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
 
 const SYSTEM_PROGRAM_ID: Pubkey = solana_program::pubkey!("11111111111111111111111111111111");
 
 use crate::{
     error::BridgeError,
-    instruction::BridgeInstruction,
-    state::{BridgeConfig, BridgeStatus, UserBridgeState},
+    event::{TokensCancelledEvent, TokensRefundedEvent, TokensUnlockedEvent},
+    instruction::{BridgeInstruction, MAX_UNLOCK_BATCH},
+    state::{
+        eth_address_to_bytes32, is_valid_destination, namespaced_nonce, nonce_origin, AllowedMints, BridgeConfig,
+        BridgeStatus, MintFees, UserBridgeState, ValidatorFees, NONCE_ORIGIN_SOLANA, SIGNATURE_SCHEME_ED25519,
+        SIGNATURE_SCHEME_SECP256K1,
+    },
+    token,
 };
 
 
@@ -67,15 +78,31 @@ pub fn process_instruction(
         }
         BridgeInstruction::UnlockTokens { nonce, signatures } => {
             msg!("Instruction: UnlockTokens");
-            process_unlock_tokens(program_id, accounts, nonce, signatures)
+            process_unlock_tokens(program_id, accounts, nonce, UnlockAmount::Remaining, signatures)
         }
         BridgeInstruction::UpdateConfig {
             new_admin,
             new_relayer,
             new_fee,
+            new_supported_chains,
+            new_transfer_ttl,
+            new_validator_fee_bps,
+            new_max_total_locked,
+            new_signature_scheme,
         } => {
             msg!("Instruction: UpdateConfig");
-            process_update_config(program_id, accounts, new_admin, new_relayer, new_fee)
+            process_update_config(
+                program_id,
+                accounts,
+                new_admin,
+                new_relayer,
+                new_fee,
+                new_supported_chains,
+                new_transfer_ttl,
+                new_validator_fee_bps,
+                new_max_total_locked,
+                new_signature_scheme,
+            )
         }
         BridgeInstruction::Pause => {
             msg!("Instruction: Pause");
@@ -85,6 +112,61 @@ pub fn process_instruction(
             msg!("Instruction: Unpause");
             process_unpause(program_id, accounts)
         }
+        BridgeInstruction::AddAllowedMint { mint } => {
+            msg!("Instruction: AddAllowedMint");
+            process_add_allowed_mint(program_id, accounts, mint)
+        }
+        BridgeInstruction::RemoveAllowedMint { mint } => {
+            msg!("Instruction: RemoveAllowedMint");
+            process_remove_allowed_mint(program_id, accounts, mint)
+        }
+        BridgeInstruction::RefundLock { nonce } => {
+            msg!("Instruction: RefundLock");
+            process_refund_lock(program_id, accounts, nonce)
+        }
+        BridgeInstruction::UnlockTokensBatch { items } => {
+            msg!("Instruction: UnlockTokensBatch");
+            process_unlock_tokens_batch(program_id, accounts, items)
+        }
+        BridgeInstruction::CancelLock { nonce } => {
+            msg!("Instruction: CancelLock");
+            process_cancel_lock(program_id, accounts, nonce)
+        }
+        BridgeInstruction::ClaimValidatorFees => {
+            msg!("Instruction: ClaimValidatorFees");
+            process_claim_validator_fees(program_id, accounts, None)
+        }
+        BridgeInstruction::UnlockTokensPartial {
+            nonce,
+            amount,
+            signatures,
+        } => {
+            msg!("Instruction: UnlockTokensPartial");
+            process_unlock_tokens(program_id, accounts, nonce, UnlockAmount::Partial(amount), signatures)
+        }
+        BridgeInstruction::CloseBridgeState { nonce } => {
+            msg!("Instruction: CloseBridgeState");
+            process_close_bridge_state(program_id, accounts, nonce)
+        }
+        BridgeInstruction::AddRelayerAuthority { relayer } => {
+            msg!("Instruction: AddRelayerAuthority");
+            process_add_relayer_authority(program_id, accounts, relayer)
+        }
+        BridgeInstruction::RemoveRelayerAuthority { relayer } => {
+            msg!("Instruction: RemoveRelayerAuthority");
+            process_remove_relayer_authority(program_id, accounts, relayer)
+        }
+        BridgeInstruction::SetChainFee {
+            chain,
+            fee_basis_points,
+        } => {
+            msg!("Instruction: SetChainFee");
+            process_set_chain_fee(program_id, accounts, chain, fee_basis_points)
+        }
+        BridgeInstruction::ClaimValidatorFeesSecp256k1 { validator, signature } => {
+            msg!("Instruction: ClaimValidatorFeesSecp256k1");
+            process_claim_validator_fees(program_id, accounts, Some((validator, signature)))
+        }
     }
 }
 
@@ -180,6 +262,14 @@ fn process_initialize(
         nonce: 0,
         validators,
         validator_threshold,
+        supported_chains: BridgeConfig::ALL_CHAINS,
+        transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+        validator_fee_bps: 0,
+        fees_per_validator: 0,
+        relayer_authorities: Vec::new(),
+        max_total_locked: 0,
+        signature_scheme: SIGNATURE_SCHEME_ED25519,
+        chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
     };
 
     bridge_config
@@ -217,6 +307,8 @@ fn process_lock_tokens(
     let system_program = next_account_info(account_info_iter)?;
     let _rent_sysvar = next_account_info(account_info_iter)?;
     let _clock_sysvar = next_account_info(account_info_iter)?;
+    let allowed_mints_account = next_account_info(account_info_iter)?;
+    let mint_fees_account = next_account_info(account_info_iter)?;
 
     let rent = Rent::get()?;
     let clock = Clock::get()?;
@@ -231,7 +323,9 @@ fn process_lock_tokens(
         return Err(BridgeError::IncorrectOwner.into());
     }
 
-    let mut bridge_config = BridgeConfig::try_from_slice(&bridge_config_account.data.borrow())
+    token::check_token_program(token_program)?;
+
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     if bridge_config.is_paused {
@@ -244,18 +338,55 @@ fn process_lock_tokens(
         return Err(BridgeError::InsufficientFunds.into());
     }
 
-    if destination_chain == 0 || destination_chain > 10 {
+    if destination_chain == 0 || destination_chain > BridgeConfig::MAX_CHAIN_ID {
         msg!("Invalid destination chain: {}", destination_chain);
         return Err(BridgeError::InvalidDestination.into());
     }
 
-    let fee = amount
-        .checked_mul(bridge_config.fee_basis_points as u64)
+    if !bridge_config.supports_chain(destination_chain) {
+        msg!("Destination chain {} is not enabled for this bridge", destination_chain);
+        return Err(BridgeError::InvalidDestination.into());
+    }
+
+    if !is_valid_destination(destination_chain, &destination_address) {
+        msg!("Destination address is not a valid recipient on chain {}", destination_chain);
+        return Err(BridgeError::InvalidDestination.into());
+    }
+
+    let (allowed_mints_pda, _bump) = AllowedMints::find_address(program_id, bridge_config_account.key);
+    if allowed_mints_account.key != &allowed_mints_pda {
+        msg!("Invalid allowed mints PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // No allowlist account yet means no mint has been approved
+    let allowed_mints = if allowed_mints_account.owner == program_id {
+        AllowedMints::deserialize(&mut &allowed_mints_account.data.borrow()[..])
+            .map_err(|_| ProgramError::InvalidAccountData)?
+    } else {
+        AllowedMints::default()
+    };
+
+    if !allowed_mints.contains(token_mint_account.key) {
+        msg!("Token mint {} is not on the allowlist", token_mint_account.key);
+        return Err(BridgeError::UnsupportedMint.into());
+    }
+
+    // A Token-2022 transfer fee is withheld from what reaches the vault, so the
+    // bridge fee and the locked amount come out of what is actually received
+    let transfer_fee = token::transfer_fee(token_mint_account, token_program, amount)?;
+    let received = amount.checked_sub(transfer_fee).ok_or(BridgeError::Overflow)?;
+    if transfer_fee > 0 {
+        msg!("Token transfer fee: {}, Received by vault: {}", transfer_fee, received);
+    }
+
+    let fee = received
+        .checked_mul(bridge_config.lock_fee_basis_points(destination_chain) as u64)
         .ok_or(BridgeError::Overflow)?
         .checked_div(10000)
         .ok_or(BridgeError::Overflow)?;
 
-    let net_amount = amount.checked_sub(fee).ok_or(BridgeError::Overflow)?;
+    let net_amount = received.checked_sub(fee).ok_or(BridgeError::Overflow)?;
 
     msg!(
         "Lock amount: {}, Fee: {}, Net amount: {}",
@@ -264,9 +395,24 @@ fn process_lock_tokens(
         net_amount
     );
 
-    let user_token_data = user_token_account.try_borrow_data()?;
-    let user_token =
-        TokenAccount::unpack(&user_token_data).map_err(|_| ProgramError::InvalidAccountData)?;
+    let total_locked = bridge_config
+        .total_locked
+        .checked_add(net_amount)
+        .ok_or(BridgeError::Overflow)?;
+    if bridge_config.exceeds_lock_cap(total_locked) {
+        msg!(
+            "Lock would raise total_locked to {}, over the cap of {}",
+            total_locked,
+            bridge_config.max_total_locked
+        );
+        return Err(BridgeError::VaultCapExceeded.into());
+    }
+
+    // The validators' part of the fee stays in the vault, in this mint, until each claims it
+    let (fee_per_validator, validator_fees) =
+        bridge_config.validator_fee_split(fee).ok_or(BridgeError::Overflow)?;
+
+    let user_token = token::unpack_account(user_token_account, token_program)?;
 
     if user_token.amount < amount {
         msg!(
@@ -282,16 +428,11 @@ fn process_lock_tokens(
         return Err(ProgramError::InvalidAccountData.into());
     }
 
-    drop(user_token_data);
-
-    let current_nonce = bridge_config.nonce;
-    bridge_config.nonce = bridge_config
-        .nonce
-        .checked_add(1)
-        .ok_or(BridgeError::Overflow)?;
+    let current_nonce =
+        namespaced_nonce(NONCE_ORIGIN_SOLANA, bridge_config.nonce).ok_or(BridgeError::Overflow)?;
 
     let nonce_bytes = current_nonce.to_le_bytes();
-    let (user_bridge_state_pda, _user_bridge_bump) = Pubkey::find_program_address(
+    let (user_bridge_state_pda, user_bridge_bump) = Pubkey::find_program_address(
         &[b"bridge", user_account.key.as_ref(), &nonce_bytes],
         program_id,
     );
@@ -301,12 +442,36 @@ fn process_lock_tokens(
         return Err(BridgeError::InvalidPDA.into());
     }
 
+    // Take the tokens before using up the nonce or creating any state. A failure anywhere
+    // in the instruction rolls back every write, this CPI's included, so the order is
+    // not needed for atomicity; it keeps every later write behind a completed transfer.
+    msg!("Transferring {} tokens from user to vault", amount);
+
+    log_compute("lock transfer start");
+    token::transfer(
+        token_program,
+        user_token_account,
+        vault_token_account,
+        user_account,
+        Some(token_mint_account),
+        amount,
+        &[],
+    )?;
+    log_compute("lock transfer end");
+
+    msg!("Token transfer successful");
+
+    bridge_config.nonce = bridge_config
+        .nonce
+        .checked_add(1)
+        .ok_or(BridgeError::Overflow)?;
+
     let space = UserBridgeState::LEN;
     let rent_lamports = rent.minimum_balance(space);
 
     msg!("Creating user bridge state account");
 
-    invoke(
+    invoke_signed(
         &system_instruction::create_account(
             user_account.key,
             user_bridge_state_account.key,
@@ -319,8 +484,14 @@ fn process_lock_tokens(
             user_bridge_state_account.clone(),
             system_program.clone(),
         ],
+        &[&[b"bridge", user_account.key.as_ref(), &nonce_bytes, &[user_bridge_bump]]],
     )?;
 
+    let expiry = clock
+        .unix_timestamp
+        .checked_add(bridge_config.transfer_ttl)
+        .ok_or(BridgeError::Overflow)?;
+
     let user_bridge_state = UserBridgeState {
         user: *user_account.key,
         locked_amount: net_amount,
@@ -331,6 +502,8 @@ fn process_lock_tokens(
         nonce: current_nonce,
         timestamp: clock.unix_timestamp,
         unlocked: false,
+        expiry,
+        remaining_amount: net_amount,
     };
 
     user_bridge_state
@@ -339,44 +512,32 @@ fn process_lock_tokens(
 
     msg!("User bridge state created with nonce: {}", current_nonce);
 
-    msg!("Transferring {} tokens from user to vault", amount);
-
-    let transfer_instruction = spl_token::instruction::transfer(
-        token_program.key,
-        user_token_account.key,
-        vault_token_account.key,
-        user_account.key,
-        &[],
-        amount,
-    )?;
-
-    invoke(
-        &transfer_instruction,
-        &[
-            user_token_account.clone(),
-            vault_token_account.clone(),
-            user_account.clone(),
-            token_program.clone(),
-        ],
-    )?;
-
-    msg!("Token transfer successful");
-
-    bridge_config.total_locked = bridge_config
-        .total_locked
-        .checked_add(net_amount)
-        .ok_or(BridgeError::Overflow)?;
+    bridge_config.total_locked = total_locked;
 
     bridge_config
-        .serialize(&mut &mut bridge_config_account.data.borrow_mut()[..])
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
+    if validator_fees > 0 {
+        let mut mint_fees =
+            load_mint_fees(program_id, bridge_config_account, mint_fees_account, token_mint_account.key)?;
+        mint_fees.fees_per_validator = mint_fees
+            .fees_per_validator
+            .checked_add(fee_per_validator)
+            .ok_or(BridgeError::Overflow)?;
+        mint_fees
+            .serialize(&mut &mut mint_fees_account.data.borrow_mut()[..])
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        msg!("Validator fees: {} ({} per validator)", validator_fees, fee_per_validator);
+    }
+
     msg!("EVENT: TokensLocked");
     msg!("  user: {}", user_account.key);
     msg!("  token_mint: {}", token_mint_account.key);
     msg!("  amount: {}", net_amount);
     msg!("  destination_chain: {}", destination_chain);
     msg!("  destination_address: {:?}", destination_address);
+    msg!("  expiry: {}", expiry);
     msg!("  nonce: {}", current_nonce);
     msg!("  timestamp: {}", clock.unix_timestamp);
 
@@ -387,6 +548,7 @@ fn process_unlock_tokens(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     nonce: u64,
+    amount: UnlockAmount,
     signatures: Vec<[u8; 64]>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
@@ -399,27 +561,230 @@ fn process_unlock_tokens(
     let user_bridge_state_account = next_account_info(account_info_iter)?;
     let bridge_config_account = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+    let token_mint = next_account_info(account_info_iter).ok();
+
+    let vault = UnlockVault {
+        vault_token_account,
+        vault_pda_account,
+        bridge_config_account,
+        token_program,
+        instructions_sysvar,
+        token_mint,
+    };
+
+    let mut bridge_config = load_unlock_config(program_id, relayer_account, &vault)?;
+
+    unlock_transfer(
+        program_id,
+        &vault,
+        &mut bridge_config,
+        UnlockItem {
+            user_account,
+            user_token_account,
+            user_bridge_state_account,
+            nonce,
+            amount,
+            signatures: &signatures,
+        },
+    )?;
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(())
+}
+
+/// Unlock several Ethereum→Solana transfers against one config and vault.
+///
+/// Any failing item fails the instruction, so the batch is all-or-nothing.
+fn process_unlock_tokens_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    items: Vec<(u64, u64, Vec<[u8; 64]>)>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let relayer_account = next_account_info(account_info_iter)?;
+    let vault_token_account = next_account_info(account_info_iter)?;
+    let vault_pda_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    if items.is_empty() || items.len() > MAX_UNLOCK_BATCH {
+        msg!(
+            "Invalid batch size. Max: {}, Got: {}",
+            MAX_UNLOCK_BATCH,
+            items.len()
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // The mint, when passed, follows the last item's accounts
+    let token_mint = accounts.get(BATCH_HEADER_ACCOUNTS + BATCH_ITEM_ACCOUNTS * items.len());
+
+    let vault = UnlockVault {
+        vault_token_account,
+        vault_pda_account,
+        bridge_config_account,
+        token_program,
+        instructions_sysvar,
+        token_mint,
+    };
+
+    let mut bridge_config = load_unlock_config(program_id, relayer_account, &vault)?;
+
+    for (index, (nonce, amount, signatures)) in items.iter().enumerate() {
+        msg!("Batch item {}: nonce {}", index, nonce);
+
+        let user_account = next_account_info(account_info_iter)?;
+        let user_token_account = next_account_info(account_info_iter)?;
+        let user_bridge_state_account = next_account_info(account_info_iter)?;
+
+        unlock_transfer(
+            program_id,
+            &vault,
+            &mut bridge_config,
+            UnlockItem {
+                user_account,
+                user_token_account,
+                user_bridge_state_account,
+                nonce: *nonce,
+                amount: UnlockAmount::Stated(*amount),
+                signatures,
+            },
+        )?;
+    }
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
 
+    msg!("Batch unlocked {} transfers", items.len());
+
+    Ok(())
+}
+
+/// Accounts before the per-item ones in `UnlockTokensBatch`
+const BATCH_HEADER_ACCOUNTS: usize = 6;
+/// `(user, user_token_account, user_bridge_state)` per `UnlockTokensBatch` item
+const BATCH_ITEM_ACCOUNTS: usize = 3;
+
+/// Accounts shared by every transfer in an unlock
+struct UnlockVault<'a, 'b> {
+    vault_token_account: &'a AccountInfo<'b>,
+    vault_pda_account: &'a AccountInfo<'b>,
+    bridge_config_account: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+    #[cfg_attr(feature = "in-program-ed25519", allow(dead_code))]
+    instructions_sysvar: &'a AccountInfo<'b>,
+    /// Optional trailing account; Token-2022 vaults need it to transfer
+    token_mint: Option<&'a AccountInfo<'b>>,
+}
+
+/// How much of a pending lock an unlock releases
+#[derive(Clone, Copy)]
+enum UnlockAmount {
+    /// Whatever is still locked, signed over the full `locked_amount`
+    Remaining,
+    /// Like `Remaining`, but the caller states `locked_amount` and it is checked (batched unlocks)
+    Stated(u64),
+    /// One tranche, signed over `create_partial_unlock_message`
+    Partial(u64),
+}
+
+/// One pending transfer to release
+struct UnlockItem<'a, 'b, 'c> {
+    user_account: &'a AccountInfo<'b>,
+    user_token_account: &'a AccountInfo<'b>,
+    user_bridge_state_account: &'a AccountInfo<'b>,
+    nonce: u64,
+    amount: UnlockAmount,
+    signatures: &'c [[u8; 64]],
+}
+
+/// Check the relayer and vault PDA, returning the bridge config to update
+fn load_unlock_config(
+    program_id: &Pubkey,
+    relayer_account: &AccountInfo,
+    vault: &UnlockVault,
+) -> Result<BridgeConfig, ProgramError> {
     if !relayer_account.is_signer {
         msg!("Relayer must sign the unlock transaction");
         return Err(BridgeError::MissingRequiredSignature.into());
     }
 
-    if bridge_config_account.owner != program_id {
+    if vault.bridge_config_account.owner != program_id {
         msg!("Bridge config has incorrect owner");
         return Err(BridgeError::IncorrectOwner.into());
     }
 
-    let mut bridge_config = BridgeConfig::try_from_slice(&bridge_config_account.data.borrow())
+    let bridge_config = BridgeConfig::load(&vault.bridge_config_account.data.borrow())
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
-    if relayer_account.key != &bridge_config.relayer_authority {
+    if !bridge_config.is_relayer(relayer_account.key) {
+        msg!("Relayer {} is not authorized", relayer_account.key);
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    let (expected_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.bridge_config_account.key.as_ref()],
+        program_id,
+    );
+
+    if vault.vault_pda_account.key != &expected_vault_pda {
+        msg!("Invalid vault PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    if vault_bump != bridge_config.vault_pda_bump {
+        msg!("Vault PDA bump mismatch");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    token::check_token_program(vault.token_program)?;
+
+    Ok(bridge_config)
+}
+
+/// Verify validator signatures for one pending lock and release it from the
+/// vault. `bridge_config.total_locked` is updated in memory; the caller writes it.
+fn unlock_transfer(
+    program_id: &Pubkey,
+    vault: &UnlockVault,
+    bridge_config: &mut BridgeConfig,
+    item: UnlockItem,
+) -> ProgramResult {
+    let UnlockItem {
+        user_account,
+        user_token_account,
+        user_bridge_state_account,
+        nonce,
+        amount,
+        signatures,
+    } = item;
+
+    // Every signature costs a verification or key recovery, so bound the work up front
+    if signatures.len() > BridgeConfig::MAX_VALIDATORS {
         msg!(
-            "Relayer is not authorized. Expected: {}, Got: {}",
-            bridge_config.relayer_authority,
-            relayer_account.key
+            "Too many signatures. Max: {}, Got: {}",
+            BridgeConfig::MAX_VALIDATORS,
+            signatures.len()
         );
-        return Err(BridgeError::Unauthorized.into());
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // Unlocks release Solana locks; a nonce from another chain's namespace names a
+    // transfer that never had a lock here, whatever record it is presented with
+    if nonce_origin(nonce) != NONCE_ORIGIN_SOLANA {
+        msg!(
+            "Nonce {:#x} was assigned by chain {}; only Solana lock nonces can be unlocked",
+            nonce,
+            nonce_origin(nonce)
+        );
+        return Err(BridgeError::InvalidNonce.into());
     }
 
     if user_bridge_state_account.owner != program_id {
@@ -455,17 +820,36 @@ fn process_unlock_tokens(
         return Err(BridgeError::Unauthorized.into());
     }
 
-    let (expected_vault_pda, vault_bump) =
-        Pubkey::find_program_address(&[b"vault", bridge_config_account.key.as_ref()], program_id);
+    let clock = Clock::get()?;
+    if user_bridge_state.is_expired(clock.unix_timestamp) {
+        msg!(
+            "Transfer expired at {}, now {}; it can only be refunded",
+            user_bridge_state.expiry,
+            clock.unix_timestamp
+        );
+        return Err(BridgeError::TransferExpired.into());
+    }
 
-    if vault_pda_account.key != &expected_vault_pda {
-        msg!("Invalid vault PDA");
-        return Err(BridgeError::InvalidPDA.into());
+    if let UnlockAmount::Stated(amount) = amount {
+        if amount != user_bridge_state.locked_amount {
+            msg!(
+                "Amount mismatch. Locked: {}, Got: {}",
+                user_bridge_state.locked_amount,
+                amount
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
     }
 
-    if vault_bump != bridge_config.vault_pda_bump {
-        msg!("Vault PDA bump mismatch");
-        return Err(BridgeError::InvalidPDA.into());
+    if let UnlockAmount::Partial(amount) = amount {
+        if amount == 0 || amount > user_bridge_state.remaining_amount {
+            msg!(
+                "Partial unlock of {} exceeds remaining amount {}",
+                amount,
+                user_bridge_state.remaining_amount
+            );
+            return Err(BridgeError::ExceedsRemainingAmount.into());
+        }
     }
 
     if signatures.len() < bridge_config.validator_threshold as usize {
@@ -477,22 +861,45 @@ fn process_unlock_tokens(
         return Err(BridgeError::ThresholdNotMet.into());
     }
 
-    let message_data = create_unlock_message(
-        nonce,
-        user_account.key,
-        user_bridge_state.locked_amount,
-    );
+    let (release_amount, message_data) = match amount {
+        UnlockAmount::Partial(amount) => (
+            amount,
+            create_partial_unlock_message(
+                nonce,
+                user_account.key,
+                &user_bridge_state.token_mint,
+                amount,
+                user_bridge_state.remaining_amount,
+            ),
+        ),
+        UnlockAmount::Remaining | UnlockAmount::Stated(_) => (
+            user_bridge_state.remaining_amount,
+            create_unlock_message(
+                nonce,
+                user_account.key,
+                &user_bridge_state.token_mint,
+                user_bridge_state.locked_amount,
+            ),
+        ),
+    };
 
-    let mut valid_signature_count = 0;
-    for (sig_idx, signature) in signatures.iter().enumerate() {
-        for validator_pubkey in &bridge_config.validators {
-            if verify_ed25519_signature(&message_data, signature, validator_pubkey.as_ref()) {
-                msg!("Valid signature {} from validator {}", sig_idx, validator_pubkey);
-                valid_signature_count += 1;
-                break;
-            }
+    log_compute("signature verification start");
+
+    let valid_signature_count = match bridge_config.signature_scheme {
+        SIGNATURE_SCHEME_ED25519 => count_ed25519_signers(vault, &message_data, signatures, bridge_config)?,
+        SIGNATURE_SCHEME_SECP256K1 => count_secp256k1_signers(
+            &message_data,
+            signatures,
+            &bridge_config.validators,
+            bridge_config.validator_threshold as usize,
+        ),
+        scheme => {
+            msg!("Unknown signature scheme {}", scheme);
+            return Err(ProgramError::InvalidAccountData);
         }
-    }
+    };
+
+    log_compute("signature verification end");
 
     if valid_signature_count < bridge_config.validator_threshold as usize {
         msg!(
@@ -509,41 +916,61 @@ fn process_unlock_tokens(
         signatures.len()
     );
 
-    msg!(
-        "Unlocking {} tokens to user",
-        user_bridge_state.locked_amount
-    );
-
-    let transfer_instruction = spl_token::instruction::transfer(
-        token_program.key,
-        vault_token_account.key,
-        user_token_account.key,
-        vault_pda_account.key,
-        &[],
-        user_bridge_state.locked_amount,
+    // `total_locked` is only bookkeeping; check the vault really holds the release
+    // so an accounting bug fails clearly instead of deep inside the token program.
+    // The vault PDA signs for every token account it owns, so the mint must be the lock's.
+    let vault_token = token::unpack_account_for(
+        vault.vault_token_account,
+        vault.token_program,
+        &user_bridge_state.token_mint,
+        vault.vault_pda_account.key,
+    )?;
+    if vault_token.amount < release_amount {
+        msg!(
+            "Vault holds less than the unlock. Have: {}, Need: {}",
+            vault_token.amount,
+            release_amount
+        );
+        return Err(BridgeError::InsufficientFunds.into());
+    }
+    token::unpack_account_for(
+        user_token_account,
+        vault.token_program,
+        &user_bridge_state.token_mint,
+        &user_bridge_state.user,
     )?;
 
+    msg!("Unlocking {} tokens to user", release_amount);
+
     let vault_seeds = &[
         b"vault",
-        bridge_config_account.key.as_ref(),
+        vault.bridge_config_account.key.as_ref(),
         &[bridge_config.vault_pda_bump],
     ];
 
-    invoke_signed(
-        &transfer_instruction,
-        &[
-            vault_token_account.clone(),
-            user_token_account.clone(),
-            vault_pda_account.clone(),
-            token_program.clone(),
-        ],
+    log_compute("unlock transfer start");
+    token::transfer(
+        vault.token_program,
+        vault.vault_token_account,
+        user_token_account,
+        vault.vault_pda_account,
+        vault.token_mint,
+        release_amount,
         &[vault_seeds],
     )?;
+    log_compute("unlock transfer end");
 
     msg!("Token transfer successful");
 
-    user_bridge_state.unlocked = true;
-    user_bridge_state.status = BridgeStatus::Completed;
+    user_bridge_state.remaining_amount = user_bridge_state
+        .remaining_amount
+        .checked_sub(release_amount)
+        .ok_or(BridgeError::Overflow)?;
+    let completed = user_bridge_state.remaining_amount == 0;
+    if completed {
+        user_bridge_state.unlocked = true;
+        user_bridge_state.status = BridgeStatus::Completed;
+    }
 
     user_bridge_state
         .serialize(&mut &mut user_bridge_state_account.data.borrow_mut()[..])
@@ -551,35 +978,58 @@ fn process_unlock_tokens(
 
     bridge_config.total_locked = bridge_config
         .total_locked
-        .checked_sub(user_bridge_state.locked_amount)
+        .checked_sub(release_amount)
         .ok_or(BridgeError::Overflow)?;
 
-    bridge_config
-        .serialize(&mut &mut bridge_config_account.data.borrow_mut()[..])
-        .map_err(|_| ProgramError::InvalidAccountData)?;
+    // The relayer treats `TokensUnlocked` as delivery, so tranches before the last only log
+    if !completed {
+        msg!("EVENT: TokensPartiallyUnlocked");
+        msg!("  user: {}", user_account.key);
+        msg!("  amount: {}", release_amount);
+        msg!("  remaining: {}", user_bridge_state.remaining_amount);
+        msg!("  nonce: {}", nonce);
+        return Ok(());
+    }
 
     msg!("EVENT: TokensUnlocked");
     msg!("  user: {}", user_account.key);
-    msg!("  amount: {}", user_bridge_state.locked_amount);
+    msg!("  amount: {}", release_amount);
     msg!("  nonce: {}", nonce);
 
+    TokensUnlockedEvent {
+        user: *user_account.key,
+        token_mint: user_bridge_state.token_mint,
+        amount: user_bridge_state.locked_amount,
+        nonce,
+    }
+    .emit();
+
     Ok(())
 }
 
-fn process_update_config(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    new_admin: Option<Pubkey>,
-    new_relayer: Option<Pubkey>,
-    new_fee: Option<u16>,
-) -> ProgramResult {
+/// Return a pending lock to the user once it has expired or `REFUND_TIMEOUT`
+/// has passed, whichever comes first.
+///
+/// Nothing on Solana records that the destination chain already minted, so the
+/// relayer authority must co-sign to attest the transfer was never delivered.
+fn process_refund_lock(program_id: &Pubkey, accounts: &[AccountInfo], nonce: u64) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
-    let admin_account = next_account_info(account_info_iter)?;
+    let user_account = next_account_info(account_info_iter)?;
+    let relayer_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let vault_token_account = next_account_info(account_info_iter)?;
+    let vault_pda_account = next_account_info(account_info_iter)?;
+    let user_bridge_state_account = next_account_info(account_info_iter)?;
     let bridge_config_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let _clock_sysvar = next_account_info(account_info_iter)?;
+    let token_mint = next_account_info(account_info_iter).ok();
 
-    if !admin_account.is_signer {
-        msg!("Admin must sign the update config transaction");
+    let clock = Clock::get()?;
+
+    if !user_account.is_signer || !relayer_account.is_signer {
+        msg!("User and relayer must both sign the refund transaction");
         return Err(BridgeError::MissingRequiredSignature.into());
     }
 
@@ -588,22 +1038,371 @@ fn process_update_config(
         return Err(BridgeError::IncorrectOwner.into());
     }
 
-    let mut bridge_config = BridgeConfig::try_from_slice(&bridge_config_account.data.borrow())
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
-    if admin_account.key != &bridge_config.admin {
-        msg!(
-            "Only current admin can update config. Expected: {}, Got: {}",
-            bridge_config.admin,
-            admin_account.key
-        );
+    if !bridge_config.is_relayer(relayer_account.key) {
+        msg!("Relayer {} is not authorized", relayer_account.key);
         return Err(BridgeError::Unauthorized.into());
     }
 
-    if let Some(new_admin_key) = new_admin {
-        msg!(
-            "Updating admin from {} to {}",
-            bridge_config.admin,
+    if user_bridge_state_account.owner != program_id {
+        msg!("User bridge state has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut user_bridge_state =
+        UserBridgeState::try_from_slice(&user_bridge_state_account.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if user_bridge_state.nonce != nonce {
+        msg!(
+            "Nonce mismatch. Expected: {}, Got: {}",
+            user_bridge_state.nonce,
+            nonce
+        );
+        return Err(BridgeError::InvalidNonce.into());
+    }
+
+    if user_account.key != &user_bridge_state.user {
+        msg!("Only the locking user can request a refund");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    if user_bridge_state.unlocked {
+        msg!("Tokens have already been unlocked");
+        return Err(BridgeError::AlreadyUnlocked.into());
+    }
+
+    if user_bridge_state.status != BridgeStatus::Pending {
+        msg!("Invalid bridge status: {:?}", user_bridge_state.status);
+        return Err(BridgeError::InvalidStatus.into());
+    }
+
+    let refundable_at = user_bridge_state
+        .timestamp
+        .checked_add(UserBridgeState::REFUND_TIMEOUT)
+        .ok_or(BridgeError::Overflow)?
+        .min(user_bridge_state.expiry);
+
+    if clock.unix_timestamp < refundable_at {
+        msg!("Refund not available until {}", refundable_at);
+        return Err(BridgeError::InvalidStatus.into());
+    }
+
+    let (expected_vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config_account.key.as_ref()], program_id);
+
+    if vault_pda_account.key != &expected_vault_pda || vault_bump != bridge_config.vault_pda_bump {
+        msg!("Invalid vault PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // Only the lock's own token may leave the vault, and only back to the locking user
+    token::check_token_program(token_program)?;
+    token::unpack_account_for(
+        vault_token_account,
+        token_program,
+        &user_bridge_state.token_mint,
+        vault_pda_account.key,
+    )?;
+    token::unpack_account_for(
+        user_token_account,
+        token_program,
+        &user_bridge_state.token_mint,
+        &user_bridge_state.user,
+    )?;
+
+    msg!("Refunding {} tokens to user", user_bridge_state.remaining_amount);
+
+    let vault_seeds = &[
+        b"vault",
+        bridge_config_account.key.as_ref(),
+        &[bridge_config.vault_pda_bump],
+    ];
+
+    token::transfer(
+        token_program,
+        vault_token_account,
+        user_token_account,
+        vault_pda_account,
+        token_mint,
+        user_bridge_state.remaining_amount,
+        &[vault_seeds],
+    )?;
+
+    user_bridge_state.status = BridgeStatus::Refunded;
+
+    user_bridge_state
+        .serialize(&mut &mut user_bridge_state_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    bridge_config.total_locked = bridge_config
+        .total_locked
+        .checked_sub(user_bridge_state.remaining_amount)
+        .ok_or(BridgeError::Overflow)?;
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    msg!("EVENT: TokensRefunded");
+    msg!("  user: {}", user_account.key);
+    msg!("  amount: {}", user_bridge_state.remaining_amount);
+    msg!("  nonce: {}", nonce);
+
+    TokensRefundedEvent {
+        user: *user_account.key,
+        token_mint: user_bridge_state.token_mint,
+        amount: user_bridge_state.remaining_amount,
+        nonce,
+    }
+    .emit();
+
+    Ok(())
+}
+
+fn process_cancel_lock(program_id: &Pubkey, accounts: &[AccountInfo], nonce: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let user_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let vault_token_account = next_account_info(account_info_iter)?;
+    let vault_pda_account = next_account_info(account_info_iter)?;
+    let user_bridge_state_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let token_mint = next_account_info(account_info_iter).ok();
+
+    if !admin_account.is_signer {
+        msg!("Admin must sign the cancel transaction");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!("Only admin can cancel a lock");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    if user_bridge_state_account.owner != program_id {
+        msg!("User bridge state has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut user_bridge_state =
+        UserBridgeState::try_from_slice(&user_bridge_state_account.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if user_bridge_state.nonce != nonce {
+        msg!(
+            "Nonce mismatch. Expected: {}, Got: {}",
+            user_bridge_state.nonce,
+            nonce
+        );
+        return Err(BridgeError::InvalidNonce.into());
+    }
+
+    if user_account.key != &user_bridge_state.user {
+        msg!("User does not own this lock");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    if user_bridge_state.unlocked {
+        msg!("Tokens have already been unlocked");
+        return Err(BridgeError::AlreadyUnlocked.into());
+    }
+
+    if user_bridge_state.status != BridgeStatus::Pending {
+        msg!("Invalid bridge status: {:?}", user_bridge_state.status);
+        return Err(BridgeError::InvalidStatus.into());
+    }
+
+    let (expected_vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config_account.key.as_ref()], program_id);
+
+    if vault_pda_account.key != &expected_vault_pda || vault_bump != bridge_config.vault_pda_bump {
+        msg!("Invalid vault PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // Only the lock's own token may leave the vault, and only back to the locking user
+    token::check_token_program(token_program)?;
+    token::unpack_account_for(
+        vault_token_account,
+        token_program,
+        &user_bridge_state.token_mint,
+        vault_pda_account.key,
+    )?;
+    token::unpack_account_for(
+        user_token_account,
+        token_program,
+        &user_bridge_state.token_mint,
+        &user_bridge_state.user,
+    )?;
+
+    msg!("Cancelling lock, returning {} tokens to user", user_bridge_state.remaining_amount);
+
+    let vault_seeds = &[
+        b"vault",
+        bridge_config_account.key.as_ref(),
+        &[bridge_config.vault_pda_bump],
+    ];
+
+    token::transfer(
+        token_program,
+        vault_token_account,
+        user_token_account,
+        vault_pda_account,
+        token_mint,
+        user_bridge_state.remaining_amount,
+        &[vault_seeds],
+    )?;
+
+    user_bridge_state.status = BridgeStatus::Cancelled;
+
+    user_bridge_state
+        .serialize(&mut &mut user_bridge_state_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    bridge_config.total_locked = bridge_config
+        .total_locked
+        .checked_sub(user_bridge_state.remaining_amount)
+        .ok_or(BridgeError::Overflow)?;
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    msg!("EVENT: TokensCancelled");
+    msg!("  user: {}", user_account.key);
+    msg!("  amount: {}", user_bridge_state.remaining_amount);
+    msg!("  nonce: {}", nonce);
+
+    TokensCancelledEvent {
+        user: *user_account.key,
+        token_mint: user_bridge_state.token_mint,
+        amount: user_bridge_state.remaining_amount,
+        nonce,
+    }
+    .emit();
+
+    Ok(())
+}
+
+/// Hand a finished transfer's rent back to the user who paid it at lock time. The
+/// account is drained and zeroed, so the runtime deletes it at the end of the transaction;
+/// a later unlock for the same nonce then fails the owner check.
+fn process_close_bridge_state(program_id: &Pubkey, accounts: &[AccountInfo], nonce: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user_account = next_account_info(account_info_iter)?;
+    let user_bridge_state_account = next_account_info(account_info_iter)?;
+
+    if !user_account.is_signer {
+        msg!("User must sign to close their bridge state");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if user_bridge_state_account.owner != program_id {
+        msg!("User bridge state has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let (expected_state_pda, _) = Pubkey::find_program_address(
+        &[b"bridge", user_account.key.as_ref(), &nonce.to_le_bytes()],
+        program_id,
+    );
+    if user_bridge_state_account.key != &expected_state_pda {
+        msg!("Invalid user bridge state PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let user_bridge_state = UserBridgeState::try_from_slice(&user_bridge_state_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if user_bridge_state.nonce != nonce || user_account.key != &user_bridge_state.user {
+        msg!("Bridge state does not belong to nonce {} of {}", nonce, user_account.key);
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    let finished = match user_bridge_state.status {
+        BridgeStatus::Completed => user_bridge_state.unlocked,
+        BridgeStatus::Refunded | BridgeStatus::Cancelled => true,
+        BridgeStatus::Pending => false,
+    };
+    if !finished {
+        msg!("Bridge state for nonce {} is still {:?}", nonce, user_bridge_state.status);
+        return Err(BridgeError::InvalidStatus.into());
+    }
+
+    let lamports = user_bridge_state_account.lamports();
+    **user_account.try_borrow_mut_lamports()? = user_account
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(BridgeError::Overflow)?;
+    **user_bridge_state_account.try_borrow_mut_lamports()? = 0;
+    user_bridge_state_account.data.borrow_mut().fill(0);
+
+    msg!("EVENT: BridgeStateClosed");
+    msg!("  user: {}", user_account.key);
+    msg!("  nonce: {}", nonce);
+    msg!("  lamports: {}", lamports);
+
+    Ok(())
+}
+
+fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Option<Pubkey>,
+    new_relayer: Option<Pubkey>,
+    new_fee: Option<u16>,
+    new_supported_chains: Option<u16>,
+    new_transfer_ttl: Option<i64>,
+    new_validator_fee_bps: Option<u16>,
+    new_max_total_locked: Option<u64>,
+    new_signature_scheme: Option<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+
+    if !admin_account.is_signer {
+        msg!("Admin must sign the update config transaction");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!(
+            "Only current admin can update config. Expected: {}, Got: {}",
+            bridge_config.admin,
+            admin_account.key
+        );
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    if let Some(new_admin_key) = new_admin {
+        msg!(
+            "Updating admin from {} to {}",
+            bridge_config.admin,
             new_admin_key
         );
         bridge_config.admin = new_admin_key;
@@ -631,15 +1430,263 @@ fn process_update_config(
         bridge_config.fee_basis_points = new_fee_value;
     }
 
-    bridge_config
-        .serialize(&mut &mut bridge_config_account.data.borrow_mut()[..])
+    if let Some(chains) = new_supported_chains {
+        if chains & !BridgeConfig::ALL_CHAINS != 0 {
+            msg!("Supported chains mask {:#06x} has bits outside chains 1-{}", chains, BridgeConfig::MAX_CHAIN_ID);
+            return Err(BridgeError::InvalidDestination.into());
+        }
+        msg!(
+            "Updating supported chains from {:#06x} to {:#06x}",
+            bridge_config.supported_chains,
+            chains
+        );
+        bridge_config.supported_chains = chains;
+    }
+
+    if let Some(ttl) = new_transfer_ttl {
+        if ttl <= 0 {
+            msg!("Transfer TTL must be positive, got {}", ttl);
+            return Err(ProgramError::InvalidArgument);
+        }
+        msg!(
+            "Updating transfer TTL from {}s to {}s",
+            bridge_config.transfer_ttl,
+            ttl
+        );
+        bridge_config.transfer_ttl = ttl;
+    }
+
+    if let Some(validator_fee_bps) = new_validator_fee_bps {
+        if validator_fee_bps > 10000 {
+            msg!("Validator fee share must be <= 10000 basis points of the fee");
+            return Err(BridgeError::InvalidFee.into());
+        }
+        msg!(
+            "Updating validator fee share from {} to {} basis points",
+            bridge_config.validator_fee_bps,
+            validator_fee_bps
+        );
+        bridge_config.validator_fee_bps = validator_fee_bps;
+    }
+
+    // Lowering the cap below what is already locked only stops new locks
+    if let Some(cap) = new_max_total_locked {
+        msg!(
+            "Updating total_locked cap from {} to {} (0 is uncapped)",
+            bridge_config.max_total_locked,
+            cap
+        );
+        bridge_config.max_total_locked = cap;
+    }
+
+    // The validator keys are not rewritten; they must already be keys of the new scheme
+    if let Some(scheme) = new_signature_scheme {
+        if !BridgeConfig::supports_signature_scheme(scheme) {
+            msg!("Unknown signature scheme {}", scheme);
+            return Err(ProgramError::InvalidArgument);
+        }
+        msg!(
+            "Updating signature scheme from {} to {}",
+            bridge_config.signature_scheme,
+            scheme
+        );
+        bridge_config.signature_scheme = scheme;
+    }
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    msg!("Bridge config updated successfully");
+
+    Ok(())
+}
+
+/// Pay a validator everything a mint's `fees_per_validator` has grown by since its last
+/// claim of that mint. The `ValidatorFees` account is created on the first claim, paid
+/// for by the signer.
+///
+/// An Ed25519 validator signs the transaction itself. A secp256k1 validator can't, so
+/// `secp256k1_claim` carries it and its signature over `create_claim_fees_message`,
+/// and whoever submits the claim only pays for it.
+fn process_claim_validator_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    secp256k1_claim: Option<(Pubkey, [u8; 64])>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_account = next_account_info(account_info_iter)?;
+    let validator_token_account = next_account_info(account_info_iter)?;
+    let vault_token_account = next_account_info(account_info_iter)?;
+    let vault_pda_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let mint_fees_account = next_account_info(account_info_iter)?;
+    let validator_fees_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_mint = next_account_info(account_info_iter)?;
+
+    if !payer_account.is_signer {
+        msg!("Claimant must sign the claim transaction");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let validator = match secp256k1_claim {
+        None => *payer_account.key,
+        Some((validator, _)) if bridge_config.signature_scheme == SIGNATURE_SCHEME_SECP256K1 => validator,
+        Some(_) => {
+            msg!("Signed claims are only for secp256k1 validators");
+            return Err(BridgeError::Unauthorized.into());
+        }
+    };
+
+    if !bridge_config.validators.contains(&validator) {
+        msg!("{} is not a bridge validator", validator);
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    let (expected_vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config_account.key.as_ref()], program_id);
+    if vault_pda_account.key != &expected_vault_pda || vault_bump != bridge_config.vault_pda_bump {
+        msg!("Invalid vault PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // Fees are only owed in the mint they were charged in, out of the vault's own account
+    token::check_token_program(token_program)?;
+    token::unpack_account_for(vault_token_account, token_program, token_mint.key, vault_pda_account.key)?;
+    let mint_fees = load_mint_fees(program_id, bridge_config_account, mint_fees_account, token_mint.key)?;
+
+    let (validator_fees_pda, bump) =
+        ValidatorFees::find_address(program_id, bridge_config_account.key, &validator, token_mint.key);
+    if validator_fees_account.key != &validator_fees_pda {
+        msg!("Invalid validator fees PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut validator_fees = if validator_fees_account.owner == &SYSTEM_PROGRAM_ID {
+        let rent = Rent::get()?;
+        msg!("Creating validator fees account");
+
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_account.key,
+                validator_fees_account.key,
+                rent.minimum_balance(ValidatorFees::LEN),
+                ValidatorFees::LEN as u64,
+                program_id,
+            ),
+            &[
+                payer_account.clone(),
+                validator_fees_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                ValidatorFees::SEED,
+                bridge_config_account.key.as_ref(),
+                validator.as_ref(),
+                token_mint.key.as_ref(),
+                &[bump],
+            ]],
+        )?;
+
+        ValidatorFees {
+            validator,
+            mint: *token_mint.key,
+            claimed: 0,
+        }
+    } else if validator_fees_account.owner == program_id {
+        ValidatorFees::try_from_slice(&validator_fees_account.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?
+    } else {
+        msg!("Validator fees account has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    };
+
+    // `claimed` moves on with every claim, so a signature is good for one claim only
+    if let Some((_, signature)) = secp256k1_claim {
+        let message = create_claim_fees_message(
+            bridge_config_account.key,
+            &validator,
+            token_mint.key,
+            validator_token_account.key,
+            validator_fees.claimed,
+        );
+        if recover_eth_address(&message, &signature) != Some(validator.to_bytes()) {
+            msg!("Claim is not signed by validator {}", validator);
+            return Err(BridgeError::Unauthorized.into());
+        }
+    }
+
+    let amount = mint_fees
+        .fees_per_validator
+        .checked_sub(validator_fees.claimed)
+        .ok_or(BridgeError::Overflow)?;
+    if amount == 0 {
+        msg!("Validator {} has nothing to claim", validator);
+        return Err(BridgeError::NoFeesToClaim.into());
+    }
+
+    msg!("Paying {} of {} in validator fees to {}", amount, token_mint.key, validator);
+
+    token::transfer(
+        token_program,
+        vault_token_account,
+        validator_token_account,
+        vault_pda_account,
+        Some(token_mint),
+        amount,
+        &[&[
+            b"vault",
+            bridge_config_account.key.as_ref(),
+            &[bridge_config.vault_pda_bump],
+        ]],
+    )?;
+
+    validator_fees.claimed = mint_fees.fees_per_validator;
+    validator_fees
+        .serialize(&mut &mut validator_fees_account.data.borrow_mut()[..])
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
-    msg!("Bridge config updated successfully");
+    msg!("EVENT: ValidatorFeesClaimed");
+    msg!("  validator: {}", validator);
+    msg!("  mint: {}", token_mint.key);
+    msg!("  amount: {}", amount);
 
     Ok(())
 }
 
+/// The `MintFees` account of `mint`, checked against its PDA
+fn load_mint_fees(
+    program_id: &Pubkey,
+    bridge_config_account: &AccountInfo,
+    mint_fees_account: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<MintFees, ProgramError> {
+    let (mint_fees_pda, _bump) = MintFees::find_address(program_id, bridge_config_account.key, mint);
+    if mint_fees_account.key != &mint_fees_pda {
+        msg!("Invalid mint fees PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    // Mints allowed before fees were kept per mint get theirs from `AddAllowedMint` again
+    if mint_fees_account.owner != program_id {
+        msg!("Mint {} has no fees account; add it to the allowlist again", mint);
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    MintFees::try_from_slice(&mint_fees_account.data.borrow()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
 fn process_pause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -656,7 +1703,7 @@ fn process_pause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult
         return Err(BridgeError::IncorrectOwner.into());
     }
 
-    let mut bridge_config = BridgeConfig::try_from_slice(&bridge_config_account.data.borrow())
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     if admin_account.key != &bridge_config.admin {
@@ -672,7 +1719,7 @@ fn process_pause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult
     bridge_config.is_paused = true;
 
     bridge_config
-        .serialize(&mut &mut bridge_config_account.data.borrow_mut()[..])
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     msg!("Bridge has been paused");
@@ -698,7 +1745,7 @@ fn process_unpause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResu
         return Err(BridgeError::IncorrectOwner.into());
     }
 
-    let mut bridge_config = BridgeConfig::try_from_slice(&bridge_config_account.data.borrow())
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     if admin_account.key != &bridge_config.admin {
@@ -714,7 +1761,7 @@ fn process_unpause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResu
     bridge_config.is_paused = false;
 
     bridge_config
-        .serialize(&mut &mut bridge_config_account.data.borrow_mut()[..])
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
         .map_err(|_| ProgramError::InvalidAccountData)?;
 
     msg!("Bridge has been unpaused");
@@ -723,70 +1770,616 @@ fn process_unpause(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResu
     Ok(())
 }
 
-fn create_unlock_message(nonce: u64, user: &Pubkey, amount: u64) -> [u8; 32] {
+fn process_add_allowed_mint(program_id: &Pubkey, accounts: &[AccountInfo], mint: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let allowed_mints_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let mint_fees_account = next_account_info(account_info_iter)?;
+
+    if !admin_account.is_signer {
+        msg!("Admin must sign the add allowed mint transaction");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!("Only admin can manage the mint allowlist");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    let (allowed_mints_pda, bump) = AllowedMints::find_address(program_id, bridge_config_account.key);
+    if allowed_mints_account.key != &allowed_mints_pda {
+        msg!("Invalid allowed mints PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    let mut allowed_mints = if allowed_mints_account.owner == &SYSTEM_PROGRAM_ID {
+        let rent = Rent::get()?;
+        msg!("Creating allowed mints account");
+
+        invoke_signed(
+            &system_instruction::create_account(
+                admin_account.key,
+                allowed_mints_account.key,
+                rent.minimum_balance(AllowedMints::LEN),
+                AllowedMints::LEN as u64,
+                program_id,
+            ),
+            &[
+                admin_account.clone(),
+                allowed_mints_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[AllowedMints::SEED, bridge_config_account.key.as_ref(), &[bump]]],
+        )?;
+
+        AllowedMints::default()
+    } else if allowed_mints_account.owner == program_id {
+        AllowedMints::deserialize(&mut &allowed_mints_account.data.borrow()[..])
+            .map_err(|_| ProgramError::InvalidAccountData)?
+    } else {
+        msg!("Allowed mints account has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    };
+
+    // Also run for mints already allowed, which is how ones allowed before fees were
+    // kept per mint get their account
+    let (mint_fees_pda, mint_fees_bump) = MintFees::find_address(program_id, bridge_config_account.key, &mint);
+    if mint_fees_account.key != &mint_fees_pda {
+        msg!("Invalid mint fees PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    if mint_fees_account.owner == &SYSTEM_PROGRAM_ID {
+        let rent = Rent::get()?;
+        msg!("Creating fees account for mint {}", mint);
+
+        invoke_signed(
+            &system_instruction::create_account(
+                admin_account.key,
+                mint_fees_account.key,
+                rent.minimum_balance(MintFees::LEN),
+                MintFees::LEN as u64,
+                program_id,
+            ),
+            &[
+                admin_account.clone(),
+                mint_fees_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[
+                MintFees::SEED,
+                bridge_config_account.key.as_ref(),
+                mint.as_ref(),
+                &[mint_fees_bump],
+            ]],
+        )?;
+
+        MintFees {
+            mint,
+            fees_per_validator: 0,
+        }
+        .serialize(&mut &mut mint_fees_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    }
+
+    if allowed_mints.contains(&mint) {
+        msg!("Mint {} is already allowed", mint);
+        return Ok(());
+    }
+
+    if allowed_mints.mints.len() >= AllowedMints::MAX_MINTS {
+        msg!("Mint allowlist is full (max {})", AllowedMints::MAX_MINTS);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    allowed_mints.mints.push(mint);
+
+    allowed_mints
+        .serialize(&mut &mut allowed_mints_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    msg!("Mint {} added to allowlist", mint);
+
+    Ok(())
+}
+
+fn process_remove_allowed_mint(program_id: &Pubkey, accounts: &[AccountInfo], mint: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let allowed_mints_account = next_account_info(account_info_iter)?;
+
+    if !admin_account.is_signer {
+        msg!("Admin must sign the remove allowed mint transaction");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!("Only admin can manage the mint allowlist");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    let (allowed_mints_pda, _bump) = AllowedMints::find_address(program_id, bridge_config_account.key);
+    if allowed_mints_account.key != &allowed_mints_pda {
+        msg!("Invalid allowed mints PDA");
+        return Err(BridgeError::InvalidPDA.into());
+    }
+
+    if allowed_mints_account.owner != program_id {
+        msg!("Allowed mints account has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut allowed_mints = AllowedMints::deserialize(&mut &allowed_mints_account.data.borrow()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if !allowed_mints.contains(&mint) {
+        msg!("Mint {} is not on the allowlist", mint);
+        return Err(BridgeError::UnsupportedMint.into());
+    }
+
+    allowed_mints.mints.retain(|allowed| allowed != &mint);
+
+    allowed_mints
+        .serialize(&mut &mut allowed_mints_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    msg!("Mint {} removed from allowlist", mint);
+
+    Ok(())
+}
+
+/// Check the admin and return the bridge config for a relayer allowlist change
+fn load_relayer_admin_config(
+    program_id: &Pubkey,
+    admin_account: &AccountInfo,
+    bridge_config_account: &AccountInfo,
+) -> Result<BridgeConfig, ProgramError> {
+    if !admin_account.is_signer {
+        msg!("Admin must sign relayer allowlist changes");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!("Only admin can manage the relayer allowlist");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    Ok(bridge_config)
+}
+
+/// Write `bridge_config` back, first growing the account to `BridgeConfig::LEN` if it is
+/// shorter, with `payer` topping up its rent. This is how a pre-allowlist config moves
+/// to the current layout: its relayer allowlist doesn't fit in `LEGACY_LEN`.
+fn write_resized_bridge_config<'a>(
+    bridge_config: &BridgeConfig,
+    bridge_config_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let space = bridge_config_account.data_len().max(BridgeConfig::LEN);
+
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    if bridge_config_account.lamports() < required_lamports {
+        invoke(
+            &system_instruction::transfer(
+                payer.key,
+                bridge_config_account.key,
+                required_lamports - bridge_config_account.lamports(),
+            ),
+            &[payer.clone(), bridge_config_account.clone(), system_program.clone()],
+        )?;
+    }
+
+    if bridge_config_account.data_len() != space {
+        bridge_config_account.realloc(space, true)?;
+    }
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)
+}
+
+fn process_add_relayer_authority(program_id: &Pubkey, accounts: &[AccountInfo], relayer: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let mut bridge_config = load_relayer_admin_config(program_id, admin_account, bridge_config_account)?;
+
+    if bridge_config.is_relayer(&relayer) {
+        msg!("Relayer {} is already authorized", relayer);
+        return Ok(());
+    }
+
+    if bridge_config.relayer_authorities.len() >= BridgeConfig::MAX_RELAYER_AUTHORITIES {
+        msg!("Relayer allowlist is full (max {})", BridgeConfig::MAX_RELAYER_AUTHORITIES);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    bridge_config.relayer_authorities.push(relayer);
+    write_resized_bridge_config(&bridge_config, bridge_config_account, admin_account, system_program)?;
+
+    msg!("Relayer {} authorized", relayer);
+
+    Ok(())
+}
+
+fn process_remove_relayer_authority(program_id: &Pubkey, accounts: &[AccountInfo], relayer: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let mut bridge_config = load_relayer_admin_config(program_id, admin_account, bridge_config_account)?;
+
+    if !bridge_config.relayer_authorities.contains(&relayer) {
+        if relayer == bridge_config.relayer_authority {
+            msg!("Relayer {} is the primary relayer; replace it with UpdateConfig instead", relayer);
+        } else {
+            msg!("Relayer {} is not on the allowlist", relayer);
+        }
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    bridge_config.relayer_authorities.retain(|authorized| authorized != &relayer);
+    write_resized_bridge_config(&bridge_config, bridge_config_account, admin_account, system_program)?;
+
+    msg!("Relayer {} removed from allowlist", relayer);
+
+    Ok(())
+}
+
+fn process_set_chain_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain: u8,
+    fee_basis_points: Option<u16>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin_account = next_account_info(account_info_iter)?;
+    let bridge_config_account = next_account_info(account_info_iter)?;
+
+    if !admin_account.is_signer {
+        msg!("Admin must sign chain fee changes");
+        return Err(BridgeError::MissingRequiredSignature.into());
+    }
+
+    if bridge_config_account.owner != program_id {
+        msg!("Bridge config has incorrect owner");
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+
+    let mut bridge_config = BridgeConfig::load(&bridge_config_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if admin_account.key != &bridge_config.admin {
+        msg!("Only admin can set chain fees");
+        return Err(BridgeError::Unauthorized.into());
+    }
+
+    if chain == 0 || chain > BridgeConfig::MAX_CHAIN_ID {
+        msg!("Chain {} is outside chains 1-{}", chain, BridgeConfig::MAX_CHAIN_ID);
+        return Err(BridgeError::InvalidDestination.into());
+    }
+
+    if fee_basis_points.is_some_and(|fee| fee > 10000) {
+        msg!("Fee basis points must be <= 10000 (100%)");
+        return Err(BridgeError::InvalidFee.into());
+    }
+
+    bridge_config.chain_fee_basis_points[chain as usize] = fee_basis_points.unwrap_or(BridgeConfig::CHAIN_FEE_UNSET);
+    msg!(
+        "Chain {} lock fee is now {} basis points",
+        chain,
+        bridge_config.lock_fee_basis_points(chain)
+    );
+
+    bridge_config
+        .store(&mut bridge_config_account.data.borrow_mut()[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(())
+}
+
+/// Message validators sign to approve an unlock:
+/// `sha256("unlock:" || nonce || user || mint || amount)`
+pub fn create_unlock_message(nonce: u64, user: &Pubkey, mint: &Pubkey, amount: u64) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(b"unlock:");
     hasher.update(nonce.to_le_bytes());
     hasher.update(user.as_ref());
+    hasher.update(mint.as_ref());
+    hasher.update(amount.to_le_bytes());
+    let result = hasher.finalize();
+    let mut message = [0u8; 32];
+    message.copy_from_slice(&result);
+    message
+}
+
+/// Message validators sign to approve one tranche of a transfer:
+/// `sha256("unlock_partial:" || nonce || user || mint || amount || remaining)`.
+///
+/// `remaining` is the amount still locked before this tranche, so a signature
+/// can't be replayed once the tranche has been released.
+pub fn create_partial_unlock_message(
+    nonce: u64,
+    user: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    remaining: u64,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"unlock_partial:");
+    hasher.update(nonce.to_le_bytes());
+    hasher.update(user.as_ref());
+    hasher.update(mint.as_ref());
     hasher.update(amount.to_le_bytes());
+    hasher.update(remaining.to_le_bytes());
+    let result = hasher.finalize();
+    let mut message = [0u8; 32];
+    message.copy_from_slice(&result);
+    message
+}
+
+/// Message a secp256k1 validator signs to claim its fees in `mint` into `destination`:
+/// `sha256("claim_fees:" || bridge_config || validator || mint || destination || claimed)`,
+/// where `claimed` is what its `ValidatorFees` account has recorded so far
+pub fn create_claim_fees_message(
+    bridge_config: &Pubkey,
+    validator: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    claimed: u64,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"claim_fees:");
+    hasher.update(bridge_config.as_ref());
+    hasher.update(validator.as_ref());
+    hasher.update(mint.as_ref());
+    hasher.update(destination.as_ref());
+    hasher.update(claimed.to_le_bytes());
     let result = hasher.finalize();
     let mut message = [0u8; 32];
     message.copy_from_slice(&result);
     message
 }
 
-fn verify_ed25519_signature(message: &[u8; 32], signature: &[u8; 64], pubkey: &[u8]) -> bool {
-    // Validate pubkey length
-    if pubkey.len() != 32 {
-        msg!("Invalid pubkey length: {}", pubkey.len());
-        return false;
+/// Log `label` followed by the remaining compute units. Only emits anything
+/// with the `debug-compute` feature; otherwise the call compiles to nothing.
+#[inline(always)]
+fn log_compute(_label: &str) {
+    #[cfg(feature = "debug-compute")]
+    {
+        msg!("Compute checkpoint: {}", _label);
+        solana_program::log::sol_log_compute_units();
     }
+}
+
+/// Count distinct Ed25519 validators with a valid signature over `message`, checked in
+/// the program or through the precompile depending on `in-program-ed25519`
+#[cfg_attr(feature = "in-program-ed25519", allow(unused_variables))]
+fn count_ed25519_signers(
+    vault: &UnlockVault,
+    message: &[u8; 32],
+    signatures: &[[u8; 64]],
+    bridge_config: &BridgeConfig,
+) -> Result<usize, ProgramError> {
+    #[cfg(feature = "in-program-ed25519")]
+    let valid_signers = count_valid_signers(
+        message,
+        signatures,
+        &bridge_config.validators,
+        bridge_config.validator_threshold as usize,
+    );
+
+    #[cfg(not(feature = "in-program-ed25519"))]
+    let valid_signers = count_precompile_signers(
+        vault.instructions_sysvar,
+        message,
+        signatures,
+        &bridge_config.validators,
+        bridge_config.validator_threshold as usize,
+    )?;
 
-    // Convert to fixed-size array
-    let pubkey_bytes = match <[u8; 32]>::try_from(pubkey) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            msg!("Failed to convert pubkey to [u8; 32]");
-            return false;
+    Ok(valid_signers)
+}
+
+/// Count distinct validators whose Ethereum address is recovered from a signature over
+/// `message`, stopping at `threshold`. Signatures are EIP-2098 compact: `r || s` with
+/// the recovery id in the top bit of `s`, so each costs one `secp256k1_recover`.
+fn count_secp256k1_signers(
+    message: &[u8; 32],
+    signatures: &[[u8; 64]],
+    validators: &[Pubkey],
+    threshold: usize,
+) -> usize {
+    let mut has_signed = vec![false; validators.len()];
+    let mut valid_signers = 0;
+
+    for (sig_idx, signature) in signatures.iter().enumerate() {
+        if valid_signers >= threshold {
+            break;
         }
-    };
 
-    // Validate signature length (already validated by parameter type, but double-check)
-    if signature.len() != 64 {
-        msg!("Invalid signature length: {}", signature.len());
-        return false;
+        let Some(address) = recover_eth_address(message, signature) else {
+            msg!("Signature {} does not recover a secp256k1 key", sig_idx);
+            continue;
+        };
+
+        let signer = validators.iter().enumerate().position(|(validator_idx, validator)| {
+            !has_signed[validator_idx] && validator.to_bytes() == address
+        });
+
+        match signer {
+            Some(validator_idx) => {
+                msg!("Valid signature {} from validator {}", sig_idx, validators[validator_idx]);
+                has_signed[validator_idx] = true;
+                valid_signers += 1;
+            }
+            None => msg!("Signature {} does not recover any remaining validator", sig_idx),
+        }
     }
 
-    // Use ed25519-dalek for signature verification
+    valid_signers
+}
+
+/// Address of the key behind compact `signature` over `message`, left-padded like
+/// `eth_address_to_bytes32`
+fn recover_eth_address(message: &[u8; 32], signature: &[u8; 64]) -> Option<[u8; 32]> {
+    let mut signature = *signature;
+    let recovery_id = signature[32] >> 7;
+    signature[32] &= 0x7f;
+
+    let key = secp256k1_recover(message, recovery_id, &signature).ok()?;
+    let hash = keccak::hash(&key.to_bytes());
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash.0[12..]);
+    Some(eth_address_to_bytes32(&address))
+}
+
+/// Count distinct validators with a valid signature over `message`, stopping as
+/// soon as `threshold` signers are found. Only built with `in-program-ed25519`.
+///
+/// Validator keys are decompressed once and each signature is parsed once. A
+/// validator that already matched is skipped for later signatures, so one
+/// validator can't be counted twice and the search space shrinks as we go.
+///
+/// Worst-case compute: unlock caps `signatures` at `MAX_VALIDATORS` (5), so at
+/// most 5 key decompressions and 5 x 5 = 25 ed25519 verifications, reached only
+/// when no signature matches. A valid set ordered like `validators` needs
+/// `threshold` verifications.
+#[cfg(feature = "in-program-ed25519")]
+fn count_valid_signers(
+    message: &[u8; 32],
+    signatures: &[[u8; 64]],
+    validators: &[Pubkey],
+    threshold: usize,
+) -> usize {
     use ed25519_dalek::{PublicKey, Signature, Verifier};
 
-    // Create PublicKey from bytes
-    let public_key = match PublicKey::from_bytes(&pubkey_bytes) {
-        Ok(pk) => pk,
-        Err(e) => {
-            msg!("Invalid public key: {:?}", e);
-            return false;
+    let validator_keys: Vec<Option<PublicKey>> = validators
+        .iter()
+        .map(|validator| match PublicKey::from_bytes(validator.as_ref()) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                msg!("Invalid validator public key {}: {:?}", validator, e);
+                None
+            }
+        })
+        .collect();
+    let mut has_signed = vec![false; validator_keys.len()];
+    let mut valid_signers = 0;
+
+    for (sig_idx, signature) in signatures.iter().enumerate() {
+        if valid_signers >= threshold {
+            break;
+        }
+
+        let sig = match Signature::from_bytes(signature) {
+            Ok(sig) => sig,
+            Err(e) => {
+                msg!("Invalid signature format at {}: {:?}", sig_idx, e);
+                continue;
+            }
+        };
+
+        let signer = validator_keys.iter().enumerate().position(|(validator_idx, key)| {
+            !has_signed[validator_idx]
+                && key.as_ref().is_some_and(|key| key.verify(message, &sig).is_ok())
+        });
+
+        match signer {
+            Some(validator_idx) => {
+                msg!("Valid signature {} from validator {}", sig_idx, validators[validator_idx]);
+                has_signed[validator_idx] = true;
+                valid_signers += 1;
+            }
+            None => msg!("Signature {} does not match any remaining validator", sig_idx),
         }
-    };
+    }
+
+    valid_signers
+}
 
-    // Create Signature from bytes
-    let sig = match Signature::from_bytes(signature) {
-        Ok(s) => s,
-        Err(e) => {
-            msg!("Invalid signature format: {:?}", e);
-            return false;
+/// Count distinct validators whose signature over `message` was checked by an
+/// Ed25519Program instruction earlier in this transaction, stopping at `threshold`.
+///
+/// The runtime fails the whole transaction if any precompile check fails, so a
+/// signature listed there is valid; here we only match it against `signatures`
+/// and the configured validator set. No curve arithmetic runs in the program.
+#[cfg(not(feature = "in-program-ed25519"))]
+fn count_precompile_signers(
+    instructions_sysvar: &AccountInfo,
+    message: &[u8; 32],
+    signatures: &[[u8; 64]],
+    validators: &[Pubkey],
+    threshold: usize,
+) -> Result<usize, ProgramError> {
+    // Also rejects an account that isn't the instructions sysvar
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+
+    let mut verified = Vec::new();
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if instruction.program_id == ed25519_program::id() {
+            verified.extend(crate::instruction::parse_ed25519_instruction(&instruction.data, message));
         }
-    };
+    }
 
-    // Verify the signature
-    match public_key.verify(message, &sig) {
-        Ok(()) => {
-            msg!("Signature verification successful");
-            true
+    let mut has_signed = vec![false; validators.len()];
+    let mut valid_signers = 0;
+
+    for (sig_idx, signature) in signatures.iter().enumerate() {
+        if valid_signers >= threshold {
+            break;
         }
-        Err(e) => {
-            msg!("Signature verification failed: {:?}", e);
-            false
+
+        let signer = validators.iter().enumerate().position(|(validator_idx, validator)| {
+            !has_signed[validator_idx]
+                && verified.iter().any(|(key, sig)| key == validator && sig == signature)
+        });
+
+        match signer {
+            Some(validator_idx) => {
+                msg!("Valid signature {} from validator {}", sig_idx, validators[validator_idx]);
+                has_signed[validator_idx] = true;
+                valid_signers += 1;
+            }
+            None => msg!("Signature {} has no matching Ed25519 instruction from a remaining validator", sig_idx),
         }
     }
+
+    Ok(valid_signers)
 }
@@ -8,8 +8,13 @@ pub enum BridgeStatus {
     Pending = 0,
     Completed = 1,
     Cancelled = 2,
+    /// Returned to the user by `RefundLock` after `UserBridgeState::REFUND_TIMEOUT`
+    Refunded = 3,
 }
 
+/// Stored as plain Borsh with no version tag, in an account that may be longer than the
+/// serialized config. Read and write it with `load` and `store`, which also handle
+/// accounts still in the pre-allowlist layout.
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
 pub struct BridgeConfig {
     pub admin: Pubkey,
@@ -21,12 +26,245 @@ pub struct BridgeConfig {
     pub nonce: u64,
     pub validators: Vec<Pubkey>,
     pub validator_threshold: u8,
+    /// Bit `i` set means locks to destination chain `i` are accepted
+    pub supported_chains: u16,
+    /// Seconds a new lock stays unlockable; stamped onto it as `UserBridgeState::expiry`
+    pub transfer_ttl: i64,
+    /// Share of each lock fee, in basis points of the fee, paid out to validators
+    pub validator_fee_bps: u16,
+    /// Validator fees owed to each validator before fees were kept per mint. Locks now
+    /// accrue into the mint's `MintFees` instead, so this no longer grows.
+    pub fees_per_validator: u64,
+    /// Relayers authorized alongside `relayer_authority`, managed with
+    /// `AddRelayerAuthority` / `RemoveRelayerAuthority`. Empty on configs from before
+    /// the allowlist, which leaves `relayer_authority` as the only relayer.
+    pub relayer_authorities: Vec<Pubkey>,
+    /// Most `total_locked` may reach; a lock that would pass it fails with
+    /// `VaultCapExceeded`. 0 leaves the bridge uncapped.
+    pub max_total_locked: u64,
+    /// How `validators` sign unlocks: `SIGNATURE_SCHEME_ED25519` (the default) or
+    /// `SIGNATURE_SCHEME_SECP256K1`, under which each validator entry is an Ethereum
+    /// address left-padded like `eth_address_to_bytes32`
+    pub signature_scheme: u8,
+    /// Lock fee for each destination chain, indexed by chain id, replacing
+    /// `fee_basis_points` for that chain. `CHAIN_FEE_UNSET` falls back to it.
+    pub chain_fee_basis_points: [u16; Self::CHAIN_FEE_SLOTS],
 }
 
 impl BridgeConfig {
-    pub const LEN: usize = 256;
+    pub const LEN: usize = 272 + 4 + Self::MAX_RELAYER_AUTHORITIES * 32 + 8 + 1 + 2 * Self::CHAIN_FEE_SLOTS;
+    /// Size `Initialize` gave configs before `relayer_authorities` was added. Accounts of
+    /// exactly this size hold that layout: the fields up to `fees_per_validator`.
+    pub const LEGACY_LEN: usize = 272;
     pub const DISCRIMINATOR: &'static [u8] = b"bridgecfg";
     pub const MAX_VALIDATORS: usize = 5;
+    /// Most relayers `relayer_authorities` holds besides `relayer_authority`
+    pub const MAX_RELAYER_AUTHORITIES: usize = 3;
+    pub const MAX_CHAIN_ID: u8 = 10;
+    /// Every destination chain id from 1 to `MAX_CHAIN_ID`
+    pub const ALL_CHAINS: u16 = ((1u16 << (Self::MAX_CHAIN_ID + 1)) - 1) & !1;
+    /// One `chain_fee_basis_points` entry per chain id up to `MAX_CHAIN_ID`; entry 0 is unused
+    pub const CHAIN_FEE_SLOTS: usize = Self::MAX_CHAIN_ID as usize + 1;
+    /// `chain_fee_basis_points` entry of a chain charged the base `fee_basis_points`
+    pub const CHAIN_FEE_UNSET: u16 = u16::MAX;
+    /// `transfer_ttl` set by `Initialize`
+    pub const DEFAULT_TRANSFER_TTL: i64 = UserBridgeState::REFUND_TIMEOUT;
+
+    /// Read the config from its account's data. Trailing bytes past the config are
+    /// ignored, and a `LEGACY_LEN` account is decoded as the pre-allowlist layout, with
+    /// its `relayer_authority` as the only relayer and later settings at their defaults.
+    pub fn load(data: &[u8]) -> borsh::io::Result<Self> {
+        if data.len() == Self::LEGACY_LEN {
+            return LegacyBridgeConfig::deserialize(&mut &data[..]).map(Self::from);
+        }
+        Self::deserialize(&mut &data[..])
+    }
+
+    /// Write the config back into the account data `load` read it from. A `LEGACY_LEN`
+    /// account keeps the pre-allowlist layout, so it can only take settings that layout
+    /// has room for until it is grown to `LEN`.
+    pub fn store(&self, data: &mut [u8]) -> borsh::io::Result<()> {
+        if data.len() != Self::LEGACY_LEN {
+            return self.serialize(&mut &mut data[..]);
+        }
+
+        if !self.relayer_authorities.is_empty()
+            || self.max_total_locked != 0
+            || self.signature_scheme != SIGNATURE_SCHEME_ED25519
+            || self.chain_fee_basis_points != [Self::CHAIN_FEE_UNSET; Self::CHAIN_FEE_SLOTS]
+        {
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                "setting does not fit the pre-allowlist config layout",
+            ));
+        }
+
+        LegacyBridgeConfig {
+            admin: self.admin,
+            vault_pda_bump: self.vault_pda_bump,
+            relayer_authority: self.relayer_authority,
+            fee_basis_points: self.fee_basis_points,
+            is_paused: self.is_paused,
+            total_locked: self.total_locked,
+            nonce: self.nonce,
+            validators: self.validators.clone(),
+            validator_threshold: self.validator_threshold,
+            supported_chains: self.supported_chains,
+            transfer_ttl: self.transfer_ttl,
+            validator_fee_bps: self.validator_fee_bps,
+            fees_per_validator: self.fees_per_validator,
+        }
+        .serialize(&mut &mut data[..])
+    }
+
+    pub fn supports_chain(&self, chain: u8) -> bool {
+        chain != 0 && chain <= Self::MAX_CHAIN_ID && self.supported_chains & (1u16 << chain) != 0
+    }
+
+    /// Fee in basis points charged on locks to `destination_chain`
+    pub fn lock_fee_basis_points(&self, destination_chain: u8) -> u16 {
+        match self.chain_fee_basis_points.get(destination_chain as usize) {
+            Some(&fee) if fee != Self::CHAIN_FEE_UNSET => fee,
+            _ => self.fee_basis_points,
+        }
+    }
+
+    /// Whether unlocks can verify signatures under `scheme`
+    pub fn supports_signature_scheme(scheme: u8) -> bool {
+        scheme == SIGNATURE_SCHEME_ED25519 || scheme == SIGNATURE_SCHEME_SECP256K1
+    }
+
+    /// Whether `total_locked` reaching `total` would pass `max_total_locked`
+    pub fn exceeds_lock_cap(&self, total: u64) -> bool {
+        self.max_total_locked != 0 && total > self.max_total_locked
+    }
+
+    /// Whether `key` may act as the relayer: `relayer_authority` or any key on the allowlist
+    pub fn is_relayer(&self, key: &Pubkey) -> bool {
+        key == &self.relayer_authority || self.relayer_authorities.contains(key)
+    }
+
+    /// Validators' share of a lock `fee`, split evenly: `(per validator, total paid out)`.
+    /// The rounding remainder stays with the protocol.
+    pub fn validator_fee_split(&self, fee: u64) -> Option<(u64, u64)> {
+        if self.validators.is_empty() {
+            return Some((0, 0));
+        }
+        let share = fee.checked_mul(self.validator_fee_bps as u64)? / 10000;
+        let per_validator = share / self.validators.len() as u64;
+        Some((per_validator, per_validator.checked_mul(self.validators.len() as u64)?))
+    }
+}
+
+/// `BridgeConfig` as laid out before `relayer_authorities` was added
+#[derive(BorshDeserialize, BorshSerialize)]
+struct LegacyBridgeConfig {
+    admin: Pubkey,
+    vault_pda_bump: u8,
+    relayer_authority: Pubkey,
+    fee_basis_points: u16,
+    is_paused: bool,
+    total_locked: u64,
+    nonce: u64,
+    validators: Vec<Pubkey>,
+    validator_threshold: u8,
+    supported_chains: u16,
+    transfer_ttl: i64,
+    validator_fee_bps: u16,
+    fees_per_validator: u64,
+}
+
+impl From<LegacyBridgeConfig> for BridgeConfig {
+    fn from(legacy: LegacyBridgeConfig) -> Self {
+        Self {
+            admin: legacy.admin,
+            vault_pda_bump: legacy.vault_pda_bump,
+            relayer_authority: legacy.relayer_authority,
+            fee_basis_points: legacy.fee_basis_points,
+            is_paused: legacy.is_paused,
+            total_locked: legacy.total_locked,
+            nonce: legacy.nonce,
+            validators: legacy.validators,
+            validator_threshold: legacy.validator_threshold,
+            supported_chains: legacy.supported_chains,
+            transfer_ttl: legacy.transfer_ttl,
+            validator_fee_bps: legacy.validator_fee_bps,
+            fees_per_validator: legacy.fees_per_validator,
+            relayer_authorities: Vec::new(),
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [Self::CHAIN_FEE_UNSET; Self::CHAIN_FEE_SLOTS],
+        }
+    }
+}
+
+/// `BridgeConfig::signature_scheme` for validators signing with Ed25519 keys
+pub const SIGNATURE_SCHEME_ED25519: u8 = 0;
+/// `BridgeConfig::signature_scheme` for validators signing with secp256k1 (Ethereum) keys,
+/// recovered in the program
+pub const SIGNATURE_SCHEME_SECP256K1: u8 = 1;
+
+/// Running total of validator fees owed to each validator in one mint, in a PDA
+/// derived from the bridge config and the mint. `AddAllowedMint` creates it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct MintFees {
+    pub mint: Pubkey,
+    pub fees_per_validator: u64,
+}
+
+impl MintFees {
+    pub const LEN: usize = 32 + 8;
+    pub const SEED: &'static [u8] = b"mint_fees";
+
+    pub fn find_address(program_id: &Pubkey, bridge_config: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, bridge_config.as_ref(), mint.as_ref()], program_id)
+    }
+}
+
+/// How much of a mint's `MintFees::fees_per_validator` one validator has claimed,
+/// in a PDA derived from the bridge config, the validator's key and the mint
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct ValidatorFees {
+    pub validator: Pubkey,
+    pub mint: Pubkey,
+    pub claimed: u64,
+}
+
+impl ValidatorFees {
+    pub const LEN: usize = 32 + 32 + 8;
+    pub const SEED: &'static [u8] = b"validator_fees";
+
+    pub fn find_address(
+        program_id: &Pubkey,
+        bridge_config: &Pubkey,
+        validator: &Pubkey,
+        mint: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, bridge_config.as_ref(), validator.as_ref(), mint.as_ref()],
+            program_id,
+        )
+    }
+}
+
+/// Mints `LockTokens` accepts, stored in a PDA derived from the bridge config
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct AllowedMints {
+    pub mints: Vec<Pubkey>,
+}
+
+impl AllowedMints {
+    pub const MAX_MINTS: usize = 8;
+    pub const LEN: usize = 4 + Self::MAX_MINTS * 32;
+    pub const SEED: &'static [u8] = b"allowed_mints";
+
+    pub fn find_address(program_id: &Pubkey, bridge_config: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, bridge_config.as_ref()], program_id)
+    }
+
+    pub fn contains(&self, mint: &Pubkey) -> bool {
+        self.mints.contains(mint)
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -41,11 +279,60 @@ pub struct UserBridgeState {
     pub nonce: u64,
     pub timestamp: i64,
     pub unlocked: bool,
+    /// Unix time after which `UnlockTokens` rejects this transfer and it can only be refunded
+    pub expiry: i64,
+    /// Part of `locked_amount` not yet released by `UnlockTokensPartial`; the transfer
+    /// completes when it reaches zero
+    pub remaining_amount: u64,
 }
 
 impl UserBridgeState {
-    pub const LEN: usize = 131;
+    pub const LEN: usize = 139;
     pub const DISCRIMINATOR: &'static [u8] = b"userbridge";
+    /// Seconds after locking before a still-pending transfer can be refunded,
+    /// unless it expires sooner
+    pub const REFUND_TIMEOUT: i64 = 7 * 24 * 60 * 60;
+
+    /// Whether signatures for this transfer are no longer accepted at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expiry
+    }
+}
+
+/// Transfer nonces are namespaced by the chain the transfer started on, so a nonce
+/// assigned on one chain can never name a transfer from another. The top byte holds the
+/// origin chain id: `NONCE_ORIGIN_SOLANA` for locks made by this program, otherwise the
+/// ids used for `destination_chain`. The low 56 bits are the origin's own sequence number.
+pub const NONCE_ORIGIN_SHIFT: u32 = 56;
+/// Origin id of nonces assigned by `LockTokens`. Zero, so those nonces equal the plain
+/// `BridgeConfig::nonce` sequence and locks made before namespacing keep their nonce.
+pub const NONCE_ORIGIN_SOLANA: u8 = 0;
+/// Largest sequence number a namespaced nonce can carry
+pub const NONCE_SEQUENCE_MAX: u64 = (1 << NONCE_ORIGIN_SHIFT) - 1;
+
+/// Nonce for the `sequence`-th transfer from `origin_chain`, or `None` once the
+/// sequence no longer fits below the origin byte
+pub fn namespaced_nonce(origin_chain: u8, sequence: u64) -> Option<u64> {
+    (sequence <= NONCE_SEQUENCE_MAX).then(|| (origin_chain as u64) << NONCE_ORIGIN_SHIFT | sequence)
+}
+
+/// Chain a namespaced nonce was assigned by
+pub fn nonce_origin(nonce: u64) -> u8 {
+    (nonce >> NONCE_ORIGIN_SHIFT) as u8
+}
+
+/// `destination_chain` id of Ethereum
+pub const CHAIN_ETHEREUM: u8 = 1;
+
+/// Whether tokens sent to `destination_address` on `destination_chain` can arrive. An
+/// all-zero address never can; on Ethereum it must also be a left-padded 20-byte address
+/// (see `eth_address_to_bytes32`) other than the zero address.
+pub fn is_valid_destination(destination_chain: u8, destination_address: &[u8; 32]) -> bool {
+    if destination_chain == CHAIN_ETHEREUM {
+        return destination_address[..12].iter().all(|b| *b == 0)
+            && destination_address[12..].iter().any(|b| *b != 0);
+    }
+    destination_address.iter().any(|b| *b != 0)
 }
 
 pub fn eth_address_to_bytes32(eth_address: &[u8; 20]) -> [u8; 32] {
@@ -62,12 +349,81 @@ pub fn bytes32_to_eth_address(bytes32: &[u8; 32]) -> [u8; 20] {
 #[cfg(test)]
 mod tests {
     use super::*;
+    fn test_config() -> BridgeConfig {
+        BridgeConfig {
+            admin: Pubkey::new_unique(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: vec![Pubkey::new_unique()],
+            validator_threshold: 1,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        }
+    }
+
     #[test]
     fn test_bridge_status_values() {
         assert_eq!(BridgeStatus::Pending as u8, 0);
         assert_eq!(BridgeStatus::Completed as u8, 1);
         assert_eq!(BridgeStatus::Cancelled as u8, 2);
+        assert_eq!(BridgeStatus::Refunded as u8, 3);
+    }
+
+    #[test]
+    fn test_bridge_status_borsh_round_trip() {
+        for status in [
+            BridgeStatus::Pending,
+            BridgeStatus::Completed,
+            BridgeStatus::Cancelled,
+            BridgeStatus::Refunded,
+        ] {
+            let bytes = borsh::to_vec(&status).unwrap();
+            assert_eq!(bytes, vec![status as u8]);
+            assert_eq!(BridgeStatus::try_from_slice(&bytes).unwrap(), status);
+        }
     }
+    #[test]
+    fn test_nonce_namespaces_do_not_overlap() {
+        let solana = namespaced_nonce(NONCE_ORIGIN_SOLANA, 7).unwrap();
+        let ethereum = namespaced_nonce(1, 7).unwrap();
+        assert_eq!(solana, 7);
+        assert_ne!(solana, ethereum);
+        assert_eq!(nonce_origin(solana), NONCE_ORIGIN_SOLANA);
+        assert_eq!(nonce_origin(ethereum), 1);
+        assert_eq!(ethereum & NONCE_SEQUENCE_MAX, 7);
+
+        assert_eq!(nonce_origin(namespaced_nonce(1, NONCE_SEQUENCE_MAX).unwrap()), 1);
+        assert_eq!(namespaced_nonce(NONCE_ORIGIN_SOLANA, NONCE_SEQUENCE_MAX + 1), None);
+    }
+
+    #[test]
+    fn test_destination_address_validity() {
+        let padded = eth_address_to_bytes32(&[0x11; 20]);
+        assert!(is_valid_destination(CHAIN_ETHEREUM, &padded));
+        assert!(is_valid_destination(2, &[7u8; 32]));
+
+        // Nowhere accepts the zero address
+        assert!(!is_valid_destination(CHAIN_ETHEREUM, &[0u8; 32]));
+        assert!(!is_valid_destination(2, &[0u8; 32]));
+
+        // Ethereum needs the 12 padding bytes clear
+        let mut unpadded = padded;
+        unpadded[0] = 1;
+        assert!(!is_valid_destination(CHAIN_ETHEREUM, &unpadded));
+        assert!(!is_valid_destination(CHAIN_ETHEREUM, &[1u8; 32]));
+        assert!(is_valid_destination(2, &unpadded));
+    }
+
     #[test]
     fn test_eth_address_conversion() {
         let eth_addr = [
@@ -84,15 +440,12 @@ mod tests {
     #[test]
     fn test_serialization() {
         let config = BridgeConfig {
-            admin: Pubkey::new_unique(),
-            relayer_authority: Pubkey::new_unique(),
-            vault_pda_bump: 255,
             fee_basis_points: 50,
-            is_paused: false,
             total_locked: 1_000_000_000,
             nonce: 42,
             validators: vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()],
             validator_threshold: 2,
+            ..test_config()
         };
         let serialized = borsh::to_vec(&config).unwrap();
         let deserialized = BridgeConfig::try_from_slice(&serialized).unwrap();
@@ -100,5 +453,259 @@ mod tests {
         assert_eq!(config.nonce, deserialized.nonce);
         assert_eq!(config.validators.len(), 3);
         assert_eq!(config.validator_threshold, 2);
+        assert_eq!(deserialized.supported_chains, BridgeConfig::ALL_CHAINS);
+    }
+
+    #[test]
+    fn test_load_reads_padded_and_pre_allowlist_accounts() {
+        let config = BridgeConfig {
+            total_locked: 500,
+            validators: vec![Pubkey::new_unique(); BridgeConfig::MAX_VALIDATORS],
+            ..test_config()
+        };
+
+        // As `Initialize` allocates it, with room to spare past the config
+        let mut data = vec![0u8; BridgeConfig::LEN];
+        config.store(&mut data).unwrap();
+        assert!(BridgeConfig::try_from_slice(&data).is_err());
+        assert_eq!(BridgeConfig::load(&data).unwrap().total_locked, 500);
+
+        // The pre-allowlist layout is the current one cut off after `fees_per_validator`
+        let mut legacy = vec![0u8; BridgeConfig::LEGACY_LEN];
+        config.store(&mut legacy).unwrap();
+        let current = borsh::to_vec(&config).unwrap();
+        let legacy_end = current.len() - (4 + 8 + 1 + 2 * BridgeConfig::CHAIN_FEE_SLOTS);
+        assert_eq!(legacy[..legacy_end], current[..legacy_end]);
+        assert!(legacy[legacy_end..].iter().all(|&byte| byte == 0));
+
+        let loaded = BridgeConfig::load(&legacy).unwrap();
+        assert_eq!(borsh::to_vec(&loaded).unwrap(), current);
+        assert!(loaded.is_relayer(&config.relayer_authority));
+        assert_eq!(loaded.lock_fee_basis_points(CHAIN_ETHEREUM), config.fee_basis_points);
+
+        // Settings from after the allowlist have nowhere to go in that layout
+        let capped = BridgeConfig {
+            max_total_locked: 1_000,
+            ..loaded
+        };
+        assert!(capped.store(&mut legacy).is_err());
+    }
+
+    #[test]
+    fn test_supported_chains_bitmask() {
+        let mut config = test_config();
+        assert_eq!(BridgeConfig::ALL_CHAINS, 0b0000_0111_1111_1110);
+        assert!((1..=10).all(|chain| config.supports_chain(chain)));
+        assert!(!config.supports_chain(0));
+        assert!(!config.supports_chain(11));
+
+        config.supported_chains = 1 << 1;
+        assert!(config.supports_chain(1));
+        assert!(!config.supports_chain(2));
+    }
+
+    #[test]
+    fn test_validator_fee_split() {
+        let mut config = BridgeConfig {
+            fee_basis_points: 100,
+            validators: vec![Pubkey::new_unique(); 3],
+            validator_threshold: 2,
+            ..test_config()
+        };
+        assert_eq!(config.validator_fee_split(1_000), Some((0, 0)));
+
+        // Half of a 1000 fee across three validators; the leftover 2 stays with the protocol
+        config.validator_fee_bps = 5_000;
+        assert_eq!(config.validator_fee_split(1_000), Some((166, 498)));
+
+        config.validator_fee_bps = 10_000;
+        assert_eq!(config.validator_fee_split(999), Some((333, 999)));
+        assert_eq!(config.validator_fee_split(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_lock_fee_falls_back_to_base_fee() {
+        let mut config = BridgeConfig {
+            fee_basis_points: 100,
+            ..test_config()
+        };
+        assert_eq!(config.lock_fee_basis_points(CHAIN_ETHEREUM), 100);
+
+        config.chain_fee_basis_points[CHAIN_ETHEREUM as usize] = 30;
+        config.chain_fee_basis_points[2] = 0;
+        assert_eq!(config.lock_fee_basis_points(CHAIN_ETHEREUM), 30);
+        assert_eq!(config.lock_fee_basis_points(2), 0);
+        assert_eq!(config.lock_fee_basis_points(3), 100);
+        assert_eq!(config.lock_fee_basis_points(BridgeConfig::MAX_CHAIN_ID + 1), 100);
+    }
+
+    #[test]
+    fn test_allowed_mints_fit_allocation() {
+        let allowed = AllowedMints {
+            mints: vec![Pubkey::new_unique(); AllowedMints::MAX_MINTS],
+        };
+        assert_eq!(borsh::to_vec(&allowed).unwrap().len(), AllowedMints::LEN);
+        assert!(allowed.contains(&allowed.mints[0]));
+        assert!(!allowed.contains(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_accounts_fit_allocation() {
+        let config = BridgeConfig {
+            validators: vec![Pubkey::new_unique(); BridgeConfig::MAX_VALIDATORS],
+            relayer_authorities: vec![Pubkey::new_unique(); BridgeConfig::MAX_RELAYER_AUTHORITIES],
+            ..test_config()
+        };
+        assert!(borsh::to_vec(&config).unwrap().len() <= BridgeConfig::LEN);
+        assert!(config.is_relayer(&config.relayer_authority));
+        assert!(config.is_relayer(&config.relayer_authorities[0]));
+        assert!(!config.is_relayer(&Pubkey::new_unique()));
+        assert_eq!(borsh::to_vec(&ValidatorFees::default()).unwrap().len(), ValidatorFees::LEN);
+        assert_eq!(borsh::to_vec(&MintFees::default()).unwrap().len(), MintFees::LEN);
+
+        let state = UserBridgeState {
+            user: Pubkey::new_unique(),
+            locked_amount: 1,
+            token_mint: Pubkey::new_unique(),
+            destination_chain: 1,
+            destination_address: [0; 32],
+            status: BridgeStatus::Pending,
+            nonce: 0,
+            timestamp: 100,
+            unlocked: false,
+            expiry: 100 + BridgeConfig::DEFAULT_TRANSFER_TTL,
+            remaining_amount: 1,
+        };
+        assert_eq!(borsh::to_vec(&state).unwrap().len(), UserBridgeState::LEN);
+        assert!(!state.is_expired(state.expiry - 1));
+        assert!(state.is_expired(state.expiry));
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn pubkey() -> impl Strategy<Value = Pubkey> {
+            any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+        }
+
+        fn bridge_status() -> impl Strategy<Value = BridgeStatus> {
+            prop_oneof![
+                Just(BridgeStatus::Pending),
+                Just(BridgeStatus::Completed),
+                Just(BridgeStatus::Cancelled),
+                Just(BridgeStatus::Refunded),
+            ]
+        }
+
+        fn signature_scheme() -> impl Strategy<Value = u8> {
+            prop_oneof![Just(SIGNATURE_SCHEME_ED25519), Just(SIGNATURE_SCHEME_SECP256K1)]
+        }
+
+        /// Any config `Initialize` and the admin instructions can produce
+        fn bridge_config() -> impl Strategy<Value = BridgeConfig> {
+            let validators = prop::collection::vec(pubkey(), 1..=BridgeConfig::MAX_VALIDATORS)
+                .prop_flat_map(|validators| {
+                    let threshold = 1..=validators.len() as u8;
+                    (Just(validators), threshold)
+                });
+            let keys = (pubkey(), pubkey(), prop::collection::vec(pubkey(), 0..=BridgeConfig::MAX_RELAYER_AUTHORITIES));
+            let fees = (0..=10_000u16, 0..=10_000u16, any::<u64>());
+            let totals = (any::<u64>(), any::<u64>(), any::<u64>());
+            let settings = (
+                any::<u8>(),
+                any::<bool>(),
+                any::<u16>().prop_map(|chains| chains & BridgeConfig::ALL_CHAINS),
+                1..=i64::MAX,
+                signature_scheme(),
+            );
+            let chain_fees = prop::array::uniform11(prop_oneof![
+                Just(BridgeConfig::CHAIN_FEE_UNSET),
+                0..=10_000u16
+            ]);
+
+            (validators, keys, fees, totals, settings, chain_fees).prop_map(
+                |(
+                    (validators, validator_threshold),
+                    (admin, relayer_authority, relayer_authorities),
+                    (fee_basis_points, validator_fee_bps, fees_per_validator),
+                    (total_locked, nonce, max_total_locked),
+                    (vault_pda_bump, is_paused, supported_chains, transfer_ttl, signature_scheme),
+                    chain_fee_basis_points,
+                )| BridgeConfig {
+                    admin,
+                    vault_pda_bump,
+                    relayer_authority,
+                    fee_basis_points,
+                    is_paused,
+                    total_locked,
+                    nonce,
+                    validators,
+                    validator_threshold,
+                    supported_chains,
+                    transfer_ttl,
+                    validator_fee_bps,
+                    fees_per_validator,
+                    relayer_authorities,
+                    max_total_locked,
+                    signature_scheme,
+                    chain_fee_basis_points,
+                },
+            )
+        }
+
+        fn user_bridge_state() -> impl Strategy<Value = UserBridgeState> {
+            let parties = (pubkey(), pubkey(), any::<u8>(), any::<[u8; 32]>());
+            let amounts = (any::<u64>(), any::<u64>(), any::<u64>());
+            let progress = (bridge_status(), any::<bool>(), any::<i64>(), any::<i64>());
+
+            (parties, amounts, progress).prop_map(
+                |(
+                    (user, token_mint, destination_chain, destination_address),
+                    (locked_amount, remaining_amount, nonce),
+                    (status, unlocked, timestamp, expiry),
+                )| UserBridgeState {
+                    user,
+                    locked_amount,
+                    token_mint,
+                    destination_chain,
+                    destination_address,
+                    status,
+                    nonce,
+                    timestamp,
+                    unlocked,
+                    expiry,
+                    remaining_amount,
+                },
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn test_bridge_config_round_trips_within_len(config in bridge_config()) {
+                let bytes = borsh::to_vec(&config).unwrap();
+                prop_assert!(bytes.len() <= BridgeConfig::LEN, "{} bytes > LEN {}", bytes.len(), BridgeConfig::LEN);
+                let decoded = BridgeConfig::try_from_slice(&bytes).unwrap();
+                prop_assert_eq!(borsh::to_vec(&decoded).unwrap(), bytes.clone());
+
+                let mut data = vec![0u8; BridgeConfig::LEN];
+                config.store(&mut data).unwrap();
+                prop_assert_eq!(borsh::to_vec(&BridgeConfig::load(&data).unwrap()).unwrap(), bytes);
+            }
+
+            #[test]
+            fn test_user_bridge_state_round_trips_at_len(state in user_bridge_state()) {
+                let bytes = borsh::to_vec(&state).unwrap();
+                prop_assert_eq!(bytes.len(), UserBridgeState::LEN);
+                let decoded = UserBridgeState::try_from_slice(&bytes).unwrap();
+                prop_assert_eq!(borsh::to_vec(&decoded).unwrap(), bytes);
+            }
+
+            #[test]
+            fn test_bridge_status_round_trips(status in bridge_status()) {
+                let bytes = borsh::to_vec(&status).unwrap();
+                prop_assert_eq!(BridgeStatus::try_from_slice(&bytes).unwrap(), status);
+            }
+        }
     }
 }
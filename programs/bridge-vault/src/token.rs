@@ -0,0 +1,138 @@
+//! Token program access for the vault. Either SPL Token or Token-2022 may hold a mint's
+//! accounts; which one is whatever the instruction passes as `token_program`.
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+use crate::error::BridgeError;
+
+/// Fail unless `token_program` is SPL Token or Token-2022
+pub fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if token_program.key != &spl_token::id() && token_program.key != &spl_token_2022::id() {
+        msg!("Unsupported token program {}", token_program.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// `account` as a token account of `token_program`, extensions or not
+pub fn unpack_account(account: &AccountInfo, token_program: &AccountInfo) -> Result<Account, ProgramError> {
+    if account.owner != token_program.key {
+        msg!("Token account {} is not owned by {}", account.key, token_program.key);
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+    let data = account.try_borrow_data()?;
+    StateWithExtensions::<Account>::unpack(&data)
+        .map(|state| state.base)
+        .map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// `account` as a token account of `token_program` that holds `mint` and belongs to `owner`
+pub fn unpack_account_for(
+    account: &AccountInfo,
+    token_program: &AccountInfo,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Account, ProgramError> {
+    let state = unpack_account(account, token_program)?;
+    if state.mint != *mint {
+        msg!("Token account {} holds mint {}, expected {}", account.key, state.mint, mint);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if state.owner != *owner {
+        msg!("Token account {} belongs to {}, expected {}", account.key, state.owner, owner);
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+    Ok(state)
+}
+
+/// What Token-2022 withholds from a transfer of `amount` of `mint` this epoch, out of
+/// what the recipient gets. Zero for SPL Token mints and mints without a transfer fee.
+pub fn transfer_fee(mint: &AccountInfo, token_program: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    if mint.owner != token_program.key {
+        msg!("Mint {} is not owned by {}", mint.key, token_program.key);
+        return Err(BridgeError::IncorrectOwner.into());
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&data).map_err(|_| ProgramError::InvalidAccountData)?;
+    let Ok(fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or_else(|| BridgeError::Overflow.into())
+}
+
+/// Move `amount` from `source` to `destination`, signed by `authority` (with
+/// `signer_seeds` when it is a PDA). Given the mint this is a `TransferChecked`, which
+/// Token-2022 needs for mints with a transfer fee; without it only SPL Token is allowed.
+pub fn transfer<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    mint: Option<&AccountInfo<'a>>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let Some(mint) = mint else {
+        if token_program.key != &spl_token::id() {
+            msg!("Token-2022 transfers need the token mint account");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let instruction = spl_token::instruction::transfer(
+            token_program.key,
+            source.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+        )?;
+        return invoke_signed(
+            &instruction,
+            &[source.clone(), destination.clone(), authority.clone(), token_program.clone()],
+            signer_seeds,
+        );
+    };
+
+    let decimals = {
+        let data = mint.try_borrow_data()?;
+        StateWithExtensions::<Mint>::unpack(&data)
+            .map_err(|_| ProgramError::InvalidAccountData)?
+            .base
+            .decimals
+    };
+    let instruction = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    invoke_signed(
+        &instruction,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
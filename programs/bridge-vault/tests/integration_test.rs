@@ -1,20 +1,27 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use bridge_vault::{
     instruction::BridgeInstruction,
-    state::{BridgeConfig, BridgeStatus, UserBridgeState},
+    processor::{create_claim_fees_message, create_partial_unlock_message, create_unlock_message},
+    state::{
+        eth_address_to_bytes32, namespaced_nonce, BridgeConfig, BridgeStatus, MintFees, UserBridgeState,
+        ValidatorFees, CHAIN_ETHEREUM, NONCE_ORIGIN_SOLANA, SIGNATURE_SCHEME_ED25519, SIGNATURE_SCHEME_SECP256K1,
+    },
     BridgeError,
 };
 use solana_program::{
-    instruction::Instruction,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    keccak,
+    program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
 };
 use solana_program_test::*;
 use solana_sdk::{
+    account::Account,
+    compute_budget::ComputeBudgetInstruction,
     signature::{Keypair, Signer},
-    transaction::Transaction,
-    transport::TransportError,
+    transaction::{Transaction, TransactionError},
 };
-use spl_token;
 
 #[tokio::test]
 async fn test_initialize() {
@@ -25,9 +32,9 @@ async fn test_initialize() {
         processor!(bridge_vault::process_instruction),
     );
 
+    let admin = funded_keypair(&mut program_test);
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let admin = Keypair::new();
     let relayer = Keypair::new();
     let bridge_config = Keypair::new();
 
@@ -64,7 +71,7 @@ async fn test_initialize() {
         .expect("Failed to get bridge config account")
         .expect("Bridge config account not found");
 
-    let config = BridgeConfig::try_from_slice(&account.data).unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
     assert_eq!(config.admin, admin.pubkey());
     assert_eq!(config.relayer_authority, relayer.pubkey());
     assert_eq!(config.fee_basis_points, 50);
@@ -83,9 +90,9 @@ async fn test_pause_and_unpause() {
         processor!(bridge_vault::process_instruction),
     );
 
+    let admin = funded_keypair(&mut program_test);
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let admin = Keypair::new();
     let relayer = Keypair::new();
     let bridge_config = Keypair::new();
     let validators = vec![Keypair::new().pubkey()];
@@ -126,7 +133,7 @@ async fn test_pause_and_unpause() {
         .unwrap()
         .unwrap();
 
-    let config = BridgeConfig::try_from_slice(&account.data).unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
     assert!(config.is_paused);
 
     let unpause_ix = BridgeInstruction::create_unpause_instruction(
@@ -145,7 +152,7 @@ async fn test_pause_and_unpause() {
         .unwrap()
         .unwrap();
 
-    let config = BridgeConfig::try_from_slice(&account.data).unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
     assert!(!config.is_paused);
 }
 
@@ -158,9 +165,9 @@ async fn test_update_config() {
         processor!(bridge_vault::process_instruction),
     );
 
+    let admin = funded_keypair(&mut program_test);
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let admin = Keypair::new();
     let new_admin = Keypair::new();
     let relayer = Keypair::new();
     let new_relayer = Keypair::new();
@@ -194,6 +201,11 @@ async fn test_update_config() {
         Some(new_admin.pubkey()),
         Some(new_relayer.pubkey()),
         Some(100),
+        None,
+        Some(3_600),
+        Some(5_000),
+        None,
+        None,
     );
 
     let mut update_tx = Transaction::new_with_payer(&[update_ix], Some(&payer.pubkey()));
@@ -206,14 +218,169 @@ async fn test_update_config() {
         .unwrap()
         .unwrap();
 
-    let config = BridgeConfig::try_from_slice(&account.data).unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
     assert_eq!(config.admin, new_admin.pubkey());
     assert_eq!(config.relayer_authority, new_relayer.pubkey());
     assert_eq!(config.fee_basis_points, 100);
+    assert_eq!(config.transfer_ttl, 3_600);
+    assert_eq!(config.validator_fee_bps, 5_000);
 }
 
+/// `Initialize` allocates `BridgeConfig::LEN`, more than the config it writes, and
+/// the instructions after it have to read the config out of that account
 #[tokio::test]
-async fn test_invalid_fee_initialization() {
+async fn test_lock_against_initialized_config() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let admin = funded_keypair(&mut program_test);
+    let user = funded_keypair(&mut program_test);
+    let bridge_config = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let user_token_account = Pubkey::new_unique();
+    let vault_token_account = Pubkey::new_unique();
+
+    let (vault_pda, _bump) = Pubkey::find_program_address(
+        &[b"vault", bridge_config.pubkey().as_ref()],
+        &program_id,
+    );
+    let (user_bridge_state, _bump) = Pubkey::find_program_address(
+        &[b"bridge", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &program_id,
+    );
+    add_spl_token_accounts(
+        &mut program_test,
+        mint,
+        [(user_token_account, user.pubkey(), 10_000), (vault_token_account, vault_pda, 0)],
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let init_ix = BridgeInstruction::create_initialize_instruction(
+        &program_id,
+        &admin.pubkey(),
+        &bridge_config.pubkey(),
+        &vault_pda,
+        &Pubkey::new_unique(),
+        100,
+        vec![Pubkey::new_unique()],
+        1,
+    );
+    let mut init_tx = Transaction::new_with_payer(&[init_ix], Some(&payer.pubkey()));
+    init_tx.sign(&[&payer, &admin, &bridge_config], recent_blockhash);
+    banks_client.process_transaction(init_tx).await.unwrap();
+
+    let instructions = [
+        BridgeInstruction::create_add_allowed_mint_instruction(
+            &program_id,
+            &admin.pubkey(),
+            &bridge_config.pubkey(),
+            &mint,
+        ),
+        BridgeInstruction::create_lock_tokens_instruction(
+            &program_id,
+            &user.pubkey(),
+            &user_token_account,
+            &vault_token_account,
+            &user_bridge_state,
+            &bridge_config.pubkey(),
+            &mint,
+            10_000,
+            CHAIN_ETHEREUM,
+            eth_address_to_bytes32(&[1u8; 20]),
+        ),
+    ];
+    let mut lock_tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    lock_tx.sign(&[&payer, &admin, &user], recent_blockhash);
+    banks_client.process_transaction(lock_tx).await.unwrap();
+
+    let account = banks_client.get_account(bridge_config.pubkey()).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), BridgeConfig::LEN);
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.nonce, 1);
+    assert_eq!(config.total_locked, 9_900);
+}
+
+#[tokio::test]
+async fn test_pre_allowlist_config_loads_and_migrates() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_lock_fixture(&mut program_test, program_id, 10_000);
+
+    // Rewrite the fixture's config as `Initialize` laid it out before the relayer allowlist
+    let mut context = program_test.start_with_context().await;
+    let mut account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    let relayer = config.relayer_authority;
+    account.data = vec![0u8; BridgeConfig::LEGACY_LEN];
+    config.store(&mut account.data).unwrap();
+    context.set_account(&fixture.bridge_config, &account.into());
+
+    // Locks read and write it in place, without moving it to the current layout
+    let allow_ix = fixture.allow_mint_instruction();
+    let mut transaction =
+        Transaction::new_with_payer(&[allow_ix, fixture.lock_instruction(10_000)], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin, &fixture.user], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), BridgeConfig::LEGACY_LEN);
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 10_000);
+    assert!(config.relayer_authorities.is_empty());
+    assert!(config.is_relayer(&relayer));
+
+    // A setting the old layout has no room for is refused until the config is migrated
+    let cap_ix = BridgeInstruction::create_update_config_instruction(
+        &program_id,
+        &fixture.admin.pubkey(),
+        &fixture.bridge_config,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(1_000_000),
+        None,
+    );
+    let mut transaction = Transaction::new_with_payer(&[cap_ix.clone()], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+
+    // Changing the allowlist grows it to `LEN`, after which every setting can be stored
+    let backup_relayer = Pubkey::new_unique();
+    let add_ix = BridgeInstruction::create_add_relayer_authority_instruction(
+        &program_id,
+        &fixture.admin.pubkey(),
+        &fixture.bridge_config,
+        &backup_relayer,
+    );
+    let mut transaction = Transaction::new_with_payer(&[add_ix, cap_ix], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), BridgeConfig::LEN);
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.relayer_authority, relayer);
+    assert_eq!(config.relayer_authorities, vec![backup_relayer]);
+    assert_eq!(config.max_total_locked, 1_000_000);
+    assert_eq!(config.total_locked, 10_000);
+}
+
+#[tokio::test]
+async fn test_lock_rejects_disabled_destination_chain() {
     let program_id = Pubkey::new_unique();
     let mut program_test = ProgramTest::new(
         "bridge_vault",
@@ -221,9 +388,160 @@ async fn test_invalid_fee_initialization() {
         processor!(bridge_vault::process_instruction),
     );
 
+    let admin = Keypair::new();
+    let user = Keypair::new();
+    let bridge_config = Pubkey::new_unique();
+    let (_vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+    add_bridge_config_account(
+        &mut program_test,
+        bridge_config,
+        &BridgeConfig {
+            admin: admin.pubkey(),
+            vault_pda_bump: vault_bump,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: vec![Pubkey::new_unique()],
+            validator_threshold: 1,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        },
+        program_id,
+    );
+
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
+    // Ethereum (chain 1) only
+    let update_ix = BridgeInstruction::create_update_config_instruction(
+        &program_id,
+        &admin.pubkey(),
+        &bridge_config,
+        None,
+        None,
+        None,
+        Some(1 << 1),
+        None,
+        None,
+        None,
+        None,
+    );
+    let mut update_tx = Transaction::new_with_payer(&[update_ix], Some(&payer.pubkey()));
+    update_tx.sign(&[&payer, &admin], recent_blockhash);
+    banks_client.process_transaction(update_tx).await.unwrap();
+
+    let account = banks_client.get_account(bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert!(config.supports_chain(1));
+    assert!(!config.supports_chain(2));
+
+    let (user_bridge_state, _bump) = Pubkey::find_program_address(
+        &[b"bridge", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &program_id,
+    );
+    let sui_chain = 2;
+    let lock_ix = BridgeInstruction::create_lock_tokens_instruction(
+        &program_id,
+        &user.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &user_bridge_state,
+        &bridge_config,
+        &Pubkey::new_unique(),
+        1_000,
+        sui_chain,
+        [1u8; 32],
+    );
+    let mut lock_tx = Transaction::new_with_payer(&[lock_ix], Some(&payer.pubkey()));
+    lock_tx.sign(&[&payer, &user], recent_blockhash);
+
+    let result = banks_client.process_transaction(lock_tx).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::InvalidDestination as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_update_config_rejects_unknown_chain_bits() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
     let admin = Keypair::new();
+    let bridge_config = Pubkey::new_unique();
+    add_bridge_config_account(
+        &mut program_test,
+        bridge_config,
+        &BridgeConfig {
+            admin: admin.pubkey(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: vec![Pubkey::new_unique()],
+            validator_threshold: 1,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        },
+        program_id,
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let update_ix = BridgeInstruction::create_update_config_instruction(
+        &program_id,
+        &admin.pubkey(),
+        &bridge_config,
+        None,
+        None,
+        None,
+        Some(1 << 11),
+        None,
+        None,
+        None,
+        None,
+    );
+    let mut update_tx = Transaction::new_with_payer(&[update_ix], Some(&payer.pubkey()));
+    update_tx.sign(&[&payer, &admin], recent_blockhash);
+
+    let result = banks_client.process_transaction(update_tx).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::InvalidDestination as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_fee_initialization() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+
+    let admin = funded_keypair(&mut program_test);
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
     let relayer = Keypair::new();
     let bridge_config = Keypair::new();
     let validators = vec![Keypair::new().pubkey()];
@@ -260,9 +578,9 @@ async fn test_invalid_validator_threshold() {
         processor!(bridge_vault::process_instruction),
     );
 
+    let admin = funded_keypair(&mut program_test);
     let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
-    let admin = Keypair::new();
     let relayer = Keypair::new();
     let bridge_config = Keypair::new();
     let validators = vec![Keypair::new().pubkey(), Keypair::new().pubkey()];
@@ -289,3 +607,2451 @@ async fn test_invalid_validator_threshold() {
     let result = banks_client.process_transaction(transaction).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_unlock_rejects_oversized_signature_vector() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let user = Keypair::new();
+    let bridge_config = Keypair::new();
+    let (vault_pda, _bump) = Pubkey::find_program_address(
+        &[b"vault", bridge_config.pubkey().as_ref()],
+        &program_id,
+    );
+    let (user_bridge_state, _bump) = Pubkey::find_program_address(
+        &[b"bridge", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &program_id,
+    );
+
+    let signatures = vec![[7u8; 64]; BridgeConfig::MAX_VALIDATORS + 1];
+
+    let ix = BridgeInstruction::create_unlock_tokens_instruction(
+        &program_id,
+        &payer.pubkey(),
+        &user.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &vault_pda,
+        &user_bridge_state,
+        &bridge_config.pubkey(),
+        0,
+        signatures,
+    );
+
+    let mut transaction = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+}
+
+/// Accounts for a pending lock that `UnlockTokens` can release
+struct UnlockFixture {
+    program_id: Pubkey,
+    admin: Keypair,
+    relayer: Keypair,
+    validators: Vec<Keypair>,
+    user: Keypair,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    vault_token_account: Pubkey,
+    vault_pda: Pubkey,
+    user_bridge_state: Pubkey,
+    bridge_config: Pubkey,
+    nonce: u64,
+    amount: u64,
+}
+
+impl UnlockFixture {
+    fn unlock_instruction(&self, signatures: Vec<[u8; 64]>) -> Instruction {
+        BridgeInstruction::create_unlock_tokens_instruction(
+            &self.program_id,
+            &self.relayer.pubkey(),
+            &self.user.pubkey(),
+            &self.user_token_account,
+            &self.vault_token_account,
+            &self.vault_pda,
+            &self.user_bridge_state,
+            &self.bridge_config,
+            self.nonce,
+            signatures,
+        )
+    }
+
+    fn sign(&self, validator: &Keypair) -> [u8; 64] {
+        let message = create_unlock_message(self.nonce, &self.user.pubkey(), &self.mint, self.amount);
+        validator.sign_message(&message).into()
+    }
+
+    /// The unlock signed by `signers`, preceded by the Ed25519Program
+    /// instruction the default (precompile) verification path expects
+    fn signed_unlock_instructions(&self, signers: &[&Keypair]) -> Vec<Instruction> {
+        let signatures: Vec<[u8; 64]> = signers.iter().map(|v| self.sign(v)).collect();
+        let mut instructions = Vec::new();
+
+        #[cfg(not(feature = "in-program-ed25519"))]
+        {
+            let message = create_unlock_message(self.nonce, &self.user.pubkey(), &self.mint, self.amount);
+            let pairs: Vec<(Pubkey, [u8; 64])> = signers
+                .iter()
+                .zip(&signatures)
+                .map(|(v, sig)| (v.pubkey(), *sig))
+                .collect();
+            instructions.push(BridgeInstruction::create_ed25519_verify_instruction(&message, &pairs));
+        }
+
+        instructions.push(self.unlock_instruction(signatures));
+        instructions
+    }
+
+    /// The unlock signed by secp256k1 `signers`, which need no precompile instruction
+    fn secp256k1_unlock_instruction(&self, signers: &[&libsecp256k1::SecretKey]) -> Instruction {
+        let message = create_unlock_message(self.nonce, &self.user.pubkey(), &self.mint, self.amount);
+        self.unlock_instruction(signers.iter().map(|key| secp256k1_sign(key, &message)).collect())
+    }
+
+    /// A partial unlock of `amount` signed by `signers`, while `remaining` is still locked
+    fn signed_partial_unlock_instructions(
+        &self,
+        signers: &[&Keypair],
+        amount: u64,
+        remaining: u64,
+    ) -> Vec<Instruction> {
+        let message =
+            create_partial_unlock_message(self.nonce, &self.user.pubkey(), &self.mint, amount, remaining);
+        let signatures: Vec<[u8; 64]> = signers.iter().map(|v| v.sign_message(&message).into()).collect();
+        let mut instructions = Vec::new();
+
+        #[cfg(not(feature = "in-program-ed25519"))]
+        {
+            let pairs: Vec<(Pubkey, [u8; 64])> = signers
+                .iter()
+                .zip(&signatures)
+                .map(|(v, sig)| (v.pubkey(), *sig))
+                .collect();
+            instructions.push(BridgeInstruction::create_ed25519_verify_instruction(&message, &pairs));
+        }
+
+        instructions.push(BridgeInstruction::create_unlock_partial_instruction(
+            &self.program_id,
+            &self.relayer.pubkey(),
+            &self.user.pubkey(),
+            &self.user_token_account,
+            &self.vault_token_account,
+            &self.vault_pda,
+            &self.user_bridge_state,
+            &self.bridge_config,
+            self.nonce,
+            amount,
+            signatures,
+        ));
+        instructions
+    }
+}
+
+/// A keypair with lamports to pay for the accounts it creates
+fn funded_keypair(program_test: &mut ProgramTest) -> Keypair {
+    let keypair = Keypair::new();
+    program_test.add_account(
+        keypair.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+    keypair
+}
+
+fn add_packed_account<T: Pack>(program_test: &mut ProgramTest, address: Pubkey, state: T, owner: Pubkey) {
+    let mut data = vec![0u8; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    program_test.add_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+}
+
+fn add_borsh_account<T: BorshSerialize>(program_test: &mut ProgramTest, address: Pubkey, state: &T, owner: Pubkey) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data: borsh::to_vec(state).unwrap(),
+            owner,
+            ..Account::default()
+        },
+    );
+}
+
+/// A config in an account of `BridgeConfig::LEN`, the size `Initialize` gives it
+fn add_bridge_config_account(program_test: &mut ProgramTest, address: Pubkey, config: &BridgeConfig, owner: Pubkey) {
+    let mut data = vec![0u8; BridgeConfig::LEN];
+    config.store(&mut data).unwrap();
+    program_test.add_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+}
+
+/// Accounts for a user about to lock `amount` of `mint`
+struct LockFixture {
+    program_id: Pubkey,
+    admin: Keypair,
+    user: Keypair,
+    mint: Pubkey,
+    user_token_account: Pubkey,
+    vault_token_account: Pubkey,
+    vault_pda: Pubkey,
+    user_bridge_state: Pubkey,
+    bridge_config: Pubkey,
+}
+
+impl LockFixture {
+    fn lock_instruction(&self, amount: u64) -> Instruction {
+        BridgeInstruction::create_lock_tokens_instruction(
+            &self.program_id,
+            &self.user.pubkey(),
+            &self.user_token_account,
+            &self.vault_token_account,
+            &self.user_bridge_state,
+            &self.bridge_config,
+            &self.mint,
+            amount,
+            1,
+            eth_address_to_bytes32(&[1u8; 20]),
+        )
+    }
+
+    fn allow_mint_instruction(&self) -> Instruction {
+        BridgeInstruction::create_add_allowed_mint_instruction(
+            &self.program_id,
+            &self.admin.pubkey(),
+            &self.bridge_config,
+            &self.mint,
+        )
+    }
+
+    /// Put `mint` on the allowlist, which every lock of it needs first
+    async fn allow_mint(
+        &self,
+        banks_client: &mut BanksClient,
+        payer: &Keypair,
+        recent_blockhash: solana_sdk::hash::Hash,
+    ) {
+        let mut allow_tx = Transaction::new_with_payer(&[self.allow_mint_instruction()], Some(&payer.pubkey()));
+        allow_tx.sign(&[payer, &self.admin], recent_blockhash);
+        banks_client.process_transaction(allow_tx).await.unwrap();
+    }
+}
+
+fn setup_lock_fixture(program_test: &mut ProgramTest, program_id: Pubkey, balance: u64) -> LockFixture {
+    setup_lock_fixture_with_fees(program_test, program_id, balance, 0, 0, vec![Pubkey::new_unique()])
+}
+
+/// Like `setup_lock_fixture`, with a lock fee of which `validator_fee_bps` goes to `validators`
+fn setup_lock_fixture_with_fees(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    balance: u64,
+    fee_basis_points: u16,
+    validator_fee_bps: u16,
+    validators: Vec<Pubkey>,
+) -> LockFixture {
+    setup_lock_fixture_with(
+        program_test,
+        program_id,
+        balance,
+        fee_basis_points,
+        validator_fee_bps,
+        validators,
+        add_spl_token_accounts,
+    )
+}
+
+/// SPL Token `mint` and its token accounts, given as `(address, owner, amount)`
+fn add_spl_token_accounts(program_test: &mut ProgramTest, mint: Pubkey, accounts: [(Pubkey, Pubkey, u64); 2]) {
+    add_packed_account(
+        program_test,
+        mint,
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: accounts.iter().map(|(_, _, amount)| amount).sum(),
+            ..spl_token::state::Mint::default()
+        },
+        spl_token::id(),
+    );
+    for (address, owner, amount) in accounts {
+        add_packed_account(
+            program_test,
+            address,
+            spl_token::state::Account {
+                mint,
+                owner,
+                amount,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            },
+            spl_token::id(),
+        );
+    }
+}
+
+/// Like `setup_lock_fixture_with_fees`, with the mint and the user's and vault's token
+/// accounts created by `add_token_accounts`
+fn setup_lock_fixture_with(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    balance: u64,
+    fee_basis_points: u16,
+    validator_fee_bps: u16,
+    validators: Vec<Pubkey>,
+    add_token_accounts: impl FnOnce(&mut ProgramTest, Pubkey, [(Pubkey, Pubkey, u64); 2]),
+) -> LockFixture {
+    let admin = funded_keypair(program_test);
+    let user = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let bridge_config = Pubkey::new_unique();
+    let user_token_account = Pubkey::new_unique();
+    let vault_token_account = Pubkey::new_unique();
+
+    let (vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+    let (user_bridge_state, _bump) = Pubkey::find_program_address(
+        &[b"bridge", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+        &program_id,
+    );
+
+    program_test.add_account(
+        user.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+    add_token_accounts(
+        program_test,
+        mint,
+        [(user_token_account, user.pubkey(), balance), (vault_token_account, vault_pda, 0)],
+    );
+    add_bridge_config_account(
+        program_test,
+        bridge_config,
+        &BridgeConfig {
+            admin: admin.pubkey(),
+            vault_pda_bump: vault_bump,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators,
+            validator_threshold: 1,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        },
+        program_id,
+    );
+
+    LockFixture {
+        program_id,
+        admin,
+        user,
+        mint,
+        user_token_account,
+        vault_token_account,
+        vault_pda,
+        user_bridge_state,
+        bridge_config,
+    }
+}
+
+/// Token-2022 `mint` withholding `transfer_fee_bps` of every transfer, and token accounts
+/// carrying the withheld-fee extension such a mint requires
+fn add_token_2022_accounts(
+    transfer_fee_bps: u16,
+) -> impl FnOnce(&mut ProgramTest, Pubkey, [(Pubkey, Pubkey, u64); 2]) {
+    use spl_token_2022::{
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+            BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+        },
+        state::{Account as TokenAccount, AccountState, Mint},
+    };
+
+    move |program_test: &mut ProgramTest, mint: Pubkey, accounts: [(Pubkey, Pubkey, u64); 2]| {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: transfer_fee_bps.into(),
+        };
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+        state.base = Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: accounts.iter().map(|(_, _, amount)| amount).sum(),
+            ..Mint::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        add_raw_account(program_test, mint, data, spl_token_2022::id());
+
+        for (address, owner, amount) in accounts {
+            let len =
+                ExtensionType::try_calculate_account_len::<TokenAccount>(&[ExtensionType::TransferFeeAmount]).unwrap();
+            let mut data = vec![0u8; len];
+            let mut state = StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data).unwrap();
+            state.init_extension::<TransferFeeAmount>(true).unwrap();
+            state.base = TokenAccount {
+                mint,
+                owner,
+                amount,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            };
+            state.pack_base();
+            state.init_account_type().unwrap();
+            add_raw_account(program_test, address, data, spl_token_2022::id());
+        }
+    }
+}
+
+fn add_raw_account(program_test: &mut ProgramTest, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            ..Account::default()
+        },
+    );
+}
+
+#[tokio::test]
+async fn test_lock_token_2022_mint_with_transfer_fee() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    // 1% withheld by the token on every transfer, then a 0.5% bridge fee
+    let fixture = setup_lock_fixture_with(
+        &mut program_test,
+        program_id,
+        10_000,
+        50,
+        0,
+        vec![Pubkey::new_unique()],
+        add_token_2022_accounts(100),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let allow_ix = fixture.allow_mint_instruction();
+    let lock_ix = BridgeInstruction::with_token_program(
+        fixture.lock_instruction(10_000),
+        &spl_token_2022::id(),
+        None,
+    );
+    let mut transaction = Transaction::new_with_payer(&[allow_ix, lock_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.admin, &fixture.user], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let token_account = |data: Vec<u8>| {
+        use spl_token_2022::extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions};
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data).unwrap();
+        let withheld = u64::from(state.get_extension::<TransferFeeAmount>().unwrap().withheld_amount);
+        (state.base.amount, withheld)
+    };
+
+    // The vault got 9_900 with 100 withheld; the bridge fee and the lock come out of the 9_900
+    let vault = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    assert_eq!(token_account(vault.data), (9_900, 100));
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.locked_amount, 9_851);
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 9_851);
+
+    // Paying out of the vault goes through the same program, with the mint trailing
+    let cancel_ix = BridgeInstruction::with_token_program(
+        BridgeInstruction::create_cancel_lock_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &fixture.vault_pda,
+            &fixture.user_bridge_state,
+            &fixture.bridge_config,
+            state.nonce,
+        ),
+        &spl_token_2022::id(),
+        Some(&fixture.mint),
+    );
+    let mut transaction = Transaction::new_with_payer(&[cancel_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // 9_851 leaves the vault and the user's account withholds 1% of it, rounded up
+    let vault = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    assert_eq!(token_account(vault.data), (49, 100));
+    let user = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(token_account(user.data), (9_752, 99));
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    assert_eq!(BridgeConfig::load(&account.data).unwrap().total_locked, 0);
+}
+
+#[tokio::test]
+async fn test_lock_with_allowed_mint() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_lock_fixture(&mut program_test, program_id, 5_000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    fixture.allow_mint(&mut banks_client, &payer, recent_blockhash).await;
+
+    let mut lock_tx = Transaction::new_with_payer(&[fixture.lock_instruction(5_000)], Some(&payer.pubkey()));
+    lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+    banks_client.process_transaction(lock_tx).await.unwrap();
+
+    let account = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    let vault_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(vault_token.amount, 5_000);
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.expiry, state.timestamp + BridgeConfig::DEFAULT_TRANSFER_TTL);
+}
+
+#[tokio::test]
+async fn test_lock_rejects_zero_and_malformed_destinations() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_lock_fixture(&mut program_test, program_id, 5_000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    fixture.allow_mint(&mut banks_client, &payer, recent_blockhash).await;
+
+    let mut unpadded = eth_address_to_bytes32(&[1u8; 20]);
+    unpadded[0] = 0xff;
+    let sui_chain = 2;
+    for (destination_chain, destination_address) in [
+        (CHAIN_ETHEREUM, [0u8; 32]),
+        (CHAIN_ETHEREUM, unpadded),
+        (CHAIN_ETHEREUM, [1u8; 32]),
+        (sui_chain, [0u8; 32]),
+    ] {
+        let lock_ix = BridgeInstruction::create_lock_tokens_instruction(
+            &program_id,
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &fixture.user_bridge_state,
+            &fixture.bridge_config,
+            &fixture.mint,
+            1_000,
+            destination_chain,
+            destination_address,
+        );
+        let mut lock_tx = Transaction::new_with_payer(&[lock_ix], Some(&payer.pubkey()));
+        lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+
+        let result = banks_client.process_transaction(lock_tx).await;
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::InvalidDestination as u32)),
+            "chain {} address {:?}",
+            destination_chain,
+            destination_address
+        );
+    }
+
+    // Nothing reached the vault
+    let account = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 0);
+}
+
+#[tokio::test]
+async fn test_lock_up_to_cap_then_reject() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_lock_fixture(&mut program_test, program_id, 10_000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let allow_ix = fixture.allow_mint_instruction();
+    let set_cap = |cap: u64| {
+        BridgeInstruction::create_update_config_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(cap),
+            None,
+        )
+    };
+    let mut setup_tx = Transaction::new_with_payer(&[allow_ix, set_cap(6_000)], Some(&payer.pubkey()));
+    setup_tx.sign(&[&payer, &fixture.admin], recent_blockhash);
+    banks_client.process_transaction(setup_tx).await.unwrap();
+
+    // Each lock gets the user bridge state PDA for the next config nonce
+    let lock = |nonce: u64, amount: u64| {
+        let (user_bridge_state, _bump) = Pubkey::find_program_address(
+            &[
+                b"bridge",
+                fixture.user.pubkey().as_ref(),
+                &namespaced_nonce(NONCE_ORIGIN_SOLANA, nonce).unwrap().to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let lock_ix = BridgeInstruction::create_lock_tokens_instruction(
+            &program_id,
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &user_bridge_state,
+            &fixture.bridge_config,
+            &fixture.mint,
+            amount,
+            CHAIN_ETHEREUM,
+            eth_address_to_bytes32(&[1u8; 20]),
+        );
+        let mut lock_tx = Transaction::new_with_payer(&[lock_ix], Some(&payer.pubkey()));
+        lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+        lock_tx
+    };
+
+    // Filling the cap exactly is allowed
+    banks_client.process_transaction(lock(0, 4_000)).await.unwrap();
+    banks_client.process_transaction(lock(1, 2_000)).await.unwrap();
+
+    let result = banks_client.process_transaction(lock(2, 1)).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::VaultCapExceeded as u32))
+    );
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 6_000);
+    assert_eq!(config.max_total_locked, 6_000);
+    assert_eq!(config.nonce, 2);
+
+    // A cap of 0 lifts the limit
+    let mut uncap_tx = Transaction::new_with_payer(&[set_cap(0)], Some(&payer.pubkey()));
+    uncap_tx.sign(&[&payer, &fixture.admin], recent_blockhash);
+    banks_client.process_transaction(uncap_tx).await.unwrap();
+    banks_client.process_transaction(lock(2, 2)).await.unwrap();
+
+    let account = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 6_002);
+}
+
+#[tokio::test]
+async fn test_failed_lock_transfer_leaves_no_state() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    // The user's token account is frozen, so the transfer into the vault fails
+    let fixture = setup_lock_fixture_with(
+        &mut program_test,
+        program_id,
+        5_000,
+        0,
+        0,
+        vec![Pubkey::new_unique()],
+        |program_test, mint, [user, vault]| {
+            add_spl_token_accounts(program_test, mint, [vault, (Pubkey::new_unique(), Pubkey::new_unique(), 0)]);
+            let (address, owner, amount) = user;
+            add_packed_account(
+                program_test,
+                address,
+                spl_token::state::Account {
+                    mint,
+                    owner,
+                    amount,
+                    state: spl_token::state::AccountState::Frozen,
+                    ..spl_token::state::Account::default()
+                },
+                spl_token::id(),
+            );
+        },
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    fixture.allow_mint(&mut banks_client, &payer, recent_blockhash).await;
+
+    let mut lock_tx = Transaction::new_with_payer(&[fixture.lock_instruction(1_000)], Some(&payer.pubkey()));
+    lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+    let result = banks_client.process_transaction(lock_tx).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(spl_token::error::TokenError::AccountFrozen as u32)
+        )
+    );
+
+    // No transfer record, no nonce used up, nothing counted as locked
+    assert!(banks_client.get_account(fixture.user_bridge_state).await.unwrap().is_none());
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.nonce, 0);
+    assert_eq!(config.total_locked, 0);
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 5_000);
+}
+
+#[tokio::test]
+async fn test_lock_charges_destination_chain_fee() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    // 1% base fee
+    let fixture =
+        setup_lock_fixture_with_fees(&mut program_test, program_id, 30_000, 100, 0, vec![Pubkey::new_unique()]);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let set_chain_fee = |chain: u8, fee: Option<u16>| {
+        BridgeInstruction::create_set_chain_fee_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            chain,
+            fee,
+        )
+    };
+    let admin_tx = |instructions: &[Instruction]| {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &fixture.admin], recent_blockhash);
+        transaction
+    };
+
+    for (chain, fee, error) in [
+        (CHAIN_ETHEREUM, Some(10_001), BridgeError::InvalidFee),
+        (0, Some(30), BridgeError::InvalidDestination),
+        (BridgeConfig::MAX_CHAIN_ID + 1, Some(30), BridgeError::InvalidDestination),
+    ] {
+        let result = banks_client.process_transaction(admin_tx(&[set_chain_fee(chain, fee)])).await;
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+        );
+    }
+
+    // 0.3% to Ethereum; chain 2 keeps the base fee
+    let allow_ix = fixture.allow_mint_instruction();
+    banks_client
+        .process_transaction(admin_tx(&[allow_ix, set_chain_fee(CHAIN_ETHEREUM, Some(30))]))
+        .await
+        .unwrap();
+
+    let user_bridge_state = |nonce: u64| {
+        Pubkey::find_program_address(
+            &[b"bridge", fixture.user.pubkey().as_ref(), &nonce.to_le_bytes()],
+            &program_id,
+        )
+        .0
+    };
+    let lock = |nonce: u64, destination_chain: u8| {
+        let lock_ix = BridgeInstruction::create_lock_tokens_instruction(
+            &program_id,
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &user_bridge_state(nonce),
+            &fixture.bridge_config,
+            &fixture.mint,
+            10_000,
+            destination_chain,
+            eth_address_to_bytes32(&[1u8; 20]),
+        );
+        let mut lock_tx = Transaction::new_with_payer(&[lock_ix], Some(&payer.pubkey()));
+        lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+        lock_tx
+    };
+
+    banks_client.process_transaction(lock(0, CHAIN_ETHEREUM)).await.unwrap();
+    banks_client.process_transaction(lock(1, 2)).await.unwrap();
+
+    // Cleared, Ethereum is back on the base fee
+    banks_client
+        .process_transaction(admin_tx(&[set_chain_fee(CHAIN_ETHEREUM, None)]))
+        .await
+        .unwrap();
+    banks_client.process_transaction(lock(2, CHAIN_ETHEREUM)).await.unwrap();
+
+    for (nonce, locked_amount) in [(0, 9_970), (1, 9_900), (2, 9_900)] {
+        let account = banks_client.get_account(user_bridge_state(nonce)).await.unwrap().unwrap();
+        let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+        assert_eq!(state.locked_amount, locked_amount, "nonce {}", nonce);
+    }
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.lock_fee_basis_points(CHAIN_ETHEREUM), 100);
+    assert_eq!(config.total_locked, 9_970 + 9_900 + 9_900);
+}
+
+#[tokio::test]
+async fn test_lock_rejects_mint_not_on_allowlist() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_lock_fixture(&mut program_test, program_id, 5_000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Another mint is allowed, and ours is added then removed again
+    let instructions = [
+        BridgeInstruction::create_add_allowed_mint_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            &Pubkey::new_unique(),
+        ),
+        fixture.allow_mint_instruction(),
+        BridgeInstruction::create_remove_allowed_mint_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            &fixture.mint,
+        ),
+    ];
+    let mut admin_tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    admin_tx.sign(&[&payer, &fixture.admin], recent_blockhash);
+    banks_client.process_transaction(admin_tx).await.unwrap();
+
+    let mut lock_tx = Transaction::new_with_payer(&[fixture.lock_instruction(5_000)], Some(&payer.pubkey()));
+    lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+
+    let result = banks_client.process_transaction(lock_tx).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::UnsupportedMint as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_validator_claims_its_share_of_lock_fees() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let validators = [Keypair::new(), Keypair::new()];
+    // 1% fee on 10_000 is 100, half of which is split between the two validators
+    let fixture = setup_lock_fixture_with_fees(
+        &mut program_test,
+        program_id,
+        10_000,
+        100,
+        5_000,
+        validators.iter().map(|validator| validator.pubkey()).collect(),
+    );
+
+    let outsider = Keypair::new();
+    let mut token_accounts = Vec::new();
+    for owner in [validators[0].pubkey(), outsider.pubkey()] {
+        program_test.add_account(
+            owner,
+            Account {
+                lamports: 1_000_000_000,
+                ..Account::default()
+            },
+        );
+        let token_account = Pubkey::new_unique();
+        add_packed_account(
+            &mut program_test,
+            token_account,
+            spl_token::state::Account {
+                mint: fixture.mint,
+                owner,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            },
+            spl_token::id(),
+        );
+        token_accounts.push(token_account);
+    }
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let allow_ix = fixture.allow_mint_instruction();
+    let mut transaction =
+        Transaction::new_with_payer(&[allow_ix, fixture.lock_instruction(10_000)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.admin, &fixture.user], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let (mint_fees, _bump) = MintFees::find_address(&program_id, &fixture.bridge_config, &fixture.mint);
+    let account = banks_client.get_account(mint_fees).await.unwrap().unwrap();
+    assert_eq!(MintFees::try_from_slice(&account.data).unwrap().fees_per_validator, 25);
+
+    let claim_ix = |claimant: &Keypair, token_account: &Pubkey| {
+        BridgeInstruction::create_claim_validator_fees_instruction(
+            &program_id,
+            &claimant.pubkey(),
+            token_account,
+            &fixture.vault_token_account,
+            &fixture.vault_pda,
+            &fixture.bridge_config,
+            &fixture.mint,
+        )
+    };
+
+    // The vault's account holds `fixture.mint`, so it can't pay out fees of another mint
+    let other_mint_ix = BridgeInstruction::create_claim_validator_fees_instruction(
+        &program_id,
+        &validators[0].pubkey(),
+        &token_accounts[0],
+        &fixture.vault_token_account,
+        &fixture.vault_pda,
+        &fixture.bridge_config,
+        &Pubkey::new_unique(),
+    );
+    let mut transaction = Transaction::new_with_payer(&[other_mint_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &validators[0]], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+
+    let mut transaction =
+        Transaction::new_with_payer(&[claim_ix(&validators[0], &token_accounts[0])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &validators[0]], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(token_accounts[0]).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 25);
+    let account = banks_client.get_account(fixture.vault_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 9_975);
+
+    let (validator_fees, _bump) =
+        ValidatorFees::find_address(&program_id, &fixture.bridge_config, &validators[0].pubkey(), &fixture.mint);
+    let account = banks_client.get_account(validator_fees).await.unwrap().unwrap();
+    assert_eq!(ValidatorFees::try_from_slice(&account.data).unwrap().claimed, 25);
+
+    // Nothing has accrued since the first claim
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let mut transaction =
+        Transaction::new_with_payer(&[claim_ix(&validators[0], &token_accounts[0])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &validators[0]], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::NoFeesToClaim as u32))
+    );
+
+    let mut transaction =
+        Transaction::new_with_payer(&[claim_ix(&outsider, &token_accounts[1])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &outsider], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_secp256k1_validator_claims_with_a_signed_message() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let key = secp256k1_key(7);
+    let validator = secp256k1_validator(&key);
+    // 1% fee on 10_000 is 100, half of which is split between the two validators
+    let fixture = setup_lock_fixture_with_fees(
+        &mut program_test,
+        program_id,
+        10_000,
+        100,
+        5_000,
+        vec![validator, secp256k1_validator(&secp256k1_key(8))],
+    );
+
+    let mut token_accounts = Vec::new();
+    for _ in 0..2 {
+        let token_account = Pubkey::new_unique();
+        add_packed_account(
+            &mut program_test,
+            token_account,
+            spl_token::state::Account {
+                mint: fixture.mint,
+                owner: Pubkey::new_unique(),
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            },
+            spl_token::id(),
+        );
+        token_accounts.push(token_account);
+    }
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let scheme_ix = BridgeInstruction::create_update_config_instruction(
+        &program_id,
+        &fixture.admin.pubkey(),
+        &fixture.bridge_config,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(SIGNATURE_SCHEME_SECP256K1),
+    );
+    let allow_ix = fixture.allow_mint_instruction();
+    let mut transaction = Transaction::new_with_payer(
+        &[scheme_ix, allow_ix, fixture.lock_instruction(10_000)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.admin, &fixture.user], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Signed for the first token account; anyone may submit it, but only to that account
+    let message = create_claim_fees_message(&fixture.bridge_config, &validator, &fixture.mint, &token_accounts[0], 0);
+    let signature = secp256k1_sign(&key, &message);
+    let claim_ix = |token_account: &Pubkey| {
+        BridgeInstruction::create_claim_validator_fees_secp256k1_instruction(
+            &program_id,
+            &payer.pubkey(),
+            &validator,
+            signature,
+            token_account,
+            &fixture.vault_token_account,
+            &fixture.vault_pda,
+            &fixture.bridge_config,
+            &fixture.mint,
+        )
+    };
+
+    let mut transaction = Transaction::new_with_payer(&[claim_ix(&token_accounts[1])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+
+    let mut transaction = Transaction::new_with_payer(&[claim_ix(&token_accounts[0])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(token_accounts[0]).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 25);
+
+    // The claim moved `claimed` on, so the same signature can't be used again
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[claim_ix(&token_accounts[0])], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+}
+
+/// A pending lock seeded by `setup_unlock_batch_fixture`
+struct PendingLock {
+    user: Keypair,
+    user_token_account: Pubkey,
+    user_bridge_state: Pubkey,
+    nonce: u64,
+    amount: u64,
+}
+
+/// Shared config and vault with `locks` all pending against them
+struct UnlockBatchFixture {
+    program_id: Pubkey,
+    admin: Keypair,
+    relayer: Keypair,
+    validators: Vec<Keypair>,
+    mint: Pubkey,
+    vault_token_account: Pubkey,
+    vault_pda: Pubkey,
+    bridge_config: Pubkey,
+    locks: Vec<PendingLock>,
+}
+
+/// Seed a bridge config, a pending `UserBridgeState` and funded token accounts
+/// directly, so unlock tests don't depend on running `LockTokens` first.
+fn setup_unlock_fixture(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    validator_count: usize,
+    validator_threshold: u8,
+) -> UnlockFixture {
+    let mut batch = setup_unlock_batch_fixture(program_test, program_id, validator_count, validator_threshold, 1);
+    let lock = batch.locks.remove(0);
+
+    UnlockFixture {
+        program_id,
+        admin: batch.admin,
+        relayer: batch.relayer,
+        validators: batch.validators,
+        user: lock.user,
+        mint: batch.mint,
+        user_token_account: lock.user_token_account,
+        vault_token_account: batch.vault_token_account,
+        vault_pda: batch.vault_pda,
+        user_bridge_state: lock.user_bridge_state,
+        bridge_config: batch.bridge_config,
+        nonce: lock.nonce,
+        amount: lock.amount,
+    }
+}
+
+/// Like `setup_unlock_fixture`, but with `lock_count` pending locks (nonces
+/// `0..lock_count`, one user each) held by the same vault.
+fn setup_unlock_batch_fixture(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    validator_count: usize,
+    validator_threshold: u8,
+    lock_count: u64,
+) -> UnlockBatchFixture {
+    let admin = funded_keypair(program_test);
+    let relayer = Keypair::new();
+    let validators: Vec<Keypair> = (0..validator_count).map(|_| Keypair::new()).collect();
+    let mint = Pubkey::new_unique();
+    let bridge_config = Pubkey::new_unique();
+    let vault_token_account = Pubkey::new_unique();
+    let amount = 1_000_000u64;
+    let total = amount * lock_count;
+
+    let (vault_pda, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+
+    add_packed_account(
+        program_test,
+        mint,
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: total,
+            ..spl_token::state::Mint::default()
+        },
+        spl_token::id(),
+    );
+    add_packed_account(
+        program_test,
+        vault_token_account,
+        spl_token::state::Account {
+            mint,
+            owner: vault_pda,
+            amount: total,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        },
+        spl_token::id(),
+    );
+
+    add_bridge_config_account(
+        program_test,
+        bridge_config,
+        &BridgeConfig {
+            admin: admin.pubkey(),
+            vault_pda_bump: vault_bump,
+            relayer_authority: relayer.pubkey(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: total,
+            nonce: lock_count,
+            validators: validators.iter().map(|v| v.pubkey()).collect(),
+            validator_threshold,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        },
+        program_id,
+    );
+
+    let locks = (0..lock_count)
+        .map(|nonce| {
+            let user = Keypair::new();
+            let user_token_account = Pubkey::new_unique();
+            let (user_bridge_state, _bump) = Pubkey::find_program_address(
+                &[b"bridge", user.pubkey().as_ref(), &nonce.to_le_bytes()],
+                &program_id,
+            );
+
+            add_packed_account(
+                program_test,
+                user_token_account,
+                spl_token::state::Account {
+                    mint,
+                    owner: user.pubkey(),
+                    amount: 0,
+                    state: spl_token::state::AccountState::Initialized,
+                    ..spl_token::state::Account::default()
+                },
+                spl_token::id(),
+            );
+            add_borsh_account(
+                program_test,
+                user_bridge_state,
+                &UserBridgeState {
+                    user: user.pubkey(),
+                    locked_amount: amount,
+                    token_mint: mint,
+                    destination_chain: 1,
+                    destination_address: [0u8; 32],
+                    status: BridgeStatus::Pending,
+                    nonce,
+                    timestamp: 0,
+                    unlocked: false,
+                    // Never expires; expiry tests rewrite it
+                    expiry: i64::MAX,
+                    remaining_amount: amount,
+                },
+                program_id,
+            );
+
+            PendingLock {
+                user,
+                user_token_account,
+                user_bridge_state,
+                nonce,
+                amount,
+            }
+        })
+        .collect();
+
+    UnlockBatchFixture {
+        program_id,
+        admin,
+        relayer,
+        validators,
+        mint,
+        vault_token_account,
+        vault_pda,
+        bridge_config,
+        locks,
+    }
+}
+
+/// Worst-case verification load: every validator configured, every signature
+/// required. Run with `cargo test-sbf` to meter the real BPF compute cost.
+#[tokio::test]
+async fn test_unlock_with_max_validators_fits_compute_budget() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let max_validators = BridgeConfig::MAX_VALIDATORS;
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, max_validators, max_validators as u8);
+    program_test.set_compute_max_units(1_400_000);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Submit in reverse validator order so every signature has to search the remaining keys
+    let signers: Vec<&Keypair> = fixture.validators.iter().rev().collect();
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+    instructions.extend(fixture.signed_unlock_instructions(&signers));
+
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    let user_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(user_token.amount, fixture.amount);
+}
+
+#[tokio::test]
+async fn test_unlock_does_not_count_duplicate_signer() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let validator = &fixture.validators[0];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&[validator, validator]),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::ThresholdNotMet as u32))
+    );
+}
+
+#[cfg(not(feature = "in-program-ed25519"))]
+#[tokio::test]
+async fn test_unlock_with_ed25519_precompile_instruction() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[2]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert!(state.unlocked);
+    assert_eq!(state.status, BridgeStatus::Completed);
+}
+
+fn secp256k1_key(seed: u8) -> libsecp256k1::SecretKey {
+    libsecp256k1::SecretKey::parse(&[seed; 32]).unwrap()
+}
+
+/// `key`'s Ethereum address as a `SIGNATURE_SCHEME_SECP256K1` validator entry
+fn secp256k1_validator(key: &libsecp256k1::SecretKey) -> Pubkey {
+    let public_key = libsecp256k1::PublicKey::from_secret_key(key).serialize();
+    let hash = keccak::hash(&public_key[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash.0[12..]);
+    Pubkey::new_from_array(eth_address_to_bytes32(&address))
+}
+
+/// EIP-2098 compact signature: `r || s`, with the recovery id in the top bit of `s`
+fn secp256k1_sign(key: &libsecp256k1::SecretKey, message: &[u8; 32]) -> [u8; 64] {
+    let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(message), key);
+    let mut compact = signature.serialize();
+    compact[32] |= recovery_id.serialize() << 7;
+    compact
+}
+
+/// Replace the fixture's validator set
+async fn set_validators(context: &mut ProgramTestContext, fixture: &UnlockFixture, validators: Vec<Pubkey>) {
+    let mut account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let mut config = BridgeConfig::load(&account.data).unwrap();
+    config.validators = validators;
+    config.store(&mut account.data).unwrap();
+    context.set_account(&fixture.bridge_config, &account.into());
+}
+
+/// Switch the fixture's config to `scheme` through `UpdateConfig`
+async fn set_signature_scheme(
+    context: &mut ProgramTestContext,
+    fixture: &UnlockFixture,
+    scheme: u8,
+) -> Result<(), BanksClientError> {
+    let update_ix = BridgeInstruction::create_update_config_instruction(
+        &fixture.program_id,
+        &fixture.admin.pubkey(),
+        &fixture.bridge_config,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(scheme),
+    );
+    let mut transaction = Transaction::new_with_payer(&[update_ix], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+#[tokio::test]
+async fn test_unlock_with_secp256k1_validators() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    let keys: Vec<_> = (1..=3).map(secp256k1_key).collect();
+    set_validators(&mut context, &fixture, keys.iter().map(secp256k1_validator).collect()).await;
+
+    assert_eq!(
+        set_signature_scheme(&mut context, &fixture, 2).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+    set_signature_scheme(&mut context, &fixture, SIGNATURE_SCHEME_SECP256K1).await.unwrap();
+
+    let unlock_ix = fixture.secp256k1_unlock_instruction(&[&keys[2], &keys[0]]);
+    let mut transaction = Transaction::new_with_payer(&[unlock_ix], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Completed);
+    let account = context.banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, fixture.amount);
+}
+
+#[tokio::test]
+async fn test_secp256k1_scheme_rejects_ed25519_signatures() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    set_signature_scheme(&mut context, &fixture, SIGNATURE_SCHEME_SECP256K1).await.unwrap();
+
+    // Signatures the Ed25519 scheme accepts from these same validators
+    let signers = [&fixture.validators[0], &fixture.validators[2]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::ThresholdNotMet as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_ed25519_scheme_rejects_secp256k1_signatures() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    // The validator set `test_unlock_with_secp256k1_validators` unlocks with, left on Ed25519
+    let mut context = program_test.start_with_context().await;
+    let keys: Vec<_> = (1..=3).map(secp256k1_key).collect();
+    set_validators(&mut context, &fixture, keys.iter().map(secp256k1_validator).collect()).await;
+
+    let unlock_ix = fixture.secp256k1_unlock_instruction(&[&keys[2], &keys[0]]);
+    let mut transaction = Transaction::new_with_payer(&[unlock_ix], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::ThresholdNotMet as u32))
+    );
+}
+
+/// Run with `cargo test --features debug-compute` to see the checkpoints.
+#[cfg(feature = "debug-compute")]
+#[tokio::test]
+async fn test_debug_compute_logs_remaining_units() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    let result = banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    assert!(result.result.is_ok());
+
+    let logs = result.metadata.unwrap().log_messages;
+    for label in [
+        "signature verification start",
+        "signature verification end",
+        "unlock transfer start",
+        "unlock transfer end",
+    ] {
+        let checkpoint = logs
+            .iter()
+            .position(|log| log.ends_with(&format!("Compute checkpoint: {}", label)))
+            .unwrap_or_else(|| panic!("missing checkpoint {:?} in {:#?}", label, logs));
+        assert!(logs[checkpoint + 1].contains("units remaining"));
+    }
+}
+
+#[cfg(not(feature = "in-program-ed25519"))]
+#[tokio::test]
+async fn test_unlock_rejects_signatures_without_precompile_instruction() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // Genuine signatures, but nothing in the transaction had the runtime check them
+    let signatures = vec![fixture.sign(&fixture.validators[0]), fixture.sign(&fixture.validators[1])];
+    let mut transaction =
+        Transaction::new_with_payer(&[fixture.unlock_instruction(signatures)], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::ThresholdNotMet as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_refund_marks_refunded_and_releases_total_locked() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    // The fixture's lock is timestamped at 0, long past the refund timeout
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let refund_ix = BridgeInstruction::create_refund_lock_instruction(
+        &program_id,
+        &fixture.user.pubkey(),
+        &fixture.relayer.pubkey(),
+        &fixture.user_token_account,
+        &fixture.vault_token_account,
+        &fixture.vault_pda,
+        &fixture.user_bridge_state,
+        &fixture.bridge_config,
+        fixture.nonce,
+    );
+    let mut transaction = Transaction::new_with_payer(&[refund_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.user, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Refunded);
+    assert!(!state.unlocked);
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 0);
+
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    let user_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(user_token.amount, fixture.amount);
+
+    // A refunded transfer can no longer be unlocked
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], banks_client.get_latest_blockhash().await.unwrap());
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::InvalidStatus as u32))
+    );
+}
+
+/// Restamp the fixture's lock as made at `locked_at` and expiring at `expiry`,
+/// then warp the cluster clock to `now`
+async fn warp_to_expiry(context: &mut ProgramTestContext, fixture: &UnlockFixture, locked_at: i64, expiry: i64, now: i64) {
+    let mut account = context
+        .banks_client
+        .get_account(fixture.user_bridge_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    state.timestamp = locked_at;
+    state.expiry = expiry;
+    account.data = borsh::to_vec(&state).unwrap();
+    context.set_account(&fixture.user_bridge_state, &account.into());
+
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = now;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_unlock_before_expiry_succeeds() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    let locked_at = 1_700_000_000;
+    warp_to_expiry(&mut context, &fixture, locked_at, locked_at + 3_600, locked_at + 3_599).await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert!(state.unlocked);
+    assert_eq!(state.status, BridgeStatus::Completed);
+}
+
+#[tokio::test]
+async fn test_unlock_after_expiry_is_rejected_and_refundable() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    // Expired an hour in, well before `REFUND_TIMEOUT`
+    let locked_at = 1_700_000_000;
+    warp_to_expiry(&mut context, &fixture, locked_at, locked_at + 3_600, locked_at + 3_600).await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::TransferExpired as u32))
+    );
+
+    let refund_ix = BridgeInstruction::create_refund_lock_instruction(
+        &program_id,
+        &fixture.user.pubkey(),
+        &fixture.relayer.pubkey(),
+        &fixture.user_token_account,
+        &fixture.vault_token_account,
+        &fixture.vault_pda,
+        &fixture.user_bridge_state,
+        &fixture.bridge_config,
+        fixture.nonce,
+    );
+    let mut transaction = Transaction::new_with_payer(&[refund_ix], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.user, &fixture.relayer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Refunded);
+}
+
+#[tokio::test]
+async fn test_unlock_from_underfunded_vault_is_rejected() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+
+    // `total_locked` still claims the full lock, but the vault holds one token less
+    let mut account = context
+        .banks_client
+        .get_account(fixture.vault_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut vault_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    vault_token.amount = fixture.amount - 1;
+    spl_token::state::Account::pack(vault_token, &mut account.data).unwrap();
+    context.set_account(&fixture.vault_token_account, &account.into());
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            unlock_index,
+            InstructionError::Custom(BridgeError::InsufficientFunds as u32)
+        )
+    );
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert!(!state.unlocked);
+    assert_eq!(state.status, BridgeStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_unlock_rejects_vault_account_of_another_mint() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+
+    // Still the vault PDA's, and funded, but holding a different token than the lock
+    let mut account = context
+        .banks_client
+        .get_account(fixture.vault_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut vault_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    vault_token.mint = Pubkey::new_unique();
+    spl_token::state::Account::pack(vault_token, &mut account.data).unwrap();
+    context.set_account(&fixture.vault_token_account, &account.into());
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::InvalidAccountData)
+    );
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_unlock_rejects_ethereum_origin_nonce() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let mut fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+
+    // An Ethereum-origin transfer with the same sequence number as the user's Solana lock,
+    // validator-signed and presented with a record that carries its nonce
+    let ethereum_nonce = namespaced_nonce(1, fixture.nonce).unwrap();
+    assert_ne!(ethereum_nonce, fixture.nonce);
+    let mut account = context
+        .banks_client
+        .get_account(fixture.user_bridge_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    state.nonce = ethereum_nonce;
+    account.data = borsh::to_vec(&state).unwrap();
+    let (ethereum_state, _bump) = Pubkey::find_program_address(
+        &[b"bridge", fixture.user.pubkey().as_ref(), &ethereum_nonce.to_le_bytes()],
+        &program_id,
+    );
+    context.set_account(&ethereum_state, &account.into());
+
+    let solana_state = std::mem::replace(&mut fixture.user_bridge_state, ethereum_state);
+    fixture.nonce = ethereum_nonce;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::InvalidNonce as u32))
+    );
+
+    // The Solana lock itself is untouched
+    let account = context.banks_client.get_account(solana_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Pending);
+    let account = context.banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, 0);
+}
+
+#[tokio::test]
+async fn test_unlock_accepts_any_allowlisted_relayer() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let mut fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    let backup_relayer = Keypair::new();
+    let outsider = Keypair::new();
+
+    // Only the admin manages the allowlist
+    let add_ix = |admin: &Keypair| {
+        BridgeInstruction::create_add_relayer_authority_instruction(
+            &program_id,
+            &admin.pubkey(),
+            &fixture.bridge_config,
+            &backup_relayer.pubkey(),
+        )
+    };
+    let mut transaction = Transaction::new_with_payer(&[add_ix(&outsider)], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &outsider], context.last_blockhash);
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+
+    let mut transaction = Transaction::new_with_payer(&[add_ix(&fixture.admin)], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.relayer_authorities, vec![backup_relayer.pubkey()]);
+    assert!(config.is_relayer(&fixture.relayer.pubkey()));
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    fixture.relayer = outsider;
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+
+    // The primary relayer key is not needed while another allowlisted one is
+    fixture.relayer = backup_relayer;
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Completed);
+}
+
+#[tokio::test]
+async fn test_removed_relayer_can_no_longer_unlock() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let mut fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let mut context = program_test.start_with_context().await;
+    let backup_relayer = Keypair::new();
+
+    let instructions = [
+        BridgeInstruction::create_add_relayer_authority_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            &backup_relayer.pubkey(),
+        ),
+        BridgeInstruction::create_remove_relayer_authority_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            &backup_relayer.pubkey(),
+        ),
+    ];
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert!(config.relayer_authorities.is_empty());
+
+    // The primary relayer is rotated with UpdateConfig, not removed
+    let remove_primary = BridgeInstruction::create_remove_relayer_authority_instruction(
+        &program_id,
+        &fixture.admin.pubkey(),
+        &fixture.bridge_config,
+        &fixture.relayer.pubkey(),
+    );
+    let mut transaction = Transaction::new_with_payer(&[remove_primary], Some(&context.payer.pubkey()));
+    transaction.sign(&[&context.payer, &fixture.admin], context.last_blockhash);
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    fixture.relayer = backup_relayer;
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, &fixture.relayer], context.last_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+    assert_eq!(
+        context.banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(unlock_index, InstructionError::Custom(BridgeError::Unauthorized as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_only_admin_can_cancel_lock() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let cancel_ix = |signer: &Pubkey| {
+        BridgeInstruction::create_cancel_lock_instruction(
+            &program_id,
+            signer,
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &fixture.vault_pda,
+            &fixture.user_bridge_state,
+            &fixture.bridge_config,
+            fixture.nonce,
+        )
+    };
+
+    // Neither the relayer nor the user may cancel
+    for impostor in [&fixture.relayer, &fixture.user] {
+        let mut transaction = Transaction::new_with_payer(&[cancel_ix(&impostor.pubkey())], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, impostor], recent_blockhash);
+        let result = banks_client.process_transaction(transaction).await;
+        assert_eq!(
+            result.unwrap_err().unwrap(),
+            TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::Unauthorized as u32))
+        );
+    }
+
+    let mut transaction = Transaction::new_with_payer(&[cancel_ix(&fixture.admin.pubkey())], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.admin], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Cancelled);
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 0);
+
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    let user_token = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(user_token.amount, fixture.amount);
+
+    // Already cancelled; the compute budget instruction keeps the transaction from
+    // being deduplicated against the first cancel
+    let mut transaction = Transaction::new_with_payer(
+        &[cancel_ix(&fixture.admin.pubkey()), ComputeBudgetInstruction::set_compute_unit_limit(200_001)],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.admin], banks_client.get_latest_blockhash().await.unwrap());
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::InvalidStatus as u32))
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_and_unlock_only_pay_the_locking_user() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    // Same mint, but the admin's account rather than the user's
+    let admin_token_account = Pubkey::new_unique();
+    add_packed_account(
+        &mut program_test,
+        admin_token_account,
+        spl_token::state::Account {
+            mint: fixture.mint,
+            owner: fixture.admin.pubkey(),
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        },
+        spl_token::id(),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let cancel_ix = BridgeInstruction::create_cancel_lock_instruction(
+        &program_id,
+        &fixture.admin.pubkey(),
+        &fixture.user.pubkey(),
+        &admin_token_account,
+        &fixture.vault_token_account,
+        &fixture.vault_pda,
+        &fixture.user_bridge_state,
+        &fixture.bridge_config,
+        fixture.nonce,
+    );
+    let mut transaction = Transaction::new_with_payer(&[cancel_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.admin], recent_blockhash);
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::IncorrectOwner as u32))
+    );
+
+    let redirected = UnlockFixture {
+        user_token_account: admin_token_account,
+        ..fixture
+    };
+    let signers = [&redirected.validators[0], &redirected.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &redirected.signed_unlock_instructions(&signers),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &redirected.relayer], recent_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+    assert_eq!(
+        banks_client.process_transaction(transaction).await.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            unlock_index,
+            InstructionError::Custom(BridgeError::IncorrectOwner as u32)
+        )
+    );
+
+    let account = banks_client.get_account(redirected.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.status, BridgeStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_unlock_batch_releases_three_transfers() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    // Threshold 1 keeps three items and their Ed25519 checks inside one packet
+    let fixture = setup_unlock_batch_fixture(&mut program_test, program_id, 2, 1, 3);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let validator = &fixture.validators[0];
+    let mut instructions = Vec::new();
+    let mut items = Vec::new();
+    let mut recipients = Vec::new();
+
+    for lock in &fixture.locks {
+        let message = create_unlock_message(lock.nonce, &lock.user.pubkey(), &fixture.mint, lock.amount);
+        let signature: [u8; 64] = validator.sign_message(&message).into();
+
+        #[cfg(not(feature = "in-program-ed25519"))]
+        instructions.push(BridgeInstruction::create_ed25519_verify_instruction(
+            &message,
+            &[(validator.pubkey(), signature)],
+        ));
+
+        items.push((lock.nonce, lock.amount, vec![signature]));
+        recipients.push((lock.user.pubkey(), lock.user_token_account, lock.user_bridge_state));
+    }
+
+    instructions.push(BridgeInstruction::create_unlock_batch_instruction(
+        &fixture.program_id,
+        &fixture.relayer.pubkey(),
+        &fixture.vault_token_account,
+        &fixture.vault_pda,
+        &fixture.bridge_config,
+        &recipients,
+        items,
+    ));
+
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    for lock in &fixture.locks {
+        let account = banks_client.get_account(lock.user_bridge_state).await.unwrap().unwrap();
+        let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+        assert!(state.unlocked);
+        assert_eq!(state.status, BridgeStatus::Completed);
+
+        let account = banks_client.get_account(lock.user_token_account).await.unwrap().unwrap();
+        let user_token = spl_token::state::Account::unpack(&account.data).unwrap();
+        assert_eq!(user_token.amount, lock.amount);
+    }
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    let config = BridgeConfig::load(&account.data).unwrap();
+    assert_eq!(config.total_locked, 0);
+}
+
+#[tokio::test]
+async fn test_partial_unlock_in_two_parts() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let first = fixture.amount * 2 / 5;
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_partial_unlock_instructions(&signers, first, fixture.amount),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.remaining_amount, fixture.amount - first);
+    assert!(!state.unlocked);
+    assert_eq!(state.status, BridgeStatus::Pending);
+
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, first);
+
+    let second = fixture.amount - first;
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_partial_unlock_instructions(&signers, second, second),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.remaining_amount, 0);
+    assert!(state.unlocked);
+    assert_eq!(state.status, BridgeStatus::Completed);
+
+    let account = banks_client.get_account(fixture.user_token_account).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Account::unpack(&account.data).unwrap().amount, fixture.amount);
+
+    let account = banks_client.get_account(fixture.bridge_config).await.unwrap().unwrap();
+    assert_eq!(BridgeConfig::load(&account.data).unwrap().total_locked, 0);
+}
+
+#[tokio::test]
+async fn test_partial_unlock_rejects_over_unlock() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_partial_unlock_instructions(&signers, fixture.amount + 1, fixture.amount),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    let unlock_index = transaction.message.instructions.len() as u8 - 1;
+
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            unlock_index,
+            InstructionError::Custom(BridgeError::ExceedsRemainingAmount as u32)
+        )
+    );
+
+    let account = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap();
+    let state = UserBridgeState::try_from_slice(&account.data).unwrap();
+    assert_eq!(state.remaining_amount, fixture.amount);
+}
+
+#[tokio::test]
+async fn test_close_bridge_state_returns_rent_to_user() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "bridge_vault",
+        program_id,
+        processor!(bridge_vault::process_instruction),
+    );
+    let fixture = setup_unlock_fixture(&mut program_test, program_id, 3, 2);
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let close = BridgeInstruction::create_close_bridge_state_instruction(
+        &program_id,
+        &fixture.user.pubkey(),
+        &fixture.user_bridge_state,
+        fixture.nonce,
+    );
+
+    // Still pending: the state has to stay
+    let mut transaction = Transaction::new_with_payer(&[close.clone()], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.user], recent_blockhash);
+    let result = banks_client.process_transaction(transaction).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(BridgeError::InvalidStatus as u32))
+    );
+
+    let signers = [&fixture.validators[0], &fixture.validators[1]];
+    let mut transaction = Transaction::new_with_payer(
+        &fixture.signed_unlock_instructions(&signers),
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &fixture.relayer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let rent = banks_client.get_account(fixture.user_bridge_state).await.unwrap().unwrap().lamports;
+    let user_lamports = |account: Option<Account>| account.map_or(0, |account| account.lamports);
+    let before = user_lamports(banks_client.get_account(fixture.user.pubkey()).await.unwrap());
+
+    let recent_blockhash = banks_client.get_new_latest_blockhash(&recent_blockhash).await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[close], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &fixture.user], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let after = user_lamports(banks_client.get_account(fixture.user.pubkey()).await.unwrap());
+    assert_eq!(after, before + rent);
+    assert!(banks_client.get_account(fixture.user_bridge_state).await.unwrap().is_none());
+}
+
+/// Compute budgets for the main instructions, checked against the BPF build so that
+/// regressions fail a test instead of a mainnet transaction. Only meaningful under
+/// `cargo test-sbf`: natively built programs aren't metered. Raise a budget in the
+/// same change that makes an instruction more expensive, never on its own.
+#[cfg(feature = "test-sbf")]
+mod compute_budget {
+    use super::*;
+
+    // Estimates rather than measured costs; confirm them against a `cargo test-sbf` run
+    const LOCK_BUDGET: u64 = 60_000;
+    const UNLOCK_MAX_VALIDATORS_BUDGET: u64 = 80_000;
+    const UPDATE_CONFIG_BUDGET: u64 = 10_000;
+
+    /// Units `instructions` consume when simulated, with enough compute allowed that
+    /// an over-budget instruction still reports its cost instead of failing
+    async fn units_consumed(
+        banks_client: &mut BanksClient,
+        payer: &Keypair,
+        signers: &[&Keypair],
+        recent_blockhash: solana_sdk::hash::Hash,
+        instructions: Vec<Instruction>,
+    ) -> u64 {
+        let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        all_instructions.extend(instructions);
+        let mut transaction = Transaction::new_with_payer(&all_instructions, Some(&payer.pubkey()));
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        transaction.sign(&all_signers, recent_blockhash);
+
+        let simulation = banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        simulation.simulation_details.unwrap().units_consumed
+    }
+
+    fn assert_within_budget(instruction: &str, units: u64, budget: u64) {
+        assert!(units <= budget, "{} used {} compute units, over its budget of {}", instruction, units, budget);
+    }
+
+    #[tokio::test]
+    async fn test_lock_compute_budget() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new("bridge_vault", program_id, None);
+        let fixture = setup_lock_fixture(&mut program_test, program_id, 10_000);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        fixture.allow_mint(&mut banks_client, &payer, recent_blockhash).await;
+
+        let units = units_consumed(
+            &mut banks_client,
+            &payer,
+            &[&fixture.user],
+            recent_blockhash,
+            vec![fixture.lock_instruction(10_000)],
+        )
+        .await;
+        assert_within_budget("lock", units, LOCK_BUDGET);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_at_max_validators_compute_budget() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new("bridge_vault", program_id, None);
+        let max_validators = BridgeConfig::MAX_VALIDATORS;
+        let fixture = setup_unlock_fixture(&mut program_test, program_id, max_validators, max_validators as u8);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        // Reverse validator order, so every signature searches the remaining keys
+        let signers: Vec<&Keypair> = fixture.validators.iter().rev().collect();
+        let units = units_consumed(
+            &mut banks_client,
+            &payer,
+            &[&fixture.relayer],
+            recent_blockhash,
+            fixture.signed_unlock_instructions(&signers),
+        )
+        .await;
+        assert_within_budget("unlock", units, UNLOCK_MAX_VALIDATORS_BUDGET);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_compute_budget() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new("bridge_vault", program_id, None);
+        let fixture = setup_lock_fixture(&mut program_test, program_id, 0);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let update_ix = BridgeInstruction::create_update_config_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            None,
+            Some(Pubkey::new_unique()),
+            Some(100),
+            Some(BridgeConfig::ALL_CHAINS),
+            Some(3_600),
+            Some(5_000),
+            Some(1_000_000),
+            Some(SIGNATURE_SCHEME_ED25519),
+        );
+        let units =
+            units_consumed(&mut banks_client, &payer, &[&fixture.admin], recent_blockhash, vec![update_ix]).await;
+        assert_within_budget("update_config", units, UPDATE_CONFIG_BUDGET);
+    }
+}
@@ -0,0 +1,264 @@
+//! Token amounts with the decimals they are denominated in. Solana's token and the
+//! wrapped token on Ethereum need not share decimals, so amounts moving between chains
+//! are rescaled here instead of being copied across as bare integers.
+
+use crate::{
+    config::{EthereumConfig, SolanaConfig},
+    error::{RelayerError, Result},
+    types::{Chain, RelayerTransaction},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef},
+    Decode, Encode, Sqlite, Type,
+};
+use std::{fmt, str::FromStr};
+
+/// Most decimals an amount can carry; `10^38` is the largest power of ten in a `u128`
+pub const MAX_DECIMALS: u8 = 38;
+
+/// `raw` base units of a token with `decimals` decimals, so `Amount::new(1_500, 3)` is 1.5.
+///
+/// Written as a decimal string with exactly `decimals` fractional digits (`"1.500"`),
+/// which keeps the decimals when stored or sent as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Amount {
+    pub raw: u128,
+    pub decimals: u8,
+}
+
+impl Amount {
+    pub const fn new(raw: u128, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// The same amount in base units of a token with `decimals` decimals. Fails rather than
+    /// rounding when dropping decimals would lose a nonzero digit, or when it overflows.
+    pub fn rescale(self, decimals: u8) -> Result<Self> {
+        let out_of_range =
+            || RelayerError::ParseError(format!("{} cannot be expressed with {} decimals", self, decimals));
+        let factor = |digits: u8| 10u128.checked_pow(u32::from(digits)).ok_or_else(out_of_range);
+
+        let raw = if decimals >= self.decimals {
+            self.raw.checked_mul(factor(decimals - self.decimals)?).ok_or_else(out_of_range)?
+        } else {
+            let divisor = factor(self.decimals - decimals)?;
+            if self.raw % divisor != 0 {
+                return Err(out_of_range());
+            }
+            self.raw / divisor
+        };
+        Ok(Self::new(raw, decimals))
+    }
+
+    /// Base units as the `u64` the bridge program and message hashes take
+    pub fn to_u64(self) -> Result<u64> {
+        u64::try_from(self.raw).map_err(|_| RelayerError::ParseError(format!("{} overflows u64 base units", self)))
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = format!("{:0>width$}", self.raw, width = usize::from(self.decimals) + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - usize::from(self.decimals));
+        if fraction.is_empty() {
+            write!(f, "{}", whole)
+        } else {
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+impl FromStr for Amount {
+    type Err = RelayerError;
+
+    /// Parse a decimal string; its fractional digits give the decimals, so `"1.50"` is
+    /// 150 base units of a two-decimal token
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || RelayerError::ParseError(format!("Invalid amount {:?}", s));
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) || (s.contains('.') && fraction.is_empty()) {
+            return Err(invalid());
+        }
+
+        let decimals = u8::try_from(fraction.len()).ok().filter(|d| *d <= MAX_DECIMALS).ok_or_else(invalid)?;
+        let raw = format!("{}{}", whole, fraction).parse::<u128>().map_err(|_| invalid())?;
+        Ok(Self::new(raw, decimals))
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Stored as TEXT in the same decimal form, so a u128 never has to fit SQLite's INTEGER
+impl Type<Sqlite> for Amount {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <String as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Amount {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> std::result::Result<IsNull, BoxDynError> {
+        <String as Encode<'q, Sqlite>>::encode(self.to_string(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Amount {
+    fn decode(value: SqliteValueRef<'r>) -> std::result::Result<Self, BoxDynError> {
+        Ok(<&str as Decode<'r, Sqlite>>::decode(value)?.parse()?)
+    }
+}
+
+/// Decimals of the bridged token on each chain. Amounts cross unscaled unless decimals are
+/// configured for the destination; validators must be configured the same as the relayer,
+/// since they check the scaled amount the relayer asks them to sign.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenDecimals {
+    pub solana: Option<u8>,
+    pub ethereum: Option<u8>,
+}
+
+impl TokenDecimals {
+    pub fn from_configs(solana: &SolanaConfig, ethereum: &EthereumConfig) -> Self {
+        Self {
+            solana: solana.token_decimals,
+            ethereum: ethereum.token_decimals,
+        }
+    }
+
+    pub fn on(&self, chain: Chain) -> Option<u8> {
+        match chain {
+            Chain::Solana => self.solana,
+            Chain::Ethereum => self.ethereum,
+            Chain::Sui => None,
+        }
+    }
+
+    /// `raw` base units on `from`. Without decimals for `from` they are taken to be the
+    /// destination's, so the amount crosses unscaled.
+    pub fn source_amount(&self, raw: u64, from: Chain, to: Chain) -> Amount {
+        let decimals = self.on(from).or(self.on(to)).unwrap_or(0);
+        Amount::new(u128::from(raw), decimals)
+    }
+
+    /// `tx`'s net amount as recorded on the source chain; rows recorded without decimals
+    /// are read as in [`TokenDecimals::source_amount`]
+    pub fn net_amount(&self, tx: &RelayerTransaction) -> Amount {
+        tx.net_amount
+            .unwrap_or_else(|| self.source_amount(tx.amount as u64, tx.from_chain, tx.to_chain))
+    }
+
+    /// `amount` in base units of the token on `to`
+    pub fn delivered(&self, amount: Amount, to: Chain) -> Result<u64> {
+        match self.on(to) {
+            Some(decimals) => amount.rescale(decimals)?.to_u64(),
+            None => amount.to_u64(),
+        }
+    }
+
+    /// What `tx` releases on its destination, in that token's base units
+    pub fn delivered_amount(&self, tx: &RelayerTransaction) -> Result<u64> {
+        self.delivered(self.net_amount(tx), tx.to_chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescale() {
+        let one_sol = Amount::new(1_000_000_000, 9);
+        assert_eq!(one_sol.rescale(18).unwrap(), Amount::new(10u128.pow(18), 18));
+        assert_eq!(one_sol.rescale(6).unwrap(), Amount::new(1_000_000, 6));
+        assert_eq!(one_sol.rescale(9).unwrap(), one_sol);
+        assert_eq!(Amount::new(10u128.pow(18), 18).rescale(9).unwrap(), one_sol);
+
+        // Dropping a nonzero digit or overflowing is an error, never a rounding
+        assert!(Amount::new(1_000_000_001, 9).rescale(6).is_err());
+        assert!(Amount::new(u128::MAX, 0).rescale(1).is_err());
+        assert!(Amount::new(1, 0).rescale(39).is_err());
+
+        assert_eq!(Amount::new(u128::from(u64::MAX), 0).to_u64().unwrap(), u64::MAX);
+        assert!(Amount::new(u128::from(u64::MAX) + 1, 0).to_u64().is_err());
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        for (amount, text) in [
+            (Amount::new(1_500, 3), "1.500"),
+            (Amount::new(5, 9), "0.000000005"),
+            (Amount::new(0, 2), "0.00"),
+            (Amount::new(42, 0), "42"),
+            (Amount::new(u128::MAX, 38), "3.40282366920938463463374607431768211455"),
+        ] {
+            assert_eq!(amount.to_string(), text);
+            assert_eq!(text.parse::<Amount>().unwrap(), amount);
+            assert_eq!(serde_json::to_string(&amount).unwrap(), format!("\"{}\"", text));
+            assert_eq!(serde_json::from_str::<Amount>(&format!("\"{}\"", text)).unwrap(), amount);
+        }
+
+        for invalid in ["", ".5", "1.", "-1", "1.2.3", "1e9", " 1", "0x10", "340282366920938463463374607431768211456"] {
+            assert!(invalid.parse::<Amount>().is_err(), "{:?}", invalid);
+        }
+        assert!(serde_json::from_str::<Amount>("1500").is_err(), "numbers lose the decimals");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_round_trip() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let amount = Amount::new(u128::MAX, 18);
+        let (stored, text): (Amount, String) = sqlx::query_as("SELECT ?, ?")
+            .bind(amount)
+            .bind(amount)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, amount);
+        assert_eq!(text, "340282366920938463463.374607431768211455");
+    }
+
+    #[test]
+    fn test_token_decimals_scale_only_when_destination_is_configured() {
+        let unset = TokenDecimals::default();
+        let amount = unset.source_amount(1_500, Chain::Solana, Chain::Ethereum);
+        assert_eq!(unset.delivered(amount, Chain::Ethereum).unwrap(), 1_500);
+
+        let decimals = TokenDecimals {
+            solana: Some(9),
+            ethereum: Some(18),
+        };
+        let locked = decimals.source_amount(1_500, Chain::Solana, Chain::Ethereum);
+        assert_eq!(locked, Amount::new(1_500, 9));
+        assert_eq!(decimals.delivered(locked, Chain::Ethereum).unwrap(), 1_500_000_000_000);
+        let burned = decimals.source_amount(1_500_000_000_000, Chain::Ethereum, Chain::Solana);
+        assert_eq!(decimals.delivered(burned, Chain::Solana).unwrap(), 1_500);
+        // Dust below Solana's precision can't be delivered
+        let dust = decimals.source_amount(1_500_000_000_001, Chain::Ethereum, Chain::Solana);
+        assert!(decimals.delivered(dust, Chain::Solana).is_err());
+
+        // Only Ethereum configured: Solana amounts are read with its decimals, so unscaled
+        let ethereum_only = TokenDecimals {
+            solana: None,
+            ethereum: Some(18),
+        };
+        let locked = ethereum_only.source_amount(1_500, Chain::Solana, Chain::Ethereum);
+        assert_eq!(ethereum_only.delivered(locked, Chain::Ethereum).unwrap(), 1_500);
+    }
+}
@@ -0,0 +1,738 @@
+use crate::{
+    attestation::{self, Attestation},
+    config::{redact_urls_in, EthereumConfig, SolanaConfig, ValidatorConfig},
+    db::{Database, VolumeStats},
+    error::{RelayerError, Result},
+    failover::{EndpointHealth, EthereumRpc, SolanaRpc},
+    retry::with_timeout,
+    types::{Chain, RelayerTransaction},
+};
+use alloy::providers::Provider;
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::StatusCode,
+    response::Response,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+/// Close code `/ws/tx/:nonce` ends with when the relayer has no transfer with that nonce
+const CLOSE_UNKNOWN_NONCE: u16 = 4404;
+
+/// Most transfers one `GET /txs` page returns
+const HISTORY_LIMIT: u32 = 100;
+
+/// Window `GET /stats/volume` covers when none is given
+const DEFAULT_VOLUME_WINDOW: Duration = Duration::from_secs(24 * 3600);
+
+/// A subsystem that takes longer than this to answer `/health` is reported down
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shared state for the relayer HTTP API
+#[derive(Clone)]
+pub struct ApiState {
+    db: Database,
+    solana_client: Arc<SolanaRpc>,
+    ethereum_provider: Arc<EthereumRpc>,
+    attestation_signer: Option<Arc<Keypair>>,
+    /// Probed by `GET /validators`
+    validators: Arc<Vec<ValidatorConfig>>,
+}
+
+impl ApiState {
+    pub fn new(
+        solana_config: &SolanaConfig,
+        ethereum_config: &EthereumConfig,
+        db: Database,
+        attestation_signer: Option<Keypair>,
+    ) -> Result<Self> {
+        let solana_client = SolanaRpc::solana(
+            &solana_config.rpc_urls,
+            CommitmentConfig::from_str(&solana_config.commitment)
+                .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?,
+        )?;
+
+        let ethereum_provider = EthereumRpc::ethereum(&ethereum_config.rpc_urls)?;
+
+        Ok(Self {
+            db,
+            solana_client: Arc::new(solana_client),
+            ethereum_provider: Arc::new(ethereum_provider),
+            attestation_signer: attestation_signer.map(Arc::new),
+            validators: Arc::new(Vec::new()),
+        })
+    }
+
+    /// Report on `validators` from `GET /validators`
+    pub fn with_validators(mut self, validators: Vec<ValidatorConfig>) -> Self {
+        self.validators = Arc::new(validators);
+        self
+    }
+}
+
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/tx/:nonce", get(get_transaction))
+        .route("/txs", get(list_transactions))
+        .route("/ws/tx/:nonce", get(watch_transaction))
+        .route("/attestation/:nonce", get(get_attestation))
+        .route("/validators", get(validators))
+        .route("/stats/volume", get(volume_stats))
+        .with_state(state)
+}
+
+/// Serve the API until the task is cancelled
+pub async fn serve(listen_addr: &str, state: ApiState) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(listen_addr)
+        .await
+        .map_err(|e| RelayerError::ConfigError(format!("Failed to bind API on {}: {}", listen_addr, e)))?;
+
+    info!("API listening on {}", listen_addr);
+
+    axum::serve(listener, router(state))
+        .await
+        .map_err(|e| RelayerError::NetworkError(format!("API server error: {}", e)))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubsystemHealth {
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// Per-endpoint failover state for RPC subsystems
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub solana: SubsystemHealth,
+    pub ethereum: SubsystemHealth,
+    pub database: SubsystemHealth,
+}
+
+/// One configured validator and how its `/health` endpoint answered just now
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidatorHealth {
+    pub name: String,
+    pub eth_address: String,
+    pub sol_public_key: String,
+    pub healthy: bool,
+    /// `None` when the validator has no endpoint to probe
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// `GET /validators` - probe every configured validator's `/health` concurrently
+async fn validators(State(state): State<ApiState>) -> Json<Vec<ValidatorHealth>> {
+    let http = reqwest::Client::new();
+    let reports = state.validators.iter().map(|validator| {
+        let http = http.clone();
+        async move {
+            let (healthy, latency_ms, error) = match validator.endpoint.as_deref() {
+                Some(endpoint) => {
+                    let url = format!("{}/health", endpoint.trim_end_matches('/'));
+                    let health = probe(async move {
+                        http.get(&url)
+                            .send()
+                            .await
+                            .and_then(|response| response.error_for_status())
+                            .map(|_| ())
+                            .map_err(|e| redact_urls_in(&e.to_string()))
+                    })
+                    .await;
+                    (health.healthy, Some(health.latency_ms), health.error)
+                }
+                None => (false, None, Some("no endpoint configured".to_string())),
+            };
+            ValidatorHealth {
+                name: validator.name.clone(),
+                eth_address: validator.eth_address.clone(),
+                sol_public_key: validator.sol_public_key.clone(),
+                healthy,
+                latency_ms,
+                error,
+            }
+        }
+    });
+
+    Json(futures::future::join_all(reports).await)
+}
+
+/// `GET /health` - 200 only when the Solana RPC, Ethereum RPC and database all respond.
+/// RPC checks go through the failover clients, so a healthy secondary endpoint counts.
+/// Errors are served unauthenticated, so URLs in them are cut down to scheme and host.
+async fn health(State(state): State<ApiState>) -> (StatusCode, Json<HealthReport>) {
+    let (mut solana, mut ethereum, database) = tokio::join!(
+        probe(async {
+            state
+                .solana_client
+                .call(|client| async move { client.get_slot().await })
+                .await
+                .map(|_| ())
+                .map_err(|e| redact_urls_in(&e.to_string()))
+        }),
+        probe(async {
+            state
+                .ethereum_provider
+                .call(|provider| async move { provider.get_block_number().await })
+                .await
+                .map(|_| ())
+                .map_err(|e| redact_urls_in(&e.to_string()))
+        }),
+        probe(async { state.db.ping().await.map_err(|e| redact_urls_in(&e.to_string())) }),
+    );
+    solana.endpoints = state.solana_client.health();
+    ethereum.endpoints = state.ethereum_provider.health();
+
+    let healthy = solana.healthy && ethereum.healthy && database.healthy;
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        warn!(
+            "Health check failed: solana={:?}, ethereum={:?}, database={:?}",
+            solana.error, ethereum.error, database.error
+        );
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(HealthReport {
+            healthy,
+            solana,
+            ethereum,
+            database,
+        }),
+    )
+}
+
+/// A transfer as shown to users. `amount` is what the recipient gets;
+/// `gross_amount - fee_amount == amount`.
+#[derive(Debug, Serialize)]
+pub struct TxResponse {
+    pub nonce: u64,
+    pub from_chain: String,
+    pub to_chain: String,
+    pub from_tx_hash: String,
+    pub to_tx_hash: Option<String>,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: u64,
+    pub gross_amount: u64,
+    pub fee_amount: u64,
+    /// Lowercase status name, e.g. `signaturescollected`
+    pub status: String,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RelayerTransaction> for TxResponse {
+    fn from(tx: RelayerTransaction) -> Self {
+        Self {
+            nonce: tx.nonce as u64,
+            from_chain: tx.from_chain.to_string(),
+            to_chain: tx.to_chain.to_string(),
+            from_tx_hash: tx.from_tx_hash,
+            to_tx_hash: tx.to_tx_hash,
+            sender: tx.sender,
+            recipient: tx.recipient,
+            amount: tx.amount as u64,
+            gross_amount: tx.gross_amount as u64,
+            fee_amount: tx.fee_amount as u64,
+            status: tx.status.to_string().to_lowercase(),
+            error_message: tx.error_message,
+            created_at: tx.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TxsListResponse {
+    pub transactions: Vec<TxResponse>,
+    pub total: usize,
+    /// `before` for the next page; absent on the last one
+    pub next_before: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryQuery {
+    /// Only transfers this address sent or received
+    pub user: Option<String>,
+    /// Only transfers the relayer recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Cursor from the previous page's `next_before`
+    pub before: Option<i64>,
+    /// Page size, capped at `HISTORY_LIMIT`
+    pub limit: Option<u32>,
+}
+
+/// Picks one of the transfers sharing a nonce, since each source chain numbers its own
+#[derive(Debug, Default, Deserialize)]
+pub struct NonceQuery {
+    /// Chain the transfer started on, as in `TxResponse::from_chain`; Solana when absent
+    pub from_chain: Option<Chain>,
+}
+
+impl NonceQuery {
+    fn from_chain(&self) -> Chain {
+        self.from_chain.unwrap_or(Chain::Solana)
+    }
+}
+
+/// `GET /tx/:nonce?from_chain=` - one transfer, 404 if the relayer hasn't seen the nonce
+async fn get_transaction(
+    State(state): State<ApiState>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<NonceQuery>,
+) -> std::result::Result<Json<TxResponse>, StatusCode> {
+    match state.db.get_transaction_by_nonce(query.from_chain(), nonce).await {
+        Ok(Some(tx)) => Ok(Json(tx.into())),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            warn!("Failed to load transaction nonce={}: {}", nonce, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// `GET /txs?user=&since=&before=&limit=` - most recent transfers, newest first, a page at a time
+async fn list_transactions(
+    State(state): State<ApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> std::result::Result<Json<TxsListResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(HISTORY_LIMIT).clamp(1, HISTORY_LIMIT);
+    let txs = state
+        .db
+        .get_transaction_history(query.user.as_deref(), query.since, query.before, limit)
+        .await
+        .map_err(|e| {
+            warn!("Failed to load transaction history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let next_before = match txs.last() {
+        Some(tx) if txs.len() == limit as usize => Some(tx.id),
+        _ => None,
+    };
+    let transactions: Vec<TxResponse> = txs.into_iter().map(TxResponse::from).collect();
+    Ok(Json(TxsListResponse {
+        total: transactions.len(),
+        transactions,
+        next_before,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct VolumeQuery {
+    /// How far back to count, in seconds; defaults to `DEFAULT_VOLUME_WINDOW`
+    pub window_secs: Option<u64>,
+}
+
+/// `GET /stats/volume?window_secs=` - gross, net and fee volume and transfer count per direction
+async fn volume_stats(
+    State(state): State<ApiState>,
+    Query(query): Query<VolumeQuery>,
+) -> std::result::Result<Json<VolumeStats>, StatusCode> {
+    let window = query.window_secs.map(Duration::from_secs).unwrap_or(DEFAULT_VOLUME_WINDOW);
+    state.db.get_volume_stats(window).await.map(Json).map_err(|e| {
+        warn!("Failed to load volume stats: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// `GET /attestation/:nonce?from_chain=` - the transfer's source event, validator signatures
+/// and destination tx hash, signed by the relayer. 503 when no signing key is configured.
+async fn get_attestation(
+    State(state): State<ApiState>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<NonceQuery>,
+) -> std::result::Result<Json<Attestation>, StatusCode> {
+    let signer = state.attestation_signer.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let tx = match state.db.get_transaction_by_nonce(query.from_chain(), nonce).await {
+        Ok(Some(tx)) => tx,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            warn!("Failed to load transaction nonce={}: {}", nonce, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    attestation::attest(&tx, signer).map(Json).map_err(|e| {
+        warn!("Failed to attest nonce={}: {}", nonce, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// `GET /ws/tx/:nonce?from_chain=` - WebSocket sending the transfer (as in `GET /tx/:nonce`)
+/// now and after every status change, closing once it reaches a terminal state.
+/// An unknown nonce is closed at once with `CLOSE_UNKNOWN_NONCE`.
+async fn watch_transaction(
+    State(state): State<ApiState>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<NonceQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let from_chain = query.from_chain();
+    ws.on_upgrade(move |socket| stream_status(socket, state.db, from_chain, nonce))
+}
+
+async fn stream_status(mut socket: WebSocket, db: Database, from_chain: Chain, nonce: u64) {
+    // Subscribe before the first read so no update can slip in between
+    let mut updates = db.subscribe_status_updates();
+    let mut check_db = true;
+    let mut found = false;
+
+    loop {
+        if check_db {
+            match db.get_transaction_by_nonce(from_chain, nonce).await {
+                Ok(Some(tx)) => {
+                    found = true;
+                    let terminal = tx.status.is_terminal();
+                    if !send_tx(&mut socket, tx).await || terminal {
+                        break;
+                    }
+                }
+                Ok(None) if !found => {
+                    let close = CloseFrame {
+                        code: CLOSE_UNKNOWN_NONCE,
+                        reason: format!("no transfer with nonce {}", nonce).into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(close))).await;
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to load transaction nonce={} for watcher: {}", nonce, e);
+                    break;
+                }
+            }
+        }
+
+        // Read the socket too, so a client that goes away ends the watcher now rather
+        // than at the next update that fails to send
+        check_db = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => update.from_chain == from_chain && update.nonce == nonce,
+                // Missed some updates; the DB has the latest state
+                Err(RecvError::Lagged(_)) => true,
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                    debug!("Status watcher for nonce {} disconnected", nonce);
+                    return;
+                }
+                Some(Ok(_)) => false,
+            },
+        };
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// Returns false once the client has gone away
+async fn send_tx(socket: &mut WebSocket, tx: RelayerTransaction) -> bool {
+    let body = match serde_json::to_string(&TxResponse::from(tx)) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to encode transaction update: {}", e);
+            return false;
+        }
+    };
+
+    match socket.send(Message::Text(body)).await {
+        Ok(()) => true,
+        Err(e) => {
+            debug!("Status watcher disconnected: {}", e);
+            false
+        }
+    }
+}
+
+async fn probe<F>(check: F) -> SubsystemHealth
+where
+    F: Future<Output = std::result::Result<(), String>>,
+{
+    let started = Instant::now();
+    let result = with_timeout(PROBE_TIMEOUT, "health probe", check)
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    SubsystemHealth {
+        healthy: result.is_ok(),
+        latency_ms,
+        error: result.err(),
+        endpoints: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance_status, eth_tx_hash, sol_tx_signature};
+    use crate::types::{Chain, TransactionStatus};
+    use axum::routing::post;
+    use futures::StreamExt;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+    use serde_json::{json, Value};
+
+    /// JSON-RPC endpoint answering both Solana's `getSlot` and Ethereum's `eth_blockNumber`
+    async fn mock_rpc() -> String {
+        let app = Router::new().route(
+            "/",
+            post(|Json(body): Json<Value>| async move {
+                let result = match body["method"].as_str().unwrap_or_default() {
+                    "getSlot" => json!(1234),
+                    "eth_blockNumber" => json!("0x10"),
+                    _ => Value::Null,
+                };
+                Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    /// An address nothing is listening on
+    async fn failing_rpc() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}", addr)
+    }
+
+    async fn test_state(solana_url: &str, ethereum_url: &str) -> ApiState {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        ApiState {
+            db,
+            solana_client: Arc::new(
+                SolanaRpc::solana(&[solana_url.to_string()], CommitmentConfig::confirmed()).unwrap(),
+            ),
+            ethereum_provider: Arc::new(EthereumRpc::ethereum(&[ethereum_url.to_string()]).unwrap()),
+            attestation_signer: None,
+            validators: Arc::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_ok_when_all_subsystems_respond() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await;
+
+        let (status, Json(report)) = health(State(state)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(report.healthy);
+        assert!(report.solana.healthy && report.ethereum.healthy && report.database.healthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_503_when_rpc_fails() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&failing_rpc().await, &rpc).await;
+
+        let (status, Json(report)) = health(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!report.healthy);
+        assert!(!report.solana.healthy);
+        assert!(report.solana.error.is_some());
+        assert!(report.ethereum.healthy);
+        assert!(report.database.healthy);
+    }
+
+    #[tokio::test]
+    async fn test_health_errors_do_not_leak_rpc_keys() {
+        let keyed = format!("{}/v3/secret-api-key", failing_rpc().await);
+        let state = test_state(&keyed, &format!("{}?api-key=secret-api-key", failing_rpc().await)).await;
+
+        let (status, Json(report)) = health(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(report.solana.error.is_some() && report.ethereum.error.is_some());
+        let body = serde_json::to_string(&report).unwrap();
+        assert!(!body.contains("secret-api-key"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_validators_reports_each_validators_health() {
+        use crate::validator_client::ValidatorService;
+        use alloy::signers::local::PrivateKeySigner;
+
+        let service = ValidatorService::new(PrivateKeySigner::random(), Keypair::new());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, Arc::new(service).router()).await.unwrap() });
+
+        let validator = |name: &str, endpoint: Option<String>| ValidatorConfig {
+            name: name.to_string(),
+            eth_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0".to_string(),
+            sol_public_key: format!("{}-key", name),
+            endpoint,
+            auth_token: None,
+            auth_scheme: Default::default(),
+        };
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await.with_validators(vec![
+            validator("up", Some(format!("http://{}", addr))),
+            validator("down", Some(failing_rpc().await)),
+            validator("unreachable", None),
+        ]);
+
+        let Json(report) = validators(State(state)).await;
+        assert_eq!(report.len(), 3);
+        assert!(report[0].healthy && report[0].latency_ms.is_some() && report[0].error.is_none());
+        assert_eq!(report[0].sol_public_key, "up-key");
+        assert!(!report[1].healthy && report[1].error.is_some());
+        assert!(!report[2].healthy && report[2].latency_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_history_reports_gross_and_fee() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await;
+        state
+            .db
+            .create_transaction(4, Chain::Solana, Chain::Ethereum, &sol_tx_signature(4), "user", "0xabc", 995, 5)
+            .await
+            .unwrap();
+
+        // A burn sharing the lock's nonce
+        state
+            .db
+            .create_transaction(4, Chain::Ethereum, Chain::Solana, &eth_tx_hash(4), "0xabc", "user", 2_000, 0)
+            .await
+            .unwrap();
+
+        let Json(tx) = get_transaction(State(state.clone()), Path(4), Query(NonceQuery::default())).await.unwrap();
+        assert_eq!((tx.amount, tx.gross_amount, tx.fee_amount), (995, 1_000, 5));
+        assert_eq!(tx.status, "pending");
+        let burn = Query(NonceQuery { from_chain: Some(Chain::Ethereum) });
+        let Json(tx) = get_transaction(State(state.clone()), Path(4), burn).await.unwrap();
+        assert_eq!((tx.from_chain.as_str(), tx.amount), ("Ethereum", 2_000));
+
+        let query = HistoryQuery {
+            user: Some("user".to_string()),
+            ..Default::default()
+        };
+        let Json(list) = list_transactions(State(state.clone()), Query(query)).await.unwrap();
+        assert_eq!(list.total, 2);
+        assert_eq!(list.transactions[1].fee_amount, 5);
+        assert_eq!(list.next_before, None);
+
+        assert_eq!(
+            get_transaction(State(state), Path(5), Query(NonceQuery::default())).await.unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ws_streams_status_until_terminal() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await;
+        let db = state.db.clone();
+        let id = db
+            .create_transaction(6, Chain::Solana, Chain::Ethereum, &sol_tx_signature(6), "user", "0xabc", 1_000, 0)
+            .await
+            .unwrap();
+        let burn_id = db
+            .create_transaction(6, Chain::Ethereum, Chain::Solana, &eth_tx_hash(6), "0xabc", "user", 1_000, 0)
+            .await
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws/tx/6", addr))
+            .await
+            .unwrap();
+
+        assert_eq!(next_status(&mut ws).await, "pending");
+
+        // The burn with the same nonce is not the watched transfer
+        db.update_transaction_status(burn_id, TransactionStatus::Failed, None, Some("test"))
+            .await
+            .unwrap();
+        advance_status(&db, id, TransactionStatus::Confirmed, Some("0xmint")).await;
+        for status in ["signaturescollected", "submitted", "confirmed"] {
+            assert_eq!(next_status(&mut ws).await, status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ws_closes_unknown_nonce_with_an_error() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws/tx/404", addr))
+            .await
+            .unwrap();
+        match ws.next().await.unwrap().unwrap() {
+            WsMessage::Close(Some(frame)) => assert_eq!(u16::from(frame.code), CLOSE_UNKNOWN_NONCE),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ws_watcher_ends_when_client_closes() {
+        let rpc = mock_rpc().await;
+        let state = test_state(&rpc, &rpc).await;
+        let db = state.db.clone();
+        db.create_transaction(7, Chain::Solana, Chain::Ethereum, &sol_tx_signature(7), "user", "0xabc", 1_000, 0)
+            .await
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws/tx/7", addr))
+            .await
+            .unwrap();
+        assert_eq!(next_status(&mut ws).await, "pending");
+        assert_eq!(db.status_subscribers(), 1);
+
+        // No status update follows, so only reading the socket can notice the close
+        ws.close(None).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while db.status_subscribers() > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("watcher still subscribed after the client closed");
+    }
+
+    async fn next_status<S>(ws: &mut S) -> Value
+    where
+        S: futures::Stream<Item = std::result::Result<WsMessage, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        match ws.next().await.unwrap().unwrap() {
+            WsMessage::Text(text) => serde_json::from_str::<Value>(&text).unwrap()["status"].clone(),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+}
@@ -1,6 +1,11 @@
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use crate::amount::MAX_DECIMALS;
+use crate::wire::WireFormat;
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::fmt::{self, Display};
 use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -9,25 +14,273 @@ pub struct Config {
     pub relayer: RelayerConfig,
     pub database: DatabaseConfig,
     pub validators: Vec<ValidatorConfig>,
+    /// Only used when built with the `sui` feature
+    #[serde(default)]
+    pub sui: Option<SuiConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SolanaConfig {
-    pub rpc_url: String,
+    /// RPC endpoints in failover order; a single URL, a comma-separated list or an array
+    #[serde(alias = "rpc_url", deserialize_with = "deserialize_url_list")]
+    pub rpc_urls: Vec<String>,
     pub ws_url: String,
     pub bridge_program_id: String,
+    /// Refuse to start unless `bridge_program_id` is the id compiled into `bridge_vault`.
+    /// Set it in production; leave it off for a localnet deployment under another id.
+    #[serde(default)]
+    pub require_canonical_program_id: bool,
     pub commitment: String,
+    /// Commitment the monitor reads bridge transactions at; falls back to `commitment`
+    #[serde(default)]
+    pub monitor_commitment: Option<String>,
+    /// Commitment a submitted unlock must reach before it counts as confirmed; defaults
+    /// to `finalized`, since a slot that is not yet rooted can still be rolled back
+    #[serde(default)]
+    pub confirm_commitment: Option<String>,
+    /// Bridge config account holding `total_locked`; reconciliation is skipped when unset
+    #[serde(default)]
+    pub bridge_config_account: Option<String>,
+    /// SPL mint held by the vault; needed to derive token accounts for unlocks
+    #[serde(default)]
+    pub token_mint: Option<String>,
+    /// Delay between polls for new bridge transactions
+    #[serde(default = "default_chain_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Slots that must be built on top of a lock before the monitor acts on it;
+    /// 0 relies on `commitment` alone
+    #[serde(default)]
+    pub min_confirmations: u64,
+    /// Smallest net amount the relayer unlocks on Solana; smaller transfers are failed unsubmitted
+    #[serde(default)]
+    pub min_transfer_amount: Option<u64>,
+    /// Largest net amount the relayer unlocks on Solana; larger transfers are failed unsubmitted
+    #[serde(default)]
+    pub max_transfer_amount: Option<u64>,
+    /// Decimals of `token_mint`; amounts crossing to Solana are rescaled to them when set
+    #[serde(default)]
+    pub token_decimals: Option<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct EthereumConfig {
-    pub rpc_url: String,
+    /// RPC endpoints in failover order; a single URL, a comma-separated list or an array
+    #[serde(alias = "rpc_url", deserialize_with = "deserialize_url_list")]
+    pub rpc_urls: Vec<String>,
     pub ws_url: String,
     pub chain_id: u64,
     pub bridge_contract: String,
+    /// Further bridge contracts whose burns are relayed too, for deployments that front
+    /// each token with its own contract; a comma-separated list or an array
+    #[serde(default, deserialize_with = "deserialize_url_list")]
+    pub extra_bridge_contracts: Vec<String>,
     pub wrapped_sol_contract: String,
     pub validator_registry_contract: String,
     pub confirmations: u64,
+    /// EIP-1559 priority tip paid to block producers
+    #[serde(default = "default_max_priority_fee_gwei")]
+    pub max_priority_fee_gwei: f64,
+    /// Upper bound for `maxFeePerGas` (or the legacy gas price)
+    #[serde(default = "default_max_fee_per_gas_gwei")]
+    pub max_fee_per_gas_gwei: f64,
+    /// Don't submit while the going gas price (base fee plus tip, or the multiplied legacy
+    /// price) is above this; the transfer stays `SignaturesCollected` and is retried later
+    #[serde(default)]
+    pub max_gas_price_gwei: Option<f64>,
+    /// Delay between polls for new blocks
+    #[serde(default = "default_chain_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Most blocks requested per `eth_getLogs` call; providers commonly cap ranges
+    #[serde(default = "default_log_chunk_size")]
+    pub log_chunk_size: u64,
+    /// Smallest net amount the relayer mints on Ethereum; smaller transfers are failed unsubmitted
+    #[serde(default)]
+    pub min_transfer_amount: Option<u64>,
+    /// Largest net amount the relayer mints on Ethereum; larger transfers are failed unsubmitted
+    #[serde(default)]
+    pub max_transfer_amount: Option<u64>,
+    /// Decimals of the wrapped token; amounts crossing to Ethereum are rescaled to them when set
+    #[serde(default)]
+    pub token_decimals: Option<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SuiConfig {
+    pub rpc_url: String,
+    /// Package ID of the deployed bridge Move package
+    pub bridge_package_id: String,
+    #[serde(default = "default_chain_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+/// Rooted: a Solana unlock is only confirmed once its slot can no longer be rolled back
+const DEFAULT_CONFIRM_COMMITMENT: &str = "finalized";
+
+/// Shown in place of anything secret in `Debug` output
+const REDACTED: &str = "<redacted>";
+
+/// Keep only the scheme and host of `url`. Providers put API keys in the path or
+/// query (`/v3/<key>`, `?api-key=`) and databases in the userinfo, so everything
+/// else is dropped before a URL reaches a log line.
+pub fn redact_url(url: &str) -> String {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+
+    let mut redacted = match scheme {
+        Some(scheme) => format!("{}://{}", scheme, host),
+        None => host.to_string(),
+    };
+    if host.len() != rest.len() {
+        redacted.push('/');
+        redacted.push_str(REDACTED);
+    }
+    redacted
+}
+
+fn redact_urls(urls: &[String]) -> Vec<String> {
+    urls.iter().map(|url| redact_url(url)).collect()
+}
+
+/// `text` with every URL in it passed through [`redact_url`]. RPC and HTTP client
+/// errors quote the endpoint they failed on, key and all.
+pub fn redact_urls_in(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(separator) = rest.find("://") {
+        let start = rest[..separator]
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let end = rest[separator..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '(' | ')' | '<' | '>' | ','))
+            .map_or(rest.len(), |i| separator + i);
+        redacted.push_str(&rest[..start]);
+        redacted.push_str(&redact_url(&rest[start..end]));
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+impl SolanaConfig {
+    /// Commitment used to find and read bridge transactions
+    pub fn monitor_commitment(&self) -> &str {
+        self.monitor_commitment.as_deref().unwrap_or(&self.commitment)
+    }
+
+    /// Commitment used to decide a submitted Solana transaction is confirmed
+    pub fn confirm_commitment(&self) -> &str {
+        self.confirm_commitment.as_deref().unwrap_or(DEFAULT_CONFIRM_COMMITMENT)
+    }
+}
+
+impl EthereumConfig {
+    /// `bridge_contract` followed by `extra_bridge_contracts`
+    pub fn monitored_bridge_contracts(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.bridge_contract.as_str()).chain(self.extra_bridge_contracts.iter().map(String::as_str))
+    }
+}
+
+impl fmt::Debug for SolanaConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SolanaConfig")
+            .field("rpc_urls", &redact_urls(&self.rpc_urls))
+            .field("ws_url", &redact_url(&self.ws_url))
+            .field("bridge_program_id", &self.bridge_program_id)
+            .field("require_canonical_program_id", &self.require_canonical_program_id)
+            .field("commitment", &self.commitment)
+            .field("monitor_commitment", &self.monitor_commitment)
+            .field("confirm_commitment", &self.confirm_commitment)
+            .field("bridge_config_account", &self.bridge_config_account)
+            .field("token_mint", &self.token_mint)
+            .field("poll_interval_ms", &self.poll_interval_ms)
+            .field("min_confirmations", &self.min_confirmations)
+            .field("min_transfer_amount", &self.min_transfer_amount)
+            .field("max_transfer_amount", &self.max_transfer_amount)
+            .field("token_decimals", &self.token_decimals)
+            .finish()
+    }
+}
+
+impl fmt::Debug for EthereumConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EthereumConfig")
+            .field("rpc_urls", &redact_urls(&self.rpc_urls))
+            .field("ws_url", &redact_url(&self.ws_url))
+            .field("chain_id", &self.chain_id)
+            .field("bridge_contract", &self.bridge_contract)
+            .field("extra_bridge_contracts", &self.extra_bridge_contracts)
+            .field("wrapped_sol_contract", &self.wrapped_sol_contract)
+            .field("validator_registry_contract", &self.validator_registry_contract)
+            .field("confirmations", &self.confirmations)
+            .field("max_priority_fee_gwei", &self.max_priority_fee_gwei)
+            .field("max_fee_per_gas_gwei", &self.max_fee_per_gas_gwei)
+            .field("max_gas_price_gwei", &self.max_gas_price_gwei)
+            .field("poll_interval_ms", &self.poll_interval_ms)
+            .field("log_chunk_size", &self.log_chunk_size)
+            .field("min_transfer_amount", &self.min_transfer_amount)
+            .field("max_transfer_amount", &self.max_transfer_amount)
+            .field("token_decimals", &self.token_decimals)
+            .finish()
+    }
+}
+
+impl fmt::Debug for SuiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuiConfig")
+            .field("rpc_url", &redact_url(&self.rpc_url))
+            .field("bridge_package_id", &self.bridge_package_id)
+            .field("poll_interval_ms", &self.poll_interval_ms)
+            .finish()
+    }
+}
+
+/// Split a comma-separated URL list, dropping blanks
+pub fn split_url_list(urls: &str) -> Vec<String> {
+    urls.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn deserialize_url_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum UrlList {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match UrlList::deserialize(deserializer)? {
+        UrlList::One(urls) => split_url_list(&urls),
+        UrlList::Many(urls) => urls.iter().flat_map(|url| split_url_list(url)).collect(),
+    })
+}
+
+fn default_max_priority_fee_gwei() -> f64 {
+    1.5
+}
+
+fn default_max_fee_per_gas_gwei() -> f64 {
+    200.0
+}
+
+fn default_chain_poll_interval_ms() -> u64 {
+    5000
+}
+
+fn default_log_chunk_size() -> u64 {
+    2000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,21 +288,184 @@ pub struct RelayerConfig {
     pub poll_interval_ms: u64,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
+    /// Upper bound for the exponential backoff between RPC retries
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub retry_max_delay_ms: u64,
     pub gas_price_multiplier: f64,
+    #[serde(default = "default_reconcile_interval_ms")]
+    pub reconcile_interval_ms: u64,
+    /// Allowed drift (in base units) between on-chain `total_locked` and the DB before alerting
+    #[serde(default)]
+    pub reconcile_tolerance: u64,
+    /// Simulate submissions instead of broadcasting them
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default = "default_api_listen_addr")]
+    pub api_listen_addr: String,
+    /// Validator signatures needed before a transfer is submitted; should match
+    /// the on-chain `validator_threshold`
+    #[serde(default = "default_signature_threshold")]
+    pub signature_threshold: usize,
+    /// Signatures collection aims for; a transfer with `signature_threshold` but fewer than
+    /// this waits up to `target_signatures_wait_secs` for the rest. Unset means the threshold.
+    #[serde(default)]
+    pub target_signatures: Option<usize>,
+    /// How long after collection started a transfer at the threshold keeps waiting for the target
+    #[serde(default = "default_target_signatures_wait_secs")]
+    pub target_signatures_wait_secs: u64,
+    /// Base units `--selftest` locks; keep it dust, since the lock is only refundable after it expires
+    #[serde(default = "default_selftest_amount")]
+    pub selftest_amount: u64,
+    /// Solana keypair file used to sign `/attestation/:nonce` responses; the endpoint is off when unset
+    #[serde(default)]
+    pub attestation_keypair_path: Option<String>,
+    /// How long after confirmation a transfer is re-checked for a reorg that reverted it
+    #[serde(default = "default_reverify_window_secs")]
+    pub reverify_window_secs: u64,
+    /// Signature collection still short of the threshold this long after it started is
+    /// reported as stalled, and resumed first when the relayer restarts
+    #[serde(default = "default_collection_stall_secs")]
+    pub collection_stall_secs: u64,
+    /// A submission still unconfirmed this long after it was broadcast is re-broadcast, up to
+    /// `max_retries` times, then marked `Failed`; 0 waits forever
+    #[serde(default = "default_submission_timeout_secs")]
+    pub submission_timeout_secs: u64,
+    /// Finished transfers older than this move to `archived_transactions`; 0 keeps everything live
+    #[serde(default = "default_archive_after_days")]
+    pub archive_after_days: u32,
+    /// Most transfers handed to the submitter per tick, across all destination chains
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
+    /// Limit for a single Solana or Ethereum RPC call before it counts as failed
+    #[serde(default = "default_rpc_timeout_ms")]
+    pub rpc_timeout_ms: u64,
+    /// Encoding of signature requests sent to validators
+    #[serde(default)]
+    pub validator_wire_format: WireFormat,
+    /// File holding the submitter's 0x-hex Ethereum private key
+    #[serde(default)]
+    pub ethereum_key_path: Option<String>,
+    /// Env var holding the submitter's Ethereum key, used when no path is set
+    #[serde(default)]
+    pub ethereum_key_env: Option<String>,
+    /// Solana CLI JSON keypair file for the submitter
+    #[serde(default)]
+    pub solana_keypair_path: Option<String>,
+    /// Env var holding the submitter's Solana keypair as a JSON byte array, used when no path is set
+    #[serde(default)]
+    pub solana_keypair_env: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_retry_max_delay_ms() -> u64 {
+    60_000
+}
+
+fn default_reconcile_interval_ms() -> u64 {
+    60_000
+}
+
+fn default_api_listen_addr() -> String {
+    "0.0.0.0:8080".to_string()
+}
+
+fn default_signature_threshold() -> usize {
+    2
+}
+
+fn default_target_signatures_wait_secs() -> u64 {
+    30
+}
+
+fn default_selftest_amount() -> u64 {
+    1_000
+}
+
+fn default_reverify_window_secs() -> u64 {
+    3600
+}
+
+fn default_collection_stall_secs() -> u64 {
+    300
+}
+
+fn default_submission_timeout_secs() -> u64 {
+    600
+}
+
+fn default_archive_after_days() -> u32 {
+    30
+}
+
+fn default_max_batch_size() -> u32 {
+    50
+}
+
+fn default_rpc_timeout_ms() -> u64 {
+    30_000
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ValidatorConfig {
     pub name: String,
     pub eth_address: String,
     pub sol_public_key: String,
     pub endpoint: Option<String>,
+    /// Shared secret sent with every signature request
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    #[serde(default)]
+    pub auth_scheme: ValidatorAuthScheme,
+}
+
+/// How `auth_token` is presented to a validator endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorAuthScheme {
+    /// `Authorization: Bearer <token>`
+    #[default]
+    Bearer,
+    /// `X-API-Key: <token>`
+    ApiKey,
+}
+
+impl fmt::Debug for DatabaseConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatabaseConfig")
+            .field("url", &redact_url(&self.url))
+            .field("max_connections", &self.max_connections)
+            .finish()
+    }
+}
+
+impl fmt::Debug for ValidatorConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidatorConfig")
+            .field("name", &self.name)
+            .field("eth_address", &self.eth_address)
+            .field("sol_public_key", &self.sol_public_key)
+            .field("endpoint", &self.endpoint.as_deref().map(redact_url))
+            .field("auth_token", &self.auth_token.as_ref().map(|_| REDACTED))
+            .field("auth_scheme", &self.auth_scheme)
+            .finish()
+    }
+}
+
+impl FromStr for ValidatorAuthScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bearer" => Ok(Self::Bearer),
+            "api_key" | "apikey" => Ok(Self::ApiKey),
+            other => Err(format!("Unknown validator auth scheme: {}", other)),
+        }
+    }
 }
 
 impl Config {
@@ -58,18 +474,47 @@ impl Config {
 
         let config = Config {
             solana: SolanaConfig {
-                rpc_url: std::env::var("SOLANA_RPC_URL")
-                    .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string()),
+                rpc_urls: split_url_list(
+                    &std::env::var("SOLANA_RPC_URL")
+                        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string()),
+                ),
                 ws_url: std::env::var("SOLANA_WS_URL")
                     .unwrap_or_else(|_| "wss://api.devnet.solana.com".to_string()),
                 bridge_program_id: std::env::var("SOLANA_BRIDGE_PROGRAM_ID")
                     .expect("SOLANA_BRIDGE_PROGRAM_ID must be set"),
+                require_canonical_program_id: std::env::var("SOLANA_REQUIRE_CANONICAL_PROGRAM_ID")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
                 commitment: std::env::var("SOLANA_COMMITMENT")
                     .unwrap_or_else(|_| "confirmed".to_string()),
+                monitor_commitment: std::env::var("SOLANA_MONITOR_COMMITMENT").ok(),
+                confirm_commitment: std::env::var("SOLANA_CONFIRM_COMMITMENT").ok(),
+                bridge_config_account: std::env::var("SOLANA_BRIDGE_CONFIG_ACCOUNT").ok(),
+                token_mint: std::env::var("SOLANA_TOKEN_MINT").ok(),
+                poll_interval_ms: std::env::var("SOLANA_POLL_INTERVAL_MS")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()
+                    .unwrap_or(5000),
+                min_confirmations: std::env::var("SOLANA_MIN_CONFIRMATIONS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .unwrap_or(0),
+                min_transfer_amount: std::env::var("SOLANA_MIN_TRANSFER_AMOUNT")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
+                max_transfer_amount: std::env::var("SOLANA_MAX_TRANSFER_AMOUNT")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
+                token_decimals: std::env::var("SOLANA_TOKEN_DECIMALS")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
             },
             ethereum: EthereumConfig {
-                rpc_url: std::env::var("ETHEREUM_RPC_URL")
-                    .unwrap_or_else(|_| "https://sepolia.infura.io/v3/YOUR_KEY".to_string()),
+                rpc_urls: split_url_list(
+                    &std::env::var("ETHEREUM_RPC_URL")
+                        .unwrap_or_else(|_| "https://sepolia.infura.io/v3/YOUR_KEY".to_string()),
+                ),
                 ws_url: std::env::var("ETHEREUM_WS_URL")
                     .unwrap_or_else(|_| "wss://sepolia.infura.io/ws/v3/YOUR_KEY".to_string()),
                 chain_id: std::env::var("ETHEREUM_CHAIN_ID")
@@ -78,6 +523,9 @@ impl Config {
                     .expect("Invalid chain ID"),
                 bridge_contract: std::env::var("ETHEREUM_BRIDGE_CONTRACT")
                     .expect("ETHEREUM_BRIDGE_CONTRACT must be set"),
+                extra_bridge_contracts: split_url_list(
+                    &std::env::var("ETHEREUM_EXTRA_BRIDGE_CONTRACTS").unwrap_or_default(),
+                ),
                 wrapped_sol_contract: std::env::var("ETHEREUM_WRAPPED_SOL_CONTRACT")
                     .expect("ETHEREUM_WRAPPED_SOL_CONTRACT must be set"),
                 validator_registry_contract: std::env::var("ETHEREUM_VALIDATOR_REGISTRY_CONTRACT")
@@ -86,6 +534,34 @@ impl Config {
                     .unwrap_or_else(|_| "12".to_string())
                     .parse()
                     .unwrap_or(12),
+                max_priority_fee_gwei: std::env::var("ETHEREUM_MAX_PRIORITY_FEE_GWEI")
+                    .unwrap_or_else(|_| "1.5".to_string())
+                    .parse()
+                    .unwrap_or(1.5),
+                max_fee_per_gas_gwei: std::env::var("ETHEREUM_MAX_FEE_PER_GAS_GWEI")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()
+                    .unwrap_or(200.0),
+                max_gas_price_gwei: std::env::var("ETHEREUM_MAX_GAS_PRICE_GWEI")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
+                poll_interval_ms: std::env::var("ETHEREUM_POLL_INTERVAL_MS")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()
+                    .unwrap_or(5000),
+                log_chunk_size: std::env::var("ETHEREUM_LOG_CHUNK_SIZE")
+                    .unwrap_or_else(|_| "2000".to_string())
+                    .parse()
+                    .unwrap_or(2000),
+                min_transfer_amount: std::env::var("ETHEREUM_MIN_TRANSFER_AMOUNT")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
+                max_transfer_amount: std::env::var("ETHEREUM_MAX_TRANSFER_AMOUNT")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
+                token_decimals: std::env::var("ETHEREUM_TOKEN_DECIMALS")
+                    .ok()
+                    .and_then(|value| value.parse().ok()),
             },
             relayer: RelayerConfig {
                 poll_interval_ms: std::env::var("POLL_INTERVAL_MS")
@@ -100,10 +576,74 @@ impl Config {
                     .unwrap_or_else(|_| "2000".to_string())
                     .parse()
                     .unwrap_or(2000),
+                retry_max_delay_ms: std::env::var("RETRY_MAX_DELAY_MS")
+                    .unwrap_or_else(|_| "60000".to_string())
+                    .parse()
+                    .unwrap_or(60_000),
                 gas_price_multiplier: std::env::var("GAS_PRICE_MULTIPLIER")
                     .unwrap_or_else(|_| "1.2".to_string())
                     .parse()
                     .unwrap_or(1.2),
+                reconcile_interval_ms: std::env::var("RECONCILE_INTERVAL_MS")
+                    .unwrap_or_else(|_| "60000".to_string())
+                    .parse()
+                    .unwrap_or(60_000),
+                reconcile_tolerance: std::env::var("RECONCILE_TOLERANCE")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .unwrap_or(0),
+                dry_run: std::env::var("DRY_RUN")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
+                api_listen_addr: std::env::var("API_LISTEN_ADDR")
+                    .unwrap_or_else(|_| "0.0.0.0:8080".to_string()),
+                signature_threshold: std::env::var("SIGNATURE_THRESHOLD")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .unwrap_or(2),
+                target_signatures: std::env::var("TARGET_SIGNATURES").ok().and_then(|v| v.parse().ok()),
+                target_signatures_wait_secs: std::env::var("TARGET_SIGNATURES_WAIT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+                selftest_amount: std::env::var("SELFTEST_AMOUNT")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1_000),
+                attestation_keypair_path: std::env::var("ATTESTATION_KEYPAIR_PATH").ok(),
+                reverify_window_secs: std::env::var("REVERIFY_WINDOW_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()
+                    .unwrap_or(3600),
+                collection_stall_secs: std::env::var("COLLECTION_STALL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
+                submission_timeout_secs: std::env::var("SUBMISSION_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .unwrap_or(600),
+                archive_after_days: std::env::var("ARCHIVE_AFTER_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+                max_batch_size: std::env::var("MAX_BATCH_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .unwrap_or(50),
+                rpc_timeout_ms: std::env::var("RPC_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "30000".to_string())
+                    .parse()
+                    .unwrap_or(30_000),
+                validator_wire_format: std::env::var("VALIDATOR_WIRE_FORMAT")
+                    .unwrap_or_else(|_| "json".to_string())
+                    .parse()
+                    .unwrap_or_default(),
+                ethereum_key_path: std::env::var("RELAYER_ETH_KEY_PATH").ok(),
+                ethereum_key_env: std::env::var("RELAYER_ETH_KEY_ENV").ok(),
+                solana_keypair_path: std::env::var("RELAYER_SOLANA_KEYPAIR_PATH").ok(),
+                solana_keypair_env: std::env::var("RELAYER_SOLANA_KEYPAIR_ENV").ok(),
             },
             database: DatabaseConfig {
                 url: std::env::var("DATABASE_URL")
@@ -119,20 +659,43 @@ impl Config {
                     eth_address: std::env::var("VALIDATOR1_ETH_ADDRESS").unwrap_or_default(),
                     sol_public_key: std::env::var("VALIDATOR1_SOL_PUBKEY").unwrap_or_default(),
                     endpoint: std::env::var("VALIDATOR1_ENDPOINT").ok(),
+                    auth_token: std::env::var("VALIDATOR1_AUTH_TOKEN").ok(),
+                    auth_scheme: std::env::var("VALIDATOR1_AUTH_SCHEME")
+                        .unwrap_or_else(|_| "bearer".to_string())
+                        .parse()
+                        .unwrap_or_default(),
                 },
                 ValidatorConfig {
                     name: "Validator2".to_string(),
                     eth_address: std::env::var("VALIDATOR2_ETH_ADDRESS").unwrap_or_default(),
                     sol_public_key: std::env::var("VALIDATOR2_SOL_PUBKEY").unwrap_or_default(),
                     endpoint: std::env::var("VALIDATOR2_ENDPOINT").ok(),
+                    auth_token: std::env::var("VALIDATOR2_AUTH_TOKEN").ok(),
+                    auth_scheme: std::env::var("VALIDATOR2_AUTH_SCHEME")
+                        .unwrap_or_else(|_| "bearer".to_string())
+                        .parse()
+                        .unwrap_or_default(),
                 },
                 ValidatorConfig {
                     name: "Validator3".to_string(),
                     eth_address: std::env::var("VALIDATOR3_ETH_ADDRESS").unwrap_or_default(),
                     sol_public_key: std::env::var("VALIDATOR3_SOL_PUBKEY").unwrap_or_default(),
                     endpoint: std::env::var("VALIDATOR3_ENDPOINT").ok(),
+                    auth_token: std::env::var("VALIDATOR3_AUTH_TOKEN").ok(),
+                    auth_scheme: std::env::var("VALIDATOR3_AUTH_SCHEME")
+                        .unwrap_or_else(|_| "bearer".to_string())
+                        .parse()
+                        .unwrap_or_default(),
                 },
             ],
+            sui: std::env::var("SUI_RPC_URL").ok().map(|rpc_url| SuiConfig {
+                rpc_url,
+                bridge_package_id: std::env::var("SUI_BRIDGE_PACKAGE_ID").unwrap_or_default(),
+                poll_interval_ms: std::env::var("SUI_POLL_INTERVAL_MS")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()
+                    .unwrap_or(5000),
+            }),
         };
 
         Ok(config)
@@ -143,4 +706,328 @@ impl Config {
         let config: Config = toml::from_str(&content)?;
         Ok(config)
     }
+
+    /// Load the config the way the relayer binary does.
+    ///
+    /// Precedence, lowest first: the TOML file at `path` if it exists (otherwise
+    /// `from_env` and its defaults), then any environment variable that is set,
+    /// including ones from `.env`. The result is validated before it's returned,
+    /// so secrets can stay out of the file and be supplied through the environment.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        dotenv::dotenv().ok();
+
+        let mut config = match path {
+            Some(path) if path.exists() => Self::from_file(path)?,
+            _ => Self::from_env()?,
+        };
+        config.apply_env_overrides()?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Overwrite fields whose environment variable is set, using the same names as `from_env`
+    fn apply_env_overrides(&mut self) -> Result<()> {
+        let solana = &mut self.solana;
+        if let Ok(urls) = std::env::var("SOLANA_RPC_URL") {
+            solana.rpc_urls = split_url_list(&urls);
+        }
+        env_override("SOLANA_WS_URL", &mut solana.ws_url)?;
+        env_override("SOLANA_BRIDGE_PROGRAM_ID", &mut solana.bridge_program_id)?;
+        env_override("SOLANA_REQUIRE_CANONICAL_PROGRAM_ID", &mut solana.require_canonical_program_id)?;
+        env_override("SOLANA_COMMITMENT", &mut solana.commitment)?;
+        env_override_opt("SOLANA_MONITOR_COMMITMENT", &mut solana.monitor_commitment);
+        env_override_opt("SOLANA_CONFIRM_COMMITMENT", &mut solana.confirm_commitment);
+        env_override_opt("SOLANA_BRIDGE_CONFIG_ACCOUNT", &mut solana.bridge_config_account);
+        env_override_opt("SOLANA_TOKEN_MINT", &mut solana.token_mint);
+        env_override("SOLANA_POLL_INTERVAL_MS", &mut solana.poll_interval_ms)?;
+        env_override("SOLANA_MIN_CONFIRMATIONS", &mut solana.min_confirmations)?;
+        env_override_some("SOLANA_MIN_TRANSFER_AMOUNT", &mut solana.min_transfer_amount)?;
+        env_override_some("SOLANA_MAX_TRANSFER_AMOUNT", &mut solana.max_transfer_amount)?;
+        env_override_some("SOLANA_TOKEN_DECIMALS", &mut solana.token_decimals)?;
+
+        let ethereum = &mut self.ethereum;
+        if let Ok(urls) = std::env::var("ETHEREUM_RPC_URL") {
+            ethereum.rpc_urls = split_url_list(&urls);
+        }
+        env_override("ETHEREUM_WS_URL", &mut ethereum.ws_url)?;
+        env_override("ETHEREUM_CHAIN_ID", &mut ethereum.chain_id)?;
+        env_override("ETHEREUM_BRIDGE_CONTRACT", &mut ethereum.bridge_contract)?;
+        if let Ok(contracts) = std::env::var("ETHEREUM_EXTRA_BRIDGE_CONTRACTS") {
+            ethereum.extra_bridge_contracts = split_url_list(&contracts);
+        }
+        env_override("ETHEREUM_WRAPPED_SOL_CONTRACT", &mut ethereum.wrapped_sol_contract)?;
+        env_override("ETHEREUM_VALIDATOR_REGISTRY_CONTRACT", &mut ethereum.validator_registry_contract)?;
+        env_override("ETHEREUM_CONFIRMATIONS", &mut ethereum.confirmations)?;
+        env_override("ETHEREUM_MAX_PRIORITY_FEE_GWEI", &mut ethereum.max_priority_fee_gwei)?;
+        env_override("ETHEREUM_MAX_FEE_PER_GAS_GWEI", &mut ethereum.max_fee_per_gas_gwei)?;
+        if let Ok(value) = std::env::var("ETHEREUM_MAX_GAS_PRICE_GWEI") {
+            let ceiling = value
+                .parse()
+                .map_err(|e| anyhow!("Invalid ETHEREUM_MAX_GAS_PRICE_GWEI: {}", e))?;
+            ethereum.max_gas_price_gwei = Some(ceiling);
+        }
+        env_override("ETHEREUM_POLL_INTERVAL_MS", &mut ethereum.poll_interval_ms)?;
+        env_override("ETHEREUM_LOG_CHUNK_SIZE", &mut ethereum.log_chunk_size)?;
+        env_override_some("ETHEREUM_MIN_TRANSFER_AMOUNT", &mut ethereum.min_transfer_amount)?;
+        env_override_some("ETHEREUM_MAX_TRANSFER_AMOUNT", &mut ethereum.max_transfer_amount)?;
+        env_override_some("ETHEREUM_TOKEN_DECIMALS", &mut ethereum.token_decimals)?;
+
+        let relayer = &mut self.relayer;
+        env_override("POLL_INTERVAL_MS", &mut relayer.poll_interval_ms)?;
+        env_override("MAX_RETRIES", &mut relayer.max_retries)?;
+        env_override("RETRY_DELAY_MS", &mut relayer.retry_delay_ms)?;
+        env_override("RETRY_MAX_DELAY_MS", &mut relayer.retry_max_delay_ms)?;
+        env_override("GAS_PRICE_MULTIPLIER", &mut relayer.gas_price_multiplier)?;
+        env_override("RECONCILE_INTERVAL_MS", &mut relayer.reconcile_interval_ms)?;
+        env_override("RECONCILE_TOLERANCE", &mut relayer.reconcile_tolerance)?;
+        env_override("DRY_RUN", &mut relayer.dry_run)?;
+        env_override("API_LISTEN_ADDR", &mut relayer.api_listen_addr)?;
+        env_override("SIGNATURE_THRESHOLD", &mut relayer.signature_threshold)?;
+        env_override_some("TARGET_SIGNATURES", &mut relayer.target_signatures)?;
+        env_override("TARGET_SIGNATURES_WAIT_SECS", &mut relayer.target_signatures_wait_secs)?;
+        env_override("SELFTEST_AMOUNT", &mut relayer.selftest_amount)?;
+        env_override_opt("ATTESTATION_KEYPAIR_PATH", &mut relayer.attestation_keypair_path);
+        env_override("REVERIFY_WINDOW_SECS", &mut relayer.reverify_window_secs)?;
+        env_override("COLLECTION_STALL_SECS", &mut relayer.collection_stall_secs)?;
+        env_override("SUBMISSION_TIMEOUT_SECS", &mut relayer.submission_timeout_secs)?;
+        env_override("ARCHIVE_AFTER_DAYS", &mut relayer.archive_after_days)?;
+        env_override("MAX_BATCH_SIZE", &mut relayer.max_batch_size)?;
+        env_override("RPC_TIMEOUT_MS", &mut relayer.rpc_timeout_ms)?;
+        env_override("VALIDATOR_WIRE_FORMAT", &mut relayer.validator_wire_format)?;
+        env_override_opt("RELAYER_ETH_KEY_PATH", &mut relayer.ethereum_key_path);
+        env_override_opt("RELAYER_ETH_KEY_ENV", &mut relayer.ethereum_key_env);
+        env_override_opt("RELAYER_SOLANA_KEYPAIR_PATH", &mut relayer.solana_keypair_path);
+        env_override_opt("RELAYER_SOLANA_KEYPAIR_ENV", &mut relayer.solana_keypair_env);
+
+        env_override("DATABASE_URL", &mut self.database.url)?;
+        env_override("DB_MAX_CONNECTIONS", &mut self.database.max_connections)?;
+
+        for (i, validator) in self.validators.iter_mut().enumerate() {
+            let prefix = format!("VALIDATOR{}", i + 1);
+            env_override(&format!("{}_ETH_ADDRESS", prefix), &mut validator.eth_address)?;
+            env_override(&format!("{}_SOL_PUBKEY", prefix), &mut validator.sol_public_key)?;
+            env_override_opt(&format!("{}_ENDPOINT", prefix), &mut validator.endpoint);
+            env_override_opt(&format!("{}_AUTH_TOKEN", prefix), &mut validator.auth_token);
+            env_override(&format!("{}_AUTH_SCHEME", prefix), &mut validator.auth_scheme)?;
+        }
+
+        if let Ok(rpc_url) = std::env::var("SUI_RPC_URL") {
+            let sui = self.sui.get_or_insert_with(|| SuiConfig {
+                rpc_url: String::new(),
+                bridge_package_id: String::new(),
+                poll_interval_ms: default_chain_poll_interval_ms(),
+            });
+            sui.rpc_url = rpc_url;
+        }
+        if let Some(ref mut sui) = self.sui {
+            env_override("SUI_BRIDGE_PACKAGE_ID", &mut sui.bridge_package_id)?;
+            env_override("SUI_POLL_INTERVAL_MS", &mut sui.poll_interval_ms)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reject settings the relayer can't run with
+    pub fn validate(&self) -> Result<()> {
+        if self.solana.rpc_urls.is_empty() {
+            bail!("At least one Solana RPC URL is required");
+        }
+        if self.ethereum.rpc_urls.is_empty() {
+            bail!("At least one Ethereum RPC URL is required");
+        }
+        if self.solana.bridge_program_id.is_empty() {
+            bail!("SOLANA_BRIDGE_PROGRAM_ID must be set");
+        }
+        let program_id = Pubkey::from_str(&self.solana.bridge_program_id)
+            .map_err(|e| anyhow!("Invalid SOLANA_BRIDGE_PROGRAM_ID {}: {}", self.solana.bridge_program_id, e))?;
+        if self.solana.require_canonical_program_id && program_id != bridge_vault::ID {
+            bail!(
+                "SOLANA_BRIDGE_PROGRAM_ID {} is not the bridge program this relayer was built for ({}); \
+                 unset SOLANA_REQUIRE_CANONICAL_PROGRAM_ID to run against another deployment",
+                program_id,
+                bridge_vault::ID
+            );
+        }
+        if self.ethereum.bridge_contract.is_empty() {
+            bail!("ETHEREUM_BRIDGE_CONTRACT must be set");
+        }
+        for commitment in [
+            self.solana.commitment.as_str(),
+            self.solana.monitor_commitment(),
+            self.solana.confirm_commitment(),
+        ] {
+            CommitmentConfig::from_str(commitment).map_err(|e| anyhow!("Invalid commitment {}: {}", commitment, e))?;
+        }
+        if self.relayer.signature_threshold == 0 {
+            bail!("SIGNATURE_THRESHOLD must be at least 1");
+        }
+        if let Some(target) = self.relayer.target_signatures.filter(|t| *t < self.relayer.signature_threshold) {
+            bail!(
+                "TARGET_SIGNATURES ({}) is below SIGNATURE_THRESHOLD ({})",
+                target,
+                self.relayer.signature_threshold
+            );
+        }
+        if self.ethereum.max_gas_price_gwei.is_some_and(|ceiling| ceiling <= 0.0) {
+            bail!("ETHEREUM_MAX_GAS_PRICE_GWEI must be positive");
+        }
+        for (chain, min, max) in [
+            ("SOLANA", self.solana.min_transfer_amount, self.solana.max_transfer_amount),
+            ("ETHEREUM", self.ethereum.min_transfer_amount, self.ethereum.max_transfer_amount),
+        ] {
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    bail!("{}_MIN_TRANSFER_AMOUNT ({}) is above {}_MAX_TRANSFER_AMOUNT ({})", chain, min, chain, max);
+                }
+            }
+        }
+        for (chain, decimals) in [("SOLANA", self.solana.token_decimals), ("ETHEREUM", self.ethereum.token_decimals)] {
+            if decimals.is_some_and(|decimals| decimals > MAX_DECIMALS) {
+                bail!("{}_TOKEN_DECIMALS must be at most {}", chain, MAX_DECIMALS);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replace `field` with the parsed value of env var `name`, if it is set
+fn env_override<T>(name: &str, field: &mut T) -> Result<()>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(value) = std::env::var(name) {
+        *field = value.parse().map_err(|e| anyhow!("Invalid {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// `env_override` for a setting that is unset unless configured
+fn env_override_some<T>(name: &str, field: &mut Option<T>) -> Result<()>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(value) = std::env::var(name) {
+        *field = Some(value.parse().map_err(|e| anyhow!("Invalid {}: {}", name, e))?);
+    }
+    Ok(())
+}
+
+fn env_override_opt(name: &str, field: &mut Option<String>) {
+    if let Ok(value) = std::env::var(name) {
+        *field = Some(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_TOML: &str = r#"
+        [solana]
+        rpc_url = "https://api.devnet.solana.com"
+        ws_url = "wss://api.devnet.solana.com"
+        bridge_program_id = "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH"
+        commitment = "confirmed"
+
+        [ethereum]
+        rpc_url = "https://sepolia.example/v3/file-key"
+        ws_url = "wss://sepolia.example/ws/v3/file-key"
+        chain_id = 11155111
+        bridge_contract = "0xaBD6f99Fbb77051B28942abe3118bf4D8Ea9F2CA"
+        wrapped_sol_contract = "0xF718C74C9b298bCDd48Ed8801325E6ddBE2a5A5c"
+        validator_registry_contract = "0xE45DC6606979b9086375561Ff7d8f66f8C506816"
+        confirmations = 12
+
+        [relayer]
+        poll_interval_ms = 5000
+        max_retries = 3
+        retry_delay_ms = 2000
+        gas_price_multiplier = 1.2
+
+        [database]
+        url = "sqlite://relayer.db"
+        max_connections = 10
+
+        [[validators]]
+        name = "Validator1"
+        eth_address = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0"
+        sol_public_key = "5XqZ"
+    "#;
+
+    #[test]
+    fn test_env_overrides_toml() {
+        let path = std::env::temp_dir().join(format!("relayer-config-{}.toml", std::process::id()));
+        std::fs::write(&path, BASE_TOML).unwrap();
+
+        std::env::set_var("ETHEREUM_RPC_URL", "https://mainnet.example/v3/env-key");
+        std::env::set_var("VALIDATOR1_AUTH_TOKEN", "env-token");
+        let config = Config::load(Some(&path));
+        std::env::remove_var("ETHEREUM_RPC_URL");
+        std::env::remove_var("VALIDATOR1_AUTH_TOKEN");
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.ethereum.rpc_urls, vec!["https://mainnet.example/v3/env-key"]);
+        assert_eq!(config.validators[0].auth_token.as_deref(), Some("env-token"));
+        // Untouched values come from the file
+        assert_eq!(config.ethereum.chain_id, 11155111);
+        assert_eq!(config.solana.rpc_urls, vec!["https://api.devnet.solana.com"]);
+    }
+
+    #[test]
+    fn test_canonical_program_id_is_enforced_when_required() {
+        let mut config: Config = toml::from_str(BASE_TOML).unwrap();
+        let localnet = Pubkey::new_unique().to_string();
+
+        config.solana.bridge_program_id = localnet.clone();
+        config.validate().unwrap();
+
+        config.solana.require_canonical_program_id = true;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains(&localnet), "{}", err);
+
+        config.solana.bridge_program_id = bridge_vault::ID.to_string();
+        config.validate().unwrap();
+
+        config.solana.bridge_program_id = "not-a-pubkey".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("Invalid SOLANA_BRIDGE_PROGRAM_ID"));
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(redact_url("https://sepolia.infura.io/v3/abc123"), "https://sepolia.infura.io/<redacted>");
+        assert_eq!(redact_url("https://rpc.example?api-key=abc123"), "https://rpc.example/<redacted>");
+        assert_eq!(redact_url("postgres://user:pw@db:5432/relayer"), "postgres://db:5432/<redacted>");
+        assert_eq!(redact_url("http://127.0.0.1:8899"), "http://127.0.0.1:8899");
+    }
+
+    #[test]
+    fn test_redact_urls_in() {
+        assert_eq!(
+            redact_urls_in("error sending request for url (https://mainnet.helius-rpc.com/?api-key=abc123)"),
+            "error sending request for url (https://mainnet.helius-rpc.com/<redacted>)"
+        );
+        assert_eq!(
+            redact_urls_in("https://eth.llamarpc.com/abc123 and wss://ws.example/v3/def456: timed out"),
+            "https://eth.llamarpc.com/<redacted> and wss://ws.example/<redacted> timed out"
+        );
+        assert_eq!(redact_urls_in("connection refused"), "connection refused");
+    }
+
+    #[test]
+    fn test_debug_output_hides_secrets() {
+        let mut config: Config = toml::from_str(BASE_TOML).unwrap();
+        config.ethereum.rpc_urls.push("https://mainnet.example/v3/second-key".to_string());
+        config.validators[0].auth_token = Some("validator-token".to_string());
+
+        let debug = format!("{:?}", config);
+        for secret in ["file-key", "second-key", "validator-token"] {
+            assert!(!debug.contains(secret), "{} leaked: {}", secret, debug);
+        }
+        assert!(debug.contains("sepolia.example"));
+    }
 }
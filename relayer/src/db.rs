@@ -1,13 +1,28 @@
+use crate::amount::Amount;
 use crate::error::{RelayerError, Result};
-use crate::types::{Chain, RelayerTransaction, TransactionStatus};
-use chrono::Utc;
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use crate::types::{Chain, RelayerTransaction, StatusUpdate, TransactionStatus};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use sqlx::sqlite::{Sqlite, SqliteConnectOptions, SqliteConnection, SqlitePool, SqlitePoolOptions};
+use sqlx::{Executor, Transaction};
 use std::str::FromStr;
-use tracing::{info, warn};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Status updates buffered per subscriber before it starts lagging
+const STATUS_CHANNEL_CAPACITY: usize = 256;
+
+/// `from_chain:nonce:from_tx_hash`, the same for every delivery of one source-chain event
+pub fn idempotency_key(from_chain: Chain, nonce: u64, from_tx_hash: &str) -> String {
+    format!("{}:{}:{}", from_chain, nonce, from_tx_hash)
+}
 
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    status_updates: broadcast::Sender<StatusUpdate>,
 }
 
 impl Database {
@@ -23,50 +38,114 @@ impl Database {
             .connect_with(options)
             .await?;
 
-        let db = Database { pool };
+        let (status_updates, _) = broadcast::channel(STATUS_CHANNEL_CAPACITY);
+        let db = Database { pool, status_updates };
         db.run_migrations().await?;
 
         Ok(db)
     }
 
+    /// Apply every step of [`MIGRATIONS`] not yet recorded in `schema_migrations`, in order.
+    /// Each step commits together with its record, so an interrupted upgrade resumes at the
+    /// step that failed.
     async fn run_migrations(&self) -> Result<()> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS relayer_transactions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                nonce INTEGER NOT NULL UNIQUE,
-                from_chain TEXT NOT NULL,
-                to_chain TEXT NOT NULL,
-                from_tx_hash TEXT NOT NULL UNIQUE,
-                to_tx_hash TEXT,
-                sender TEXT NOT NULL,
-                recipient TEXT NOT NULL,
-                amount INTEGER NOT NULL,
-                status TEXT NOT NULL,
-                signatures TEXT,
-                error_message TEXT,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL
             )
             "#,
         )
         .execute(&self.pool)
         .await?;
 
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_nonce ON relayer_transactions(nonce);
-            CREATE INDEX IF NOT EXISTS idx_status ON relayer_transactions(status);
-            CREATE INDEX IF NOT EXISTS idx_from_tx_hash ON relayer_transactions(from_tx_hash);
-            CREATE INDEX IF NOT EXISTS idx_to_tx_hash ON relayer_transactions(to_tx_hash);
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        let applied = self.schema_version().await?;
+        if applied > MIGRATIONS.len() as i64 {
+            return Err(RelayerError::ConfigError(format!(
+                "Database schema is at version {}, but this relayer only knows {}",
+                applied,
+                MIGRATIONS.len()
+            )));
+        }
+
+        for (version, migration) in (1i64..).zip(MIGRATIONS).skip(applied as usize) {
+            info!("Applying migration {}: {}", version, migration.description);
+            let mut tx = self.pool.begin().await?;
+            (migration.apply)(&mut tx).await?;
+            sqlx::query("INSERT INTO schema_migrations (version, description, applied_at) VALUES (?, ?, ?)")
+                .bind(version)
+                .bind(migration.description)
+                .bind(Utc::now())
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
 
         Ok(())
     }
 
+    /// Latest migration recorded as applied, 0 for a database that has none
+    pub async fn schema_version(&self) -> Result<i64> {
+        let (version,): (i64,) = sqlx::query_as("SELECT COALESCE(MAX(version), 0) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(version)
+    }
+
+    /// Stream of status changes written through this database, from now on
+    pub fn subscribe_status_updates(&self) -> broadcast::Receiver<StatusUpdate> {
+        self.status_updates.subscribe()
+    }
+
+    /// Receivers from [`Self::subscribe_status_updates`] still alive, e.g. open `/ws/tx` watchers
+    pub fn status_subscribers(&self) -> usize {
+        self.status_updates.receiver_count()
+    }
+
+    /// `transfer` is the `(from_chain, nonce)` of the row whose status changed, if any
+    fn publish_status(&self, transfer: Option<(Chain, i64)>, status: TransactionStatus) {
+        if let Some((from_chain, nonce)) = transfer {
+            // No subscribers is fine
+            let _ = self.status_updates.send(StatusUpdate {
+                from_chain,
+                nonce: nonce as u64,
+                status,
+            });
+        }
+    }
+
+    /// Run `f` inside a single database transaction. Its writes are committed together
+    /// if it returns `Ok` and rolled back otherwise; status updates are only published
+    /// once the commit has succeeded.
+    pub async fn with_transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: for<'t> FnOnce(&'t mut DbTransaction) -> BoxFuture<'t, Result<T>>,
+    {
+        let mut db_tx = DbTransaction {
+            tx: self.pool.begin().await?,
+            status_updates: Vec::new(),
+        };
+
+        // Dropping `db_tx` on error rolls it back
+        let value = f(&mut db_tx).await?;
+
+        let DbTransaction { tx, status_updates } = db_tx;
+        tx.commit().await?;
+        for (transfer, status) in status_updates {
+            self.publish_status(transfer, status);
+        }
+
+        Ok(value)
+    }
+
+    /// Trivial query used by health checks
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
     pub async fn create_transaction(
         &self,
         nonce: u64,
@@ -76,35 +155,82 @@ impl Database {
         sender: &str,
         recipient: &str,
         amount: u64,
+        fee_amount: u64,
+    ) -> Result<i64> {
+        self.create_expiring_transaction(nonce, from_chain, to_chain, from_tx_hash, sender, recipient, amount, fee_amount, None)
+            .await
+    }
+
+    /// `create_transaction` for a lock the source chain stops honouring at `expires_at` (unix seconds).
+    ///
+    /// Recording the same source event twice (a monitor re-delivering it after a reconnect) is not
+    /// an error: the row is keyed by [`idempotency_key`] and the existing id is returned.
+    /// `from_tx_hash` must be well-formed for `from_chain` and is stored in its canonical form
+    /// (see [`Chain::normalize_tx_hash`]).
+    pub async fn create_expiring_transaction(
+        &self,
+        nonce: u64,
+        from_chain: Chain,
+        to_chain: Chain,
+        from_tx_hash: &str,
+        sender: &str,
+        recipient: &str,
+        amount: u64,
+        fee_amount: u64,
+        expires_at: Option<i64>,
     ) -> Result<i64> {
         let now = Utc::now();
+        let gross_amount = amount
+            .checked_add(fee_amount)
+            .ok_or_else(|| RelayerError::ParseError(format!("Amount overflow for nonce {}", nonce)))?;
+        let from_tx_hash = from_chain.normalize_tx_hash(from_tx_hash)?;
+        let key = idempotency_key(from_chain, nonce, &from_tx_hash);
         let result = sqlx::query(
             r#"
             INSERT INTO relayer_transactions
-            (nonce, from_chain, to_chain, from_tx_hash, sender, recipient, amount, status, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (nonce, from_chain, to_chain, from_tx_hash, sender, recipient, amount, gross_amount, fee_amount, status,
+             expires_at, idempotency_key, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(idempotency_key) DO NOTHING
             "#,
         )
         .bind(nonce as i64)
         .bind(from_chain)
         .bind(to_chain)
-        .bind(from_tx_hash)
+        .bind(&from_tx_hash)
         .bind(sender)
         .bind(recipient)
         .bind(amount as i64)
+        .bind(gross_amount as i64)
+        .bind(fee_amount as i64)
         .bind(TransactionStatus::Pending)
+        .bind(expires_at)
+        .bind(&key)
         .bind(now)
         .bind(now)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            let existing: (i64,) = sqlx::query_as("SELECT id FROM relayer_transactions WHERE idempotency_key = ?")
+                .bind(&key)
+                .fetch_one(&self.pool)
+                .await?;
+            debug!("Event {} already recorded as transaction {}", key, existing.0);
+            return Ok(existing.0);
+        }
+
+        self.publish_status(Some((from_chain, nonce as i64)), TransactionStatus::Pending);
         Ok(result.last_insert_rowid())
     }
 
-    pub async fn get_transaction_by_nonce(&self, nonce: u64) -> Result<Option<RelayerTransaction>> {
+    /// Transfer `nonce` from `from_chain`. Each source chain numbers its own transfers,
+    /// so the same nonce can belong to one transfer per chain.
+    pub async fn get_transaction_by_nonce(&self, from_chain: Chain, nonce: u64) -> Result<Option<RelayerTransaction>> {
         let tx = sqlx::query_as::<_, RelayerTransaction>(
-            "SELECT * FROM relayer_transactions WHERE nonce = ?",
+            "SELECT * FROM relayer_transactions WHERE from_chain = ? AND nonce = ?",
         )
+        .bind(from_chain)
         .bind(nonce as i64)
         .fetch_optional(&self.pool)
         .await?;
@@ -112,11 +238,18 @@ impl Database {
         Ok(tx)
     }
 
-    pub async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<Option<RelayerTransaction>> {
+    /// Transfer started by `tx_hash` on `from_chain`, whichever spelling of the hash is given
+    pub async fn get_transaction_by_hash(
+        &self,
+        from_chain: Chain,
+        tx_hash: &str,
+    ) -> Result<Option<RelayerTransaction>> {
+        let tx_hash = from_chain.normalize_tx_hash(tx_hash)?;
         let tx = sqlx::query_as::<_, RelayerTransaction>(
-            "SELECT * FROM relayer_transactions WHERE from_tx_hash = ?",
+            "SELECT * FROM relayer_transactions WHERE from_chain = ? AND from_tx_hash = ?",
         )
-        .bind(tx_hash)
+        .bind(from_chain)
+        .bind(&tx_hash)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -130,50 +263,163 @@ impl Database {
         to_tx_hash: Option<&str>,
         error_message: Option<&str>,
     ) -> Result<()> {
-        let now = Utc::now();
+        let mut conn = self.pool.acquire().await?;
+        let transfer = set_status(&mut conn, id, status, to_tx_hash, error_message).await?;
+
+        self.publish_status(transfer, status);
+        Ok(())
+    }
+
+    /// Record which Ethereum bridge contract emitted the burn behind transfer `id`
+    pub async fn set_source_contract(&self, id: i64, contract: &str) -> Result<()> {
+        sqlx::query("UPDATE relayer_transactions SET source_contract = ? WHERE id = ?")
+            .bind(contract)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record transfer `id`'s net amount with the decimals of its source chain's token
+    pub async fn set_net_amount(&self, id: i64, amount: Amount) -> Result<()> {
+        sqlx::query("UPDATE relayer_transactions SET net_amount = ? WHERE id = ?")
+            .bind(amount)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Store the full signature set and advance to `SignaturesCollected` atomically
+    pub async fn update_signatures(&self, id: i64, signatures: &str) -> Result<()> {
+        let signatures = signatures.to_string();
+        self.with_transaction(move |db_tx| {
+            Box::pin(async move {
+                db_tx.save_signatures(id, &signatures).await?;
+                db_tx
+                    .update_transaction_status(id, TransactionStatus::SignaturesCollected, None, None)
+                    .await
+            })
+        })
+        .await
+    }
+
+    /// Note that signature collection for transfer `id` has begun. Only the first call
+    /// counts, so the time survives retries and restarts.
+    pub async fn mark_collection_started(&self, id: i64) -> Result<()> {
         sqlx::query(
-            r#"
-            UPDATE relayer_transactions
-            SET status = ?, to_tx_hash = ?, error_message = ?, updated_at = ?
-            WHERE id = ?
-            "#,
+            "UPDATE relayer_transactions SET collection_started_at = COALESCE(collection_started_at, ?) WHERE id = ?",
         )
-        .bind(status)
-        .bind(to_tx_hash)
-        .bind(error_message)
-        .bind(now)
+        .bind(Utc::now())
         .bind(id)
         .execute(&self.pool)
         .await?;
-
         Ok(())
     }
 
-    pub async fn update_signatures(&self, id: i64, signatures: &str) -> Result<()> {
-        let now = Utc::now();
+    /// Start transfer `id`'s submission timeout from now. `account_nonce` is the Ethereum sender
+    /// nonce the broadcast used, which a replacement has to reuse; a `rebroadcast` counts toward
+    /// the re-broadcast limit, while a first submission resets the count.
+    pub async fn mark_submitted(&self, id: i64, account_nonce: Option<u64>, rebroadcast: bool) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE relayer_transactions
-            SET signatures = ?, status = ?, updated_at = ?
+            SET submitted_at = ?, submission_account_nonce = ?,
+                rebroadcasts = CASE WHEN ? THEN rebroadcasts + 1 ELSE 0 END
             WHERE id = ?
             "#,
         )
-        .bind(signatures)
-        .bind(TransactionStatus::SignaturesCollected)
-        .bind(now)
+        .bind(Utc::now())
+        .bind(account_nonce.map(|nonce| nonce as i64))
+        .bind(rebroadcast)
         .bind(id)
         .execute(&self.pool)
         .await?;
-
         Ok(())
     }
 
-    pub async fn get_pending_transactions(&self) -> Result<Vec<RelayerTransaction>> {
+    /// Pending transfers whose signature collection began at or before `started_by`,
+    /// oldest first
+    pub async fn get_stalled_collections(&self, started_by: DateTime<Utc>) -> Result<Vec<RelayerTransaction>> {
         let txs = sqlx::query_as::<_, RelayerTransaction>(
-            "SELECT * FROM relayer_transactions WHERE status = ? OR status = ? ORDER BY created_at ASC",
+            r#"
+            SELECT * FROM relayer_transactions
+            WHERE status = ? AND collection_started_at IS NOT NULL AND collection_started_at <= ?
+            ORDER BY collection_started_at ASC
+            "#,
+        )
+        .bind(TransactionStatus::Pending)
+        .bind(started_by)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(txs)
+    }
+
+    /// Store signatures gathered so far without advancing the status, so a
+    /// later pass only needs to ask the validators that are still missing
+    pub async fn save_partial_signatures(&self, id: i64, signatures: &str) -> Result<()> {
+        set_signatures(&self.pool, id, signatures).await
+    }
+
+    /// Oldest pending transfers, at most `limit` of them
+    pub async fn get_pending_transactions(&self, limit: u32) -> Result<Vec<RelayerTransaction>> {
+        let txs = sqlx::query_as::<_, RelayerTransaction>(
+            "SELECT * FROM relayer_transactions WHERE status = ? OR status = ? ORDER BY created_at ASC LIMIT ?",
         )
         .bind(TransactionStatus::Pending)
         .bind(TransactionStatus::SignaturesCollected)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(txs)
+    }
+
+    /// Pending transfers to `to_chain` in nonce order. Signature collection can run
+    /// ahead, but a transfer is only returned for submission once every earlier
+    /// nonce between the same two chains has been submitted (or finished), so a
+    /// destination that enforces sequential nonces never sees a gap. At most `limit`
+    /// rows are returned, lowest nonces first.
+    pub async fn get_releasable_transactions(
+        &self,
+        to_chain: Chain,
+        limit: u32,
+    ) -> Result<Vec<RelayerTransaction>> {
+        let txs = sqlx::query_as::<_, RelayerTransaction>(
+            r#"
+            SELECT * FROM relayer_transactions t
+            WHERE t.to_chain = ?
+              AND (t.status = ?
+                   OR (t.status = ? AND NOT EXISTS (
+                       SELECT 1 FROM relayer_transactions earlier
+                       WHERE earlier.to_chain = t.to_chain
+                         AND earlier.from_chain = t.from_chain
+                         AND earlier.nonce < t.nonce
+                         AND earlier.status IN (?, ?))))
+            ORDER BY t.nonce ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(to_chain)
+        .bind(TransactionStatus::Pending)
+        .bind(TransactionStatus::SignaturesCollected)
+        .bind(TransactionStatus::Pending)
+        .bind(TransactionStatus::SignaturesCollected)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(txs)
+    }
+
+    /// Transfers confirmed at or after `since`, oldest first
+    pub async fn get_confirmed_since(&self, since: DateTime<Utc>) -> Result<Vec<RelayerTransaction>> {
+        let txs = sqlx::query_as::<_, RelayerTransaction>(
+            "SELECT * FROM relayer_transactions WHERE status = ? AND updated_at >= ? ORDER BY updated_at ASC",
+        )
+        .bind(TransactionStatus::Confirmed)
+        .bind(since)
         .fetch_all(&self.pool)
         .await?;
 
@@ -194,15 +440,126 @@ impl Database {
         Ok(txs)
     }
 
-    pub async fn is_nonce_processed(&self, nonce: u64) -> Result<bool> {
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM relayer_transactions WHERE nonce = ?")
-            .bind(nonce as i64)
-            .fetch_one(&self.pool)
-            .await?;
+    /// Most recent transfers, newest first, optionally only those sent or received by
+    /// `user` or created at or after `since`.
+    ///
+    /// Pages are keyed by row id: pass the last id of one page as `before` to get the next.
+    pub async fn get_transaction_history(
+        &self,
+        user: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<RelayerTransaction>> {
+        let txs = sqlx::query_as::<_, RelayerTransaction>(
+            r#"
+            SELECT * FROM relayer_transactions
+            WHERE (?1 IS NULL OR sender = ?1 OR recipient = ?1)
+              AND (?2 IS NULL OR created_at >= ?2)
+              AND (?3 IS NULL OR id < ?3)
+            ORDER BY id DESC
+            LIMIT ?4
+            "#,
+        )
+        .bind(user)
+        .bind(since)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(txs)
+    }
+
+    /// Whether `nonce` from `from_chain` has been seen, including transfers since archived
+    pub async fn is_nonce_processed(&self, from_chain: Chain, nonce: u64) -> Result<bool> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT (SELECT COUNT(*) FROM relayer_transactions WHERE from_chain = ?1 AND nonce = ?2)
+                 + (SELECT COUNT(*) FROM archived_transactions WHERE from_chain = ?1 AND nonce = ?2)
+            "#,
+        )
+        .bind(from_chain)
+        .bind(nonce as i64)
+        .fetch_one(&self.pool)
+        .await?;
 
         Ok(count.0 > 0)
     }
 
+    /// Move transfers that reached a terminal status more than `older_than_days` ago
+    /// into `archived_transactions`. Returns how many rows were moved.
+    pub async fn archive_old_transactions(&self, older_than_days: u32) -> Result<u64> {
+        let now = Utc::now();
+        let cutoff = now - chrono::Duration::days(older_than_days as i64);
+        let mut db_tx = self.pool.begin().await?;
+
+        let moved = sqlx::query(
+            r#"
+            INSERT INTO archived_transactions
+            (id, nonce, from_chain, to_chain, from_tx_hash, to_tx_hash, sender, recipient, amount, gross_amount,
+             fee_amount, status, signatures, error_message, created_at, updated_at, expires_at, source_contract,
+             collection_started_at, net_amount, idempotency_key, submitted_at, submission_account_nonce,
+             rebroadcasts, archived_at)
+            SELECT id, nonce, from_chain, to_chain, from_tx_hash, to_tx_hash, sender, recipient, amount, gross_amount,
+                   fee_amount, status, signatures, error_message, created_at, updated_at, expires_at, source_contract,
+                   collection_started_at, net_amount, idempotency_key, submitted_at, submission_account_nonce,
+                   rebroadcasts, ?
+            FROM relayer_transactions
+            WHERE status IN (?, ?, ?) AND updated_at < ?
+            "#,
+        )
+        .bind(now)
+        .bind(TransactionStatus::Confirmed)
+        .bind(TransactionStatus::Failed)
+        .bind(TransactionStatus::Refunded)
+        .bind(cutoff)
+        .execute(&mut *db_tx)
+        .await?
+        .rows_affected();
+
+        let deleted = sqlx::query(
+            "DELETE FROM relayer_transactions WHERE id IN (SELECT id FROM archived_transactions WHERE archived_at = ?)",
+        )
+        .bind(now)
+        .execute(&mut *db_tx)
+        .await?
+        .rows_affected();
+
+        if deleted != moved {
+            // Rolled back on drop
+            return Err(RelayerError::Unknown(format!(
+                "Archived {} transactions but removed {} from the live table",
+                moved, deleted
+            )));
+        }
+
+        db_tx.commit().await?;
+        Ok(moved)
+    }
+
+    /// Expected vault balance according to the DB: every Solana lock still holds
+    /// tokens in the vault until a confirmed unlock back to Solana releases them.
+    /// Refunded locks have already left the vault. Archived transfers still count.
+    pub async fn get_locked_total(&self) -> Result<i64> {
+        let total: (i64,) = sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN from_chain = 'Solana' AND status != 'Refunded' THEN amount ELSE 0 END), 0)
+                - COALESCE(SUM(CASE WHEN to_chain = 'Solana' AND status = 'Confirmed' THEN amount ELSE 0 END), 0)
+            FROM (
+                SELECT from_chain, to_chain, status, amount FROM relayer_transactions
+                UNION ALL
+                SELECT from_chain, to_chain, status, amount FROM archived_transactions
+            )
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(total.0)
+    }
+
     pub async fn get_stats(&self) -> Result<TransactionStats> {
         let stats = sqlx::query_as::<_, TransactionStats>(
             r#"
@@ -212,7 +569,8 @@ impl Database {
                 SUM(CASE WHEN status = 'SignaturesCollected' THEN 1 ELSE 0 END) as signatures_collected,
                 SUM(CASE WHEN status = 'Submitted' THEN 1 ELSE 0 END) as submitted,
                 SUM(CASE WHEN status = 'Confirmed' THEN 1 ELSE 0 END) as confirmed,
-                SUM(CASE WHEN status = 'Failed' THEN 1 ELSE 0 END) as failed
+                SUM(CASE WHEN status = 'Failed' THEN 1 ELSE 0 END) as failed,
+                SUM(CASE WHEN status = 'Refunded' THEN 1 ELSE 0 END) as refunded
             FROM relayer_transactions
             "#,
         )
@@ -221,6 +579,66 @@ impl Database {
 
         Ok(stats)
     }
+
+    /// Volume per bridging direction for transfers recorded within the last `window`,
+    /// archived ones included. Failed and refunded transfers moved nothing, so they are left out.
+    pub async fn get_volume_stats(&self, window: Duration) -> Result<VolumeStats> {
+        let since = chrono::Duration::from_std(window)
+            .ok()
+            .and_then(|window| Utc::now().checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let directions = sqlx::query_as::<_, DirectionVolume>(
+            r#"
+            SELECT
+                from_chain,
+                to_chain,
+                COUNT(*) as transfers,
+                COALESCE(SUM(gross_amount), 0) as gross_volume,
+                COALESCE(SUM(amount), 0) as net_volume,
+                COALESCE(SUM(fee_amount), 0) as fee_volume
+            FROM (
+                SELECT from_chain, to_chain, status, amount, gross_amount, fee_amount, created_at
+                FROM relayer_transactions
+                UNION ALL
+                SELECT from_chain, to_chain, status, amount, gross_amount, fee_amount, created_at
+                FROM archived_transactions
+            )
+            WHERE created_at >= ? AND status NOT IN (?, ?)
+            GROUP BY from_chain, to_chain
+            ORDER BY from_chain, to_chain
+            "#,
+        )
+        .bind(since)
+        .bind(TransactionStatus::Failed)
+        .bind(TransactionStatus::Refunded)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(VolumeStats { since, directions })
+    }
+}
+
+/// What [`Database::get_volume_stats`] found in its window
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeStats {
+    /// Start of the window; transfers recorded at or after it are counted
+    pub since: DateTime<Utc>,
+    /// Directions with at least one transfer in the window
+    pub directions: Vec<DirectionVolume>,
+}
+
+/// Totals for one bridging direction, in the source chain's base units
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct DirectionVolume {
+    pub from_chain: Chain,
+    pub to_chain: Chain,
+    pub transfers: i64,
+    /// What senders put in, fees included
+    pub gross_volume: i64,
+    /// What recipients get
+    pub net_volume: i64,
+    pub fee_volume: i64,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -231,4 +649,974 @@ pub struct TransactionStats {
     pub submitted: i64,
     pub confirmed: i64,
     pub failed: i64,
+    pub refunded: i64,
+}
+
+/// Writes made inside [`Database::with_transaction`]
+pub struct DbTransaction {
+    tx: Transaction<'static, Sqlite>,
+    status_updates: Vec<(Option<(Chain, i64)>, TransactionStatus)>,
+}
+
+impl DbTransaction {
+    pub async fn update_transaction_status(
+        &mut self,
+        id: i64,
+        status: TransactionStatus,
+        to_tx_hash: Option<&str>,
+        error_message: Option<&str>,
+    ) -> Result<()> {
+        let transfer = set_status(&mut self.tx, id, status, to_tx_hash, error_message).await?;
+        self.status_updates.push((transfer, status));
+        Ok(())
+    }
+
+    pub async fn save_signatures(&mut self, id: i64, signatures: &str) -> Result<()> {
+        set_signatures(&mut *self.tx, id, signatures).await
+    }
+}
+
+/// One schema change. `apply` runs inside the transaction that records it.
+struct Migration {
+    description: &'static str,
+    apply: for<'c> fn(&'c mut SqliteConnection) -> BoxFuture<'c, Result<()>>,
+}
+
+/// Schema changes in the order they were made; step `n` is schema version `n`. Append
+/// only: a released step is never edited or reordered. Steps check before they change
+/// anything, since databases from before versioning start at version 0 with some of
+/// them already in place.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create relayer_transactions",
+        apply: |conn| Box::pin(create_transactions_table(conn)),
+    },
+    Migration {
+        description: "track gross and fee amounts",
+        apply: |conn| Box::pin(add_fee_columns(conn)),
+    },
+    Migration {
+        description: "add expires_at",
+        apply: |conn| add_column(conn, "expires_at", "INTEGER"),
+    },
+    Migration {
+        description: "add source_contract",
+        apply: |conn| add_column(conn, "source_contract", "TEXT"),
+    },
+    Migration {
+        description: "add collection_started_at",
+        apply: |conn| add_column(conn, "collection_started_at", "TEXT"),
+    },
+    Migration {
+        description: "add net_amount",
+        apply: |conn| add_column(conn, "net_amount", "TEXT"),
+    },
+    Migration {
+        description: "add idempotency_key",
+        apply: |conn| Box::pin(add_idempotency_key(conn)),
+    },
+    Migration {
+        description: "scope from_tx_hash uniqueness by chain",
+        apply: |conn| Box::pin(drop_global_source_hash_constraint(conn)),
+    },
+    Migration {
+        description: "create archived_transactions",
+        apply: |conn| Box::pin(create_archive_table(conn)),
+    },
+    Migration {
+        description: "create relayer_transactions indexes",
+        apply: |conn| Box::pin(create_indexes(conn)),
+    },
+    Migration {
+        description: "add submitted_at",
+        apply: |conn| add_column(conn, "submitted_at", "TEXT"),
+    },
+    Migration {
+        description: "add submission_account_nonce",
+        apply: |conn| add_column(conn, "submission_account_nonce", "INTEGER"),
+    },
+    Migration {
+        description: "add rebroadcasts",
+        apply: |conn| add_column(conn, "rebroadcasts", "INTEGER NOT NULL DEFAULT 0"),
+    },
+    Migration {
+        description: "archive every relayer_transactions column",
+        apply: |conn| Box::pin(add_archive_columns(conn)),
+    },
+    Migration {
+        description: "scope nonce uniqueness by chain",
+        apply: |conn| Box::pin(scope_nonce_uniqueness_by_chain(conn)),
+    },
+];
+
+async fn create_transactions_table(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS relayer_transactions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nonce INTEGER NOT NULL UNIQUE,
+            from_chain TEXT NOT NULL,
+            to_chain TEXT NOT NULL,
+            from_tx_hash TEXT NOT NULL,
+            to_tx_hash TEXT,
+            sender TEXT NOT NULL,
+            recipient TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            gross_amount INTEGER NOT NULL DEFAULT 0,
+            fee_amount INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL,
+            signatures TEXT,
+            error_message TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn add_fee_columns(conn: &mut SqliteConnection) -> Result<()> {
+    // Databases created before fees were tracked: rows there are fee-less as far as we know
+    if add_column_if_missing(conn, "gross_amount", "INTEGER NOT NULL DEFAULT 0").await? {
+        sqlx::query("UPDATE relayer_transactions SET gross_amount = amount")
+            .execute(&mut *conn)
+            .await?;
+    }
+    add_column_if_missing(conn, "fee_amount", "INTEGER NOT NULL DEFAULT 0").await?;
+    Ok(())
+}
+
+async fn add_idempotency_key(conn: &mut SqliteConnection) -> Result<()> {
+    if add_column_if_missing(conn, "idempotency_key", "TEXT").await? {
+        sqlx::query(
+            "UPDATE relayer_transactions SET idempotency_key = from_chain || ':' || nonce || ':' || from_tx_hash",
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// [`add_column_if_missing`] as a migration step
+fn add_column<'c>(
+    conn: &'c mut SqliteConnection,
+    column: &'static str,
+    definition: &'static str,
+) -> BoxFuture<'c, Result<()>> {
+    Box::pin(async move { add_column_if_missing(conn, column, definition).await.map(|_| ()) })
+}
+
+/// SQLite has no `ADD COLUMN IF NOT EXISTS`. Returns whether the column was added.
+async fn add_column_if_missing(conn: &mut SqliteConnection, column: &str, definition: &str) -> Result<bool> {
+    add_table_column_if_missing(conn, "relayer_transactions", column, definition).await
+}
+
+/// [`add_column_if_missing`] for any `table`
+async fn add_table_column_if_missing(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<bool> {
+    let existing: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if existing.0 > 0 {
+        return Ok(false);
+    }
+
+    info!("Adding column {}.{}", table, column);
+    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(true)
+}
+
+/// Source hashes used to be unique across all chains. They are now unique per `from_chain`
+/// (`idx_source_tx`); the index migration recreates the indexes the rebuild drops.
+async fn drop_global_source_hash_constraint(conn: &mut SqliteConnection) -> Result<()> {
+    rebuild_without_constraint(
+        conn,
+        "relayer_transactions",
+        "from_tx_hash TEXT NOT NULL UNIQUE",
+        "from_tx_hash TEXT NOT NULL",
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Nonces used to be unique across all chains, but each source chain numbers its own
+/// transfers, so a lock and a burn can share one. They are now unique per `from_chain`
+/// (`idx_chain_nonce`), in the archive as well.
+async fn scope_nonce_uniqueness_by_chain(conn: &mut SqliteConnection) -> Result<()> {
+    const LEGACY_COLUMN: &str = "nonce INTEGER NOT NULL UNIQUE";
+    if rebuild_without_constraint(conn, "relayer_transactions", LEGACY_COLUMN, "nonce INTEGER NOT NULL").await? {
+        create_indexes(conn).await?;
+    }
+    rebuild_without_constraint(conn, "archived_transactions", LEGACY_COLUMN, "nonce INTEGER NOT NULL").await?;
+
+    sqlx::query(
+        r#"
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_chain_nonce ON relayer_transactions(from_chain, nonce);
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_archived_chain_nonce ON archived_transactions(from_chain, nonce);
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Replace `legacy_column` with `column` in `table`'s definition. SQLite can't drop a column
+/// constraint, so the table is rebuilt, losing its indexes. Returns whether it was rebuilt;
+/// a table without `legacy_column` is left alone.
+async fn rebuild_without_constraint(
+    conn: &mut SqliteConnection,
+    table: &str,
+    legacy_column: &str,
+    column: &str,
+) -> Result<bool> {
+    let (schema,): (String,) = sqlx::query_as("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !schema.contains(legacy_column) {
+        return Ok(false);
+    }
+
+    info!("Rebuilding {} to replace `{}` with `{}`", table, legacy_column, column);
+    // Same columns in the same order, so rows copy across with `SELECT *`
+    let rebuilt = schema.replacen(legacy_column, column, 1);
+    let legacy_table = format!("{}_legacy", table);
+    sqlx::query(&format!("ALTER TABLE {} RENAME TO {}", table, legacy_table))
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(&rebuilt).execute(&mut *conn).await?;
+    sqlx::query(&format!("INSERT INTO {} SELECT * FROM {}", table, legacy_table))
+        .execute(&mut *conn)
+        .await?;
+    // Takes the old indexes with it
+    sqlx::query(&format!("DROP TABLE {}", legacy_table)).execute(&mut *conn).await?;
+
+    Ok(true)
+}
+
+async fn create_archive_table(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS archived_transactions (
+            id INTEGER PRIMARY KEY,
+            nonce INTEGER NOT NULL UNIQUE,
+            from_chain TEXT NOT NULL,
+            to_chain TEXT NOT NULL,
+            from_tx_hash TEXT NOT NULL,
+            to_tx_hash TEXT,
+            sender TEXT NOT NULL,
+            recipient TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            gross_amount INTEGER NOT NULL,
+            fee_amount INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            signatures TEXT,
+            error_message TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            archived_at TEXT NOT NULL,
+            expires_at INTEGER,
+            source_contract TEXT,
+            collection_started_at TEXT,
+            net_amount TEXT,
+            idempotency_key TEXT,
+            submitted_at TEXT,
+            submission_account_nonce INTEGER,
+            rebroadcasts INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// `relayer_transactions` columns that archives created before they were copied lack
+const ARCHIVE_COLUMNS: &[(&str, &str)] = &[
+    ("expires_at", "INTEGER"),
+    ("source_contract", "TEXT"),
+    ("collection_started_at", "TEXT"),
+    ("net_amount", "TEXT"),
+    ("idempotency_key", "TEXT"),
+    ("submitted_at", "TEXT"),
+    ("submission_account_nonce", "INTEGER"),
+    ("rebroadcasts", "INTEGER NOT NULL DEFAULT 0"),
+];
+
+async fn add_archive_columns(conn: &mut SqliteConnection) -> Result<()> {
+    for (column, definition) in ARCHIVE_COLUMNS {
+        add_table_column_if_missing(conn, "archived_transactions", column, definition).await?;
+    }
+    Ok(())
+}
+
+async fn create_indexes(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_nonce ON relayer_transactions(nonce);
+        CREATE INDEX IF NOT EXISTS idx_status ON relayer_transactions(status);
+        CREATE INDEX IF NOT EXISTS idx_from_tx_hash ON relayer_transactions(from_tx_hash);
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_source_tx ON relayer_transactions(from_chain, from_tx_hash);
+        CREATE INDEX IF NOT EXISTS idx_to_tx_hash ON relayer_transactions(to_tx_hash);
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_idempotency_key ON relayer_transactions(idempotency_key);
+        "#,
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Returns the transfer's `(from_chain, nonce)`, or `None` if no row has `id`. Rejects
+/// moves that `TransactionStatus::can_transition_to` does not allow.
+async fn set_status(
+    conn: &mut SqliteConnection,
+    id: i64,
+    status: TransactionStatus,
+    to_tx_hash: Option<&str>,
+    error_message: Option<&str>,
+) -> Result<Option<(Chain, i64)>> {
+    let current: Option<(Chain, i64, TransactionStatus)> =
+        sqlx::query_as("SELECT from_chain, nonce, status FROM relayer_transactions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    let Some((from_chain, nonce, current)) = current else {
+        return Ok(None);
+    };
+    if !current.can_transition_to(status) {
+        return Err(RelayerError::InvalidStatusTransition {
+            nonce: nonce as u64,
+            from: current,
+            to: status,
+        });
+    }
+
+    // Only if nobody moved it since we looked
+    let updated = sqlx::query(
+        r#"
+        UPDATE relayer_transactions
+        SET status = ?, to_tx_hash = ?, error_message = ?, updated_at = ?
+        WHERE id = ? AND status = ?
+        "#,
+    )
+    .bind(status)
+    .bind(to_tx_hash)
+    .bind(error_message)
+    .bind(Utc::now())
+    .bind(id)
+    .bind(current)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(RelayerError::Unknown(format!(
+            "Status of nonce {} changed while moving it to {}",
+            nonce, status
+        )));
+    }
+
+    Ok(Some((from_chain, nonce)))
+}
+
+async fn set_signatures<'e, E>(executor: E, id: i64, signatures: &str) -> Result<()>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        r#"
+        UPDATE relayer_transactions
+        SET signatures = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(signatures)
+    .bind(Utc::now())
+    .bind(id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance_status, eth_tx_hash, sol_tx_signature};
+
+    #[tokio::test]
+    async fn test_gross_and_fee_amounts_round_trip() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        db.create_transaction(1, Chain::Solana, Chain::Ethereum, &sol_tx_signature(1), "user", "0xabc", 995, 5)
+            .await
+            .unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 1).await.unwrap().unwrap();
+        assert_eq!(tx.amount, 995);
+        assert_eq!(tx.gross_amount, 1_000);
+        assert_eq!(tx.fee_amount, 5);
+
+        let history = db.get_transaction_history(Some("user"), None, None, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].fee_amount, 5);
+        assert!(db.get_transaction_history(Some("someone_else"), None, None, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_net_amount_is_stored_with_its_decimals() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let id = db
+            .create_transaction(1, Chain::Solana, Chain::Ethereum, &sol_tx_signature(1), "user", "0xabc", 1_500, 0)
+            .await
+            .unwrap();
+        assert_eq!(db.get_transaction_by_nonce(Chain::Solana, 1).await.unwrap().unwrap().net_amount, None);
+
+        db.set_net_amount(id, Amount::new(1_500, 9)).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 1).await.unwrap().unwrap();
+        assert_eq!(tx.net_amount, Some(Amount::new(1_500, 9)));
+        let (stored,): (String,) = sqlx::query_as("SELECT net_amount FROM relayer_transactions WHERE id = ?")
+            .bind(id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, "0.000001500");
+    }
+
+    #[tokio::test]
+    async fn test_migrations_are_recorded_and_applied_once() {
+        let db = Database::new_in_memory().await.unwrap();
+        assert_eq!(db.schema_version().await.unwrap(), MIGRATIONS.len() as i64);
+        db.create_transaction(1, Chain::Solana, Chain::Ethereum, &sol_tx_signature(1), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+
+        // Running again finds nothing to apply
+        db.run_migrations().await.unwrap();
+        let recorded: Vec<(i64, String)> =
+            sqlx::query_as("SELECT version, description FROM schema_migrations ORDER BY version")
+                .fetch_all(&db.pool)
+                .await
+                .unwrap();
+        let expected: Vec<(i64, String)> =
+            (1..).zip(MIGRATIONS).map(|(version, m)| (version, m.description.to_string())).collect();
+        assert_eq!(recorded, expected);
+
+        // A database from before versioning has no records; every step re-runs harmlessly
+        sqlx::query("DELETE FROM schema_migrations").execute(&db.pool).await.unwrap();
+        db.run_migrations().await.unwrap();
+        assert_eq!(db.schema_version().await.unwrap(), MIGRATIONS.len() as i64);
+        assert_eq!(db.get_transaction_by_nonce(Chain::Solana, 1).await.unwrap().unwrap().amount, 100);
+
+        // A newer relayer's schema is refused rather than guessed at
+        sqlx::query("INSERT INTO schema_migrations (version, description, applied_at) VALUES (?, 'future', ?)")
+            .bind(MIGRATIONS.len() as i64 + 1)
+            .bind(Utc::now())
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert!(matches!(db.run_migrations().await, Err(RelayerError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_history_pages_by_id() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let start = Utc::now();
+        for nonce in 1..=5 {
+            let hash = sol_tx_signature(nonce);
+            db.create_transaction(nonce, Chain::Solana, Chain::Ethereum, &hash, "user", "0xabc", 100, 0)
+                .await
+                .unwrap();
+        }
+
+        let first = db.get_transaction_history(None, None, None, 2).await.unwrap();
+        assert_eq!(first.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![5, 4]);
+        let second = db.get_transaction_history(None, None, Some(first[1].id), 2).await.unwrap();
+        assert_eq!(second.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![3, 2]);
+        let last = db.get_transaction_history(None, None, Some(second[1].id), 2).await.unwrap();
+        assert_eq!(last.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![1]);
+
+        assert_eq!(db.get_transaction_history(None, Some(start), None, 10).await.unwrap().len(), 5);
+        let later = Utc::now() + chrono::Duration::hours(1);
+        assert!(db.get_transaction_history(None, Some(later), None, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redelivered_event_is_recorded_once() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let mut updates = db.subscribe_status_updates();
+
+        let id = db
+            .create_transaction(5, Chain::Solana, Chain::Ethereum, &sol_tx_signature(5), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+        db.update_transaction_status(id, TransactionStatus::SignaturesCollected, None, None)
+            .await
+            .unwrap();
+
+        // The monitor reconnects and sees the same lock again
+        let again = db
+            .create_transaction(5, Chain::Solana, Chain::Ethereum, &sol_tx_signature(5), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+        assert_eq!(again, id);
+
+        let stats = db.get_stats().await.unwrap();
+        assert_eq!(stats.total, 1);
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 5).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+
+        assert_eq!(updates.try_recv().unwrap().status, TransactionStatus::Pending);
+        assert_eq!(updates.try_recv().unwrap().status, TransactionStatus::SignaturesCollected);
+        assert!(updates.try_recv().is_err(), "the duplicate published nothing");
+
+        // A different event claiming the same nonce is still a conflict
+        assert!(db
+            .create_transaction(5, Chain::Solana, Chain::Ethereum, &sol_tx_signature(500), "user", "0xabc", 100, 0)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lock_and_burn_can_share_a_nonce() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut updates = db.subscribe_status_updates();
+
+        let lock = db
+            .create_transaction(3, Chain::Solana, Chain::Ethereum, &sol_tx_signature(3), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+        let burn = db
+            .create_transaction(3, Chain::Ethereum, Chain::Solana, &eth_tx_hash(3), "0xsender", "user", 200, 0)
+            .await
+            .unwrap();
+        assert_ne!(lock, burn);
+
+        let sol = db.get_transaction_by_nonce(Chain::Solana, 3).await.unwrap().unwrap();
+        assert_eq!((sol.id, sol.amount), (lock, 100));
+        let eth = db.get_transaction_by_nonce(Chain::Ethereum, 3).await.unwrap().unwrap();
+        assert_eq!((eth.id, eth.amount), (burn, 200));
+        assert!(db.is_nonce_processed(Chain::Ethereum, 3).await.unwrap());
+        assert!(!db.is_nonce_processed(Chain::Sui, 3).await.unwrap());
+
+        // Updates say which of the two moved
+        assert_eq!(updates.try_recv().unwrap().from_chain, Chain::Solana);
+        assert_eq!(updates.try_recv().unwrap().from_chain, Chain::Ethereum);
+
+        // Still one transfer per nonce on each chain
+        assert!(db
+            .create_transaction(3, Chain::Ethereum, Chain::Solana, &eth_tx_hash(30), "0xsender", "user", 200, 0)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migration_scopes_nonce_uniqueness_by_chain() {
+        let db = Database::new_in_memory().await.unwrap();
+        db.create_transaction(3, Chain::Solana, Chain::Ethereum, &sol_tx_signature(3), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+
+        // Put back the global nonce constraint of databases from before the migration
+        let mut conn = db.pool.acquire().await.unwrap();
+        for table in ["relayer_transactions", "archived_transactions"] {
+            rebuild_without_constraint(&mut conn, table, "nonce INTEGER NOT NULL", "nonce INTEGER NOT NULL UNIQUE")
+                .await
+                .unwrap();
+        }
+        drop(conn);
+        sqlx::query("DELETE FROM schema_migrations WHERE version = ?")
+            .bind(MIGRATIONS.len() as i64)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        db.run_migrations().await.unwrap();
+
+        db.create_transaction(3, Chain::Ethereum, Chain::Solana, &eth_tx_hash(3), "0xsender", "user", 200, 0)
+            .await
+            .unwrap();
+        assert_eq!(db.get_transaction_by_nonce(Chain::Solana, 3).await.unwrap().unwrap().amount, 100);
+        // The rebuild kept the other indexes
+        assert!(db
+            .create_transaction(4, Chain::Solana, Chain::Ethereum, &sol_tx_signature(3), "user", "0xabc", 100, 0)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_source_hashes_are_stored_per_chain() {
+        let db = Database::new_in_memory().await.unwrap();
+        let lock = sol_tx_signature(1);
+        let burn = format!("0X{}", "AB".repeat(32));
+        db.create_transaction(1, Chain::Solana, Chain::Ethereum, &lock, "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+        db.create_transaction(2, Chain::Ethereum, Chain::Solana, &burn, "0xsender", "user", 100, 0)
+            .await
+            .unwrap();
+
+        let sol = db.get_transaction_by_hash(Chain::Solana, &lock).await.unwrap().unwrap();
+        assert_eq!((sol.nonce, sol.from_tx_hash.as_str()), (1, lock.as_str()));
+        // Stored 0x-lowercase, and found under any spelling
+        let eth = db.get_transaction_by_hash(Chain::Ethereum, &burn).await.unwrap().unwrap();
+        assert_eq!(eth.nonce, 2);
+        assert_eq!(eth.from_tx_hash, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(db.get_stats().await.unwrap().total, 2);
+
+        // A hash is only looked up among transfers from its own chain
+        assert!(db.get_transaction_by_hash(Chain::Ethereum, &lock).await.is_err());
+        assert!(db.get_transaction_by_hash(Chain::Ethereum, &eth_tx_hash(1)).await.unwrap().is_none());
+
+        // Malformed hashes never reach the table
+        assert!(db
+            .create_transaction(3, Chain::Ethereum, Chain::Solana, "0xburn3", "0xsender", "user", 100, 0)
+            .await
+            .is_err());
+        assert!(db
+            .create_transaction(3, Chain::Solana, Chain::Ethereum, &eth_tx_hash(3), "user", "0xabc", 100, 0)
+            .await
+            .is_err());
+        for chain in [Chain::Ethereum, Chain::Solana] {
+            assert!(db.get_transaction_by_nonce(chain, 3).await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_adds_later_columns_to_an_old_archive() {
+        let db = Database::new_in_memory().await.unwrap();
+        // The archive as first released, before it copied the later columns
+        sqlx::query("DROP TABLE archived_transactions").execute(&db.pool).await.unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE archived_transactions (
+                id INTEGER PRIMARY KEY,
+                nonce INTEGER NOT NULL UNIQUE,
+                from_chain TEXT NOT NULL,
+                to_chain TEXT NOT NULL,
+                from_tx_hash TEXT NOT NULL,
+                to_tx_hash TEXT,
+                sender TEXT NOT NULL,
+                recipient TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                gross_amount INTEGER NOT NULL,
+                fee_amount INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                signatures TEXT,
+                error_message TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                archived_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let archive_columns = MIGRATIONS
+            .iter()
+            .position(|m| m.description == "archive every relayer_transactions column")
+            .unwrap();
+        sqlx::query("DELETE FROM schema_migrations WHERE version > ?")
+            .bind(archive_columns as i64)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        db.run_migrations().await.unwrap();
+
+        let missing: Vec<(String,)> = sqlx::query_as(
+            "SELECT name FROM pragma_table_info('relayer_transactions') \
+             WHERE name NOT IN (SELECT name FROM pragma_table_info('archived_transactions'))",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert!(missing.is_empty(), "archive lacks {:?}", missing);
+    }
+
+    #[tokio::test]
+    async fn test_archive_moves_old_terminal_rows_only() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let old = Utc::now() - chrono::Duration::days(45);
+
+        for (nonce, status, updated_at) in [
+            (1, TransactionStatus::Confirmed, old),
+            (2, TransactionStatus::Refunded, old),
+            (3, TransactionStatus::Confirmed, Utc::now()),
+            (4, TransactionStatus::Pending, old),
+        ] {
+            let tx_hash = sol_tx_signature(nonce);
+            let id = db
+                .create_transaction(nonce, Chain::Solana, Chain::Ethereum, &tx_hash, "user", "0xabc", 100, 0)
+                .await
+                .unwrap();
+            advance_status(&db, id, status, None).await;
+            sqlx::query("UPDATE relayer_transactions SET updated_at = ? WHERE id = ?")
+                .bind(updated_at)
+                .bind(id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        let locked_before = db.get_locked_total().await.unwrap();
+
+        assert_eq!(db.archive_old_transactions(30).await.unwrap(), 2);
+
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 1).await.unwrap().is_none());
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 2).await.unwrap().is_none());
+        let status = |nonce| {
+            let db = db.clone();
+            async move { db.get_transaction_by_nonce(Chain::Solana, nonce).await.unwrap().unwrap().status }
+        };
+        assert_eq!(status(3).await, TransactionStatus::Confirmed);
+        assert_eq!(status(4).await, TransactionStatus::Pending);
+
+        let archived: Vec<(i64, TransactionStatus)> =
+            sqlx::query_as("SELECT nonce, status FROM archived_transactions ORDER BY nonce")
+                .fetch_all(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(archived, vec![(1, TransactionStatus::Confirmed), (2, TransactionStatus::Refunded)]);
+        let (key,): (Option<String>,) =
+            sqlx::query_as("SELECT idempotency_key FROM archived_transactions WHERE nonce = 1")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(key, Some(idempotency_key(Chain::Solana, 1, &sol_tx_signature(1))));
+
+        // Archived nonces still block replays and still count toward the vault balance
+        assert!(db.is_nonce_processed(Chain::Solana, 1).await.unwrap());
+        assert_eq!(db.get_locked_total().await.unwrap(), locked_before);
+
+        assert_eq!(db.archive_old_transactions(30).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_volume_stats_aggregate_per_direction() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let day = Duration::from_secs(24 * 3600);
+        async fn record(db: &Database, nonce: u64, from_chain: Chain, amount: u64, fee: u64) -> i64 {
+            let (to_chain, tx_hash) = match from_chain {
+                Chain::Solana => (Chain::Ethereum, sol_tx_signature(nonce)),
+                _ => (Chain::Solana, eth_tx_hash(nonce)),
+            };
+            db.create_transaction(nonce, from_chain, to_chain, &tx_hash, "sender", "recipient", amount, fee)
+                .await
+                .unwrap()
+        }
+
+        record(&db, 1, Chain::Solana, 995, 5).await;
+        let archived = record(&db, 2, Chain::Solana, 1_990, 10).await;
+        record(&db, 3, Chain::Ethereum, 500, 0).await;
+        let failed = record(&db, 4, Chain::Ethereum, 700, 0).await;
+        let old = record(&db, 5, Chain::Solana, 100, 0).await;
+
+        advance_status(&db, archived, TransactionStatus::Confirmed, None).await;
+        sqlx::query("UPDATE relayer_transactions SET updated_at = ? WHERE id = ?")
+            .bind(Utc::now() - chrono::Duration::days(45))
+            .bind(archived)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(db.archive_old_transactions(30).await.unwrap(), 1);
+        db.update_transaction_status(failed, TransactionStatus::Failed, None, Some("reverted"))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE relayer_transactions SET created_at = ? WHERE id = ?")
+            .bind(Utc::now() - chrono::Duration::days(2))
+            .bind(old)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let stats = db.get_volume_stats(day).await.unwrap();
+        assert!(stats.since <= Utc::now() - chrono::Duration::hours(23));
+        let volume = |from_chain, to_chain, transfers, gross_volume, net_volume, fee_volume| DirectionVolume {
+            from_chain,
+            to_chain,
+            transfers,
+            gross_volume,
+            net_volume,
+            fee_volume,
+        };
+        assert_eq!(
+            stats.directions,
+            vec![
+                volume(Chain::Ethereum, Chain::Solana, 1, 500, 500, 0),
+                volume(Chain::Solana, Chain::Ethereum, 2, 3_000, 2_985, 15),
+            ]
+        );
+
+        // A wider window reaches the older lock too
+        let stats = db.get_volume_stats(day * 7).await.unwrap();
+        assert_eq!(stats.directions[1], volume(Chain::Solana, Chain::Ethereum, 3, 3_100, 3_085, 15));
+
+        let empty = Database::new("sqlite::memory:", 1).await.unwrap();
+        assert!(empty.get_volume_stats(day).await.unwrap().directions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_transaction_leaves_no_partial_write() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let id = db
+            .create_transaction(9, Chain::Ethereum, Chain::Solana, &eth_tx_hash(9), "0xsender", "user", 100, 0)
+            .await
+            .unwrap();
+        let mut updates = db.subscribe_status_updates();
+
+        let result: Result<()> = db
+            .with_transaction(move |db_tx| {
+                Box::pin(async move {
+                    db_tx.save_signatures(id, r#"["sig"]"#).await?;
+                    db_tx
+                        .update_transaction_status(id, TransactionStatus::SignaturesCollected, None, None)
+                        .await?;
+                    Err(RelayerError::Unknown("crashed before commit".to_string()))
+                })
+            })
+            .await;
+        assert!(result.is_err());
+
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 9).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(tx.signatures, None);
+        assert!(updates.try_recv().is_err());
+
+        db.update_signatures(id, r#"["sig"]"#).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 9).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(tx.signatures.as_deref(), Some(r#"["sig"]"#));
+        assert_eq!(updates.try_recv().unwrap().status, TransactionStatus::SignaturesCollected);
+    }
+
+    #[tokio::test]
+    async fn test_submission_is_released_in_nonce_order() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        // Picked up out of order; 4 is still collecting signatures
+        let mut ids = std::collections::HashMap::new();
+        for nonce in [5u64, 3, 4] {
+            let id = db
+                .create_transaction(nonce, Chain::Solana, Chain::Ethereum, &sol_tx_signature(nonce), "user", "0xabc", 10, 0)
+                .await
+                .unwrap();
+            if nonce != 4 {
+                db.update_signatures(id, "[]").await.unwrap();
+            }
+            ids.insert(nonce, id);
+        }
+        // Another destination isn't held up by these
+        db.create_transaction(1, Chain::Ethereum, Chain::Solana, &eth_tx_hash(1), "0xsender", "user", 10, 0)
+            .await
+            .unwrap();
+
+        let releasable = |chain| {
+            let db = db.clone();
+            async move {
+                db.get_releasable_transactions(chain, 10)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|tx| tx.nonce)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(releasable(Chain::Solana).await, vec![1]);
+        // A transfer still collecting signatures holds back later submissions,
+        // but is itself handed out so collection can proceed
+        assert_eq!(releasable(Chain::Ethereum).await, vec![3, 4]);
+
+        advance_status(&db, ids[&3], TransactionStatus::Submitted, Some("0xmint3")).await;
+        assert_eq!(releasable(Chain::Ethereum).await, vec![4]);
+
+        db.update_signatures(ids[&4], "[]").await.unwrap();
+        assert_eq!(releasable(Chain::Ethereum).await, vec![4]);
+
+        db.update_transaction_status(ids[&4], TransactionStatus::Submitted, Some("0xmint4"), None)
+            .await
+            .unwrap();
+        assert_eq!(releasable(Chain::Ethereum).await, vec![5]);
+    }
+
+    #[tokio::test]
+    async fn test_illegal_status_transition_is_rejected() {
+        let db = Database::new_in_memory().await.unwrap();
+        let id = db
+            .create_transaction(7, Chain::Solana, Chain::Ethereum, &sol_tx_signature(7), "user", "0xabc", 100, 0)
+            .await
+            .unwrap();
+        advance_status(&db, id, TransactionStatus::Confirmed, Some("0xmint7")).await;
+        let mut updates = db.subscribe_status_updates();
+
+        let err = db
+            .update_transaction_status(id, TransactionStatus::Pending, None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RelayerError::InvalidStatusTransition {
+                nonce: 7,
+                from: TransactionStatus::Confirmed,
+                to: TransactionStatus::Pending,
+            }
+        ));
+
+        // Nothing was written or announced
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 7).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+        assert_eq!(tx.to_tx_hash.as_deref(), Some("0xmint7"));
+        assert!(updates.try_recv().is_err());
+
+        // Same check inside a transaction
+        let result: Result<()> = db
+            .with_transaction(move |db_tx| {
+                Box::pin(async move {
+                    db_tx
+                        .update_transaction_status(id, TransactionStatus::Submitted, None, None)
+                        .await
+                })
+            })
+            .await;
+        assert!(matches!(result, Err(RelayerError::InvalidStatusTransition { .. })));
+
+        db.update_transaction_status(id, TransactionStatus::Failed, None, Some("reverted after confirmation"))
+            .await
+            .unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 7).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_backlog_is_processed_in_batches() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        for nonce in 0..10u64 {
+            db.create_transaction(nonce, Chain::Ethereum, Chain::Solana, &eth_tx_hash(nonce), "0xsender", "user", 10, 0)
+                .await
+                .unwrap();
+        }
+
+        let batch = db.get_releasable_transactions(Chain::Solana, 4).await.unwrap();
+        assert_eq!(batch.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(db.get_pending_transactions(4).await.unwrap().len(), 4);
+
+        // Once a tick has moved its batch on, the next one picks up where it stopped
+        for tx in &batch {
+            advance_status(&db, tx.id, TransactionStatus::Submitted, Some("sig")).await;
+        }
+        let batch = db.get_releasable_transactions(Chain::Solana, 4).await.unwrap();
+        assert_eq!(batch.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::types::TransactionStatus;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,9 +24,28 @@ pub enum RelayerError {
     #[error("Transaction already processed: nonce {0}")]
     TransactionAlreadyProcessed(u64),
 
+    #[error("Invalid status transition for nonce {nonce}: {from} -> {to}")]
+    InvalidStatusTransition {
+        nonce: u64,
+        from: TransactionStatus,
+        to: TransactionStatus,
+    },
+
+    #[error("Source transaction not verified: {0}")]
+    SourceVerificationFailed(String),
+
     #[error("Transaction submission failed: {0}")]
     TransactionSubmissionFailed(String),
 
+    #[error("Destination bridge is paused")]
+    BridgePaused,
+
+    #[error("Destination contract reverted: {0}")]
+    ContractReverted(String),
+
+    #[error("Gas price {price_gwei:.2} gwei is above the {ceiling_gwei} gwei ceiling; not submitting")]
+    GasPriceAboveCeiling { price_gwei: f64, ceiling_gwei: f64 },
+
     #[error("Invalid chain: {0}")]
     InvalidChain(String),
 
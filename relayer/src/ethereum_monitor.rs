@@ -1,18 +1,23 @@
 use crate::{
-    config::EthereumConfig,
+    amount::Amount,
+    config::{redact_url, EthereumConfig},
     db::Database,
     error::{RelayerError, Result},
-    types::{BridgeEvent, Chain},
+    failover::EthereumRpc,
+    replay::ReplayProgress,
+    retry::{retry_with_backoff, RetryPolicy},
+    routing::TransferRoute,
+    types::{checksum_address, BridgeEvent},
 };
 use alloy::{
     primitives::Address,
-    providers::{Provider, ProviderBuilder, RootProvider},
+    providers::Provider,
     rpc::types::{Filter, Log},
     sol,
     sol_types::SolEvent,
-    transports::http::{Client, Http},
 };
 use std::str::FromStr;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
 // TODO: Use WebSocket subscriptions instead of polling
@@ -38,46 +43,80 @@ sol! {
 }
 
 pub struct EthereumMonitor {
-    provider: RootProvider<Http<Client>>,
-    bridge_contract: Address,
+    provider: EthereumRpc,
+    /// Every contract whose burns are relayed, `bridge_contract` first
+    bridge_contracts: Vec<Address>,
+    chain_id: u64,
     db: Database,
     confirmations: u64,
+    poll_interval: Duration,
+    log_chunk_size: u64,
+    /// Decimals recorded with each burn's amount, when configured
+    token_decimals: Option<u8>,
+    retry: RetryPolicy,
 }
 
 impl EthereumMonitor {
-    pub fn new(config: &EthereumConfig, db: Database) -> Result<Self> {
-        let provider = ProviderBuilder::new()
-            .on_http(
-                config
-                    .rpc_url
-                    .parse()
-                    .map_err(|e| RelayerError::ConfigError(format!("Invalid RPC URL: {:?}", e)))?,
-            );
-
-        let bridge_contract = Address::from_str(&config.bridge_contract)
-            .map_err(|e| RelayerError::ConfigError(format!("Invalid bridge contract address: {}", e)))?;
+    pub fn new(config: &EthereumConfig, db: Database, retry: RetryPolicy) -> Result<Self> {
+        let provider = EthereumRpc::ethereum(&config.rpc_urls)?;
+
+        let bridge_contracts = config
+            .monitored_bridge_contracts()
+            .map(|contract| {
+                Address::from_str(contract).map_err(|e| {
+                    RelayerError::ConfigError(format!("Invalid bridge contract address {}: {}", contract, e))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(Self {
             provider,
-            bridge_contract,
+            bridge_contracts,
+            chain_id: config.chain_id,
             db,
             confirmations: config.confirmations,
+            poll_interval: Duration::from_millis(config.poll_interval_ms),
+            log_chunk_size: config.log_chunk_size.max(1),
+            token_decimals: config.token_decimals,
+            retry,
         })
     }
 
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Fail if the RPC endpoint serves a different network than `chain_id` says
+    pub async fn verify_chain_id(&self) -> Result<()> {
+        let actual = retry_with_backoff(&self.retry, "Ethereum get_chain_id", || {
+            self.provider.call(|provider| async move { provider.get_chain_id().await })
+        })
+        .await?;
+
+        if actual != self.chain_id {
+            return Err(RelayerError::ConfigError(format!(
+                "Ethereum RPC {} serves chain {} but ETHEREUM_CHAIN_ID is {}",
+                redact_url(self.provider.current_url()),
+                actual,
+                self.chain_id
+            )));
+        }
+
+        Ok(())
+    }
+
 
     pub async fn start(&self) -> Result<()> {
         info!(
-            "Starting Ethereum monitor for bridge contract: {}",
-            self.bridge_contract
+            "Starting Ethereum monitor for bridge contracts: {:?}",
+            self.bridge_contracts
         );
 
     
-        let latest_block = self
-            .provider
-            .get_block_number()
-            .await
-            .map_err(|e| RelayerError::EthereumRpcError(format!("Failed to get block number: {}", e)))?;
+        let latest_block = retry_with_backoff(&self.retry, "Ethereum get_block_number", || {
+            self.provider.call(|provider| async move { provider.get_block_number().await })
+        })
+        .await?;
 
         info!("Starting from block: {}", latest_block);
 
@@ -90,11 +129,15 @@ impl EthereumMonitor {
 
         loop {
     
-            let current_block = match self.provider.get_block_number().await {
+            let current_block = match retry_with_backoff(&self.retry, "Ethereum get_block_number", || {
+                self.provider.call(|provider| async move { provider.get_block_number().await })
+            })
+            .await
+            {
                 Ok(block) => block,
                 Err(e) => {
                     error!("Error fetching current block: {}", e);
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    tokio::time::sleep(self.poll_interval).await;
                     continue;
                 }
             };
@@ -103,37 +146,81 @@ impl EthereumMonitor {
             if current_block > last_block + self.confirmations {
                 let to_block = current_block - self.confirmations;
 
-  
-                let filter = Filter::new()
-                    .address(self.bridge_contract)
-                    .event(SolanaBridge::TokensBurned::SIGNATURE)
-                    .from_block(last_block + 1)
-                    .to_block(to_block);
-
-                match self.provider.get_logs(&filter).await {
-                    Ok(logs) => {
-                        for log in logs {
-                            if let Err(e) = self.process_log(log).await {
-                                error!("Error processing log: {}", e);
-                            }
-                        }
-                        last_block = to_block;
-                    }
-                    Err(e) => {
-                        error!("Error fetching logs: {}", e);
-                    }
+                if let Err(e) = self.catch_up(&mut last_block, to_block).await {
+                    error!("Error fetching logs after block {}: {}", last_block, e);
                 }
             }
 
          
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+
+    /// Scan burns once from `from_block` up to the block that is `confirmations` deep now,
+    /// reporting after each chunk, then stop. Returns the last block scanned.
+    pub async fn backfill(&self, from_block: u64, progress: &mut dyn FnMut(ReplayProgress)) -> Result<u64> {
+        let current_block = retry_with_backoff(&self.retry, "Ethereum get_block_number", || {
+            self.provider.call(|provider| async move { provider.get_block_number().await })
+        })
+        .await?;
+        let to_block = current_block.saturating_sub(self.confirmations);
+        info!("Replaying Ethereum burns in blocks {}..={}", from_block, to_block);
+
+        let mut last_block = from_block.saturating_sub(1);
+        while last_block < to_block {
+            let chunk_end = to_block.min(last_block + self.log_chunk_size);
+            self.catch_up(&mut last_block, chunk_end).await?;
+            progress(ReplayProgress::Ethereum { block: last_block, to_block });
         }
+
+        Ok(to_block)
+    }
+
+    /// Process burns in `last_block + 1..=to_block`, at most `log_chunk_size` blocks
+    /// per request. `last_block` advances after each chunk, so a failure only
+    /// repeats the chunk it happened in.
+    async fn catch_up(&self, last_block: &mut u64, to_block: u64) -> Result<()> {
+        while *last_block < to_block {
+            let from = *last_block + 1;
+            let to = to_block.min(*last_block + self.log_chunk_size);
+
+            let filter = Filter::new()
+                .address(self.bridge_contracts.clone())
+                .event(SolanaBridge::TokensBurned::SIGNATURE)
+                .from_block(from)
+                .to_block(to);
+
+            let logs = retry_with_backoff(&self.retry, "Ethereum get_logs", || {
+                self.provider.call(|provider| {
+                    let filter = filter.clone();
+                    async move { provider.get_logs(&filter).await }
+                })
+            })
+            .await?;
+
+            for log in logs {
+                if let Err(e) = self.process_log(log).await {
+                    error!("Error processing log: {}", e);
+                }
+            }
+            debug!("Processed Ethereum blocks {}..={}", from, to);
+            *last_block = to;
+        }
+
+        Ok(())
     }
 
 
     async fn process_log(&self, log: Log) -> Result<()> {
         debug!("Processing log: {:?}", log);
 
+        let contract = log.address();
+        if !self.bridge_contracts.contains(&contract) {
+            warn!("Ignoring log from unwatched contract {}", contract);
+            return Ok(());
+        }
+
         let alloy_log = alloy::primitives::Log::new(
             log.address(),
             log.topics().to_vec(),
@@ -150,7 +237,7 @@ impl EthereumMonitor {
         );
 
     
-        if self.db.is_nonce_processed(event.nonce).await? {
+        if self.db.is_nonce_processed(TransferRoute::ETHEREUM_TO_SOLANA.from, event.nonce).await? {
             warn!("Nonce {} already processed, skipping", event.nonce);
             return Ok(());
         }
@@ -167,22 +254,23 @@ impl EthereumMonitor {
 
        
         let bridge_event = BridgeEvent::TokensBurned {
-            from_chain: Chain::Ethereum,
-            to_chain: Chain::Solana,
-            sender: format!("{:?}", event.sender),
+            from_chain: TransferRoute::ETHEREUM_TO_SOLANA.from,
+            to_chain: TransferRoute::ETHEREUM_TO_SOLANA.to,
+            sender: checksum_address(&event.sender),
             recipient: event.solanaAddress.clone(),
             amount,
             nonce: event.nonce,
             tx_hash: format!("{:?}", tx_hash),
         };
 
-        self.handle_event(bridge_event).await?;
+        self.handle_event(bridge_event, contract).await?;
 
         Ok(())
     }
 
 
-    async fn handle_event(&self, event: BridgeEvent) -> Result<()> {
+    /// Record a burn emitted by bridge contract `contract`
+    async fn handle_event(&self, event: BridgeEvent, contract: Address) -> Result<()> {
         match &event {
             BridgeEvent::TokensBurned {
                 from_chain,
@@ -208,8 +296,13 @@ impl EthereumMonitor {
                         sender,
                         recipient,
                         *amount,
+                        0,
                     )
                     .await?;
+                self.db.set_source_contract(tx_id, &checksum_address(&contract)).await?;
+                if let Some(decimals) = self.token_decimals {
+                    self.db.set_net_amount(tx_id, Amount::new(u128::from(*amount), decimals)).await?;
+                }
 
                 info!("Created relayer transaction with ID: {}", tx_id);
             }
@@ -222,3 +315,194 @@ impl EthereumMonitor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockEthereumRpc, TEST_BRIDGE_CONTRACT};
+    use crate::types::Chain;
+    use solana_sdk::pubkey::Pubkey;
+    use axum::{routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    fn block_param(value: &Value) -> u64 {
+        u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+    }
+
+    /// `eth_getLogs` endpoint that records each requested range, rejects ranges
+    /// over 2000 blocks and fails once for a request starting at `fail_from`
+    async fn mock_logs_rpc(ranges: Arc<Mutex<Vec<(u64, u64)>>>, fail_from: Arc<Mutex<Option<u64>>>) -> String {
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let ranges = ranges.clone();
+                let fail_from = fail_from.clone();
+                async move {
+                    let filter = &body["params"][0];
+                    let (from, to) = (block_param(&filter["fromBlock"]), block_param(&filter["toBlock"]));
+                    ranges.lock().unwrap().push((from, to));
+
+                    let mut fail_from = fail_from.lock().unwrap();
+                    if to - from + 1 > 2000 || *fail_from == Some(from) {
+                        *fail_from = None;
+                        return Json(json!({
+                            "jsonrpc": "2.0",
+                            "id": body["id"],
+                            "error": { "code": -32005, "message": "query returned more than 10000 results" }
+                        }));
+                    }
+                    Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": [] }))
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    fn test_config(rpc_url: String) -> EthereumConfig {
+        EthereumConfig {
+            rpc_urls: vec![rpc_url.clone()],
+            ws_url: rpc_url,
+            chain_id: 1,
+            bridge_contract: "0xaBD6f99Fbb77051B28942abe3118bf4D8Ea9F2CA".to_string(),
+            extra_bridge_contracts: vec![],
+            wrapped_sol_contract: "0xF718C74C9b298bCDd48Ed8801325E6ddBE2a5A5c".to_string(),
+            validator_registry_contract: "0xE45DC6606979b9086375561Ff7d8f66f8C506816".to_string(),
+            confirmations: 12,
+            max_priority_fee_gwei: 1.5,
+            max_fee_per_gas_gwei: 200.0,
+            max_gas_price_gwei: None,
+            poll_interval_ms: 5000,
+            log_chunk_size: 2000,
+            min_transfer_amount: None,
+            max_transfer_amount: None,
+            token_decimals: None,
+        }
+    }
+
+    fn test_retry() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            max_attempts: 1,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Endpoint that answers `eth_chainId` with `chain_id`
+    async fn mock_chain_rpc(chain_id: u64) -> String {
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| async move {
+                Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": format!("0x{:x}", chain_id) }))
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    /// Endpoint that accepts requests and never answers within a test's patience
+    async fn mock_hung_rpc() -> String {
+        let app = Router::new().route(
+            "/",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Json(json!({}))
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_hung_endpoint_times_out() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let retry = RetryPolicy {
+            max_attempts: 2,
+            timeout: Duration::from_millis(100),
+            ..test_retry()
+        };
+        let monitor = EthereumMonitor::new(&test_config(mock_hung_rpc().await), db, retry).unwrap();
+
+        let started = std::time::Instant::now();
+        let err = monitor.verify_chain_id().await.unwrap_err();
+        assert!(matches!(err, RelayerError::TimeoutError), "{:?}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_chain_id_mismatch_is_rejected() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+
+        let monitor = EthereumMonitor::new(&test_config(mock_chain_rpc(1).await), db.clone(), test_retry()).unwrap();
+        monitor.verify_chain_id().await.unwrap();
+
+        // Configured for mainnet, pointed at Sepolia
+        let monitor = EthereumMonitor::new(&test_config(mock_chain_rpc(11155111).await), db, test_retry()).unwrap();
+        let err = monitor.verify_chain_id().await.unwrap_err();
+        assert!(matches!(err, RelayerError::ConfigError(ref e) if e.contains("11155111")), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_large_block_gap_is_fetched_in_chunks() {
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let fail_from = Arc::new(Mutex::new(Some(6001)));
+        let rpc_url = mock_logs_rpc(ranges.clone(), fail_from).await;
+
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let monitor = EthereumMonitor::new(&test_config(rpc_url), db, test_retry()).unwrap();
+
+        // A failing chunk keeps the progress made before it
+        let mut last_block = 0;
+        assert!(monitor.catch_up(&mut last_block, 10_000).await.is_err());
+        assert_eq!(last_block, 6000);
+
+        monitor.catch_up(&mut last_block, 10_000).await.unwrap();
+        assert_eq!(last_block, 10_000);
+        assert_eq!(
+            *ranges.lock().unwrap(),
+            vec![(1, 2000), (2001, 4000), (4001, 6000), (6001, 8000), (6001, 8000), (8001, 10_000)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_burns_from_every_bridge_contract_are_tagged() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let mut config = test_config(rpc.url().to_string());
+        let token_bridge = Address::repeat_byte(0x22);
+        config.extra_bridge_contracts = vec![token_bridge.to_string()];
+        let monitor = EthereumMonitor::new(&config, db.clone(), test_retry()).unwrap();
+
+        let user = Pubkey::new_unique().to_string();
+        {
+            let mut chain = rpc.chain();
+            chain.add_burn(10, 1, 100, &user);
+            chain.add_burn_from(token_bridge, 20, 2, 200, &user);
+            // Not configured, so not relayed
+            chain.add_burn_from(Address::repeat_byte(0x33), 30, 3, 300, &user);
+        }
+
+        monitor.backfill(1, &mut |_| {}).await.unwrap();
+
+        let primary = db.get_transaction_by_nonce(Chain::Ethereum, 1).await.unwrap().unwrap();
+        assert_eq!(primary.source_contract, Some(TEST_BRIDGE_CONTRACT.to_string()));
+        let token = db.get_transaction_by_nonce(Chain::Ethereum, 2).await.unwrap().unwrap();
+        assert_eq!(token.source_contract, Some(checksum_address(&token_bridge)));
+        assert_eq!(token.amount, 200);
+        assert!(db.get_transaction_by_nonce(Chain::Ethereum, 3).await.unwrap().is_none());
+    }
+}
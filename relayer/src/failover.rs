@@ -0,0 +1,227 @@
+use crate::config::redact_url;
+use crate::error::{RelayerError, Result};
+use alloy::{
+    providers::{ProviderBuilder, RootProvider},
+    transports::http::{Client, Http},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Consecutive errors after which an endpoint is considered down and we rotate away
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+pub type SolanaRpc = FailoverClient<RpcClient>;
+pub type EthereumRpc = FailoverClient<RootProvider<Http<Client>>>;
+
+struct Endpoint<C> {
+    url: String,
+    client: Arc<C>,
+    consecutive_failures: AtomicU32,
+}
+
+/// Per-endpoint health snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub consecutive_failures: u32,
+    pub active: bool,
+}
+
+/// A list of RPC clients for the same chain. Calls go to the active endpoint;
+/// after `failure_threshold` consecutive errors it rotates to the next one.
+pub struct FailoverClient<C> {
+    endpoints: Vec<Endpoint<C>>,
+    active: AtomicUsize,
+    failure_threshold: u32,
+}
+
+impl<C> FailoverClient<C> {
+    pub fn new<F>(urls: &[String], failure_threshold: u32, mut build: F) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<C>,
+    {
+        if urls.is_empty() {
+            return Err(RelayerError::ConfigError("At least one RPC URL is required".to_string()));
+        }
+
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                Ok(Endpoint {
+                    url: url.clone(),
+                    client: Arc::new(build(url)?),
+                    consecutive_failures: AtomicU32::new(0),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+            failure_threshold: failure_threshold.max(1),
+        })
+    }
+
+    /// Client for the active endpoint, for work that doesn't go over the wire (e.g. building calldata)
+    pub fn current(&self) -> Arc<C> {
+        self.endpoints[self.active.load(Ordering::Relaxed)].client.clone()
+    }
+
+    pub fn current_url(&self) -> &str {
+        &self.endpoints[self.active.load(Ordering::Relaxed)].url
+    }
+
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        let active = self.active.load(Ordering::Relaxed);
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, endpoint)| EndpointHealth {
+                url: redact_url(&endpoint.url),
+                consecutive_failures: endpoint.consecutive_failures.load(Ordering::Relaxed),
+                active: i == active,
+            })
+            .collect()
+    }
+
+    /// Run `f` against the active endpoint. When an error trips the failure threshold
+    /// the next endpoint is tried immediately, so each call visits every endpoint at most once.
+    pub async fn call<T, E, F, Fut>(&self, f: F) -> std::result::Result<T, E>
+    where
+        F: Fn(Arc<C>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Display,
+    {
+        let mut tried = 0;
+
+        loop {
+            let index = self.active.load(Ordering::Relaxed);
+            let endpoint = &self.endpoints[index];
+
+            match f(endpoint.client.clone()).await {
+                Ok(value) => {
+                    if endpoint.consecutive_failures.swap(0, Ordering::Relaxed) >= self.failure_threshold {
+                        info!("RPC endpoint {} recovered", redact_url(&endpoint.url));
+                    }
+                    return Ok(value);
+                }
+                Err(e) => {
+                    tried += 1;
+                    let failures = endpoint.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+
+                    if failures < self.failure_threshold || self.endpoints.len() == 1 {
+                        return Err(e);
+                    }
+
+                    let next = (index + 1) % self.endpoints.len();
+                    if self
+                        .active
+                        .compare_exchange(index, next, Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        warn!(
+                            "RPC endpoint {} failed {} times in a row ({}), failing over to {}",
+                            redact_url(&endpoint.url),
+                            failures,
+                            e,
+                            redact_url(&self.endpoints[next].url)
+                        );
+                    }
+
+                    if tried >= self.endpoints.len() {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SolanaRpc {
+    pub fn solana(urls: &[String], commitment: CommitmentConfig) -> Result<Self> {
+        Self::new(urls, DEFAULT_FAILURE_THRESHOLD, |url| {
+            Ok(RpcClient::new_with_commitment(url.to_string(), commitment))
+        })
+    }
+}
+
+impl EthereumRpc {
+    pub fn ethereum(urls: &[String]) -> Result<Self> {
+        Self::new(urls, DEFAULT_FAILURE_THRESHOLD, |url| {
+            Ok(ProviderBuilder::new().on_http(
+                url.parse()
+                    .map_err(|e| RelayerError::ConfigError(format!("Invalid RPC URL: {:?}", e)))?,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stand-in client that either serves requests or fails like a dead endpoint
+    struct FakeClient {
+        name: &'static str,
+        up: bool,
+    }
+
+    impl FakeClient {
+        async fn get_slot(&self) -> std::result::Result<&'static str, String> {
+            if self.up {
+                Ok(self.name)
+            } else {
+                Err(format!("{} unreachable", self.name))
+            }
+        }
+    }
+
+    fn client(threshold: u32) -> FailoverClient<FakeClient> {
+        let urls = vec!["http://primary".to_string(), "http://secondary".to_string()];
+        FailoverClient::new(&urls, threshold, |url| {
+            Ok(if url.contains("primary") {
+                FakeClient { name: "primary", up: false }
+            } else {
+                FakeClient { name: "secondary", up: true }
+            })
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_secondary_serves_when_primary_fails() {
+        let rpc = client(1);
+
+        let served_by = rpc.call(|c| async move { c.get_slot().await }).await.unwrap();
+        assert_eq!(served_by, "secondary");
+        assert_eq!(rpc.current_url(), "http://secondary");
+
+        let health = rpc.health();
+        assert_eq!(health[0].consecutive_failures, 1);
+        assert!(!health[0].active);
+        assert!(health[1].active);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_waits_for_consecutive_errors() {
+        let rpc = client(2);
+
+        assert!(rpc.call(|c| async move { c.get_slot().await }).await.is_err());
+        assert_eq!(rpc.current_url(), "http://primary");
+
+        let served_by = rpc.call(|c| async move { c.get_slot().await }).await.unwrap();
+        assert_eq!(served_by, "secondary");
+    }
+
+    #[test]
+    fn test_requires_at_least_one_url() {
+        let result = FailoverClient::new(&[], 1, |_| Ok(FakeClient { name: "none", up: true }));
+        assert!(matches!(result, Err(RelayerError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::RelayerConfig;
+use crate::error::{RelayerError, Result};
+use alloy::signers::local::PrivateKeySigner;
+use solana_sdk::signature::Keypair;
+use std::str::FromStr;
+
+/// Key material for the relayer's own signing accounts, read from a file or from an
+/// environment variable so it never has to sit in the config itself.
+/// A path wins over an env var when both are configured.
+fn read_secret(path: Option<&str>, env: Option<&str>, what: &str) -> Result<Option<String>> {
+    if let Some(path) = path {
+        return std::fs::read_to_string(path)
+            .map(|secret| Some(secret.trim().to_string()))
+            .map_err(|e| RelayerError::ConfigError(format!("Failed to read {} from {}: {}", what, path, e)));
+    }
+    if let Some(name) = env {
+        return std::env::var(name)
+            .map(|secret| Some(secret.trim().to_string()))
+            .map_err(|_| RelayerError::ConfigError(format!("{} env var {} is not set", what, name)));
+    }
+    Ok(None)
+}
+
+/// The Ethereum submitter key as 0x-prefixed hex, checked to be a valid secp256k1 key
+pub fn load_ethereum_key(config: &RelayerConfig) -> Result<Option<String>> {
+    let Some(key) = read_secret(
+        config.ethereum_key_path.as_deref(),
+        config.ethereum_key_env.as_deref(),
+        "Ethereum key",
+    )?
+    else {
+        return Ok(None);
+    };
+    parse_ethereum_key(&key)?;
+    Ok(Some(key))
+}
+
+/// The Solana submitter keypair as its 64 raw bytes
+pub fn load_solana_keypair(config: &RelayerConfig) -> Result<Option<Vec<u8>>> {
+    read_secret(
+        config.solana_keypair_path.as_deref(),
+        config.solana_keypair_env.as_deref(),
+        "Solana keypair",
+    )?
+    .map(|json| parse_solana_keypair(&json))
+    .transpose()
+}
+
+/// A 32-byte secp256k1 key written as `0x`-prefixed hex
+fn parse_ethereum_key(key: &str) -> Result<PrivateKeySigner> {
+    let hex = key
+        .strip_prefix("0x")
+        .ok_or_else(|| RelayerError::ConfigError("Ethereum key must be 0x-prefixed hex".to_string()))?;
+    if hex.len() != 64 {
+        return Err(RelayerError::ConfigError(format!(
+            "Ethereum key must be 32 bytes, got {} hex characters",
+            hex.len()
+        )));
+    }
+    PrivateKeySigner::from_str(hex).map_err(|e| RelayerError::ConfigError(format!("Invalid Ethereum key: {}", e)))
+}
+
+/// A keypair in the Solana CLI's JSON format: an array of 64 byte values
+fn parse_solana_keypair(json: &str) -> Result<Vec<u8>> {
+    let bytes: Vec<u8> = serde_json::from_str(json)
+        .map_err(|e| RelayerError::ConfigError(format!("Solana keypair is not a JSON byte array: {}", e)))?;
+    Keypair::from_bytes(&bytes).map_err(|e| RelayerError::ConfigError(format!("Invalid Solana keypair: {}", e)))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{write_keypair_file, Signer};
+
+    const TEST_ETH_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn key_config() -> RelayerConfig {
+        toml::from_str(
+            r#"
+            poll_interval_ms = 5000
+            max_retries = 3
+            retry_delay_ms = 2000
+            gas_price_multiplier = 1.2
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_solana_keypair_file() {
+        let path = std::env::temp_dir().join(format!("relayer-keys-{}.json", std::process::id()));
+        let keypair = Keypair::new();
+        write_keypair_file(&keypair, &path).unwrap();
+
+        let mut config = key_config();
+        config.solana_keypair_path = Some(path.to_string_lossy().into_owned());
+        let bytes = load_solana_keypair(&config);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = Keypair::from_bytes(&bytes.unwrap().unwrap()).unwrap();
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+
+        assert!(parse_solana_keypair("[1, 2, 3]").is_err());
+        assert!(parse_solana_keypair(&Keypair::new().to_base58_string()).is_err());
+    }
+
+    #[test]
+    fn test_ethereum_hex_key_from_env() {
+        let mut config = key_config();
+        assert_eq!(load_ethereum_key(&config).unwrap(), None);
+
+        config.ethereum_key_env = Some("RELAYER_TEST_ETH_KEY".to_string());
+        assert!(load_ethereum_key(&config).is_err());
+
+        std::env::set_var("RELAYER_TEST_ETH_KEY", format!("{}\n", TEST_ETH_KEY));
+        let key = load_ethereum_key(&config);
+        std::env::remove_var("RELAYER_TEST_ETH_KEY");
+        assert_eq!(key.unwrap().as_deref(), Some(TEST_ETH_KEY));
+
+        // Bare hex and short keys are refused
+        assert!(parse_ethereum_key(TEST_ETH_KEY.trim_start_matches("0x")).is_err());
+        assert!(parse_ethereum_key("0xac09").is_err());
+    }
+}
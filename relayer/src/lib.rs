@@ -0,0 +1,25 @@
+pub mod amount;
+pub mod api;
+pub mod attestation;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod ethereum_monitor;
+pub mod failover;
+pub mod keys;
+pub mod nonce_manager;
+pub mod reconcile;
+pub mod replay;
+pub mod retry;
+pub mod routing;
+pub mod selftest;
+pub mod solana_monitor;
+#[cfg(feature = "sui")]
+pub mod sui_monitor;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod token_accounts;
+pub mod transaction_submitter;
+pub mod types;
+pub mod validator_client;
+pub mod wire;
@@ -1,21 +1,25 @@
-mod config;
-mod db;
-mod error;
-mod ethereum_monitor;
-mod solana_monitor;
-mod transaction_submitter;
-mod types;
-mod validator_client;
-
 use anyhow::Result;
-use config::Config;
-use db::Database;
-use ethereum_monitor::EthereumMonitor;
-use solana_monitor::SolanaMonitor;
+use relayer::{
+    api::{self, ApiState},
+    attestation,
+    config::{self, Config},
+    db::Database,
+    ethereum_monitor::EthereumMonitor,
+    failover::SolanaRpc,
+    keys,
+    reconcile::Reconciler,
+    retry::RetryPolicy,
+    selftest::{self, Outcome},
+    solana_monitor::SolanaMonitor,
+    transaction_submitter::TransactionSubmitter,
+    types::{Chain, TransactionStatus},
+    validator_client::ValidatorClient,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::path::Path;
+use std::str::FromStr;
 use tokio::time::{interval, Duration};
 use tracing::{error, info, warn};
-use transaction_submitter::TransactionSubmitter;
-use validator_client::ValidatorClient;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -30,15 +34,20 @@ async fn main() -> Result<()> {
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
 
     info!("Loading configuration...");
-    let config = Config::from_env()?;
+    let config_path = std::env::var("RELAYER_CONFIG").unwrap_or_else(|_| "relayer.toml".to_string());
+    let config = Config::load(Some(Path::new(&config_path)))?;
     info!("Configuration loaded");
 
+    if std::env::args().skip(1).any(|arg| arg == "--selftest") {
+        return run_selftest(&config).await;
+    }
+
     info!("Connecting to database...");
     let db = Database::new(&config.database.url, config.database.max_connections).await?;
     info!("Database connected");
 
-    info!("Solana RPC: {}", config.solana.rpc_url);
-    info!("Ethereum RPC: {}", config.ethereum.rpc_url);
+    info!("Solana RPC: {}", redacted_urls(&config.solana.rpc_urls));
+    info!("Ethereum RPC: {}", redacted_urls(&config.ethereum.rpc_urls));
     info!(
         "Bridge contracts - Solana: {}, Ethereum: {}",
         config.solana.bridge_program_id, config.ethereum.bridge_contract
@@ -54,33 +63,69 @@ async fn main() -> Result<()> {
             info!("  Submitted: {}", stats.submitted);
             info!("  Confirmed: {}", stats.confirmed);
             info!("  Failed: {}", stats.failed);
+            info!("  Refunded: {}", stats.refunded);
         }
         Err(e) => warn!("Could not fetch stats: {}", e),
     }
 
     // Create monitors and submitter
     info!("Initializing chain monitors...");
-    let solana_monitor = SolanaMonitor::new(&config.solana, db.clone())?;
-    let ethereum_monitor = EthereumMonitor::new(&config.ethereum, db.clone())?;
+    let retry_policy = RetryPolicy::from_config(&config.relayer);
+    let solana_monitor = SolanaMonitor::new(&config.solana, db.clone(), retry_policy)?;
+    let ethereum_monitor = EthereumMonitor::new(&config.ethereum, db.clone(), retry_policy)?;
+    ethereum_monitor.verify_chain_id().await?;
+    info!("Ethereum RPC serves chain {}", config.ethereum.chain_id);
 
     info!("Initializing validator client...");
-    let validator_client = ValidatorClient::new(config.validators.clone());
+    let validator_client =
+        ValidatorClient::new(config.validators.clone()).with_wire_format(config.relayer.validator_wire_format);
+    match config.solana.bridge_config_account.as_deref() {
+        Some(account) => {
+            let bridge_config = Pubkey::from_str(account)
+                .map_err(|e| anyhow::anyhow!("Invalid bridge config account: {}", e))?;
+            let commitment = CommitmentConfig::from_str(&config.solana.commitment)
+                .map_err(|e| anyhow::anyhow!("Invalid commitment: {}", e))?;
+            let rpc = SolanaRpc::solana(&config.solana.rpc_urls, commitment)?;
+            validator_client.verify_against_chain(&rpc, bridge_config).await?;
+        }
+        None => warn!("SOLANA_BRIDGE_CONFIG_ACCOUNT not set, validator set not checked against the program"),
+    }
 
     info!("Initializing transaction submitter...");
-    let transaction_submitter = TransactionSubmitter::new(
+    let mut transaction_submitter = TransactionSubmitter::new(
         config.solana.clone(),
         config.ethereum.clone(),
+        config.relayer.clone(),
         db.clone(),
         validator_client,
     )?;
+    load_signing_keys(&mut transaction_submitter, &config.relayer)?;
+
+    let reconciler = Reconciler::new(&config.solana, &config.relayer, db.clone())?;
+    if reconciler.is_none() {
+        warn!("SOLANA_BRIDGE_CONFIG_ACCOUNT not set, vault reconciliation disabled");
+    }
+
+    let attestation_signer = config
+        .relayer
+        .attestation_keypair_path
+        .as_deref()
+        .map(attestation::load_signer)
+        .transpose()?;
+    if attestation_signer.is_none() {
+        warn!("ATTESTATION_KEYPAIR_PATH not set, /attestation/:nonce disabled");
+    }
+
+    let api_state = ApiState::new(&config.solana, &config.ethereum, db.clone(), attestation_signer)?
+        .with_validators(config.validators.clone());
 
     let shutdown = tokio::signal::ctrl_c();
 
     info!("Relayer is running!");
     info!("");
     info!("Monitoring chains:");
-    info!("  Solana:   {}", config.solana.rpc_url);
-    info!("  Ethereum: {}", config.ethereum.rpc_url);
+    info!("  Solana:   {}", redacted_urls(&config.solana.rpc_urls));
+    info!("  Ethereum: {}", redacted_urls(&config.ethereum.rpc_urls));
     info!("");
     info!("Press Ctrl+C to stop");
 
@@ -119,6 +164,31 @@ async fn main() -> Result<()> {
                     if let Err(e) = process_transactions(db_clone3, transaction_submitter, relayer_config).await {
                         error!("Transaction processor error: {}", e);
                     }
+                },
+                // Serve the HTTP API (health checks)
+                async {
+                    info!("Starting API server task...");
+                    if let Err(e) = api::serve(&config.relayer.api_listen_addr, api_state).await {
+                        error!("API server error: {}", e);
+                    }
+                },
+                // Move old finished transfers out of the live table
+                async {
+                    if config.relayer.archive_after_days > 0 {
+                        info!("Starting archival task...");
+                        archive_transactions(db_clone2, config.relayer.archive_after_days).await;
+                    }
+                },
+                // Monitor Sui for TokensLocked events
+                monitor_sui(config.sui.clone(), db.clone(), retry_policy),
+                // Compare on-chain total_locked with DB totals
+                async {
+                    if let Some(ref reconciler) = reconciler {
+                        info!("Starting reconciliation task...");
+                        if let Err(e) = reconciler.start().await {
+                            error!("Reconciliation error: {}", e);
+                        }
+                    }
                 }
             )
         } => {}
@@ -130,6 +200,95 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Hand the submitter its signing keys. Broadcasting needs both; a dry run only
+/// simulates, so missing keys there are a warning rather than a startup failure.
+fn load_signing_keys(submitter: &mut TransactionSubmitter, config: &config::RelayerConfig) -> Result<()> {
+    match keys::load_ethereum_key(config)? {
+        Some(key) => submitter.set_ethereum_signer(&key)?,
+        None if config.dry_run => warn!("No Ethereum key configured, dry-run mints are simulated without a sender"),
+        None => anyhow::bail!("Set RELAYER_ETH_KEY_PATH or RELAYER_ETH_KEY_ENV to sign Ethereum mints"),
+    }
+    match keys::load_solana_keypair(config)? {
+        Some(keypair) => submitter.set_solana_keypair(&keypair)?,
+        None if config.dry_run => warn!("No Solana keypair configured, dry-run unlocks can't be simulated"),
+        None => anyhow::bail!("Set RELAYER_SOLANA_KEYPAIR_PATH or RELAYER_SOLANA_KEYPAIR_ENV to sign Solana unlocks"),
+    }
+    info!("Submitter signing keys loaded");
+    Ok(())
+}
+
+/// `--selftest`: bridge a dust amount end to end and report each stage, without
+/// touching the relayer's database or starting the main loop
+async fn run_selftest(config: &Config) -> Result<()> {
+    info!("Running self-test against {}", redacted_urls(&config.solana.rpc_urls));
+    let ethereum_key = keys::load_ethereum_key(&config.relayer)?
+        .ok_or_else(|| anyhow::anyhow!("--selftest needs RELAYER_ETH_KEY_PATH or RELAYER_ETH_KEY_ENV"))?;
+    let solana_keypair = keys::load_solana_keypair(&config.relayer)?
+        .ok_or_else(|| anyhow::anyhow!("--selftest needs RELAYER_SOLANA_KEYPAIR_PATH or RELAYER_SOLANA_KEYPAIR_ENV"))?;
+
+    let report = selftest::selftest(config, &solana_keypair, &ethereum_key).await?;
+    for (stage, outcome) in &report.stages {
+        match outcome {
+            Outcome::Passed(detail) => info!("PASS {:<8} {}", stage, detail),
+            Outcome::Failed(reason) => error!("FAIL {:<8} {}", stage, reason),
+            Outcome::Skipped => warn!("SKIP {}", stage),
+        }
+    }
+
+    if !report.passed() {
+        anyhow::bail!("Self-test failed");
+    }
+    info!("Self-test passed");
+    Ok(())
+}
+
+/// RPC URLs for log lines, without the API keys they may carry
+fn redacted_urls(urls: &[String]) -> String {
+    urls.iter().map(|url| config::redact_url(url)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(feature = "sui")]
+async fn monitor_sui(config: Option<config::SuiConfig>, db: Database, retry: RetryPolicy) {
+    let Some(config) = config else {
+        return;
+    };
+
+    info!("Starting Sui monitor task...");
+    match relayer::sui_monitor::SuiMonitor::new(&config, db, retry) {
+        Ok(monitor) => {
+            if let Err(e) = monitor.start().await {
+                error!("Sui monitor error: {}", e);
+            }
+        }
+        Err(e) => error!("Sui monitor not started: {}", e),
+    }
+}
+
+#[cfg(not(feature = "sui"))]
+async fn monitor_sui(config: Option<config::SuiConfig>, _db: Database, _retry: RetryPolicy) {
+    if config.is_some() {
+        warn!("SUI_RPC_URL is set but the relayer was built without the `sui` feature");
+    }
+}
+
+/// How often finished transfers are checked for archival
+const ARCHIVE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Periodically archive transfers that finished more than `older_than_days` ago
+async fn archive_transactions(db: Database, older_than_days: u32) {
+    let mut tick = interval(ARCHIVE_INTERVAL);
+
+    loop {
+        tick.tick().await;
+
+        match db.archive_old_transactions(older_than_days).await {
+            Ok(0) => {}
+            Ok(moved) => info!("Archived {} transactions older than {} days", moved, older_than_days),
+            Err(e) => error!("Error archiving transactions: {}", e),
+        }
+    }
+}
+
 /// Process pending transactions from the database
 async fn process_transactions(
     db: Database,
@@ -138,33 +297,63 @@ async fn process_transactions(
 ) -> Result<()> {
     let mut tick = interval(Duration::from_millis(config.poll_interval_ms));
 
+    // Collections a previous run left short of the threshold go before new work
+    match submitter.resume_stalled_collections().await {
+        Ok(0) => {}
+        Ok(resumed) => info!("Resumed {} stalled signature collections", resumed),
+        Err(e) => error!("Error resuming stalled signature collections: {}", e),
+    }
+
     loop {
         tick.tick().await;
 
-        match db.get_pending_transactions().await {
-            Ok(pending) if !pending.is_empty() => {
-                info!("Processing {} pending transactions", pending.len());
+        // Shared by all destinations so a backlog can't hold up the rest of the loop
+        let mut budget = config.max_batch_size;
+        for chain in [Chain::Solana, Chain::Ethereum, Chain::Sui] {
+            if budget == 0 {
+                break;
+            }
+            match db.get_releasable_transactions(chain, budget).await {
+                Ok(pending) if !pending.is_empty() => {
+                    info!("Processing {} pending transactions to {}", pending.len(), chain);
+                    budget -= pending.len() as u32;
 
-                for tx in pending {
-                    match submitter.process_transaction(&tx).await {
-                        Ok(_) => {
-                            info!("Successfully processed transaction nonce={}", tx.nonce);
-                        }
-                        Err(e) => {
-                            error!("Error processing transaction nonce={}: {}", tx.nonce, e);
+                    for tx in pending {
+                        match submitter.process_transaction(&tx).await {
+                            Ok(_) => {
+                                info!("Successfully processed transaction nonce={}", tx.nonce);
+                            }
+                            Err(e) => {
+                                error!("Error processing transaction nonce={}: {}", tx.nonce, e);
 
-                            // Update transaction as failed after max retries
-                            // TODO: Implement retry counter logic
+                                // Update transaction as failed after max retries
+                                // TODO: Implement retry counter logic
+                            }
                         }
                     }
                 }
+                Ok(_) => {
+                    // No pending transactions
+                }
+                Err(e) => {
+                    error!("Error fetching pending transactions to {}: {}", chain, e);
+                }
             }
-            Ok(_) => {
-                // No pending transactions
-            }
-            Err(e) => {
-                error!("Error fetching pending transactions: {}", e);
+        }
+
+        match db.get_transactions_by_status(TransactionStatus::Submitted).await {
+            Ok(submitted) => {
+                for tx in submitted {
+                    if let Err(e) = submitter.process_transaction(&tx).await {
+                        error!("Error checking confirmation for nonce={}: {}", tx.nonce, e);
+                    }
+                }
             }
+            Err(e) => error!("Error fetching submitted transactions: {}", e),
+        }
+
+        if let Err(e) = submitter.reverify_confirmed().await {
+            error!("Error re-verifying confirmed transactions: {}", e);
         }
     }
 }
@@ -0,0 +1,82 @@
+use crate::error::Result;
+use alloy::primitives::Address;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+/// Tracks the next Ethereum account nonce per signer locally, so concurrent
+/// submissions don't race on the provider's pending count.
+#[derive(Default)]
+pub struct NonceManager {
+    nonces: Mutex<HashMap<Address, u64>>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve the next nonce for `address`. On first use (or after a resync) the
+    /// starting point is fetched with `fetch_pending`, i.e. `eth_getTransactionCount(pending)`.
+    pub async fn next<F, Fut>(&self, address: Address, fetch_pending: F) -> Result<u64>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        // Hold the lock across the fetch so two callers can't both initialise
+        let mut nonces = self.nonces.lock().await;
+
+        let nonce = match nonces.get(&address) {
+            Some(nonce) => *nonce,
+            None => {
+                let pending = fetch_pending().await?;
+                info!("Synced account nonce for {}: {}", address, pending);
+                pending
+            }
+        };
+
+        nonces.insert(address, nonce + 1);
+        debug!("Reserved account nonce {} for {}", nonce, address);
+
+        Ok(nonce)
+    }
+
+    /// Forget the local nonce so the next send refetches it from the provider
+    pub async fn resync(&self, address: Address) {
+        self.nonces.lock().await.remove(&address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_back_to_back_sends_get_sequential_nonces() {
+        let manager = NonceManager::new();
+        let address = Address::repeat_byte(0x11);
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(7)
+        };
+
+        assert_eq!(manager.next(address, fetch).await.unwrap(), 7);
+        assert_eq!(manager.next(address, fetch).await.unwrap(), 8);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resync_refetches_pending_nonce() {
+        let manager = NonceManager::new();
+        let address = Address::repeat_byte(0x22);
+
+        assert_eq!(manager.next(address, || async { Ok(3) }).await.unwrap(), 3);
+        assert_eq!(manager.next(address, || async { Ok(3) }).await.unwrap(), 4);
+
+        manager.resync(address).await;
+        assert_eq!(manager.next(address, || async { Ok(4) }).await.unwrap(), 4);
+    }
+}
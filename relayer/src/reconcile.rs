@@ -0,0 +1,194 @@
+use crate::{
+    config::{RelayerConfig, SolanaConfig},
+    db::Database,
+    error::{RelayerError, Result},
+    failover::SolanaRpc,
+    retry::with_timeout,
+};
+use bridge_vault::state::BridgeConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Result of comparing the on-chain vault accounting against the DB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileReport {
+    pub on_chain_total: u64,
+    pub db_total: i64,
+    pub drift: u64,
+    pub within_tolerance: bool,
+}
+
+/// Periodically compares `BridgeConfig.total_locked` with the totals the relayer has recorded
+pub struct Reconciler {
+    rpc_client: SolanaRpc,
+    bridge_config: Pubkey,
+    db: Database,
+    tolerance: u64,
+    interval_ms: u64,
+    rpc_timeout: Duration,
+}
+
+impl Reconciler {
+    /// Returns `Ok(None)` when no bridge config account is configured
+    pub fn new(
+        solana_config: &SolanaConfig,
+        relayer_config: &RelayerConfig,
+        db: Database,
+    ) -> Result<Option<Self>> {
+        let Some(ref account) = solana_config.bridge_config_account else {
+            return Ok(None);
+        };
+
+        let bridge_config = Pubkey::from_str(account)
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid bridge config account: {}", e)))?;
+
+        let rpc_client = SolanaRpc::solana(
+            &solana_config.rpc_urls,
+            CommitmentConfig::from_str(&solana_config.commitment)
+                .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?,
+        )?;
+
+        Ok(Some(Self {
+            rpc_client,
+            bridge_config,
+            db,
+            tolerance: relayer_config.reconcile_tolerance,
+            interval_ms: relayer_config.reconcile_interval_ms,
+            rpc_timeout: Duration::from_millis(relayer_config.rpc_timeout_ms),
+        }))
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        info!(
+            "Starting reconciliation for bridge config {} every {}ms",
+            self.bridge_config, self.interval_ms
+        );
+
+        let mut tick = tokio::time::interval(tokio::time::Duration::from_millis(self.interval_ms));
+
+        loop {
+            tick.tick().await;
+
+            if let Err(e) = self.run_once().await {
+                error!("Reconciliation error: {}", e);
+            }
+        }
+    }
+
+    async fn run_once(&self) -> Result<ReconcileReport> {
+        let on_chain_total = self.fetch_total_locked().await?;
+        let report = reconcile(&self.db, on_chain_total, self.tolerance).await?;
+
+        if report.within_tolerance {
+            info!(
+                "Reconciliation OK: on-chain={}, db={}, drift={}",
+                report.on_chain_total, report.db_total, report.drift
+            );
+        } else {
+            error!(
+                "ALERT: vault drift detected: on-chain total_locked={}, db total={}, drift={} (tolerance {})",
+                report.on_chain_total, report.db_total, report.drift, self.tolerance
+            );
+        }
+
+        Ok(report)
+    }
+
+    async fn fetch_total_locked(&self) -> Result<u64> {
+        let bridge_config = self.bridge_config;
+        let data = with_timeout(
+            self.rpc_timeout,
+            "Solana get_account_data",
+            self.rpc_client
+                .call(move |client| async move { client.get_account_data(&bridge_config).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to get bridge config: {}", e)))?;
+
+        let config = BridgeConfig::load(&data)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid bridge config data: {}", e)))?;
+
+        Ok(config.total_locked)
+    }
+}
+
+/// Compare an on-chain `total_locked` value with the DB's expected vault balance
+pub async fn reconcile(db: &Database, on_chain_total: u64, tolerance: u64) -> Result<ReconcileReport> {
+    let db_total = db.get_locked_total().await?;
+
+    if db_total < 0 {
+        warn!("DB reports negative locked total {}; unlocks exceed recorded locks", db_total);
+    }
+
+    let drift = (on_chain_total as i128 - db_total as i128).unsigned_abs() as u64;
+
+    Ok(ReconcileReport {
+        on_chain_total,
+        db_total,
+        drift,
+        within_tolerance: drift <= tolerance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{advance_status, eth_tx_hash, sol_tx_signature};
+    use crate::types::{Chain, TransactionStatus};
+
+    #[tokio::test]
+    async fn test_reconcile_detects_mismatch() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+
+        db.create_transaction(1, Chain::Solana, Chain::Ethereum, &sol_tx_signature(1), "user", "0xabc", 1_000, 0)
+            .await
+            .unwrap();
+        db.create_transaction(2, Chain::Solana, Chain::Ethereum, &sol_tx_signature(2), "user", "0xabc", 500, 0)
+            .await
+            .unwrap();
+        let unlock_id = db
+            .create_transaction(3, Chain::Ethereum, Chain::Solana, &eth_tx_hash(3), "0xabc", "user", 300, 0)
+            .await
+            .unwrap();
+        advance_status(&db, unlock_id, TransactionStatus::Confirmed, Some("sol_tx_4")).await;
+
+        let consistent = reconcile(&db, 1_200, 0).await.unwrap();
+        assert_eq!(consistent.db_total, 1_200);
+        assert!(consistent.within_tolerance);
+
+        // The vault holds more than the DB accounts for
+        let report = reconcile(&db, 2_000, 10).await.unwrap();
+        assert_eq!(report.drift, 800);
+        assert!(!report.within_tolerance);
+
+        // Small drift within tolerance is not flagged
+        let report = reconcile(&db, 1_195, 10).await.unwrap();
+        assert_eq!(report.drift, 5);
+        assert!(report.within_tolerance);
+    }
+
+    #[tokio::test]
+    async fn test_refunded_lock_is_not_counted_as_locked() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+
+        db.create_transaction(1, Chain::Solana, Chain::Ethereum, &sol_tx_signature(1), "user", "0xabc", 1_000, 0)
+            .await
+            .unwrap();
+        let refunded_id = db
+            .create_transaction(2, Chain::Solana, Chain::Ethereum, &sol_tx_signature(2), "user", "0xabc", 500, 0)
+            .await
+            .unwrap();
+        db.update_transaction_status(refunded_id, TransactionStatus::Refunded, Some("sol_refund_2"), None)
+            .await
+            .unwrap();
+
+        let report = reconcile(&db, 1_000, 0).await.unwrap();
+        assert_eq!(report.db_total, 1_000);
+        assert!(report.within_tolerance);
+
+        let stats = db.get_stats().await.unwrap();
+        assert_eq!(stats.refunded, 1);
+    }
+}
@@ -0,0 +1,158 @@
+//! One-shot rebuild of the transfer table from chain history, for recovering a lost or
+//! corrupted database. Unlike the monitors' live polling, a replay covers a fixed range,
+//! reports its progress and returns. Events already recorded are skipped, so a replay
+//! that was interrupted can simply be run again.
+
+use crate::{
+    config::{EthereumConfig, SolanaConfig},
+    db::Database,
+    error::Result,
+    ethereum_monitor::EthereumMonitor,
+    retry::RetryPolicy,
+    solana_monitor::SolanaMonitor,
+};
+
+/// Reported by [`replay`] as it works through each chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayProgress {
+    /// Burns up to `block` of `to_block` have been recorded
+    Ethereum { block: u64, to_block: u64 },
+    /// `done` of the `total` Solana program transactions in range have been applied
+    Solana { done: usize, total: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplaySummary {
+    /// Last Ethereum block scanned
+    pub ethereum_to_block: u64,
+    /// Solana program transactions read
+    pub solana_transactions: usize,
+    /// Transfers in the database afterwards
+    pub transfers: i64,
+}
+
+/// Re-run both monitors' backfill into `db`: Ethereum burns from `from_block`, then Solana
+/// program transactions from `from_slot`, each up to what is confirmed deep enough now.
+///
+/// Ethereum goes first so Solana unlocks find the burns they complete. Solana locks come
+/// back `Pending`; the submitter checks the destination before minting, so ones that were
+/// already delivered are not minted twice.
+pub async fn replay(
+    solana: &SolanaConfig,
+    ethereum: &EthereumConfig,
+    retry: RetryPolicy,
+    db: Database,
+    from_slot: u64,
+    from_block: u64,
+    mut progress: impl FnMut(ReplayProgress),
+) -> Result<ReplaySummary> {
+    let ethereum_monitor = EthereumMonitor::new(ethereum, db.clone(), retry)?;
+    ethereum_monitor.verify_chain_id().await?;
+    let ethereum_to_block = ethereum_monitor.backfill(from_block, &mut progress).await?;
+
+    let solana_monitor = SolanaMonitor::new(solana, db.clone(), retry)?;
+    let solana_transactions = solana_monitor.backfill(from_slot, &mut progress).await?;
+
+    Ok(ReplaySummary {
+        ethereum_to_block,
+        solana_transactions,
+        transfers: db.get_stats().await?.total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{event_log, lock_logs, test_configs, MockEthereumRpc, MockSolanaRpc};
+    use crate::types::{Chain, TransactionStatus};
+    use bridge_vault::event::{TokensRefundedEvent, TokensUnlockedEvent};
+    use solana_sdk::pubkey::Pubkey;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_replay_rebuilds_transfers_into_empty_db() {
+        let eth_rpc = MockEthereumRpc::start().await;
+        let sol_rpc = MockSolanaRpc::start().await;
+        let (mut solana, ethereum, _) = test_configs(eth_rpc.url());
+        solana.rpc_urls = vec![sol_rpc.url().to_string()];
+        let retry = RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            max_attempts: 1,
+            timeout: Duration::from_secs(5),
+        };
+
+        // Latest block 100 with 12 confirmations: blocks 10..=88 are replayed
+        let user = Pubkey::new_unique();
+        let burn = {
+            let mut chain = eth_rpc.chain();
+            chain.add_burn(5, 100, 300, &user.to_string());
+            let burn = chain.add_burn(40, 101, 500, &user.to_string());
+            chain.add_burn(60, 102, 700, &user.to_string());
+            chain.add_burn(95, 103, 900, &user.to_string());
+            burn
+        };
+
+        // Slots 100..=500 are replayed
+        let token_mint = Pubkey::new_unique();
+        let (lock, unlock) = {
+            let mut chain = sol_rpc.chain();
+            chain.slot = 500;
+            chain.add_transaction(10, lock_logs(&user, 1, 1_000));
+            let lock = chain.add_transaction(120, lock_logs(&user, 2, 1_000));
+            chain.add_transaction(130, lock_logs(&user, 3, 2_000));
+            let refund = TokensRefundedEvent { user, token_mint, amount: 2_000, nonce: 3 };
+            chain.add_transaction(140, vec![event_log(TokensRefundedEvent::DISCRIMINATOR, &refund)]);
+            let unlocked = TokensUnlockedEvent { user, token_mint, amount: 500, nonce: 101 };
+            let unlock = chain.add_transaction(150, vec![event_log(TokensUnlockedEvent::DISCRIMINATOR, &unlocked)]);
+            (lock, unlock)
+        };
+
+        let db = Database::new_in_memory().await.unwrap();
+        let mut reported = Vec::new();
+        let summary = replay(&solana, &ethereum, retry, db.clone(), 100, 10, |p| reported.push(p))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            ReplaySummary {
+                ethereum_to_block: 88,
+                solana_transactions: 4,
+                transfers: 4,
+            }
+        );
+        assert_eq!(reported.first(), Some(&ReplayProgress::Ethereum { block: 88, to_block: 88 }));
+        assert_eq!(reported.last(), Some(&ReplayProgress::Solana { done: 4, total: 4 }));
+
+        let row = |from_chain, nonce| {
+            let db = db.clone();
+            async move { db.get_transaction_by_nonce(from_chain, nonce).await.unwrap() }
+        };
+        for (from_chain, nonce) in [(Chain::Solana, 1), (Chain::Ethereum, 100), (Chain::Ethereum, 103)] {
+            assert!(row(from_chain, nonce).await.is_none(), "{} nonce {}", from_chain, nonce);
+        }
+
+        let delivered = row(Chain::Ethereum, 101).await.unwrap();
+        assert_eq!((delivered.from_chain, delivered.to_chain), (Chain::Ethereum, Chain::Solana));
+        assert_eq!(delivered.from_tx_hash, format!("{:?}", burn));
+        assert_eq!(delivered.amount, 500);
+        assert_eq!(delivered.status, TransactionStatus::Confirmed);
+        assert_eq!(delivered.to_tx_hash, Some(unlock.to_string()));
+
+        assert_eq!(row(Chain::Ethereum, 102).await.unwrap().status, TransactionStatus::Pending);
+
+        let locked = row(Chain::Solana, 2).await.unwrap();
+        assert_eq!((locked.from_chain, locked.to_chain), (Chain::Solana, Chain::Ethereum));
+        assert_eq!(locked.from_tx_hash, lock.to_string());
+        assert_eq!(locked.sender, user.to_string());
+        assert_eq!(locked.status, TransactionStatus::Pending);
+
+        assert_eq!(row(Chain::Solana, 3).await.unwrap().status, TransactionStatus::Refunded);
+
+        // Running it again finds every event already recorded
+        let again = replay(&solana, &ethereum, retry, db.clone(), 100, 10, |_| {}).await.unwrap();
+        assert_eq!(again, summary);
+        assert_eq!(row(Chain::Ethereum, 101).await.unwrap().status, TransactionStatus::Confirmed);
+        assert_eq!(row(Chain::Solana, 3).await.unwrap().status, TransactionStatus::Refunded);
+    }
+}
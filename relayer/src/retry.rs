@@ -0,0 +1,151 @@
+use crate::{
+    config::RelayerConfig,
+    error::{RelayerError, Result},
+};
+use rand::Rng;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Exponential backoff settings shared by every RPC retry loop
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub max_attempts: u32,
+    /// Limit for a single attempt; a hung endpoint counts as a failed attempt
+    pub timeout: Duration,
+}
+
+/// Per-call RPC limit for clients built without a `RelayerConfig`
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl RetryPolicy {
+    pub fn from_config(config: &RelayerConfig) -> Self {
+        Self {
+            base_delay: Duration::from_millis(config.retry_delay_ms),
+            max_delay: Duration::from_millis(config.retry_max_delay_ms),
+            max_attempts: config.max_retries.max(1),
+            timeout: Duration::from_millis(config.rpc_timeout_ms),
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based), without jitter:
+    /// `base * 2^attempt`, capped at `max_delay`.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Same as `delay_for_attempt`, randomised into `[delay / 2, delay]` so clients
+    /// recovering from the same outage don't retry in lockstep.
+    pub fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.delay_for_attempt(attempt);
+        let half = delay / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
+
+/// Await `fut`, giving up with `TimeoutError` once `limit` has passed
+pub async fn with_timeout<F: Future>(limit: Duration, operation: &str, fut: F) -> Result<F::Output> {
+    tokio::time::timeout(limit, fut).await.map_err(|_| {
+        warn!("{} timed out after {:?}", operation, limit);
+        RelayerError::TimeoutError
+    })
+}
+
+/// Run `f` until it succeeds or `policy.max_attempts` is reached, sleeping with
+/// exponential backoff and jitter in between. Each attempt is cut off after
+/// `policy.timeout`. Exhaustion returns `TimeoutError` if the last attempt
+/// timed out and `NetworkError` otherwise.
+pub async fn retry_with_backoff<T, E, F, Fut>(policy: &RetryPolicy, operation: &str, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, E>>,
+    E: Display,
+{
+    let mut attempt = 0;
+
+    loop {
+        let (error, timed_out) = match tokio::time::timeout(policy.timeout, f()).await {
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(e)) => (e.to_string(), false),
+            Err(_) => (format!("timed out after {:?}", policy.timeout), true),
+        };
+
+        attempt += 1;
+        if attempt >= policy.max_attempts {
+            if timed_out {
+                warn!("{} timed out on all {} attempts", operation, attempt);
+                return Err(RelayerError::TimeoutError);
+            }
+            return Err(RelayerError::NetworkError(format!(
+                "{} failed after {} attempts: {}",
+                operation, attempt, error
+            )));
+        }
+
+        let delay = policy.jittered_delay(attempt - 1);
+        warn!(
+            "{} failed (attempt {}/{}): {}. Retrying in {:?}",
+            operation, attempt, policy.max_attempts, error, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+            max_attempts: 5,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn test_delay_sequence_grows_and_is_capped() {
+        let policy = policy();
+        let delays: Vec<u64> = (0..6)
+            .map(|attempt| policy.delay_for_attempt(attempt).as_millis() as u64)
+            .collect();
+
+        assert_eq!(delays, vec![100, 200, 400, 800, 1_000, 1_000]);
+        assert_eq!(policy.delay_for_attempt(64), policy.max_delay);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = policy();
+        for attempt in 0..6 {
+            let delay = policy.delay_for_attempt(attempt);
+            let jittered = policy.jittered_delay(attempt);
+            assert!(jittered >= delay / 2 && jittered <= delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_with_network_error() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+            max_attempts: 3,
+            timeout: Duration::from_secs(1),
+        };
+        let mut calls = 0;
+
+        let result: Result<()> = retry_with_backoff(&policy, "get_slot", || {
+            calls += 1;
+            async { Err::<(), _>("connection refused") }
+        })
+        .await;
+
+        assert_eq!(calls, 3);
+        assert!(matches!(result, Err(RelayerError::NetworkError(_))));
+    }
+}
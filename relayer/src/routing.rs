@@ -0,0 +1,150 @@
+//! Which directions the bridge carries transfers in. Each source chain's contract numbers
+//! its destinations its own way, so the monitors decode those codes here, and the
+//! submitter checks a transfer's route here before working on it.
+
+use crate::{
+    error::{RelayerError, Result},
+    types::{Chain, RelayerTransaction},
+};
+
+/// Direction of one transfer, from the chain its tokens were locked or burned on to the
+/// chain that releases them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransferRoute {
+    pub from: Chain,
+    pub to: Chain,
+}
+
+/// `(source, code, destination)`: the `destination_chain` value `source`'s contract
+/// emits for a lock bound to `destination`. Ethereum burns carry no code; they always
+/// go to Solana.
+const DESTINATION_CODES: [(Chain, u8, Chain); 4] = [
+    // bridge-vault program; 0 is rejected on lock
+    (Chain::Solana, 1, Chain::Ethereum),
+    (Chain::Solana, 2, Chain::Sui),
+    // Sui Move package
+    (Chain::Sui, 0, Chain::Solana),
+    (Chain::Sui, 1, Chain::Ethereum),
+];
+
+impl TransferRoute {
+    pub const SOLANA_TO_ETHEREUM: Self = Self::between(Chain::Solana, Chain::Ethereum);
+    pub const SOLANA_TO_SUI: Self = Self::between(Chain::Solana, Chain::Sui);
+    pub const ETHEREUM_TO_SOLANA: Self = Self::between(Chain::Ethereum, Chain::Solana);
+    pub const SUI_TO_SOLANA: Self = Self::between(Chain::Sui, Chain::Solana);
+    pub const SUI_TO_ETHEREUM: Self = Self::between(Chain::Sui, Chain::Ethereum);
+
+    /// Every route some source chain can start a transfer on
+    pub const SUPPORTED: [Self; 5] = [
+        Self::SOLANA_TO_ETHEREUM,
+        Self::SOLANA_TO_SUI,
+        Self::ETHEREUM_TO_SOLANA,
+        Self::SUI_TO_SOLANA,
+        Self::SUI_TO_ETHEREUM,
+    ];
+
+    const fn between(from: Chain, to: Chain) -> Self {
+        Self { from, to }
+    }
+
+    /// The route from `from` to `to`, if the bridge carries transfers that way
+    pub fn new(from: Chain, to: Chain) -> Result<Self> {
+        let route = Self::between(from, to);
+        if !route.is_supported() {
+            return Err(RelayerError::InvalidChain(format!("Unsupported route {}", route)));
+        }
+        Ok(route)
+    }
+
+    /// The route `tx` was recorded with, unchecked
+    pub fn of(tx: &RelayerTransaction) -> Self {
+        Self::between(tx.from_chain, tx.to_chain)
+    }
+
+    /// Decode the `destination_chain` a lock on `from` was emitted with
+    pub fn from_destination_code(from: Chain, code: u8) -> Result<Self> {
+        DESTINATION_CODES
+            .iter()
+            .find(|(source, c, _)| *source == from && *c == code)
+            .map(|(_, _, to)| Self::between(from, *to))
+            .ok_or_else(|| RelayerError::ParseError(format!("Unknown destination chain: {}", code)))
+    }
+
+    /// The `destination_chain` the source contract uses for this route, if it has one
+    pub fn destination_code(self) -> Option<u8> {
+        DESTINATION_CODES
+            .iter()
+            .find(|(source, _, to)| *source == self.from && *to == self.to)
+            .map(|(_, code, _)| *code)
+    }
+
+    pub fn is_supported(self) -> bool {
+        Self::SUPPORTED.contains(&self)
+    }
+}
+
+impl std::fmt::Display for TransferRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+/// Error for a transfer whose destination the relayer cannot deliver to yet
+pub fn undeliverable(chain: Chain) -> RelayerError {
+    RelayerError::InvalidChain(format!("{} not implemented", chain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAINS: [Chain; 3] = [Chain::Solana, Chain::Ethereum, Chain::Sui];
+
+    #[test]
+    fn test_supported_routes() {
+        for route in TransferRoute::SUPPORTED {
+            assert_eq!(TransferRoute::new(route.from, route.to).unwrap(), route);
+        }
+
+        // Same-chain routes and Ethereum -> Sui are not carried
+        let unsupported: Vec<_> = CHAINS
+            .iter()
+            .flat_map(|from| CHAINS.iter().map(move |to| (*from, *to)))
+            .filter(|(from, to)| TransferRoute::new(*from, *to).is_err())
+            .collect();
+        assert_eq!(
+            unsupported,
+            vec![
+                (Chain::Solana, Chain::Solana),
+                (Chain::Ethereum, Chain::Ethereum),
+                (Chain::Ethereum, Chain::Sui),
+                (Chain::Sui, Chain::Sui),
+            ]
+        );
+        assert!(matches!(
+            TransferRoute::new(Chain::Ethereum, Chain::Sui),
+            Err(RelayerError::InvalidChain(message)) if message == "Unsupported route Ethereum -> Sui"
+        ));
+    }
+
+    #[test]
+    fn test_destination_codes_round_trip() {
+        let decode = |from, code| TransferRoute::from_destination_code(from, code).ok();
+
+        assert_eq!(decode(Chain::Solana, 1), Some(TransferRoute::SOLANA_TO_ETHEREUM));
+        assert_eq!(decode(Chain::Solana, 2), Some(TransferRoute::SOLANA_TO_SUI));
+        assert_eq!(decode(Chain::Sui, 0), Some(TransferRoute::SUI_TO_SOLANA));
+        assert_eq!(decode(Chain::Sui, 1), Some(TransferRoute::SUI_TO_ETHEREUM));
+
+        for (from, code) in [(Chain::Solana, 0), (Chain::Solana, 3), (Chain::Sui, 2), (Chain::Ethereum, 1)] {
+            assert_eq!(decode(from, code), None, "{} code {}", from, code);
+        }
+
+        for route in TransferRoute::SUPPORTED {
+            match route.destination_code() {
+                Some(code) => assert_eq!(decode(route.from, code), Some(route)),
+                None => assert_eq!(route, TransferRoute::ETHEREUM_TO_SOLANA),
+            }
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! `--selftest`: one dust transfer through the whole pipeline before the relayer is trusted
+//! with real ones. The relayer's Solana keypair locks `selftest_amount` to its own Ethereum
+//! address; the lock is then recorded the way the monitor records it, signed by the
+//! configured validators, and its `mintWrapped` run as an `eth_call` with those signatures.
+//!
+//! Meant for devnet and testnet. The mint is never broadcast, so a run leaves only the dust
+//! lock behind, which can be refunded once it expires. The transfer is recorded in a
+//! database of its own, so the main loop never sees it.
+
+use crate::{
+    config::Config,
+    db::Database,
+    error::{RelayerError, Result},
+    failover::SolanaRpc,
+    retry::{retry_with_backoff, with_timeout, RetryPolicy},
+    solana_monitor::SolanaMonitor,
+    token_accounts::{derive_user_ata, derive_vault_ata},
+    transaction_submitter::{user_bridge_state_address, TransactionSubmitter},
+    types::{Chain, RelayerTransaction, TransactionStatus, ValidatorSignature},
+    validator_client::ValidatorClient,
+};
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use bridge_vault::{
+    instruction::BridgeInstruction,
+    state::{BridgeConfig, CHAIN_ETHEREUM},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use std::{fmt, str::FromStr};
+
+/// Ethereum chains the self-test refuses to lock real value for
+const MAINNET_CHAIN_IDS: &[u64] = &[1];
+
+/// One step of the self-test, run in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Lock `selftest_amount` on Solana and wait for the lock to confirm
+    Lock,
+    /// Read the lock back from chain into a pending transfer
+    Detect,
+    /// Gather `signature_threshold` signatures from the configured validators
+    Collect,
+    /// Check the bridge contract accepts the mint with those signatures
+    Verify,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Lock, Stage::Detect, Stage::Collect, Stage::Verify];
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Stage::Lock => "lock",
+            Stage::Detect => "detect",
+            Stage::Collect => "collect",
+            Stage::Verify => "verify",
+        })
+    }
+}
+
+/// How a stage went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed(String),
+    Failed(String),
+    /// Not run because an earlier stage failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Every stage in [`Stage::ALL`] order
+    pub stages: Vec<(Stage, Outcome)>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.stages.iter().all(|(_, outcome)| matches!(outcome, Outcome::Passed(_)))
+    }
+
+    /// Record how `stage` went, handing back what it produced if it passed
+    fn record<T>(&mut self, stage: Stage, result: Result<(T, String)>) -> Option<T> {
+        match result {
+            Ok((value, detail)) => {
+                self.stages.push((stage, Outcome::Passed(detail)));
+                Some(value)
+            }
+            Err(e) => {
+                self.stages.push((stage, Outcome::Failed(e.to_string())));
+                None
+            }
+        }
+    }
+}
+
+/// Run every stage against the chains, validators and bridge accounts in `config`, signing
+/// the lock with `solana_keypair` and the mint with `ethereum_key`. Configuration the test
+/// can't start without is an error; a stage that fails is reported, and the ones after it
+/// are skipped.
+pub async fn selftest(config: &Config, solana_keypair: &[u8], ethereum_key: &str) -> Result<SelfTestReport> {
+    Ok(SelfTest::new(config, solana_keypair, ethereum_key).await?.run().await)
+}
+
+struct SelfTest {
+    rpc: SolanaRpc,
+    retry: RetryPolicy,
+    db: Database,
+    monitor: SolanaMonitor,
+    submitter: TransactionSubmitter,
+    keypair: Keypair,
+    recipient: Address,
+    program_id: Pubkey,
+    bridge_config: Pubkey,
+    token_mint: Pubkey,
+    amount: u64,
+}
+
+impl SelfTest {
+    async fn new(config: &Config, solana_keypair: &[u8], ethereum_key: &str) -> Result<Self> {
+        if MAINNET_CHAIN_IDS.contains(&config.ethereum.chain_id) {
+            return Err(RelayerError::ConfigError(format!(
+                "--selftest locks real tokens; run it against a testnet, not Ethereum chain {}",
+                config.ethereum.chain_id
+            )));
+        }
+
+        let pubkey = |name: &str, value: Option<&str>| {
+            let value = value.ok_or_else(|| RelayerError::ConfigError(format!("--selftest needs {}", name)))?;
+            Pubkey::from_str(value).map_err(|e| RelayerError::ConfigError(format!("Invalid {}: {}", name, e)))
+        };
+        let program_id = pubkey("SOLANA_BRIDGE_PROGRAM_ID", Some(&config.solana.bridge_program_id))?;
+        let bridge_config = pubkey("SOLANA_BRIDGE_CONFIG_ACCOUNT", config.solana.bridge_config_account.as_deref())?;
+        let token_mint = pubkey("SOLANA_TOKEN_MINT", config.solana.token_mint.as_deref())?;
+
+        let keypair = Keypair::from_bytes(solana_keypair)
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid keypair: {}", e)))?;
+        let recipient = PrivateKeySigner::from_str(ethereum_key)
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid private key: {}", e)))?
+            .address();
+
+        // Confirm the lock at the commitment the monitor reads it back at
+        let commitment = CommitmentConfig::from_str(config.solana.monitor_commitment())
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?;
+        let rpc = SolanaRpc::solana(&config.solana.rpc_urls, commitment)?;
+        let retry = RetryPolicy::from_config(&config.relayer);
+
+        let db = Database::new("sqlite::memory:", 1).await?;
+        let monitor = SolanaMonitor::new(&config.solana, db.clone(), retry)?;
+
+        // The threshold is what the bridge needs; waiting on a larger target proves nothing more
+        let mut relayer_config = config.relayer.clone();
+        relayer_config.target_signatures = None;
+        let validator_client =
+            ValidatorClient::new(config.validators.clone()).with_wire_format(config.relayer.validator_wire_format);
+        let mut submitter = TransactionSubmitter::new(
+            config.solana.clone(),
+            config.ethereum.clone(),
+            relayer_config,
+            db.clone(),
+            validator_client,
+        )?;
+        submitter.set_ethereum_signer(ethereum_key)?;
+
+        Ok(Self {
+            rpc,
+            retry,
+            db,
+            monitor,
+            submitter,
+            keypair,
+            recipient,
+            program_id,
+            bridge_config,
+            token_mint,
+            amount: config.relayer.selftest_amount,
+        })
+    }
+
+    async fn run(&self) -> SelfTestReport {
+        let mut report = SelfTestReport::default();
+        self.run_stages(&mut report).await;
+        for stage in Stage::ALL.into_iter().skip(report.stages.len()) {
+            report.stages.push((stage, Outcome::Skipped));
+        }
+        report
+    }
+
+    async fn run_stages(&self, report: &mut SelfTestReport) -> Option<()> {
+        let lock = report.record(Stage::Lock, self.lock().await)?;
+        let tx = report.record(Stage::Detect, self.detect(&lock).await)?;
+        let tx = report.record(Stage::Collect, self.collect(&tx).await)?;
+        report.record(Stage::Verify, self.verify(&tx).await)
+    }
+
+    async fn lock(&self) -> Result<(Signature, String)> {
+        let bridge_config = self.bridge_config;
+        let data = with_timeout(
+            self.retry.timeout,
+            "Solana get_account_data",
+            self.rpc
+                .call(move |client| async move { client.get_account_data(&bridge_config).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to get bridge config: {}", e)))?;
+        let state = BridgeConfig::load(&data)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid bridge config data: {}", e)))?;
+
+        let user = self.keypair.pubkey();
+        let mut destination = [0u8; 32];
+        destination[12..].copy_from_slice(self.recipient.as_slice());
+        let instruction = BridgeInstruction::create_lock_tokens_instruction(
+            &self.program_id,
+            &user,
+            &derive_user_ata(&user, &self.token_mint),
+            &derive_vault_ata(&self.program_id, &self.bridge_config, &self.token_mint),
+            &user_bridge_state_address(&self.program_id, &user, state.nonce),
+            &self.bridge_config,
+            &self.token_mint,
+            self.amount,
+            CHAIN_ETHEREUM,
+            destination,
+        );
+
+        let recent_blockhash = retry_with_backoff(&self.retry, "Solana get_latest_blockhash", || {
+            self.rpc.call(|client| async move { client.get_latest_blockhash().await })
+        })
+        .await?;
+        let transaction =
+            Transaction::new_signed_with_payer(&[instruction], Some(&user), &[&self.keypair], recent_blockhash);
+
+        let transaction = &transaction;
+        let signature = with_timeout(
+            self.retry.timeout,
+            "Solana send_and_confirm_transaction",
+            self.rpc
+                .call(move |client| async move { client.send_and_confirm_transaction(transaction).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::TransactionSubmissionFailed(format!("lock failed: {}", e)))?;
+
+        let detail = format!("locked {} as nonce {} in {}", self.amount, state.nonce, signature);
+        Ok((signature, detail))
+    }
+
+    async fn detect(&self, lock: &Signature) -> Result<(RelayerTransaction, String)> {
+        self.monitor.process_transaction(lock).await?;
+        let tx = self
+            .db
+            .get_transaction_by_hash(Chain::Solana, &lock.to_string())
+            .await?
+            .ok_or_else(|| RelayerError::SourceVerificationFailed(format!("no lock found in {}", lock)))?;
+
+        let user = self.keypair.pubkey().to_string();
+        if tx.sender != user || tx.gross_amount as u64 != self.amount {
+            return Err(RelayerError::SourceVerificationFailed(format!(
+                "recorded a lock of {} by {}, expected {} by {}",
+                tx.gross_amount, tx.sender, self.amount, user
+            )));
+        }
+
+        let detail = format!("recorded nonce {} to {} on {}", tx.nonce, tx.recipient, tx.to_chain);
+        Ok((tx, detail))
+    }
+
+    async fn collect(&self, tx: &RelayerTransaction) -> Result<(RelayerTransaction, String)> {
+        self.submitter.process_transaction(tx).await?;
+        let tx = self.db.get_transaction_by_hash(Chain::Solana, &tx.from_tx_hash).await?.ok_or_else(|| {
+            RelayerError::Unknown(format!("transfer {} disappeared during collection", tx.nonce))
+        })?;
+        if tx.status != TransactionStatus::SignaturesCollected {
+            return Err(RelayerError::Unknown(format!(
+                "transfer is {} after collection: {}",
+                tx.status,
+                tx.error_message.as_deref().unwrap_or("no reason recorded")
+            )));
+        }
+
+        let signatures: Vec<ValidatorSignature> = serde_json::from_str(tx.signatures.as_deref().unwrap_or("[]"))?;
+        let signers: Vec<&str> = signatures.iter().map(|sig| sig.validator_address.as_str()).collect();
+        let detail = format!("{} signatures from {}", signers.len(), signers.join(", "));
+        Ok((tx, detail))
+    }
+
+    async fn verify(&self, tx: &RelayerTransaction) -> Result<((), String)> {
+        self.submitter.check_mint(tx).await?;
+        Ok(((), "bridge contract accepts the mint with these signatures".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{DatabaseConfig, ValidatorConfig},
+        testing::{
+            lock_logs, mock_validators, test_configs, validator_configs, MockEthereumRpc, MockSolanaRpc, TEST_ETH_KEY,
+        },
+    };
+
+    const SEPOLIA: u64 = 11_155_111;
+
+    fn selftest_config(solana: &MockSolanaRpc, ethereum: &MockEthereumRpc, validators: Vec<ValidatorConfig>) -> Config {
+        let (mut solana_config, _, relayer) = test_configs(solana.url());
+        let (_, mut ethereum_config, _) = test_configs(ethereum.url());
+        solana_config.bridge_config_account = Some(Pubkey::new_unique().to_string());
+        solana_config.token_mint = Some(Pubkey::new_unique().to_string());
+        ethereum_config.chain_id = SEPOLIA;
+        Config {
+            solana: solana_config,
+            ethereum: ethereum_config,
+            relayer,
+            database: DatabaseConfig {
+                url: "sqlite::memory:".to_string(),
+                max_connections: 1,
+            },
+            validators,
+            sui: None,
+        }
+    }
+
+    /// Serve a bridge config at `nonce` and have the next transaction sent log a lock by `user`
+    fn stage_lock(rpc: &MockSolanaRpc, config: &Config, user: &Pubkey, nonce: u64, amount: u64) {
+        let state = BridgeConfig {
+            admin: Pubkey::new_unique(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce,
+            validators: vec![],
+            validator_threshold: 2,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: bridge_vault::state::SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        };
+        let bridge_config = Pubkey::from_str(config.solana.bridge_config_account.as_deref().unwrap()).unwrap();
+        let expiry = chrono::Utc::now().timestamp() + 3600;
+
+        let mut chain = rpc.chain();
+        chain.accounts.insert(bridge_config, borsh::to_vec(&state).unwrap());
+        chain.send_logs = lock_logs(user, nonce, amount)
+            .into_iter()
+            .map(|log| if log.contains("expiry:") { format!("Program log:   expiry: {}", expiry) } else { log })
+            .collect();
+    }
+
+    #[tokio::test]
+    async fn test_selftest_passes_every_stage_against_mocks() {
+        let solana = MockSolanaRpc::start().await;
+        let ethereum = MockEthereumRpc::start().await;
+        let validators = mock_validators(&[true, true, true]).await;
+        let config = selftest_config(&solana, &ethereum, validator_configs(&validators));
+        let keypair = Keypair::new();
+        stage_lock(&solana, &config, &keypair.pubkey(), 5, config.relayer.selftest_amount);
+
+        let report = selftest(&config, &keypair.to_bytes(), TEST_ETH_KEY).await.unwrap();
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.stages.iter().map(|(stage, _)| *stage).collect::<Vec<_>>(), Stage::ALL);
+
+        assert!(solana.chain().calls.iter().any(|m| m == "sendTransaction"));
+        assert_eq!(validators.iter().map(|v| v.hits()).sum::<usize>(), 2, "stops at the threshold");
+        assert!(ethereum.calls().iter().any(|m| m == "eth_call"));
+        assert!(!ethereum.sent_transaction(), "the mint is only simulated");
+    }
+
+    #[tokio::test]
+    async fn test_failed_stage_skips_the_rest() {
+        let solana = MockSolanaRpc::start().await;
+        let ethereum = MockEthereumRpc::start().await;
+        let validators = mock_validators(&[true, false, false]).await;
+        let config = selftest_config(&solana, &ethereum, validator_configs(&validators));
+        let keypair = Keypair::new();
+        stage_lock(&solana, &config, &keypair.pubkey(), 5, config.relayer.selftest_amount);
+
+        let report = selftest(&config, &keypair.to_bytes(), TEST_ETH_KEY).await.unwrap();
+        assert!(!report.passed());
+        assert!(matches!(report.stages[1], (Stage::Detect, Outcome::Passed(_))));
+        assert!(matches!(
+            report.stages[2],
+            (Stage::Collect, Outcome::Failed(ref e)) if e.contains("expected 2, got 1")
+        ));
+        assert_eq!(report.stages[3], (Stage::Verify, Outcome::Skipped));
+        assert!(!ethereum.calls().iter().any(|m| m == "eth_call"));
+    }
+
+    #[tokio::test]
+    async fn test_selftest_refuses_mainnet() {
+        let solana = MockSolanaRpc::start().await;
+        let ethereum = MockEthereumRpc::start().await;
+        let mut config = selftest_config(&solana, &ethereum, vec![]);
+        config.ethereum.chain_id = 1;
+
+        let err = selftest(&config, &Keypair::new().to_bytes(), TEST_ETH_KEY).await.unwrap_err();
+        assert!(matches!(err, RelayerError::ConfigError(ref e) if e.contains("testnet")), "{:?}", err);
+        assert!(solana.chain().calls.is_empty());
+    }
+}
@@ -1,36 +1,55 @@
 use crate::{
+    amount::Amount,
     config::SolanaConfig,
     db::Database,
     error::{RelayerError, Result},
-    types::{BridgeEvent, Chain},
+    failover::SolanaRpc,
+    replay::ReplayProgress,
+    retry::{retry_with_backoff, RetryPolicy},
+    routing::TransferRoute,
+    types::{checksum_address, BridgeEvent, Chain, TransactionStatus},
+};
+use alloy::primitives::Address;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bridge_vault::{
+    event::{TokensCancelledEvent, TokensRefundedEvent, TokensUnlockedEvent},
+    state::bytes32_to_eth_address,
 };
 use solana_client::{
-    nonblocking::rpc_client::RpcClient,
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
     rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter},
     rpc_response::{Response, RpcLogsResponse},
 };
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
 use solana_transaction_status::UiTransactionEncoding;
 use std::str::FromStr;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 // TODO: Use WebSocket subscriptions instead of polling
 
+/// Most signatures `getSignaturesForAddress` returns per request
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
 pub struct SolanaMonitor {
-    rpc_client: RpcClient,
+    rpc_client: SolanaRpc,
     program_id: Pubkey,
     db: Database,
+    /// Commitment bridge transactions are listed and read at
     commitment: CommitmentConfig,
+    poll_interval: Duration,
+    min_confirmations: u64,
+    /// Decimals recorded with each lock's amount, when configured
+    token_decimals: Option<u8>,
+    retry: RetryPolicy,
 }
 
 impl SolanaMonitor {
-    pub fn new(config: &SolanaConfig, db: Database) -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            config.rpc_url.clone(),
-            CommitmentConfig::from_str(&config.commitment)
-                .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?,
-        );
+    pub fn new(config: &SolanaConfig, db: Database, retry: RetryPolicy) -> Result<Self> {
+        let commitment = CommitmentConfig::from_str(config.monitor_commitment())
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?;
+        let rpc_client = SolanaRpc::solana(&config.rpc_urls, commitment)?;
 
         let program_id = Pubkey::from_str(&config.bridge_program_id)
             .map_err(|e| RelayerError::ConfigError(format!("Invalid program ID: {}", e)))?;
@@ -39,20 +58,27 @@ impl SolanaMonitor {
             rpc_client,
             program_id,
             db,
-            commitment: CommitmentConfig::from_str(&config.commitment).unwrap(),
+            commitment,
+            poll_interval: Duration::from_millis(config.poll_interval_ms),
+            min_confirmations: config.min_confirmations,
+            token_decimals: config.token_decimals,
+            retry,
         })
     }
 
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
     /// Start monitoring Solana for bridge events
     pub async fn start(&self) -> Result<()> {
         info!("Starting Solana monitor for program: {}", self.program_id);
 
         // Get the current slot to start monitoring from
-        let slot = self
-            .rpc_client
-            .get_slot()
-            .await
-            .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to get slot: {}", e)))?;
+        let slot = retry_with_backoff(&self.retry, "Solana get_slot", || {
+            self.rpc_client.call(|client| async move { client.get_slot().await })
+        })
+        .await?;
 
         info!("Starting from slot: {}", slot);
 
@@ -64,58 +90,146 @@ impl SolanaMonitor {
         let mut last_signature: Option<Signature> = None;
 
         loop {
-            match self
-                .rpc_client
-                .get_signatures_for_address(&self.program_id)
-                .await
-            {
-                Ok(signatures) => {
-
-                    for sig_info in signatures.iter().rev() {
-                        let signature = Signature::from_str(&sig_info.signature)
-                            .map_err(|e| RelayerError::ParseError(format!("Invalid signature: {}", e)))?;
-
-
-                        if let Some(ref last_sig) = last_signature {
-                            if signature == *last_sig {
-                                continue;
-                            }
-                        }
+            if let Err(e) = self.poll_once(&mut last_signature).await {
+                error!("Error polling Solana: {}", e);
+            }
 
-            
-                        if let Err(e) = self.process_transaction(&signature).await {
-                            error!("Error processing transaction {}: {}", signature, e);
-                        }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
 
-                        last_signature = Some(signature);
-                    }
+    /// Process the program's recent transactions, oldest first. Stops at the first one
+    /// that is not `min_confirmations` slots deep yet; it is picked up again next poll.
+    async fn poll_once(&self, last_signature: &mut Option<Signature>) -> Result<()> {
+        let signatures = retry_with_backoff(&self.retry, "Solana get_signatures_for_address", || {
+            let program_id = self.program_id;
+            self.rpc_client
+                .call(move |client| async move { client.get_signatures_for_address(&program_id).await })
+        })
+        .await?;
+
+        let current_slot = if self.min_confirmations > 0 {
+            retry_with_backoff(&self.retry, "Solana get_slot", || {
+                self.rpc_client.call(|client| async move { client.get_slot().await })
+            })
+            .await?
+        } else {
+            0
+        };
+
+        for sig_info in signatures.iter().rev() {
+            let signature = Signature::from_str(&sig_info.signature)
+                .map_err(|e| RelayerError::ParseError(format!("Invalid signature: {}", e)))?;
+
+            if let Some(ref last_sig) = last_signature {
+                if signature == *last_sig {
+                    continue;
                 }
-                Err(e) => {
-                    error!("Error fetching signatures: {}", e);
+            }
+
+            if !self.is_deep_enough(sig_info.slot, current_slot) {
+                debug!(
+                    "Transaction {} at slot {} has {}/{} confirmations, waiting",
+                    signature,
+                    sig_info.slot,
+                    current_slot.saturating_sub(sig_info.slot),
+                    self.min_confirmations
+                );
+                break;
+            }
+
+            if let Err(e) = self.process_transaction(&signature).await {
+                error!("Error processing transaction {}: {}", signature, e);
+            }
+
+            *last_signature = Some(signature);
+        }
+
+        Ok(())
+    }
+
+    /// Re-read the program's transactions once, from `from_slot` up to the slot that is
+    /// `min_confirmations` deep now, and act on their events oldest first, reporting after
+    /// each. Returns how many transactions were read.
+    pub async fn backfill(&self, from_slot: u64, progress: &mut dyn FnMut(ReplayProgress)) -> Result<usize> {
+        let current_slot = retry_with_backoff(&self.retry, "Solana get_slot", || {
+            self.rpc_client.call(|client| async move { client.get_slot().await })
+        })
+        .await?;
+        let to_slot = current_slot.saturating_sub(self.min_confirmations);
+        info!("Replaying Solana program transactions in slots {}..={}", from_slot, to_slot);
+
+        // The node pages newest first; walk back until a page reaches past `from_slot`
+        let mut signatures = Vec::new();
+        let mut before: Option<Signature> = None;
+        loop {
+            let page = retry_with_backoff(&self.retry, "Solana get_signatures_for_address", || {
+                let program_id = self.program_id;
+                let commitment = self.commitment;
+                self.rpc_client.call(move |client| async move {
+                    let config = GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: None,
+                        limit: Some(SIGNATURE_PAGE_LIMIT),
+                        commitment: Some(commitment),
+                    };
+                    client.get_signatures_for_address_with_config(&program_id, config).await
+                })
+            })
+            .await?;
+
+            let last_page = page.len() < SIGNATURE_PAGE_LIMIT || page.last().map_or(true, |info| info.slot < from_slot);
+            for sig_info in &page {
+                let signature = Signature::from_str(&sig_info.signature)
+                    .map_err(|e| RelayerError::ParseError(format!("Invalid signature: {}", e)))?;
+                if (from_slot..=to_slot).contains(&sig_info.slot) {
+                    signatures.push(signature);
                 }
+                before = Some(signature);
             }
+            if last_page {
+                break;
+            }
+        }
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        let total = signatures.len();
+        for (done, signature) in signatures.iter().rev().enumerate() {
+            if let Err(e) = self.process_transaction(signature).await {
+                error!("Error processing transaction {}: {}", signature, e);
+            }
+            progress(ReplayProgress::Solana { done: done + 1, total });
         }
+
+        Ok(total)
     }
 
+    /// Whether at least `min_confirmations` slots have passed since `slot`. Compared
+    /// in slots rather than block heights, which count differently once slots are skipped.
+    fn is_deep_enough(&self, slot: u64, current_slot: u64) -> bool {
+        current_slot.saturating_sub(slot) >= self.min_confirmations
+    }
 
-    async fn process_transaction(&self, signature: &Signature) -> Result<()> {
+    /// Act on the bridge events in one program transaction, as polling does for each it finds
+    pub async fn process_transaction(&self, signature: &Signature) -> Result<()> {
         debug!("Processing transaction: {}", signature);
 
       
-        let tx = self
-            .rpc_client
-            .get_transaction_with_config(
-                signature,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::Json),
-                    commitment: Some(self.commitment),
-                    max_supported_transaction_version: Some(0),
-                },
-            )
-            .await
-            .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to get transaction: {}", e)))?;
+        let tx = retry_with_backoff(&self.retry, "Solana get_transaction", || {
+            let commitment = self.commitment;
+            self.rpc_client.call(move |client| async move {
+                client
+                    .get_transaction_with_config(
+                        signature,
+                        RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::Json),
+                            commitment: Some(commitment),
+                            max_supported_transaction_version: Some(0),
+                        },
+                    )
+                    .await
+            })
+        })
+        .await?;
 
        
         if tx.transaction.meta.as_ref().and_then(|m| m.err.as_ref()).is_some() {
@@ -127,26 +241,54 @@ impl SolanaMonitor {
         if let Some(meta) = tx.transaction.meta {
             let log_messages: Option<Vec<String>> = meta.log_messages.into();
             if let Some(log_messages) = log_messages {
-                if let Some(event) = self.parse_logs(&log_messages, signature.to_string())? {
-                    info!("Found bridge event: {:?}", event);
-                    self.handle_event(event).await?;
-                }
+                self.handle_logs(&log_messages, &signature.to_string()).await?;
             }
         }
 
         Ok(())
     }
 
+    /// Act on every bridge event in a successful transaction's logs
+    async fn handle_logs(&self, logs: &[String], tx_hash: &str) -> Result<()> {
+        if let Some(event) = Self::parse_logs(logs, tx_hash.to_string())? {
+            info!("Found bridge event: {:?}", event);
+            self.handle_event(event).await?;
+        }
 
-    fn parse_logs(&self, logs: &[String], tx_hash: String) -> Result<Option<BridgeEvent>> {
+        let program_data = parse_program_data(logs);
+        for event in program_data.iter().filter_map(|fields| TokensUnlockedEvent::from_log_data(fields)) {
+            self.handle_unlock(&event, tx_hash).await?;
+        }
+        for event in program_data.iter().filter_map(|fields| TokensRefundedEvent::from_log_data(fields)) {
+            self.handle_refund(&event, tx_hash).await?;
+        }
+        for event in program_data.iter().filter_map(|fields| TokensCancelledEvent::from_log_data(fields)) {
+            self.handle_cancel(&event, tx_hash).await?;
+        }
+
+        Ok(())
+    }
+
+
+    /// The first `TokensLocked` event in `logs`
+    pub(crate) fn parse_logs(logs: &[String], tx_hash: String) -> Result<Option<BridgeEvent>> {
         let mut in_tokens_locked_event = false;
         let mut user: Option<String> = None;
         let mut amount: Option<u64> = None;
         let mut destination_chain: Option<u8> = None;
         let mut destination_address: Option<Vec<u8>> = None;
         let mut nonce: Option<u64> = None;
+        let mut expires_at: Option<i64> = None;
+        // Logged before the event as "Lock amount: X, Fee: Y, Net amount: Z"
+        let mut fee: Option<u64> = None;
 
         for log in logs {
+            if log.contains("Lock amount:") {
+                fee = extract_value(log, "Fee:")
+                    .and_then(|value| value.split(',').next().and_then(|fee| fee.trim().parse().ok()));
+                continue;
+            }
+
             if log.contains("EVENT: TokensLocked") {
                 in_tokens_locked_event = true;
                 continue;
@@ -169,6 +311,10 @@ impl SolanaMonitor {
                     if let Some(value) = extract_value(log, "destination_address:") {
                         destination_address = parse_address_bytes(&value);
                     }
+                } else if log.contains("expiry:") {
+                    if let Some(value) = extract_value(log, "expiry:") {
+                        expires_at = value.parse().ok();
+                    }
                 } else if log.contains("nonce:") {
                     if let Some(value) = extract_value(log, "nonce:") {
                         nonce = value.parse().ok();
@@ -183,26 +329,33 @@ impl SolanaMonitor {
                         let dest_addr = destination_address.take().unwrap();
                         let nonce_val = nonce.take().unwrap();
 
-                        let to_chain = match dest_chain {
-                            1 => Chain::Ethereum,
-                            2 => Chain::Sui,
-                            _ => return Err(RelayerError::ParseError(format!("Unknown destination chain: {}", dest_chain))),
-                        };
+                        let to_chain = TransferRoute::from_destination_code(Chain::Solana, dest_chain)?.to;
 
-                        let recipient = if to_chain == Chain::Ethereum {
-                            format!("0x{}", hex::encode(&dest_addr))
-                        } else {
-                            hex::encode(&dest_addr)
+                        if amount_val == 0 {
+                            return Err(RelayerError::ParseError(format!(
+                                "Lock {} (nonce {}) has a zero amount",
+                                tx_hash, nonce_val
+                            )));
+                        }
+                        let recipient = destination_recipient(to_chain, &dest_addr)?;
+
+                        // `submit_to_solana` parses the sender again on refund, so keep bad ones out of the DB
+                        let Ok(sender) = Pubkey::from_str(&user_val) else {
+                            warn!("Lock {} (nonce {}) has an invalid user pubkey {:?}, skipping", tx_hash, nonce_val, user_val);
+                            return Ok(None);
                         };
 
                         return Ok(Some(BridgeEvent::TokensLocked {
                             from_chain: Chain::Solana,
                             to_chain,
-                            sender: user_val,
+                            sender: sender.to_string(),
                             recipient,
                             amount: amount_val,
+                            fee: fee.unwrap_or(0),
                             nonce: nonce_val,
                             tx_hash,
+                            // Locks from before expiry was added log none
+                            expires_at: expires_at.take(),
                         }));
                     }
                 }
@@ -221,10 +374,12 @@ impl SolanaMonitor {
                 sender,
                 recipient,
                 amount,
+                fee,
                 nonce,
                 tx_hash,
+                expires_at,
             } => {
-                if self.db.is_nonce_processed(*nonce).await? {
+                if self.db.is_nonce_processed(*from_chain, *nonce).await? {
                     warn!("Nonce {} already processed, skipping", nonce);
                     return Ok(());
                 }
@@ -237,7 +392,7 @@ impl SolanaMonitor {
                
                 let tx_id = self
                     .db
-                    .create_transaction(
+                    .create_expiring_transaction(
                         *nonce,
                         *from_chain,
                         *to_chain,
@@ -245,8 +400,13 @@ impl SolanaMonitor {
                         sender,
                         recipient,
                         *amount,
+                        *fee,
+                        *expires_at,
                     )
                     .await?;
+                if let Some(decimals) = self.token_decimals {
+                    self.db.set_net_amount(tx_id, Amount::new(u128::from(*amount), decimals)).await?;
+                }
 
                 info!("Created relayer transaction with ID: {}", tx_id);
             }
@@ -257,6 +417,129 @@ impl SolanaMonitor {
 
         Ok(())
     }
+
+    /// An unlock landed on Solana: the Ethereum -> Solana transfer with that nonce is done
+    async fn handle_unlock(&self, event: &TokensUnlockedEvent, tx_hash: &str) -> Result<()> {
+        let from_chain = TransferRoute::ETHEREUM_TO_SOLANA.from;
+        let Some(tx) = self.db.get_transaction_by_nonce(from_chain, event.nonce).await? else {
+            warn!("TokensUnlocked for unknown nonce {}", event.nonce);
+            return Ok(());
+        };
+
+        let route = TransferRoute::of(&tx);
+        if route != TransferRoute::ETHEREUM_TO_SOLANA {
+            warn!("TokensUnlocked nonce {} matches a {} transfer, ignoring", event.nonce, route);
+            return Ok(());
+        }
+
+        if tx.status == TransactionStatus::Confirmed {
+            debug!("Nonce {} already confirmed", event.nonce);
+            return Ok(());
+        }
+        if !tx.status.can_transition_to(TransactionStatus::Confirmed) {
+            warn!(
+                "TokensUnlocked for nonce {} but the transfer is {}, leaving it for an operator",
+                event.nonce, tx.status
+            );
+            return Ok(());
+        }
+
+        info!(
+            "Processing TokensUnlocked event: nonce={}, amount={}, user={}",
+            event.nonce, event.amount, event.user
+        );
+
+        self.db
+            .update_transaction_status(tx.id, TransactionStatus::Confirmed, Some(tx_hash), None)
+            .await
+    }
+
+    /// A Solana lock was refunded to the user, so it must not be bridged
+    async fn handle_refund(&self, event: &TokensRefundedEvent, tx_hash: &str) -> Result<()> {
+        info!(
+            "Processing TokensRefunded event: nonce={}, amount={}, user={}",
+            event.nonce, event.amount, event.user
+        );
+        self.mark_returned(event.nonce, tx_hash, None).await
+    }
+
+    /// The admin cancelled a Solana lock; like a refund, it must not be bridged
+    async fn handle_cancel(&self, event: &TokensCancelledEvent, tx_hash: &str) -> Result<()> {
+        info!(
+            "Processing TokensCancelled event: nonce={}, amount={}, user={}",
+            event.nonce, event.amount, event.user
+        );
+        self.mark_returned(event.nonce, tx_hash, Some("cancelled by admin")).await
+    }
+
+    /// Record that the tokens of Solana lock `nonce` went back to the user in `tx_hash`
+    async fn mark_returned(&self, nonce: u64, tx_hash: &str, note: Option<&str>) -> Result<()> {
+        let Some(tx) = self.db.get_transaction_by_nonce(Chain::Solana, nonce).await? else {
+            warn!("Returned lock for unknown nonce {}", nonce);
+            return Ok(());
+        };
+
+        if tx.from_chain != Chain::Solana {
+            warn!("Returned lock nonce {} matches a {}-origin transfer, ignoring", nonce, tx.from_chain);
+            return Ok(());
+        }
+
+        if tx.status == TransactionStatus::Refunded {
+            debug!("Nonce {} already marked refunded", nonce);
+            return Ok(());
+        }
+        if tx.status == TransactionStatus::Confirmed {
+            error!(
+                "ALERT: nonce {} was returned on Solana after being delivered to {}",
+                nonce, tx.to_chain
+            );
+        }
+
+        self.db
+            .update_transaction_status(tx.id, TransactionStatus::Refunded, Some(tx_hash), note)
+            .await
+    }
+}
+
+/// Base64-decoded fields of every `Program data:` line (events emitted with `sol_log_data`)
+fn parse_program_data(logs: &[String]) -> Vec<Vec<Vec<u8>>> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| {
+            data.split_whitespace()
+                .map(|field| BASE64.decode(field))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ok()
+        })
+        .collect()
+}
+
+/// Render the program's 32-byte `destination_address` for `chain`. EVM addresses
+/// are left-padded (see `bytes32_to_eth_address`), so the first 12 bytes must be
+/// zero and the rest not; they come back EIP-55 checksummed. Solana recipients
+/// are base58 pubkeys.
+pub(crate) fn destination_recipient(chain: Chain, bytes: &[u8]) -> Result<String> {
+    let bytes32: [u8; 32] = bytes.try_into().map_err(|_| {
+        RelayerError::ParseError(format!("Destination address must be 32 bytes, got {}", bytes.len()))
+    })?;
+
+    match chain {
+        Chain::Ethereum => {
+            if bytes32[..12].iter().any(|b| *b != 0) {
+                return Err(RelayerError::ParseError(format!(
+                    "Ethereum destination 0x{} is not a left-padded 20-byte address",
+                    hex::encode(bytes32)
+                )));
+            }
+            let address = Address::from(bytes32_to_eth_address(&bytes32));
+            if address.is_zero() {
+                return Err(RelayerError::ParseError("Ethereum destination is the zero address".to_string()));
+            }
+            Ok(checksum_address(&address))
+        }
+        Chain::Solana => Ok(Pubkey::from(bytes32).to_string()),
+        _ => Ok(hex::encode(bytes32)),
+    }
 }
 
 fn extract_value(log: &str, key: &str) -> Option<String> {
@@ -291,6 +574,188 @@ fn parse_address_bytes(s: &str) -> Option<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{advance_status, eth_tx_hash, sol_tx_signature, MockSolanaRpc};
+
+    fn test_config() -> SolanaConfig {
+        SolanaConfig {
+            rpc_urls: vec!["http://127.0.0.1:8899".to_string()],
+            ws_url: "ws://127.0.0.1:8900".to_string(),
+            bridge_program_id: "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH".to_string(),
+            require_canonical_program_id: false,
+            commitment: "confirmed".to_string(),
+            monitor_commitment: None,
+            confirm_commitment: None,
+            bridge_config_account: None,
+            token_mint: None,
+            poll_interval_ms: 5000,
+            min_confirmations: 0,
+            min_transfer_amount: None,
+            max_transfer_amount: None,
+            token_decimals: None,
+        }
+    }
+
+    fn test_retry() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            max_attempts: 1,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn test_monitor(db: Database) -> SolanaMonitor {
+        SolanaMonitor::new(&test_config(), db, test_retry()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_poll_interval_comes_from_config() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        assert_eq!(test_monitor(db.clone()).poll_interval(), Duration::from_secs(5));
+
+        let config = SolanaConfig {
+            poll_interval_ms: 400,
+            ..test_config()
+        };
+        let monitor = SolanaMonitor::new(&config, db, test_retry()).unwrap();
+        assert_eq!(monitor.poll_interval(), Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_lock_is_withheld_until_deep_enough() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let config = SolanaConfig {
+            rpc_urls: vec![rpc.url().to_string()],
+            min_confirmations: 32,
+            ..test_config()
+        };
+        let monitor = SolanaMonitor::new(&config, db.clone(), test_retry()).unwrap();
+
+        let mut padded = [0u8; 32];
+        padded[31] = 1;
+        let lock = {
+            let mut chain = rpc.chain();
+            chain.slot = 110;
+            chain.add_transaction(100, lock_logs(&Pubkey::new_unique().to_string(), 1_000, &padded))
+        };
+
+        // 10 of 32 slots: nothing is created and the lock is not marked as seen
+        let mut last_signature = None;
+        monitor.poll_once(&mut last_signature).await.unwrap();
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 4).await.unwrap().is_none());
+        assert_eq!(last_signature, None);
+        assert!(!rpc.chain().calls.iter().any(|m| m == "getTransaction"));
+
+        rpc.chain().slot = 132;
+        monitor.poll_once(&mut last_signature).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 4).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(tx.from_tx_hash, lock.to_string());
+        assert_eq!(last_signature, Some(lock));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_reads_at_monitor_commitment() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let config = SolanaConfig {
+            rpc_urls: vec![rpc.url().to_string()],
+            commitment: "processed".to_string(),
+            monitor_commitment: Some("confirmed".to_string()),
+            confirm_commitment: Some("finalized".to_string()),
+            ..test_config()
+        };
+        let monitor = SolanaMonitor::new(&config, db.clone(), test_retry()).unwrap();
+
+        let mut padded = [0u8; 32];
+        padded[31] = 1;
+        rpc.chain().add_transaction(100, lock_logs(&Pubkey::new_unique().to_string(), 1_000, &padded));
+        monitor.poll_once(&mut None).await.unwrap();
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 4).await.unwrap().is_some());
+
+        let chain = rpc.chain();
+        for method in ["getSignaturesForAddress", "getTransaction"] {
+            let params = chain
+                .calls
+                .iter()
+                .zip(&chain.params)
+                .find(|(called, _)| *called == method)
+                .map(|(_, params)| params)
+                .unwrap_or_else(|| panic!("{} not called", method));
+            assert_eq!(params[1]["commitment"], "confirmed", "{}", method);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tokens_unlocked_event_confirms_transfer() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let user = Pubkey::new_unique();
+        let user_key = user.to_string();
+        let id = db
+            .create_transaction(7, Chain::Ethereum, Chain::Solana, &eth_tx_hash(7), "0xsender", &user_key, 500, 0)
+            .await
+            .unwrap();
+        advance_status(&db, id, TransactionStatus::Submitted, Some("unlock_sig")).await;
+        // A Solana lock with the same sequence number is a different transfer
+        db.create_transaction(7, Chain::Solana, Chain::Ethereum, &sol_tx_signature(7), &user_key, "0xabc", 500, 0)
+            .await
+            .unwrap();
+
+        let event = TokensUnlockedEvent {
+            user,
+            token_mint: Pubkey::new_unique(),
+            amount: 500,
+            nonce: 7,
+        };
+        let logs = vec![
+            "Program log: EVENT: TokensUnlocked".to_string(),
+            format!(
+                "Program data: {} {}",
+                BASE64.encode(TokensUnlockedEvent::DISCRIMINATOR),
+                BASE64.encode(borsh::to_vec(&event).unwrap())
+            ),
+            "Program data: aGVsbG8=".to_string(),
+        ];
+
+        let events: Vec<_> = parse_program_data(&logs)
+            .iter()
+            .filter_map(|fields| TokensUnlockedEvent::from_log_data(fields))
+            .collect();
+        assert_eq!(events, vec![event]);
+
+        let monitor = test_monitor(db.clone());
+        monitor.handle_unlock(&events[0], "unlock_sig").await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 7).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+        assert_eq!(tx.to_tx_hash.as_deref(), Some("unlock_sig"));
+        let lock = db.get_transaction_by_nonce(Chain::Solana, 7).await.unwrap().unwrap();
+        assert_eq!(lock.status, TransactionStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_tokens_refunded_event_marks_lock_refunded() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let user = Pubkey::new_unique();
+        let lock_hash = sol_tx_signature(3);
+        db.create_transaction(3, Chain::Solana, Chain::Ethereum, &lock_hash, &user.to_string(), "0xabc", 250, 0)
+            .await
+            .unwrap();
+
+        let event = TokensRefundedEvent {
+            user,
+            token_mint: Pubkey::new_unique(),
+            amount: 250,
+            nonce: 3,
+        };
+        let monitor = test_monitor(db.clone());
+        monitor.handle_refund(&event, "refund_sig").await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 3).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Refunded);
+        assert_eq!(db.get_locked_total().await.unwrap(), 0);
+    }
 
     #[test]
     fn test_extract_value() {
@@ -315,4 +780,77 @@ mod tests {
             Some(vec![255, 0, 128])
         );
     }
+
+    fn lock_logs(user: &str, amount: u64, destination_address: &[u8]) -> Vec<String> {
+        vec![
+            "Program log: Lock amount: 1000, Fee: 0, Net amount: 1000".to_string(),
+            "Program log: EVENT: TokensLocked".to_string(),
+            format!("Program log:   user: {}", user),
+            format!("Program log:   amount: {}", amount),
+            "Program log:   destination_chain: 1".to_string(),
+            format!("Program log:   destination_address: {:?}", destination_address),
+            "Program log:   expiry: 1700003600".to_string(),
+            "Program log:   nonce: 4".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_parse_logs_takes_ethereum_recipient_from_padded_address() {
+        // EIP-55 test vector
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&hex::decode(&checksummed[2..]).unwrap());
+
+        let user = Pubkey::new_unique();
+        let event = SolanaMonitor::parse_logs(&lock_logs(&user.to_string(), 1_000, &padded), "sig".to_string())
+            .unwrap()
+            .unwrap();
+        match event {
+            BridgeEvent::TokensLocked { sender, recipient, amount, expires_at, .. } => {
+                assert_eq!(sender, user.to_string());
+                assert_eq!(recipient, checksummed);
+                assert_eq!(amount, 1_000);
+                assert_eq!(expires_at, Some(1_700_003_600));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_logs_rejects_malformed_destination() {
+        let user = Pubkey::new_unique().to_string();
+        let parse =
+            |amount: u64, address: &[u8]| SolanaMonitor::parse_logs(&lock_logs(&user, amount, address), "sig".to_string());
+
+        // Unpadded 20-byte address
+        assert!(matches!(parse(1_000, &[0xab; 20]), Err(RelayerError::ParseError(_))));
+        // Garbage in the padding
+        assert!(matches!(parse(1_000, &[0xab; 32]), Err(RelayerError::ParseError(_))));
+        // Zero address
+        assert!(matches!(parse(1_000, &[0u8; 32]), Err(RelayerError::ParseError(_))));
+
+        let mut padded = [0u8; 32];
+        padded[31] = 1;
+        assert!(matches!(parse(0, &padded), Err(RelayerError::ParseError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_lock_with_invalid_user_pubkey_is_not_stored() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let monitor = test_monitor(db.clone());
+        let mut padded = [0u8; 32];
+        padded[31] = 1;
+        let lock_hash = sol_tx_signature(4);
+
+        for user in ["5XqZXqZXqZ", "0OIl", ""] {
+            monitor.handle_logs(&lock_logs(user, 1_000, &padded), &lock_hash).await.unwrap();
+        }
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 4).await.unwrap().is_none());
+
+        monitor
+            .handle_logs(&lock_logs(&Pubkey::new_unique().to_string(), 1_000, &padded), &lock_hash)
+            .await
+            .unwrap();
+        assert!(db.get_transaction_by_nonce(Chain::Solana, 4).await.unwrap().is_some());
+    }
 }
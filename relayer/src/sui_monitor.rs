@@ -0,0 +1,297 @@
+use crate::{
+    config::SuiConfig,
+    db::Database,
+    error::{RelayerError, Result},
+    retry::{retry_with_backoff, RetryPolicy},
+    routing::TransferRoute,
+    solana_monitor::destination_recipient,
+    types::{BridgeEvent, Chain},
+};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Events requested per `suix_queryEvents` page
+const EVENT_PAGE_SIZE: u64 = 50;
+
+// TODO: Decode events from checkpoints instead of polling the indexer API
+
+pub struct SuiMonitor {
+    http: reqwest::Client,
+    rpc_url: String,
+    /// Fully qualified Move type of the bridge's lock event
+    event_type: String,
+    db: Database,
+    poll_interval: Duration,
+    retry: RetryPolicy,
+}
+
+/// One entry of a `suix_queryEvents` page
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiEvent {
+    pub id: SuiEventId,
+    pub parsed_json: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiEventId {
+    pub tx_digest: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventPage {
+    data: Vec<SuiEvent>,
+    next_cursor: Option<Value>,
+    has_next_page: bool,
+}
+
+/// Fields of the Move `TokensLocked` event
+#[derive(Debug, Deserialize)]
+struct TokensLocked {
+    sender: String,
+    #[serde(deserialize_with = "u64_from_string")]
+    amount: u64,
+    destination_chain: u8,
+    destination_address: Vec<u8>,
+    #[serde(deserialize_with = "u64_from_string")]
+    nonce: u64,
+}
+
+/// Sui's JSON encodes u64 fields as decimal strings
+fn u64_from_string<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+impl SuiMonitor {
+    pub fn new(config: &SuiConfig, db: Database, retry: RetryPolicy) -> Result<Self> {
+        if config.bridge_package_id.is_empty() {
+            return Err(RelayerError::ConfigError("SUI_BRIDGE_PACKAGE_ID must be set".to_string()));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            rpc_url: config.rpc_url.clone(),
+            event_type: format!("{}::bridge::TokensLocked", config.bridge_package_id),
+            db,
+            poll_interval: Duration::from_millis(config.poll_interval_ms),
+            retry,
+        })
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        info!("Starting Sui monitor for event type: {}", self.event_type);
+
+        let mut cursor: Option<Value> = None;
+
+        loop {
+            match self.query_events(cursor.clone()).await {
+                Ok(page) => {
+                    for event in &page.data {
+                        if let Err(e) = self.process_event(event).await {
+                            error!("Error processing Sui event {}: {}", event.id.tx_digest, e);
+                        }
+                    }
+                    if page.next_cursor.is_some() {
+                        cursor = page.next_cursor;
+                    }
+                    if page.has_next_page {
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    error!("Error fetching Sui events: {}", e);
+                }
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn query_events(&self, cursor: Option<Value>) -> Result<EventPage> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "suix_queryEvents",
+            "params": [{ "MoveEventType": self.event_type }, cursor, EVENT_PAGE_SIZE, false],
+        });
+
+        let response: Value = retry_with_backoff(&self.retry, "Sui query_events", || async {
+            self.http
+                .post(&self.rpc_url)
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Value>()
+                .await
+        })
+        .await?;
+
+        if let Some(err) = response.get("error") {
+            return Err(RelayerError::NetworkError(format!("Sui RPC error: {}", err)));
+        }
+        Ok(serde_json::from_value(response["result"].clone())?)
+    }
+
+    async fn process_event(&self, event: &SuiEvent) -> Result<()> {
+        debug!("Processing Sui event: {:?}", event);
+        let bridge_event = Self::parse_event(event)?;
+        self.handle_event(bridge_event).await
+    }
+
+    /// Map a `TokensLocked` Move event onto the bridge's event model
+    pub(crate) fn parse_event(event: &SuiEvent) -> Result<BridgeEvent> {
+        let locked: TokensLocked = serde_json::from_value(event.parsed_json.clone())
+            .map_err(|e| RelayerError::ParseError(format!("Malformed TokensLocked event: {}", e)))?;
+
+        let to_chain = TransferRoute::from_destination_code(Chain::Sui, locked.destination_chain)?.to;
+        if locked.amount == 0 {
+            return Err(RelayerError::ParseError(format!(
+                "Lock {} (nonce {}) has a zero amount",
+                event.id.tx_digest, locked.nonce
+            )));
+        }
+
+        Ok(BridgeEvent::TokensLocked {
+            from_chain: Chain::Sui,
+            to_chain,
+            sender: locked.sender,
+            recipient: destination_recipient(to_chain, &locked.destination_address)?,
+            amount: locked.amount,
+            fee: 0,
+            nonce: locked.nonce,
+            tx_hash: event.id.tx_digest.clone(),
+            expires_at: None,
+        })
+    }
+
+    async fn handle_event(&self, event: BridgeEvent) -> Result<()> {
+        match &event {
+            BridgeEvent::TokensLocked {
+                from_chain,
+                to_chain,
+                sender,
+                recipient,
+                amount,
+                fee,
+                nonce,
+                tx_hash,
+                ..
+            } => {
+                if self.db.is_nonce_processed(*from_chain, *nonce).await? {
+                    warn!("Nonce {} already processed, skipping", nonce);
+                    return Ok(());
+                }
+
+                info!(
+                    "Processing Sui TokensLocked event: nonce={}, amount={}, to={}",
+                    nonce, amount, to_chain
+                );
+
+                let tx_id = self
+                    .db
+                    .create_transaction(*nonce, *from_chain, *to_chain, tx_hash, sender, recipient, *amount, *fee)
+                    .await?;
+
+                info!("Created relayer transaction with ID: {}", tx_id);
+            }
+            BridgeEvent::TokensBurned { .. } => {
+                warn!("Unexpected TokensBurned event from Sui");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn test_monitor(db: Database) -> SuiMonitor {
+        let config = SuiConfig {
+            rpc_url: "http://127.0.0.1:9000".to_string(),
+            bridge_package_id: "0x2c8d".to_string(),
+            poll_interval_ms: 5000,
+        };
+        let retry = RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            max_attempts: 1,
+            timeout: Duration::from_secs(5),
+        };
+        SuiMonitor::new(&config, db, retry).unwrap()
+    }
+
+    fn lock_event(destination_chain: u8, destination_address: &[u8], amount: u64, nonce: u64) -> SuiEvent {
+        serde_json::from_value(json!({
+            "id": { "txDigest": format!("sui_digest_{}", nonce), "eventSeq": "0" },
+            "packageId": "0x2c8d",
+            "transactionModule": "bridge",
+            "sender": "0xa11ce",
+            "type": "0x2c8d::bridge::TokensLocked",
+            "parsedJson": {
+                "sender": "0xa11ce",
+                "amount": amount.to_string(),
+                "destination_chain": destination_chain,
+                "destination_address": destination_address,
+                "nonce": nonce.to_string()
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sui_lock_creates_transaction() {
+        let db = Database::new("sqlite::memory:", 1).await.unwrap();
+        let monitor = test_monitor(db.clone());
+
+        let user = Pubkey::new_unique();
+        monitor.process_event(&lock_event(0, user.as_ref(), 700, 21)).await.unwrap();
+
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&[0x11; 20]);
+        monitor.process_event(&lock_event(1, &padded, 300, 22)).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Sui, 21).await.unwrap().unwrap();
+        assert_eq!(tx.from_chain, Chain::Sui);
+        assert_eq!(tx.to_chain, Chain::Solana);
+        assert_eq!(tx.from_tx_hash, "sui_digest_21");
+        assert_eq!(tx.sender, "0xa11ce");
+        assert_eq!(tx.recipient, user.to_string());
+        assert_eq!(tx.amount, 700);
+
+        let tx = db.get_transaction_by_nonce(Chain::Sui, 22).await.unwrap().unwrap();
+        assert_eq!(tx.to_chain, Chain::Ethereum);
+        assert_eq!(tx.recipient, format!("0x{}", "11".repeat(20)));
+
+        // Seen again on a later poll
+        monitor.process_event(&lock_event(0, user.as_ref(), 700, 21)).await.unwrap();
+        assert_eq!(db.get_stats().await.unwrap().total, 2);
+    }
+
+    #[test]
+    fn test_malformed_sui_lock_is_rejected() {
+        let user = Pubkey::new_unique();
+        // Unknown destination chain
+        assert!(SuiMonitor::parse_event(&lock_event(2, user.as_ref(), 700, 1)).is_err());
+        // Zero amount
+        assert!(SuiMonitor::parse_event(&lock_event(0, user.as_ref(), 0, 1)).is_err());
+        // Short destination
+        assert!(SuiMonitor::parse_event(&lock_event(0, &[1; 20], 700, 1)).is_err());
+
+        let mut event = lock_event(0, user.as_ref(), 700, 1);
+        event.parsed_json["amount"] = json!(700);
+        assert!(SuiMonitor::parse_event(&event).is_err());
+    }
+}
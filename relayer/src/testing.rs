@@ -0,0 +1,656 @@
+//! In-memory stand-ins for the database, chain RPCs and validator nodes, so
+//! transfer state transitions can be exercised without network access.
+//!
+//! Built for the crate's own tests and, with the `test-util` feature, for
+//! downstream crates.
+
+use crate::config::{EthereumConfig, RelayerConfig, SolanaConfig, ValidatorConfig};
+use crate::db::Database;
+use crate::error::Result;
+use crate::ethereum_monitor::SolanaBridge;
+use crate::types::{Chain, ChainSignature, RelayerTransaction, TransactionStatus, ValidatorSignature};
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::sol_types::SolEvent;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use borsh::BorshSerialize;
+use axum::{http::StatusCode, routing::post, Json, Router};
+use chrono::Utc;
+use serde_json::{json, Value};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Ethereum recipient used by [`pending_mint`]
+pub const TEST_ETH_RECIPIENT: &str = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0";
+
+/// `bridge_contract` of [`test_configs`], which [`MockEthereumChain::add_burn`] burns on
+pub const TEST_BRIDGE_CONTRACT: &str = "0xaBD6f99Fbb77051B28942abe3118bf4D8Ea9F2CA";
+
+/// Anvil's first default account
+pub const TEST_ETH_KEY: &str = "0xac0974bec39a17e36ba4a6b4d8ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+impl Database {
+    /// Fresh, migrated SQLite database that lives as long as the returned handle.
+    /// Limited to one connection, since every `:memory:` connection is its own database.
+    pub async fn new_in_memory() -> Result<Self> {
+        Database::new("sqlite::memory:", 1).await
+    }
+}
+
+/// Configs pointing every chain at `rpc_url`, with dry run on
+pub fn test_configs(rpc_url: &str) -> (SolanaConfig, EthereumConfig, RelayerConfig) {
+    let solana = SolanaConfig {
+        rpc_urls: vec![rpc_url.to_string()],
+        ws_url: rpc_url.to_string(),
+        bridge_program_id: "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH".to_string(),
+        require_canonical_program_id: false,
+        commitment: "confirmed".to_string(),
+        monitor_commitment: None,
+        confirm_commitment: None,
+        bridge_config_account: None,
+        token_mint: None,
+        poll_interval_ms: 5000,
+        min_confirmations: 0,
+        min_transfer_amount: None,
+        max_transfer_amount: None,
+        token_decimals: None,
+    };
+    let ethereum = EthereumConfig {
+        rpc_urls: vec![rpc_url.to_string()],
+        ws_url: rpc_url.to_string(),
+        chain_id: 1,
+        bridge_contract: TEST_BRIDGE_CONTRACT.to_string(),
+        extra_bridge_contracts: vec![],
+        wrapped_sol_contract: "0xF718C74C9b298bCDd48Ed8801325E6ddBE2a5A5c".to_string(),
+        validator_registry_contract: "0xE45DC6606979b9086375561Ff7d8f66f8C506816".to_string(),
+        confirmations: 12,
+        max_priority_fee_gwei: 1.5,
+        max_fee_per_gas_gwei: 200.0,
+        max_gas_price_gwei: None,
+        poll_interval_ms: 5000,
+        log_chunk_size: 2000,
+        min_transfer_amount: None,
+        max_transfer_amount: None,
+        token_decimals: None,
+    };
+    let relayer = RelayerConfig {
+        poll_interval_ms: 5000,
+        max_retries: 3,
+        retry_delay_ms: 2000,
+        retry_max_delay_ms: 60_000,
+        gas_price_multiplier: 1.2,
+        reconcile_interval_ms: 60_000,
+        reconcile_tolerance: 0,
+        dry_run: true,
+        api_listen_addr: "127.0.0.1:0".to_string(),
+        signature_threshold: 2,
+        target_signatures: None,
+        target_signatures_wait_secs: 30,
+        selftest_amount: 1_000,
+        attestation_keypair_path: None,
+        reverify_window_secs: 3600,
+        collection_stall_secs: 300,
+        submission_timeout_secs: 600,
+        archive_after_days: 30,
+        max_batch_size: 50,
+        rpc_timeout_ms: 30_000,
+        validator_wire_format: Default::default(),
+        ethereum_key_path: None,
+        ethereum_key_env: None,
+        solana_keypair_path: None,
+        solana_keypair_env: None,
+    };
+    (solana, ethereum, relayer)
+}
+
+/// Serve `app` on an ephemeral local port and return its base URL
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    format!("http://{}", addr)
+}
+
+/// A `TokensBurned` from [`TEST_ETH_RECIPIENT`] that [`MockEthereumRpc`] serves as a log
+#[derive(Debug, Clone)]
+pub struct MockBurn {
+    /// Bridge contract that emitted it
+    pub contract: Address,
+    pub block: u64,
+    pub tx_hash: B256,
+    pub nonce: u64,
+    pub amount: u64,
+    pub solana_address: String,
+}
+
+/// What [`MockEthereumRpc`] reports about the chain; tests edit it between steps
+#[derive(Debug)]
+pub struct MockEthereumChain {
+    pub chain_id: u64,
+    pub latest_block: u64,
+    /// Base fee reported by `eth_feeHistory`, in wei
+    pub base_fee: u128,
+    /// Answer to every `processedNonces` call
+    pub nonce_processed: bool,
+    /// `(block, success)` per transaction hash; `eth_sendRawTransaction` mines into `latest_block`
+    pub receipts: HashMap<B256, (u64, bool)>,
+    /// `TokensBurned` logs served by `eth_getLogs`
+    pub burns: Vec<MockBurn>,
+    /// Every JSON-RPC method asked for, in order
+    pub calls: Vec<String>,
+}
+
+impl Default for MockEthereumChain {
+    fn default() -> Self {
+        MockEthereumChain {
+            chain_id: 1,
+            latest_block: 100,
+            base_fee: 1_000_000_000,
+            nonce_processed: false,
+            receipts: HashMap::new(),
+            burns: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+}
+
+/// Ethereum JSON-RPC endpoint backed by a [`MockEthereumChain`]. Answers what the
+/// submitter needs to price, send and confirm a `mintWrapped`.
+pub struct MockEthereumRpc {
+    url: String,
+    chain: Arc<Mutex<MockEthereumChain>>,
+}
+
+impl MockEthereumRpc {
+    pub async fn start() -> Self {
+        let chain = Arc::new(Mutex::new(MockEthereumChain::default()));
+        let state = chain.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let chain = state.clone();
+                async move {
+                    let result = chain.lock().unwrap().answer(&body);
+                    Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+                }
+            }),
+        );
+
+        MockEthereumRpc {
+            url: serve(app).await,
+            chain,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn chain(&self) -> MutexGuard<'_, MockEthereumChain> {
+        self.chain.lock().unwrap()
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.chain().calls.clone()
+    }
+
+    /// Whether anything was broadcast
+    pub fn sent_transaction(&self) -> bool {
+        self.calls()
+            .iter()
+            .any(|m| m == "eth_sendRawTransaction" || m == "eth_sendTransaction")
+    }
+}
+
+impl MockEthereumChain {
+    /// Record a `TokensBurned` of `amount` to `solana_address` mined in `block`; returns its tx hash
+    pub fn add_burn(&mut self, block: u64, nonce: u64, amount: u64, solana_address: &str) -> B256 {
+        let contract = Address::from_str(TEST_BRIDGE_CONTRACT).unwrap();
+        self.add_burn_from(contract, block, nonce, amount, solana_address)
+    }
+
+    /// `add_burn`, emitted by bridge contract `contract`
+    pub fn add_burn_from(
+        &mut self,
+        contract: Address,
+        block: u64,
+        nonce: u64,
+        amount: u64,
+        solana_address: &str,
+    ) -> B256 {
+        let tx_hash = keccak256(format!("burn {} of nonce {}", self.burns.len(), nonce));
+        self.burns.push(MockBurn {
+            contract,
+            block,
+            tx_hash,
+            nonce,
+            amount,
+            solana_address: solana_address.to_string(),
+        });
+        tx_hash
+    }
+
+    fn answer(&mut self, body: &Value) -> Value {
+        let method = body["method"].as_str().unwrap_or_default().to_string();
+        self.calls.push(method.clone());
+
+        match method.as_str() {
+            "eth_chainId" => json!(format!("0x{:x}", self.chain_id)),
+            "eth_blockNumber" => json!(format!("0x{:x}", self.latest_block)),
+            "eth_gasPrice" => json!("0x3b9aca00"),
+            "eth_feeHistory" => json!({
+                "oldestBlock": format!("0x{:x}", self.latest_block),
+                "baseFeePerGas": [format!("0x{:x}", self.base_fee), format!("0x{:x}", self.base_fee)],
+                "gasUsedRatio": [0.5],
+            }),
+            "eth_getTransactionCount" => json!("0x0"),
+            "eth_getLogs" => {
+                let filter = &body["params"][0];
+                let block = |key: &str| {
+                    let value = filter[key].as_str().unwrap_or_default().trim_start_matches("0x");
+                    u64::from_str_radix(value, 16).unwrap_or_default()
+                };
+                let (from, to) = (block("fromBlock"), block("toBlock"));
+                // One address or an array of them; none means every contract
+                let addresses: Vec<Address> = match &filter["address"] {
+                    Value::String(address) => vec![address.parse().unwrap()],
+                    Value::Array(addresses) => addresses.iter().map(|a| a.as_str().unwrap().parse().unwrap()).collect(),
+                    _ => vec![],
+                };
+                self.burns
+                    .iter()
+                    .filter(|burn| (from..=to).contains(&burn.block))
+                    .filter(|burn| addresses.is_empty() || addresses.contains(&burn.contract))
+                    .enumerate()
+                    .map(|(index, burn)| {
+                        let data = SolanaBridge::TokensBurned {
+                            sender: Address::from_str(TEST_ETH_RECIPIENT).unwrap(),
+                            amount: U256::from(burn.amount),
+                            solanaAddress: burn.solana_address.clone(),
+                            nonce: burn.nonce,
+                        }
+                        .encode_log_data();
+                        json!({
+                            "address": burn.contract,
+                            "topics": data.topics(),
+                            "data": data.data,
+                            "blockHash": B256::repeat_byte(burn.block as u8),
+                            "blockNumber": format!("0x{:x}", burn.block),
+                            "transactionHash": burn.tx_hash,
+                            "transactionIndex": "0x0",
+                            "logIndex": format!("0x{:x}", index),
+                            "removed": false
+                        })
+                    })
+                    .collect()
+            }
+            "eth_estimateGas" => json!("0x5208"),
+            "eth_call" => json!(B256::with_last_byte(self.nonce_processed as u8)),
+            "eth_sendRawTransaction" => {
+                let raw = body["params"][0].as_str().unwrap_or_default();
+                let hash = keccak256(hex::decode(raw.trim_start_matches("0x")).unwrap_or_default());
+                self.receipts.insert(hash, (self.latest_block, true));
+                json!(hash)
+            }
+            "eth_getTransactionReceipt" => {
+                let receipt = body["params"][0]
+                    .as_str()
+                    .and_then(|hash| hash.parse::<B256>().ok())
+                    .and_then(|hash| self.receipts.get(&hash).map(|receipt| (hash, *receipt)));
+                match receipt {
+                    Some((hash, (block, success))) => json!({
+                        "type": "0x2",
+                        "status": if success { "0x1" } else { "0x0" },
+                        "cumulativeGasUsed": "0x5208",
+                        "logsBloom": format!("0x{}", "00".repeat(256)),
+                        "logs": [],
+                        "transactionHash": hash,
+                        "transactionIndex": "0x0",
+                        "blockHash": B256::repeat_byte(block as u8),
+                        "blockNumber": format!("0x{:x}", block),
+                        "gasUsed": "0x5208",
+                        "effectiveGasPrice": "0x3b9aca00",
+                        "from": Address::ZERO,
+                        "to": Address::ZERO,
+                        "contractAddress": null
+                    }),
+                    None => Value::Null,
+                }
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+/// A transaction [`MockSolanaRpc`] knows about
+#[derive(Debug, Clone)]
+pub struct MockSolanaTransaction {
+    pub signature: Signature,
+    pub slot: u64,
+    pub logs: Vec<String>,
+}
+
+/// What [`MockSolanaRpc`] reports about the chain; tests edit it between steps
+#[derive(Debug, Default)]
+pub struct MockSolanaChain {
+    pub slot: u64,
+    /// Transactions mentioning the bridge program, oldest first
+    pub transactions: Vec<MockSolanaTransaction>,
+    /// Account data served by `getAccountInfo`
+    pub accounts: HashMap<Pubkey, Vec<u8>>,
+    /// Confirmation level `getSignatureStatuses` reports per signature; others are unknown
+    pub statuses: HashMap<Signature, String>,
+    /// Every JSON-RPC method asked for, in order
+    pub calls: Vec<String>,
+    /// Params of every request, alongside `calls`
+    pub params: Vec<Value>,
+    /// Logs of transactions sent with `sendTransaction`, which land in `slot` already finalized
+    pub send_logs: Vec<String>,
+}
+
+impl MockSolanaChain {
+    pub fn add_transaction(&mut self, slot: u64, logs: Vec<String>) -> Signature {
+        let signature = Signature::new_unique();
+        self.transactions.push(MockSolanaTransaction { signature, slot, logs });
+        signature
+    }
+
+    fn answer(&mut self, body: &Value) -> Value {
+        let method = body["method"].as_str().unwrap_or_default().to_string();
+        self.calls.push(method.clone());
+        self.params.push(body["params"].clone());
+
+        match method.as_str() {
+            "getVersion" => json!({ "solana-core": "2.1.0", "feature-set": 0 }),
+            "getSlot" => json!(self.slot),
+            "getLatestBlockhash" => json!({
+                "context": { "slot": self.slot },
+                "value": { "blockhash": Hash::default().to_string(), "lastValidBlockHeight": self.slot + 150 },
+            }),
+            "sendTransaction" => {
+                // A serialized transaction opens with a one-byte signature count, then the fee payer's
+                let wire = BASE64.decode(body["params"][0].as_str().unwrap_or_default()).unwrap_or_default();
+                let signature = Signature::try_from(&wire[1..65]).unwrap();
+                let logs = self.send_logs.clone();
+                self.transactions.push(MockSolanaTransaction { signature, slot: self.slot, logs });
+                self.statuses.insert(signature, "finalized".to_string());
+                json!(signature.to_string())
+            }
+            "getAccountInfo" => {
+                let account = Pubkey::from_str(body["params"][0].as_str().unwrap_or_default())
+                    .ok()
+                    .and_then(|address| self.accounts.get(&address))
+                    .map(|data| {
+                        json!({
+                            "data": [BASE64.encode(data), "base64"],
+                            "executable": false,
+                            "lamports": 1_000_000,
+                            "owner": Pubkey::default().to_string(),
+                            "rentEpoch": 0,
+                            "space": data.len(),
+                        })
+                    });
+                json!({ "context": { "slot": self.slot }, "value": account })
+            }
+            // Newest first, like the real node
+            "getSignaturesForAddress" => self
+                .transactions
+                .iter()
+                .rev()
+                .map(|tx| {
+                    json!({
+                        "signature": tx.signature.to_string(),
+                        "slot": tx.slot,
+                        "err": null,
+                        "memo": null,
+                        "blockTime": null,
+                        "confirmationStatus": "confirmed"
+                    })
+                })
+                .collect(),
+            "getSignatureStatuses" => {
+                let statuses: Vec<Value> = body["params"][0]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|signature| {
+                        let status = Signature::from_str(signature.as_str().unwrap_or_default())
+                            .ok()
+                            .and_then(|signature| self.statuses.get(&signature));
+                        match status {
+                            Some(status) => json!({
+                                "slot": self.slot,
+                                // Finalized statuses no longer count confirmations
+                                "confirmations": if status == "finalized" { Value::Null } else { json!(1) },
+                                "err": null,
+                                "status": { "Ok": null },
+                                "confirmationStatus": status,
+                            }),
+                            None => Value::Null,
+                        }
+                    })
+                    .collect();
+                json!({ "context": { "slot": self.slot }, "value": statuses })
+            }
+            "getTransaction" => {
+                let signature = body["params"][0].as_str().unwrap_or_default();
+                match self.transactions.iter().find(|tx| tx.signature.to_string() == signature) {
+                    Some(tx) => json!({
+                        "slot": tx.slot,
+                        "transaction": ["", "base64"],
+                        "meta": {
+                            "err": null,
+                            "status": { "Ok": null },
+                            "fee": 5000,
+                            "preBalances": [],
+                            "postBalances": [],
+                            "logMessages": tx.logs,
+                        },
+                        "blockTime": null
+                    }),
+                    None => Value::Null,
+                }
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Solana JSON-RPC endpoint backed by a [`MockSolanaChain`]. Answers what the
+/// monitor needs to find and read bridge transactions, plus `getAccountInfo`
+/// for the accounts in [`MockSolanaChain::accounts`] and `sendTransaction`.
+pub struct MockSolanaRpc {
+    url: String,
+    chain: Arc<Mutex<MockSolanaChain>>,
+}
+
+impl MockSolanaRpc {
+    pub async fn start() -> Self {
+        let chain = Arc::new(Mutex::new(MockSolanaChain::default()));
+        let state = chain.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let chain = state.clone();
+                async move {
+                    let result = chain.lock().unwrap().answer(&body);
+                    Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+                }
+            }),
+        );
+
+        MockSolanaRpc {
+            url: serve(app).await,
+            chain,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn chain(&self) -> MutexGuard<'_, MockSolanaChain> {
+        self.chain.lock().unwrap()
+    }
+}
+
+/// JSON-only validator endpoint that signs while `up` is set and answers 503 otherwise
+pub struct MockValidator {
+    pub up: Arc<AtomicBool>,
+    /// Signature requests received, including refused ones
+    pub hits: Arc<AtomicUsize>,
+    pub config: ValidatorConfig,
+}
+
+impl MockValidator {
+    pub async fn start(name: &str, eth_address: &str, up: bool) -> Self {
+        let up = Arc::new(AtomicBool::new(up));
+        let hits = Arc::new(AtomicUsize::new(0));
+
+        let sign = |signature: ChainSignature| {
+            let up = up.clone();
+            let hits = hits.clone();
+            // Only reads JSON, so a Borsh body is refused with 415 before it counts as a hit
+            move |Json(_request): Json<Value>| {
+                let up = up.clone();
+                let hits = hits.clone();
+                async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    if !up.load(Ordering::SeqCst) {
+                        return Err(StatusCode::SERVICE_UNAVAILABLE);
+                    }
+                    Ok(Json(json!({ "signature": signature.to_string() })))
+                }
+            }
+        };
+        let app = Router::new()
+            .route(
+                "/sign-ethereum",
+                post(sign(ChainSignature::Ecdsa { r: [1; 32], s: [1; 32], v: 27 })),
+            )
+            .route("/sign-solana", post(sign(ChainSignature::Ed25519([1; 64]))));
+
+        MockValidator {
+            config: ValidatorConfig {
+                name: name.to_string(),
+                eth_address: eth_address.to_string(),
+                sol_public_key: String::new(),
+                endpoint: Some(serve(app).await),
+                auth_token: None,
+                auth_scheme: Default::default(),
+            },
+            up,
+            hits,
+        }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+}
+
+/// One mock validator per entry, up or down as given
+pub async fn mock_validators(states: &[bool]) -> Vec<MockValidator> {
+    let mut validators = Vec::new();
+    for (i, &up) in states.iter().enumerate() {
+        validators.push(MockValidator::start(&format!("Validator{}", i + 1), &format!("0x{:040x}", i + 1), up).await);
+    }
+    validators
+}
+
+pub fn validator_configs(validators: &[MockValidator]) -> Vec<ValidatorConfig> {
+    validators.iter().map(|v| v.config.clone()).collect()
+}
+
+/// Well-formed Solana signature standing in for the source transaction of `nonce`
+pub fn sol_tx_signature(nonce: u64) -> String {
+    let mut bytes = [7u8; 64];
+    bytes[..8].copy_from_slice(&nonce.to_le_bytes());
+    Signature::from(bytes).to_string()
+}
+
+/// Well-formed Ethereum transaction hash standing in for the source transaction of `nonce`
+pub fn eth_tx_hash(nonce: u64) -> String {
+    format!("0x{:064x}", nonce)
+}
+
+/// Logs of a `LockTokens` of `amount` (no fee) by `user` to [`TEST_ETH_RECIPIENT`], as the
+/// Solana monitor reads them
+pub fn lock_logs(user: &Pubkey, nonce: u64, amount: u64) -> Vec<String> {
+    let mut destination = [0u8; 32];
+    destination[12..].copy_from_slice(Address::from_str(TEST_ETH_RECIPIENT).unwrap().as_slice());
+    vec![
+        format!("Program log: Lock amount: {}, Fee: 0, Net amount: {}", amount, amount),
+        "Program log: EVENT: TokensLocked".to_string(),
+        format!("Program log:   user: {}", user),
+        format!("Program log:   amount: {}", amount),
+        "Program log:   destination_chain: 1".to_string(),
+        format!("Program log:   destination_address: {:?}", destination),
+        "Program log:   expiry: 1700003600".to_string(),
+        format!("Program log:   nonce: {}", nonce),
+    ]
+}
+
+/// `Program data` log line carrying a program event, as `sol_log_data` writes it
+pub fn event_log<E: BorshSerialize>(discriminator: &[u8], event: &E) -> String {
+    format!(
+        "Program data: {} {}",
+        BASE64.encode(discriminator),
+        BASE64.encode(borsh::to_vec(event).unwrap())
+    )
+}
+
+/// Insert a pending Solana → Ethereum transfer of 1000 for `nonce` and return its row
+pub async fn pending_mint(db: &Database, nonce: u64) -> RelayerTransaction {
+    db.create_transaction(
+        nonce,
+        Chain::Solana,
+        Chain::Ethereum,
+        &sol_tx_signature(nonce),
+        "5XqZXqZXqZ",
+        TEST_ETH_RECIPIENT,
+        1_000,
+        0,
+    )
+    .await
+    .unwrap();
+    db.get_transaction_by_nonce(Chain::Solana, nonce).await.unwrap().unwrap()
+}
+
+/// Store `count` ECDSA signatures on `tx`, moving it to `SignaturesCollected`
+pub async fn collect_mint_signatures(db: &Database, tx: &RelayerTransaction, count: usize) -> RelayerTransaction {
+    let signatures: Vec<_> = (0..count)
+        .map(|i| ValidatorSignature {
+            validator_address: format!("0x{:040x}", i + 1),
+            signature: ChainSignature::Ecdsa { r: [1; 32], s: [1; 32], v: 27 },
+            signed_at: Utc::now(),
+        })
+        .collect();
+    db.update_signatures(tx.id, &serde_json::to_string(&signatures).unwrap())
+        .await
+        .unwrap();
+    db.get_transaction_by_nonce(tx.from_chain, tx.nonce as u64).await.unwrap().unwrap()
+}
+
+/// Record `status` on transfer `id`, first walking it through the steps the submitter
+/// records on the way there from `Pending`. `to_tx_hash` goes with the last step.
+pub async fn advance_status(db: &Database, id: i64, status: TransactionStatus, to_tx_hash: Option<&str>) {
+    use TransactionStatus::*;
+
+    let path = [SignaturesCollected, Submitted, Confirmed];
+    let steps = match path.iter().position(|step| *step == status) {
+        Some(last) => &path[..=last],
+        None => std::slice::from_ref(&status),
+    };
+    for (i, step) in steps.iter().enumerate() {
+        let hash = if i + 1 == steps.len() { to_tx_hash } else { None };
+        db.update_transaction_status(id, *step, hash, None).await.unwrap();
+    }
+}
+
+pub fn stored_signatures(tx: &RelayerTransaction) -> Vec<ValidatorSignature> {
+    serde_json::from_str(tx.signatures.as_deref().unwrap_or("[]")).unwrap()
+}
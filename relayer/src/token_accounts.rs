@@ -0,0 +1,44 @@
+//! Token accounts the bridge program moves tokens between. The relayer's unlocks and
+//! the CLI both derive them here, so they always agree with each other and the program.
+
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+/// The vault PDA for `bridge_config`, seeded `["vault", bridge_config]` as in the program
+pub fn vault_pda(program_id: &Pubkey, bridge_config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], program_id).0
+}
+
+/// The vault PDA's associated token account for `token_mint`, which holds locked tokens
+pub fn derive_vault_ata(program_id: &Pubkey, bridge_config: &Pubkey, token_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&vault_pda(program_id, bridge_config), token_mint)
+}
+
+/// `user`'s associated token account for `token_mint`, which locks draw from and unlocks pay into
+pub fn derive_user_ata(user: &Pubkey, token_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(user, token_mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_atas_match_spl_derivation() {
+        let program_id = Pubkey::new_unique();
+        let bridge_config = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let (vault, _bump) = Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+        assert_eq!(vault_pda(&program_id, &bridge_config), vault);
+        assert_eq!(
+            derive_vault_ata(&program_id, &bridge_config, &mint),
+            get_associated_token_address(&vault, &mint)
+        );
+        assert_eq!(derive_user_ata(&user, &mint), get_associated_token_address(&user, &mint));
+
+        // Each mint gets its own account
+        assert_ne!(derive_user_ata(&user, &mint), derive_user_ata(&user, &Pubkey::new_unique()));
+    }
+}
@@ -1,36 +1,47 @@
 use crate::{
-    config::{EthereumConfig, SolanaConfig},
+    amount::TokenDecimals,
+    config::{EthereumConfig, RelayerConfig, SolanaConfig},
     db::Database,
     error::{RelayerError, Result},
-    types::{Chain, RelayerTransaction, TransactionStatus, ValidatorSignature},
+    failover::{EthereumRpc, SolanaRpc},
+    nonce_manager::NonceManager,
+    retry::{retry_with_backoff, with_timeout, RetryPolicy},
+    routing::{undeliverable, TransferRoute},
+    token_accounts::{derive_user_ata, derive_vault_ata, vault_pda},
+    types::{checksum_address, Chain, ChainSignature, RelayerTransaction, TransactionStatus, ValidatorSignature},
     validator_client::ValidatorClient,
 };
 use alloy::{
     contract::CallBuilder,
+    eips::{BlockId, BlockNumberOrTag},
     network::{Ethereum, EthereumWallet, TransactionBuilder},
-    primitives::{Address, Bytes, U256},
-    providers::{Provider, ProviderBuilder, RootProvider},
+    primitives::{Address, Bytes, B256, U256},
+    providers::{Provider, ProviderBuilder},
     rpc::types::TransactionRequest,
     signers::local::PrivateKeySigner,
     sol,
-    transports::http::{Client, Http},
+    sol_types::{Panic, Revert, SolError},
+    transports::TransportError,
 };
-use borsh::BorshSerialize;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use borsh::BorshDeserialize;
+use bridge_vault::{
+    instruction::BridgeInstruction,
+    processor::create_unlock_message,
+    state::{BridgeConfig, UserBridgeState, SIGNATURE_SCHEME_ED25519, SIGNATURE_SCHEME_SECP256K1},
+    BridgeError,
+};
+use solana_client::client_error::ClientError;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
-    instruction::{AccountMeta, Instruction},
+    instruction::InstructionError,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 use std::str::FromStr;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
-// TODO: Implement actual transaction submission for both chains
-// TODO: Add gas estimation and nonce management for Ethereum
-// TODO: Properly serialize Solana instructions
-
 sol! {
     #[allow(missing_docs)]
     #[sol(rpc)]
@@ -42,49 +53,78 @@ sol! {
             string memory solanaSender,
             bytes[] memory signatures
         ) external;
+
+        function processedNonces(uint256 nonce) external view returns (bool);
+
+        /// OpenZeppelin `Pausable`'s revert while the bridge is paused
+        error EnforcedPause();
     }
 }
 
+/// Note left on a transfer whose nonce the destination had already processed
+/// when we went to submit it
+const ALREADY_PROCESSED_NOTE: &str = "already processed on destination";
+
+/// What the destination chain currently says about a submitted transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Not found yet, or not buried deep enough
+    Pending,
+    Confirmed,
+    /// Included but failed, with the reason
+    Reverted(String),
+}
+
 pub struct TransactionSubmitter {
-    solana_client: RpcClient,
-    ethereum_provider: RootProvider<Http<Client>>,
+    solana_client: SolanaRpc,
+    ethereum_provider: EthereumRpc,
     solana_config: SolanaConfig,
     ethereum_config: EthereumConfig,
+    relayer_config: RelayerConfig,
     db: Database,
     validator_client: ValidatorClient,
     relayer_eth_signer: Option<PrivateKeySigner>,
     relayer_sol_keypair: Option<Keypair>,
+    nonce_manager: NonceManager,
+    retry: RetryPolicy,
+    /// Commitment a Solana unlock must reach before it counts as confirmed
+    confirm_commitment: CommitmentConfig,
 }
 
 impl TransactionSubmitter {
     pub fn new(
         solana_config: SolanaConfig,
         ethereum_config: EthereumConfig,
+        relayer_config: RelayerConfig,
         db: Database,
         validator_client: ValidatorClient,
     ) -> Result<Self> {
-        let solana_client = RpcClient::new_with_commitment(
-            solana_config.rpc_url.clone(),
+        let solana_client = SolanaRpc::solana(
+            &solana_config.rpc_urls,
             CommitmentConfig::from_str(&solana_config.commitment)
                 .map_err(|e| RelayerError::ConfigError(format!("Invalid commitment: {}", e)))?,
-        );
+        )?;
 
-        let ethereum_provider = ProviderBuilder::new().on_http(
-            ethereum_config
-                .rpc_url
-                .parse()
-                .map_err(|e| RelayerError::ConfigError(format!("Invalid RPC URL: {:?}", e)))?,
-        );
+        let confirm_commitment = CommitmentConfig::from_str(solana_config.confirm_commitment())
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid confirm commitment: {}", e)))?;
+
+        let ethereum_provider = EthereumRpc::ethereum(&ethereum_config.rpc_urls)?;
+
+        let retry = RetryPolicy::from_config(&relayer_config);
 
         Ok(Self {
             solana_client,
             ethereum_provider,
             solana_config,
             ethereum_config,
+            relayer_config,
             db,
             validator_client,
             relayer_eth_signer: None,
             relayer_sol_keypair: None,
+            nonce_manager: NonceManager::new(),
+            retry,
+            confirm_commitment,
         })
     }
 
@@ -109,6 +149,30 @@ impl TransactionSubmitter {
 
     pub async fn process_transaction(&self, tx: &RelayerTransaction) -> Result<()> {
         info!("Processing transaction: nonce={}, status={}", tx.nonce, tx.status);
+        TransferRoute::new(tx.from_chain, tx.to_chain)?;
+
+        // Past expiry the source chain only allows a refund, so delivering it too would pay twice
+        if let Some(expires_at) = tx.expires_at {
+            let open = matches!(tx.status, TransactionStatus::Pending | TransactionStatus::SignaturesCollected);
+            if open && tx.is_expired(chrono::Utc::now().timestamp()) {
+                warn!("Transfer {} expired at {}, no longer collecting or submitting it", tx.nonce, expires_at);
+                let message = format!("Expired at {}; awaiting refund on {}", expires_at, tx.from_chain);
+                return self
+                    .db
+                    .update_transaction_status(tx.id, TransactionStatus::Failed, None, Some(&message))
+                    .await;
+            }
+        }
+
+        if tx.status == TransactionStatus::Pending {
+            if let Some(reason) = self.amount_out_of_bounds(tx) {
+                warn!("Transfer {} not delivered: {}", tx.nonce, reason);
+                return self
+                    .db
+                    .update_transaction_status(tx.id, TransactionStatus::Failed, None, Some(&reason))
+                    .await;
+            }
+        }
 
         match tx.status {
             TransactionStatus::Pending => {
@@ -126,43 +190,138 @@ impl TransactionSubmitter {
             TransactionStatus::Failed => {
                 warn!("Transaction {} has failed status", tx.nonce);
             }
+            TransactionStatus::Refunded => {
+                info!("Transaction {} was refunded on the source chain", tx.nonce);
+            }
         }
 
         Ok(())
     }
 
 
+    /// Why `tx` falls outside the amount limits configured for its destination, if it does.
+    /// Checked before signing so a transfer the relayer won't deliver costs no gas.
+    fn amount_out_of_bounds(&self, tx: &RelayerTransaction) -> Option<String> {
+        let (min, max) = match tx.to_chain {
+            Chain::Ethereum => (self.ethereum_config.min_transfer_amount, self.ethereum_config.max_transfer_amount),
+            Chain::Solana => (self.solana_config.min_transfer_amount, self.solana_config.max_transfer_amount),
+            Chain::Sui => (None, None),
+        };
+        let amount = match self.token_decimals().delivered_amount(tx) {
+            Ok(amount) => amount,
+            Err(e) => return Some(e.to_string()),
+        };
+        let route = TransferRoute::of(tx);
+
+        if let Some(min) = min.filter(|min| amount < *min) {
+            return Some(format!("Amount {} is below the {} minimum of {}", amount, route, min));
+        }
+        if let Some(max) = max.filter(|max| amount > *max) {
+            return Some(format!("Amount {} is above the {} maximum of {}", amount, route, max));
+        }
+        None
+    }
+
+    fn token_decimals(&self) -> TokenDecimals {
+        TokenDecimals::from_configs(&self.solana_config, &self.ethereum_config)
+    }
+
     async fn collect_signatures(&self, tx: &RelayerTransaction) -> Result<()> {
         info!("Collecting signatures for nonce {}", tx.nonce);
 
-        let signatures = match tx.to_chain {
+        // The default config lists validators without endpoints; say so rather than
+        // failing every transfer with zero signatures
+        if !self.validator_client.has_endpoints() {
+            return Err(RelayerError::ConfigError(
+                "no validator endpoints configured; set `endpoint` on at least SIGNATURE_THRESHOLD \
+                 of the [[validators]] entries"
+                    .to_string(),
+            ));
+        }
+
+        let amount = self.token_decimals().delivered_amount(tx)?;
+        let mut request = match tx.to_chain {
             Chain::Ethereum => {
                 let recipient = Address::from_str(&tx.recipient)
                     .map_err(|e| RelayerError::ParseError(format!("Invalid recipient address: {}", e)))?;
 
-                self.validator_client
-                    .collect_signatures_for_ethereum_mint(
-                        recipient,
-                        U256::from(tx.amount as u64),
-                        tx.nonce as u64,
-                        &tx.sender,
-                    )
-                    .await?
+                self.validator_client.ethereum_mint_request(
+                    recipient,
+                    U256::from(amount),
+                    tx.nonce as u64,
+                    &tx.sender,
+                )
             }
             Chain::Solana => {
-                self.validator_client
-                    .collect_signatures_for_solana_unlock(
-                        &tx.recipient,
-                        tx.amount as u64,
-                        tx.nonce as u64,
-                        &tx.sender,
-                    )
-                    .await?
+                let recipient = Pubkey::from_str(&tx.recipient)
+                    .map_err(|e| RelayerError::ParseError(format!("Invalid recipient pubkey: {}", e)))?;
+
+                // Validators sign with whichever key the program checks
+                let signature_scheme = self.bridge_signature_scheme(&self.bridge_config_account()?).await?;
+                self.validator_client.solana_unlock_request(
+                    &recipient,
+                    &self.solana_token_mint()?,
+                    amount,
+                    tx.nonce as u64,
+                    signature_scheme,
+                )
             }
             Chain::Sui => {
-                return Err(RelayerError::InvalidChain("Sui not implemented".to_string()));
+                return Err(undeliverable(tx.to_chain));
             }
         };
+        request.source_tx_hash = tx.from_tx_hash.clone();
+        self.db.mark_collection_started(tx.id).await?;
+
+        // Resume from whatever an earlier pass already gathered
+        let mut signatures: Vec<ValidatorSignature> = match tx.signatures {
+            Some(ref sig_json) => serde_json::from_str(sig_json)?,
+            None => Vec::new(),
+        };
+        let threshold = self.relayer_config.signature_threshold.max(1);
+        let target = self.relayer_config.target_signatures.unwrap_or(threshold).max(threshold);
+
+        for validator in self.validator_client.outstanding(&request, &signatures) {
+            if signatures.len() >= target {
+                break;
+            }
+
+            match self.validator_client.request_signature(validator, &request).await {
+                Ok(signature) => {
+                    signatures.push(signature);
+                    self.db
+                        .save_partial_signatures(tx.id, &serde_json::to_string(&signatures)?)
+                        .await?;
+                }
+                Err(e) => warn!("No signature from validator {} for nonce {}: {}", validator.name, tx.nonce, e),
+            }
+        }
+
+        if signatures.len() < threshold {
+            return Err(RelayerError::InsufficientSignatures {
+                expected: threshold,
+                got: signatures.len(),
+            });
+        }
+
+        // Enough to submit, but hold out for the target while validators that haven't
+        // signed may still come back within the wait
+        if signatures.len() < target {
+            let started_at = tx.collection_started_at.unwrap_or_else(chrono::Utc::now);
+            let wait = Duration::from_secs(self.relayer_config.target_signatures_wait_secs);
+            let waited = (chrono::Utc::now() - started_at).to_std().unwrap_or_default();
+            let reachable = !self.validator_client.outstanding(&request, &signatures).is_empty();
+            if reachable && waited < wait {
+                info!(
+                    "Nonce {} has {} of {} target signatures, waiting up to {:?} for the rest",
+                    tx.nonce,
+                    signatures.len(),
+                    target,
+                    wait - waited
+                );
+                return Ok(());
+            }
+        }
 
         // Store signatures in database
         let signatures_json = serde_json::to_string(&signatures)
@@ -184,15 +343,80 @@ impl TransactionSubmitter {
             return Err(RelayerError::InvalidSignature("No signatures found".to_string()));
         };
 
-        let tx_hash = match tx.to_chain {
+        if self.relayer_config.dry_run {
+            let outcome = match tx.to_chain {
+                Chain::Ethereum => self.simulate_ethereum(tx, signatures).await?,
+                Chain::Solana => self.simulate_solana(tx, signatures).await?,
+                Chain::Sui => {
+                    return Err(undeliverable(tx.to_chain));
+                }
+            };
+
+            warn!(
+                "DRY RUN: no transaction was broadcast for nonce {} ({})",
+                tx.nonce, outcome
+            );
+
+            self.db
+                .update_transaction_status(
+                    tx.id,
+                    TransactionStatus::SignaturesCollected,
+                    None,
+                    Some(&format!("dry run: {}", outcome)),
+                )
+                .await?;
+
+            return Ok(());
+        }
+
+        // A crash between sending and recording the submission leaves the row here
+        // even though the destination already has it; sending again would only revert
+        if self.destination_processed(tx, false).await? {
+            warn!(
+                "Nonce {} was already processed on {}, skipping to confirmation",
+                tx.nonce, tx.to_chain
+            );
+            self.db
+                .update_transaction_status(tx.id, TransactionStatus::Submitted, None, Some(ALREADY_PROCESSED_NOTE))
+                .await?;
+            return Ok(());
+        }
+
+        let result = match tx.to_chain {
             Chain::Ethereum => {
-                self.submit_to_ethereum(tx, signatures).await?
+                self.submit_to_ethereum(tx, signatures)
+                    .await
+                    .map(|(tx_hash, account_nonce)| (tx_hash, Some(account_nonce)))
             }
             Chain::Solana => {
-                self.submit_to_solana(tx, signatures).await?
+                self.submit_to_solana(tx, signatures).await.map(|tx_hash| (tx_hash, None))
             }
             Chain::Sui => {
-                return Err(RelayerError::InvalidChain("Sui not implemented".to_string()));
+                return Err(undeliverable(tx.to_chain));
+            }
+        };
+
+        let (tx_hash, account_nonce) = match result {
+            Ok(submission) => submission,
+            // Processed since the check above, e.g. by another relayer; nothing left to send
+            Err(RelayerError::TransactionAlreadyProcessed(nonce)) => {
+                warn!("Nonce {} was processed on {} while submitting it", nonce, tx.to_chain);
+                self.db
+                    .update_transaction_status(tx.id, TransactionStatus::Submitted, None, Some(ALREADY_PROCESSED_NOTE))
+                    .await?;
+                return Ok(());
+            }
+            Err(e) => {
+                // Keep the status so the submission is retried, but record why it failed
+                self.db
+                    .update_transaction_status(
+                        tx.id,
+                        TransactionStatus::SignaturesCollected,
+                        None,
+                        Some(&e.to_string()),
+                    )
+                    .await?;
+                return Err(e);
             }
         };
 
@@ -206,6 +430,8 @@ impl TransactionSubmitter {
             )
             .await?;
 
+        self.db.mark_submitted(tx.id, account_nonce, false).await?;
+
         info!("Transaction submitted: {}", tx_hash);
         Ok(())
     }
@@ -215,7 +441,7 @@ impl TransactionSubmitter {
         &self,
         tx: &RelayerTransaction,
         signatures: Vec<ValidatorSignature>,
-    ) -> Result<String> {
+    ) -> Result<(String, u64)> {
         info!("Submitting mint to Ethereum for nonce {}", tx.nonce);
 
     
@@ -224,50 +450,195 @@ impl TransactionSubmitter {
             .as_ref()
             .ok_or_else(|| RelayerError::ConfigError("Ethereum signer not configured".to_string()))?;
 
+        let request = self.build_mint_request(tx, &signatures)?;
+
+        let pricing = self.fetch_gas_pricing().await?;
+        info!("Using gas pricing: {:?}", pricing);
+
+        let from = signer.address();
+        let account_nonce = self
+            .nonce_manager
+            .next(from, || self.fetch_pending_nonce(from))
+            .await?;
+
+        let tx_hash = self
+            .send_ethereum_request(signer, request, pricing, account_nonce, tx.nonce as u64)
+            .await?;
+        Ok((tx_hash, account_nonce))
+    }
+
+    /// Re-send a stuck mint under the same account nonce with bumped fees (replace-by-fee)
+    pub async fn replace_ethereum_transaction(
+        &self,
+        tx: &RelayerTransaction,
+        signatures: Vec<ValidatorSignature>,
+        account_nonce: u64,
+    ) -> Result<String> {
+        let signer = self
+            .relayer_eth_signer
+            .as_ref()
+            .ok_or_else(|| RelayerError::ConfigError("Ethereum signer not configured".to_string()))?;
+
+        let request = self.build_mint_request(tx, &signatures)?;
+        let pricing = self.fetch_gas_pricing().await?.bumped();
+
+        info!(
+            "Replacing Ethereum transaction for nonce {} (account nonce {}) with {:?}",
+            tx.nonce, account_nonce, pricing
+        );
+
+        self.send_ethereum_request(signer, request, pricing, account_nonce, tx.nonce as u64)
+            .await
+    }
 
+    fn build_mint_request(
+        &self,
+        tx: &RelayerTransaction,
+        signatures: &[ValidatorSignature],
+    ) -> Result<TransactionRequest> {
         let recipient = Address::from_str(&tx.recipient)
             .map_err(|e| RelayerError::ParseError(format!("Invalid recipient: {}", e)))?;
-        let amount = U256::from(tx.amount as u64);
+        let amount = U256::from(self.token_decimals().delivered_amount(tx)?);
         let nonce = tx.nonce as u64;
 
   
-        let signature_bytes: Vec<Bytes> = signatures
+        let signature_bytes = signatures
             .iter()
-            .map(|s| Bytes::from(hex::decode(s.signature.trim_start_matches("0x")).unwrap_or_default()))
-            .collect();
+            .map(|s| match s.signature {
+                ChainSignature::Ecdsa { .. } => Ok(Bytes::from(s.signature.to_bytes())),
+                _ => Err(RelayerError::InvalidSignature(format!(
+                    "Expected ECDSA signature from {}",
+                    s.validator_address
+                ))),
+            })
+            .collect::<Result<Vec<Bytes>>>()?;
 
 
+        let bridge_address = self.bridge_address()?;
+
+        let provider = self.ethereum_provider.current();
+        let contract = SolanaBridge::new(bridge_address, &*provider);
+
+    
+        info!(
+            "Calling mintWrapped: recipient={}, amount={}, nonce={}",
+            checksum_address(&recipient),
+            amount,
+            nonce
+        );
+
+        Ok(contract
+            .mintWrapped(
+                recipient,
+                amount,
+                nonce,
+                tx.sender.clone(),
+                signature_bytes,
+            )
+            .into_transaction_request())
+    }
+
+    fn bridge_address(&self) -> Result<Address> {
+        Address::from_str(&self.ethereum_config.bridge_contract)
+            .map_err(|e| RelayerError::ConfigError(format!("Invalid bridge address: {}", e)))
+    }
+
+    async fn send_ethereum_request(
+        &self,
+        signer: &PrivateKeySigner,
+        request: TransactionRequest,
+        pricing: GasPricing,
+        account_nonce: u64,
+        transfer_nonce: u64,
+    ) -> Result<String> {
+        let from = signer.address();
+
         let wallet = EthereumWallet::from(signer.clone());
         let provider = ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(wallet)
             .on_http(
-                self.ethereum_config
-                    .rpc_url
+                self.ethereum_provider
+                    .current_url()
                     .parse()
                     .map_err(|e| RelayerError::ConfigError(format!("Invalid RPC URL: {:?}", e)))?,
             );
 
+        let request = pricing
+            .apply(request)
+            .with_from(from)
+            .with_nonce(account_nonce);
 
-        let bridge_address = Address::from_str(&self.ethereum_config.bridge_contract)
-            .map_err(|e| RelayerError::ConfigError(format!("Invalid bridge address: {}", e)))?;
+        let sent = with_timeout(self.retry.timeout, "Ethereum send_transaction", provider.send_transaction(request))
+            .await
+            .and_then(|sent| sent.map_err(|e| mint_error(&e, transfer_nonce)));
 
+        match sent {
+            Ok(pending) => Ok(format!("{:?}", pending.tx_hash())),
+            Err(e) => {
+                warn!("Ethereum send failed, resyncing account nonce for {}", from);
+                self.nonce_manager.resync(from).await;
+                Err(e)
+            }
+        }
+    }
 
-        let contract = SolanaBridge::new(bridge_address, &provider);
+    async fn fetch_pending_nonce(&self, address: Address) -> Result<u64> {
+        retry_with_backoff(&self.retry, "Ethereum get_transaction_count", || async {
+            self.ethereum_provider
+                .call(|provider| async move { provider.get_transaction_count(address).pending().await })
+                .await
+        })
+        .await
+    }
 
-    
-        info!("Calling mintWrapped: recipient={}, amount={}, nonce={}", recipient, amount, nonce);
+    /// Query the provider for current fees and build the pricing for the next submission
+    async fn fetch_gas_pricing(&self) -> Result<GasPricing> {
+        let base_fee = if supports_eip1559(self.ethereum_config.chain_id) {
+            let history = retry_with_backoff(&self.retry, "Ethereum get_fee_history", || {
+                self.ethereum_provider.call(|provider| async move {
+                    provider
+                        .get_fee_history(1, BlockNumberOrTag::Latest, &[])
+                        .await
+                })
+            })
+            .await?;
+            history.next_block_base_fee()
+        } else {
+            None
+        };
 
-        let _call = contract.mintWrapped(
-            recipient,
-            amount,
-            nonce,
-            tx.sender.clone(),
-            signature_bytes,
-        );
+        let gas_price = if base_fee.is_none() {
+            retry_with_backoff(&self.retry, "Ethereum get_gas_price", || {
+                self.ethereum_provider
+                    .call(|provider| async move { provider.get_gas_price().await })
+            })
+            .await?
+        } else {
+            0
+        };
+
+        let priority_fee = gwei_to_wei(self.ethereum_config.max_priority_fee_gwei);
+        if let Some(ceiling_gwei) = self.ethereum_config.max_gas_price_gwei {
+            let price = going_gas_price(base_fee, gas_price, self.relayer_config.gas_price_multiplier, priority_fee);
+            if price > gwei_to_wei(ceiling_gwei) {
+                let price_gwei = price as f64 / 1_000_000_000.0;
+                warn!(
+                    "Gas price {:.2} gwei is above the {} gwei ceiling, deferring submission",
+                    price_gwei, ceiling_gwei
+                );
+                return Err(RelayerError::GasPriceAboveCeiling { price_gwei, ceiling_gwei });
+            }
+        }
 
-        warn!("Ethereum transaction submission placeholder");
-        Ok(format!("0x{}", hex::encode(&tx.nonce.to_le_bytes())))
+        Ok(GasPricing::compute(
+            self.ethereum_config.chain_id,
+            base_fee,
+            gas_price,
+            self.relayer_config.gas_price_multiplier,
+            priority_fee,
+            gwei_to_wei(self.ethereum_config.max_fee_per_gas_gwei),
+        ))
     }
 
 
@@ -278,31 +649,231 @@ impl TransactionSubmitter {
     ) -> Result<String> {
         info!("Submitting unlock to Solana for nonce {}", tx.nonce);
 
-        let _keypair = self
+        let keypair = self
             .relayer_sol_keypair
             .as_ref()
             .ok_or_else(|| RelayerError::ConfigError("Solana keypair not configured".to_string()))?;
 
-        let _user_pubkey = Pubkey::from_str(&tx.recipient)
+        let transaction = self
+            .build_solana_unlock_transaction(tx, &signatures, keypair)
+            .await?;
+
+        let transaction = &transaction;
+        let signature = with_timeout(
+            self.retry.timeout,
+            "Solana send_transaction",
+            self.solana_client
+                .call(move |client| async move { client.send_transaction(transaction).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::TransactionSubmissionFailed(format!("unlock failed: {}", describe_solana_error(&e))))?;
+
+        Ok(signature.to_string())
+    }
+
+    /// The mint Ethereum -> Solana transfers are unlocked in, which the unlock message names
+    fn solana_token_mint(&self) -> Result<Pubkey> {
+        self.solana_config
+            .token_mint
+            .as_deref()
+            .ok_or_else(|| RelayerError::ConfigError("Solana token mint not configured".to_string()))
+            .and_then(|key| {
+                Pubkey::from_str(key).map_err(|e| RelayerError::ConfigError(format!("Invalid token mint: {}", e)))
+            })
+    }
+
+    /// The program's `BridgeConfig` account, which unlocks name and carry the signature scheme of
+    fn bridge_config_account(&self) -> Result<Pubkey> {
+        self.solana_config
+            .bridge_config_account
+            .as_deref()
+            .ok_or_else(|| RelayerError::ConfigError("Solana bridge config account not configured".to_string()))
+            .and_then(|key| {
+                Pubkey::from_str(key)
+                    .map_err(|e| RelayerError::ConfigError(format!("Invalid bridge config account: {}", e)))
+            })
+    }
+
+    /// `BridgeConfig.signature_scheme` at `bridge_config`: which signatures the unlock carries
+    async fn bridge_signature_scheme(&self, bridge_config: &Pubkey) -> Result<u8> {
+        let bridge_config = *bridge_config;
+        let data = retry_with_backoff(&self.retry, "Solana get_account_data", || {
+            self.solana_client
+                .call(move |client| async move { client.get_account_data(&bridge_config).await })
+        })
+        .await?;
+
+        let config = BridgeConfig::load(&data)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid bridge config data: {}", e)))?;
+
+        Ok(config.signature_scheme)
+    }
+
+    async fn build_solana_unlock_transaction(
+        &self,
+        tx: &RelayerTransaction,
+        signatures: &[ValidatorSignature],
+        keypair: &Keypair,
+    ) -> Result<Transaction> {
+        let user = Pubkey::from_str(&tx.recipient)
             .map_err(|e| RelayerError::ParseError(format!("Invalid recipient pubkey: {}", e)))?;
 
-        let _program_id = Pubkey::from_str(&self.solana_config.bridge_program_id)
+        let program_id = Pubkey::from_str(&self.solana_config.bridge_program_id)
             .map_err(|e| RelayerError::ParseError(format!("Invalid program ID: {}", e)))?;
 
+        let bridge_config = self.bridge_config_account()?;
+
+        let token_mint = self.solana_token_mint()?;
+
+        let vault_pda = vault_pda(&program_id, &bridge_config);
+        let user_bridge_state = user_bridge_state_address(&program_id, &user, tx.nonce as u64);
+        let vault_token_account = derive_vault_ata(&program_id, &bridge_config, &token_mint);
+        let user_token_account = derive_user_ata(&user, &token_mint);
+
         info!("Creating unlock instruction for nonce {}", tx.nonce);
 
-        let mut _sig_bytes = Vec::new();
-        for sig in &signatures {
-            let sig_data = hex::decode(sig.signature.trim_start_matches("0x"))
-                .unwrap_or_default();
-            _sig_bytes.extend_from_slice(&sig_data);
+        // Both schemes check the same message the validators were asked to sign in
+        // `collect_signatures`
+        let amount = self.token_decimals().delivered_amount(tx)?;
+        let message = create_unlock_message(tx.nonce as u64, &user, &token_mint, amount);
+        let mut instructions = Vec::with_capacity(2);
+        let sig_bytes = match self.bridge_signature_scheme(&bridge_config).await? {
+            // The program checks Ed25519 signatures via a preceding Ed25519Program instruction
+            SIGNATURE_SCHEME_ED25519 => {
+                let signers = ed25519_signers(signatures)?;
+                instructions.push(BridgeInstruction::create_ed25519_verify_instruction(&message, &signers));
+                signers.into_iter().map(|(_, signature)| signature).collect()
+            }
+            // and recovers secp256k1 signers itself, so those go in as they are
+            SIGNATURE_SCHEME_SECP256K1 => secp256k1_signatures(signatures)?,
+            scheme => {
+                return Err(RelayerError::ConfigError(format!(
+                    "Bridge config {} uses unknown signature scheme {}",
+                    bridge_config, scheme
+                )))
+            }
+        };
+
+        let instruction = BridgeInstruction::create_unlock_tokens_instruction(
+            &program_id,
+            &keypair.pubkey(),
+            &user,
+            &user_token_account,
+            &vault_token_account,
+            &vault_pda,
+            &user_bridge_state,
+            &bridge_config,
+            tx.nonce as u64,
+            sig_bytes,
+        );
+
+        let recent_blockhash = retry_with_backoff(&self.retry, "Solana get_latest_blockhash", || {
+            self.solana_client
+                .call(|client| async move { client.get_latest_blockhash().await })
+        })
+        .await?;
+
+        instructions.push(instruction);
+        Ok(Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&keypair.pubkey()),
+            &[keypair],
+            recent_blockhash,
+        ))
+    }
+
+    /// Dry run: `eth_call` + `eth_estimateGas` the mint instead of broadcasting it
+    async fn simulate_ethereum(
+        &self,
+        tx: &RelayerTransaction,
+        signatures: Vec<ValidatorSignature>,
+    ) -> Result<String> {
+        let mut request = self.build_mint_request(tx, &signatures)?;
+        if let Some(signer) = self.relayer_eth_signer.as_ref() {
+            request = request.with_from(signer.address());
+        }
+
+        let request = &request;
+        if let Err(e) = with_timeout(
+            self.retry.timeout,
+            "Ethereum call",
+            self.ethereum_provider
+                .call(move |provider| async move { provider.call(request).await }),
+        )
+        .await?
+        {
+            return Ok(format!("mintWrapped would fail: {}", mint_error(&e, tx.nonce as u64)));
         }
 
-        let mut _instruction_data = vec![2u8];
-        _instruction_data.extend_from_slice(&(tx.nonce as u64).to_le_bytes());
+        let gas = with_timeout(
+            self.retry.timeout,
+            "Ethereum estimate_gas",
+            self.ethereum_provider
+                .call(move |provider| async move { provider.estimate_gas(request).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::EthereumRpcError(format!("Failed to estimate gas: {}", e)))?;
 
-        warn!("Solana transaction submission placeholder");
-        Ok(format!("solana_tx_{}", tx.nonce))
+        Ok(format!("mintWrapped call succeeded, estimated gas {}", gas))
+    }
+
+    /// Run `tx`'s `mintWrapped` as an `eth_call` with the signatures stored on it. Fails with
+    /// the decoded revert when the bridge would reject it; nothing is broadcast.
+    pub async fn check_mint(&self, tx: &RelayerTransaction) -> Result<()> {
+        let signatures: Vec<ValidatorSignature> = match tx.signatures {
+            Some(ref sig_json) => serde_json::from_str(sig_json)?,
+            None => return Err(RelayerError::InvalidSignature("No signatures found".to_string())),
+        };
+        let mut request = self.build_mint_request(tx, &signatures)?;
+        if let Some(signer) = self.relayer_eth_signer.as_ref() {
+            request = request.with_from(signer.address());
+        }
+
+        let request = &request;
+        with_timeout(
+            self.retry.timeout,
+            "Ethereum call",
+            self.ethereum_provider
+                .call(move |provider| async move { provider.call(request).await }),
+        )
+        .await?
+        .map_err(|e| mint_error(&e, tx.nonce as u64))?;
+        Ok(())
+    }
+
+    /// Dry run: `simulateTransaction` the unlock instead of sending it
+    async fn simulate_solana(
+        &self,
+        tx: &RelayerTransaction,
+        signatures: Vec<ValidatorSignature>,
+    ) -> Result<String> {
+        let keypair = self
+            .relayer_sol_keypair
+            .as_ref()
+            .ok_or_else(|| RelayerError::ConfigError("Solana keypair not configured".to_string()))?;
+
+        let transaction = self
+            .build_solana_unlock_transaction(tx, &signatures, keypair)
+            .await?;
+
+        let transaction = &transaction;
+        let result = with_timeout(
+            self.retry.timeout,
+            "Solana simulate_transaction",
+            self.solana_client
+                .call(move |client| async move { client.simulate_transaction(transaction).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to simulate unlock: {}", e)))?
+        .value;
+
+        Ok(match result.err {
+            Some(err) => format!("unlock would fail: {:?}", err),
+            None => format!(
+                "unlock simulation succeeded, {} compute units",
+                result.units_consumed.unwrap_or_default()
+            ),
+        })
     }
 
 
@@ -310,58 +881,1458 @@ impl TransactionSubmitter {
         info!("Checking confirmation for nonce {}", tx.nonce);
 
         if let Some(ref tx_hash) = tx.to_tx_hash {
-            let is_confirmed = match tx.to_chain {
-                Chain::Ethereum => self.check_ethereum_confirmation(tx_hash).await?,
-                Chain::Solana => self.check_solana_confirmation(tx_hash).await?,
-                Chain::Sui => {
-                    return Err(RelayerError::InvalidChain("Sui not implemented".to_string()));
+            match self.destination_status(tx, tx_hash).await? {
+                Confirmation::Confirmed => {
+                    info!("Transaction {} confirmed!", tx.nonce);
+                    self.db
+                        .update_transaction_status(tx.id, TransactionStatus::Confirmed, Some(tx_hash), None)
+                        .await?;
                 }
-            };
+                Confirmation::Reverted(reason) => {
+                    error!("Transaction {} reverted on destination: {}", tx.nonce, reason);
+                    self.db
+                        .update_transaction_status(tx.id, TransactionStatus::Failed, Some(tx_hash), Some(&reason))
+                        .await?;
+                }
+                Confirmation::Pending if self.submission_timed_out(tx) => {
+                    self.handle_submission_timeout(tx).await?;
+                }
+                Confirmation::Pending => {
+                    info!("Transaction {} not yet confirmed", tx.nonce);
+                }
+            }
+        } else {
+            self.check_processed_confirmation(tx).await?;
+        }
 
-            if is_confirmed {
-                info!("Transaction {} confirmed!", tx.nonce);
-                self.db
-                    .update_transaction_status(tx.id, TransactionStatus::Confirmed, None, None)
+        Ok(())
+    }
+
+    fn submission_timed_out(&self, tx: &RelayerTransaction) -> bool {
+        let timeout = Duration::from_secs(self.relayer_config.submission_timeout_secs);
+        match tx.submitted_at {
+            Some(submitted_at) if !timeout.is_zero() => {
+                (chrono::Utc::now() - submitted_at).to_std().unwrap_or_default() >= timeout
+            }
+            _ => false,
+        }
+    }
+
+    /// The destination transaction hasn't landed within `submission_timeout_secs`, most likely
+    /// because it was dropped from the mempool. Re-broadcast it (Ethereum: a replacement at the
+    /// same account nonce with bumped fees; Solana: a fresh blockhash), or give up and fail the
+    /// transfer once it has been re-broadcast `max_retries` times.
+    async fn handle_submission_timeout(&self, tx: &RelayerTransaction) -> Result<()> {
+        // Mined but not yet deep enough counts as pending too; that one only needs time
+        if self.destination_processed(tx, false).await? {
+            info!("Transaction {} landed and is waiting for confirmations", tx.nonce);
+            return Ok(());
+        }
+
+        if tx.rebroadcasts >= self.relayer_config.max_retries as i64 {
+            let reason = format!("not confirmed after {} re-broadcasts", tx.rebroadcasts);
+            error!("Transaction {} {}, giving up on it", tx.nonce, reason);
+            self.db
+                .update_transaction_status(tx.id, TransactionStatus::Failed, tx.to_tx_hash.as_deref(), Some(&reason))
+                .await?;
+            return Ok(());
+        }
+
+        warn!(
+            "Transaction {} unconfirmed {}s after submission, re-broadcasting it",
+            tx.nonce, self.relayer_config.submission_timeout_secs
+        );
+
+        let signatures: Vec<ValidatorSignature> = match tx.signatures {
+            Some(ref sig_json) => serde_json::from_str(sig_json)?,
+            None => return Err(RelayerError::InvalidSignature("No signatures found".to_string())),
+        };
+
+        let (tx_hash, account_nonce) = match tx.to_chain {
+            Chain::Ethereum => match tx.submission_account_nonce {
+                Some(account_nonce) => {
+                    let account_nonce = account_nonce as u64;
+                    let tx_hash = self.replace_ethereum_transaction(tx, signatures, account_nonce).await?;
+                    (tx_hash, Some(account_nonce))
+                }
+                // Submitted before account nonces were recorded, so there is nothing to replace
+                None => {
+                    let (tx_hash, account_nonce) = self.submit_to_ethereum(tx, signatures).await?;
+                    (tx_hash, Some(account_nonce))
+                }
+            },
+            Chain::Solana => (self.submit_to_solana(tx, signatures).await?, None),
+            Chain::Sui => return Err(undeliverable(tx.to_chain)),
+        };
+
+        self.db
+            .update_transaction_status(tx.id, TransactionStatus::Submitted, Some(&tx_hash), None)
+            .await?;
+        self.db.mark_submitted(tx.id, account_nonce, true).await?;
+
+        info!("Transaction re-broadcast: {}", tx_hash);
+        Ok(())
+    }
+
+    /// Confirm a transfer that was found already processed before submission. There
+    /// is no transaction hash to follow, so go by the destination's nonce record.
+    async fn check_processed_confirmation(&self, tx: &RelayerTransaction) -> Result<()> {
+        if self.destination_processed(tx, true).await? {
+            info!("Transaction {} confirmed!", tx.nonce);
+            self.db
+                .update_transaction_status(tx.id, TransactionStatus::Confirmed, None, Some(ALREADY_PROCESSED_NOTE))
+                .await?;
+        } else if self.destination_processed(tx, false).await? {
+            info!("Transaction {} not yet confirmed", tx.nonce);
+        } else {
+            warn!(
+                "Nonce {} is no longer processed on {}, queueing it for submission again",
+                tx.nonce, tx.to_chain
+            );
+            self.db
+                .update_transaction_status(tx.id, TransactionStatus::SignaturesCollected, None, None)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the destination has already applied this transfer's nonce. With
+    /// `settled`, Ethereum is read `confirmations` blocks back instead of at the tip;
+    /// Solana is read at the configured commitment either way.
+    async fn destination_processed(&self, tx: &RelayerTransaction, settled: bool) -> Result<bool> {
+        match tx.to_chain {
+            Chain::Ethereum => {
+                let block = if settled {
+                    let latest = retry_with_backoff(&self.retry, "Ethereum get_block_number", || {
+                        self.ethereum_provider
+                            .call(|provider| async move { provider.get_block_number().await })
+                    })
                     .await?;
-            } else {
-                info!("Transaction {} not yet confirmed", tx.nonce);
+                    BlockId::number(latest.saturating_sub(self.ethereum_config.confirmations.saturating_sub(1)))
+                } else {
+                    BlockId::latest()
+                };
+                self.ethereum_nonce_processed(tx.nonce as u64, block).await
+            }
+            Chain::Solana => self.solana_nonce_processed(tx, None).await,
+            Chain::Sui => Err(undeliverable(tx.to_chain)),
+        }
+    }
+
+    async fn ethereum_nonce_processed(&self, nonce: u64, block: BlockId) -> Result<bool> {
+        let bridge_address = self.bridge_address()?;
+
+        let processed = retry_with_backoff(&self.retry, "Ethereum processedNonces", || {
+            self.ethereum_provider.call(|provider| async move {
+                SolanaBridge::new(bridge_address, &*provider)
+                    .processedNonces(U256::from(nonce))
+                    .block(block)
+                    .call()
+                    .await
+            })
+        })
+        .await?;
+
+        Ok(processed._0)
+    }
+
+    /// The unlock flips `unlocked` on the lock's `UserBridgeState`. Read at `commitment`,
+    /// or the client's own when `None`.
+    async fn solana_nonce_processed(
+        &self,
+        tx: &RelayerTransaction,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<bool> {
+        let user = Pubkey::from_str(&tx.recipient)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid recipient pubkey: {}", e)))?;
+        let program_id = Pubkey::from_str(&self.solana_config.bridge_program_id)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid program ID: {}", e)))?;
+        let address = user_bridge_state_address(&program_id, &user, tx.nonce as u64);
+
+        let account = retry_with_backoff(&self.retry, "Solana get_account", || {
+            self.solana_client.call(|client| async move {
+                client
+                    .get_account_with_commitment(&address, commitment.unwrap_or_else(|| client.commitment()))
+                    .await
+                    .map(|response| response.value)
+            })
+        })
+        .await?;
+
+        let Some(account) = account else {
+            return Ok(false);
+        };
+        let state = UserBridgeState::deserialize(&mut account.data.as_slice())
+            .map_err(|e| RelayerError::ParseError(format!("Invalid user bridge state data: {}", e)))?;
+
+        Ok(state.unlocked)
+    }
+
+    /// Pick up signature collections that have been short of the threshold for
+    /// `collection_stall_secs`, such as ones a crash interrupted, keeping the signatures
+    /// already stored. Returns how many were found.
+    pub async fn resume_stalled_collections(&self) -> Result<usize> {
+        let stall = Duration::from_secs(self.relayer_config.collection_stall_secs);
+        let started_by = chrono::Utc::now() - chrono::Duration::from_std(stall).unwrap_or(chrono::Duration::MAX);
+        let stalled = self.db.get_stalled_collections(started_by).await?;
+
+        for tx in &stalled {
+            let collected = match tx.signatures {
+                Some(ref sig_json) => serde_json::from_str::<Vec<ValidatorSignature>>(sig_json)?.len(),
+                None => 0,
+            };
+            warn!(
+                "Signature collection for nonce {} stalled since {} with {} of {} signatures, resuming",
+                tx.nonce,
+                tx.collection_started_at.unwrap_or(tx.created_at),
+                collected,
+                self.relayer_config.signature_threshold.max(1)
+            );
+            if let Err(e) = self.process_transaction(tx).await {
+                error!("Error resuming collection for nonce {}: {}", tx.nonce, e);
+            }
+        }
+
+        Ok(stalled.len())
+    }
+
+    /// Re-check transfers confirmed within `reverify_window_secs` and demote any that a
+    /// reorg has since turned into a revert. A Solana unlock whose slot was rolled back is
+    /// queued for submission again.
+    pub async fn reverify_confirmed(&self) -> Result<()> {
+        let window = Duration::from_secs(self.relayer_config.reverify_window_secs);
+        let since = chrono::Utc::now() - chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+
+        for tx in self.db.get_confirmed_since(since).await? {
+            let Some(ref tx_hash) = tx.to_tx_hash else {
+                continue;
+            };
+
+            match self.destination_status(&tx, tx_hash).await {
+                Ok(Confirmation::Confirmed) => {}
+                Ok(Confirmation::Reverted(reason)) => {
+                    let message = format!("reverted after confirmation: {}", reason);
+                    error!("Transaction {} {}", tx.nonce, message);
+                    self.db
+                        .update_transaction_status(tx.id, TransactionStatus::Failed, Some(tx_hash), Some(&message))
+                        .await?;
+                }
+                Ok(Confirmation::Pending) if self.solana_unlock_rolled_back(&tx).await? => {
+                    let message = format!("unlock {} rolled back after confirmation", tx_hash);
+                    error!("Transaction {} {}, queueing it for submission again", tx.nonce, message);
+                    self.db
+                        .update_transaction_status(tx.id, TransactionStatus::SignaturesCollected, None, Some(&message))
+                        .await?;
+                }
+                Ok(Confirmation::Pending) => {
+                    warn!(
+                        "Confirmed transaction {} ({}) is no longer at the required depth, possible reorg",
+                        tx.nonce, tx_hash
+                    );
+                }
+                Err(e) => warn!("Could not re-verify transaction {}: {}", tx.nonce, e),
             }
         }
 
         Ok(())
     }
 
+    /// A Solana unlock that has dropped out of `confirm_commitment` was rolled back if the
+    /// lock's `UserBridgeState` no longer shows it unlocked there either
+    async fn solana_unlock_rolled_back(&self, tx: &RelayerTransaction) -> Result<bool> {
+        if tx.to_chain != Chain::Solana {
+            return Ok(false);
+        }
+        Ok(!self.solana_nonce_processed(tx, Some(self.confirm_commitment)).await?)
+    }
+
+    async fn destination_status(&self, tx: &RelayerTransaction, tx_hash: &str) -> Result<Confirmation> {
+        match tx.to_chain {
+            Chain::Ethereum => self.check_ethereum_confirmation(tx_hash).await,
+            Chain::Solana => self.check_solana_confirmation(tx, tx_hash).await,
+            Chain::Sui => Err(undeliverable(tx.to_chain)),
+        }
+    }
 
-    async fn check_ethereum_confirmation(&self, tx_hash: &str) -> Result<bool> {
-        let _tx_hash_bytes = hex::decode(tx_hash.trim_start_matches("0x"))
+    /// Confirmed once the receipt is `confirmations` blocks deep (its own block counts as one)
+    async fn check_ethereum_confirmation(&self, tx_hash: &str) -> Result<Confirmation> {
+        let hash = B256::from_str(tx_hash)
             .map_err(|e| RelayerError::ParseError(format!("Invalid tx hash: {}", e)))?;
 
-        warn!("Ethereum confirmation checking placeholder");
-        Ok(false)
+        let receipt = retry_with_backoff(&self.retry, "Ethereum get_transaction_receipt", || {
+            self.ethereum_provider
+                .call(|provider| async move { provider.get_transaction_receipt(hash).await })
+        })
+        .await?;
+
+        let Some(receipt) = receipt else {
+            info!("Transaction {} not found yet", tx_hash);
+            return Ok(Confirmation::Pending);
+        };
+        let Some(block_number) = receipt.block_number else {
+            return Ok(Confirmation::Pending);
+        };
+        if !receipt.status() {
+            return Ok(Confirmation::Reverted(format!("reverted in block {}", block_number)));
+        }
+
+        let latest = retry_with_backoff(&self.retry, "Ethereum get_block_number", || {
+            self.ethereum_provider
+                .call(|provider| async move { provider.get_block_number().await })
+        })
+        .await?;
+
+        let depth = latest.saturating_sub(block_number) + 1;
+        if depth < self.ethereum_config.confirmations {
+            info!(
+                "Transaction {} has {}/{} confirmations",
+                tx_hash, depth, self.ethereum_config.confirmations
+            );
+            return Ok(Confirmation::Pending);
+        }
+
+        Ok(Confirmation::Confirmed)
     }
 
-    async fn check_solana_confirmation(&self, tx_hash: &str) -> Result<bool> {
+    /// Confirmed once the unlock reaches `confirm_commitment` and the lock's `UserBridgeState`
+    /// shows it unlocked. A transaction that succeeded without unlocking is treated as reverted.
+    async fn check_solana_confirmation(&self, tx: &RelayerTransaction, tx_hash: &str) -> Result<Confirmation> {
 
         let signature = solana_sdk::signature::Signature::from_str(tx_hash)
             .map_err(|e| RelayerError::ParseError(format!("Invalid signature: {}", e)))?;
 
 
-        match self.solana_client.get_signature_status(&signature).await {
-            Ok(Some(status)) => {
-                if let Err(e) = status {
-                    error!("Transaction {} failed: {:?}", tx_hash, e);
-                    return Ok(false);
+        // Searching history keeps unlocks older than the status cache visible to `reverify_confirmed`
+        let commitment = self.confirm_commitment;
+        let status = retry_with_backoff(&self.retry, "Solana get_signature_status", || {
+            self.solana_client.call(|client| async move {
+                client
+                    .get_signature_status_with_commitment_and_history(&signature, commitment, true)
+                    .await
+            })
+        })
+        .await?;
+
+        match status {
+            Some(Err(e)) => {
+                error!("Transaction {} failed: {:?}", tx_hash, e);
+                Ok(Confirmation::Reverted(e.to_string()))
+            }
+            Some(Ok(())) => {
+                if self.solana_nonce_processed(tx, Some(commitment)).await? {
+                    Ok(Confirmation::Confirmed)
+                } else {
+                    warn!("Transaction {} succeeded but nonce {} is still locked", tx_hash, tx.nonce);
+                    Ok(Confirmation::Reverted(format!("succeeded without unlocking nonce {}", tx.nonce)))
                 }
-                Ok(true)
             }
-            Ok(None) => {
+            None => {
                 info!("Transaction {} not found yet", tx_hash);
-                Ok(false)
+                Ok(Confirmation::Pending)
             }
-            Err(e) => {
-                error!("Error checking transaction status: {}", e);
-                Ok(false)
+        }
+    }
+}
+
+/// Validator keys and signatures for an Ed25519 unlock
+fn ed25519_signers(signatures: &[ValidatorSignature]) -> Result<Vec<(Pubkey, [u8; 64])>> {
+    signatures
+        .iter()
+        .map(|sig| {
+            let ChainSignature::Ed25519(sig_data) = sig.signature else {
+                return Err(RelayerError::InvalidSignature(format!(
+                    "Expected Ed25519 signature from {}",
+                    sig.validator_address
+                )));
+            };
+            let validator = Pubkey::from_str(&sig.validator_address).map_err(|e| {
+                RelayerError::InvalidSignature(format!("Invalid validator pubkey {}: {}", sig.validator_address, e))
+            })?;
+            Ok((validator, sig_data))
+        })
+        .collect()
+}
+
+/// Signatures for a secp256k1 unlock, in the EIP-2098 compact form the program recovers:
+/// `r || s` with the recovery id in the top bit of `s`
+fn secp256k1_signatures(signatures: &[ValidatorSignature]) -> Result<Vec<[u8; 64]>> {
+    signatures
+        .iter()
+        .map(|sig| match sig.signature {
+            ChainSignature::Secp256k1(compact) => Ok(compact),
+            _ => Err(RelayerError::InvalidSignature(format!(
+                "Expected compact secp256k1 signature from {}",
+                sig.validator_address
+            ))),
+        })
+        .collect()
+}
+
+pub(crate) fn user_bridge_state_address(program_id: &Pubkey, user: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"bridge", user.as_ref(), &nonce.to_le_bytes()], program_id).0
+}
+
+/// Render a Solana client error, naming the bridge program error behind a `Custom(n)` code
+fn describe_solana_error(err: &ClientError) -> String {
+    match err.get_transaction_error() {
+        Some(TransactionError::InstructionError(index, InstructionError::Custom(code))) => {
+            match BridgeError::from_u32(code) {
+                Some(bridge_error) => {
+                    format!("instruction {} failed: {:?} ({})", index, bridge_error, bridge_error)
+                }
+                None => format!("instruction {} failed: custom program error {}", index, code),
+            }
+        }
+        _ => err.to_string(),
+    }
+}
+
+/// What a `mintWrapped` for transfer `nonce` failed with. Reverts are read from the revert
+/// data the node returns, so common ones come back as errors saying what to fix.
+fn mint_error(err: &TransportError, nonce: u64) -> RelayerError {
+    match err.as_error_resp().and_then(|payload| payload.as_revert_data()) {
+        Some(data) => mint_revert_error(&data, nonce),
+        None => RelayerError::TransactionSubmissionFailed(format!("mintWrapped failed: {}", err)),
+    }
+}
+
+fn mint_revert_error(data: &[u8], nonce: u64) -> RelayerError {
+    if SolanaBridge::EnforcedPause::abi_decode(data, true).is_ok() {
+        return RelayerError::BridgePaused;
+    }
+    if let Ok(revert) = Revert::abi_decode(data, true) {
+        return match revert.reason.as_str() {
+            "Nonce already used" => RelayerError::TransactionAlreadyProcessed(nonce),
+            "Invalid signatures" => {
+                RelayerError::InvalidSignature("mintWrapped rejected the validator signatures".to_string())
+            }
+            "Only bridge can mint" => {
+                RelayerError::ConfigError("the wrapped token does not accept mints from the bridge".to_string())
+            }
+            reason => RelayerError::ContractReverted(reason.to_string()),
+        };
+    }
+    match Panic::abi_decode(data, true) {
+        Ok(panic) => RelayerError::ContractReverted(panic.to_string()),
+        Err(_) => RelayerError::ContractReverted(format!("unrecognized revert data 0x{}", hex::encode(data))),
+    }
+}
+
+/// Chains known not to support EIP-1559 type-2 transactions
+const LEGACY_CHAIN_IDS: &[u64] = &[
+    30, // Rootstock
+    31, // Rootstock testnet
+];
+
+fn supports_eip1559(chain_id: u64) -> bool {
+    !LEGACY_CHAIN_IDS.contains(&chain_id)
+}
+
+fn gwei_to_wei(gwei: f64) -> u128 {
+    (gwei * 1_000_000_000.0) as u128
+}
+
+/// Per-gas price a submission would pay right now, before `max_fee_per_gas_gwei` caps the bid:
+/// the next base fee plus the tip, or the legacy gas price scaled by `gas_price_multiplier`
+fn going_gas_price(
+    base_fee: Option<u128>,
+    legacy_gas_price: u128,
+    gas_price_multiplier: f64,
+    priority_fee: u128,
+) -> u128 {
+    match base_fee {
+        Some(base_fee) => base_fee.saturating_add(priority_fee),
+        None => (legacy_gas_price as f64 * gas_price_multiplier) as u128,
+    }
+}
+
+/// Fee fields to set on an outgoing Ethereum transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasPricing {
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+    Legacy {
+        gas_price: u128,
+    },
+}
+
+impl GasPricing {
+    /// `maxFeePerGas` is `2 * baseFee + tip`, which survives several full blocks of
+    /// base fee growth. Legacy chains (or a provider that reports no base fee) fall
+    /// back to the node's gas price scaled by `gas_price_multiplier`. Both are capped.
+    pub fn compute(
+        chain_id: u64,
+        base_fee: Option<u128>,
+        legacy_gas_price: u128,
+        gas_price_multiplier: f64,
+        priority_fee: u128,
+        cap: u128,
+    ) -> Self {
+        match base_fee {
+            Some(base_fee) if supports_eip1559(chain_id) => {
+                let max_fee_per_gas = base_fee
+                    .saturating_mul(2)
+                    .saturating_add(priority_fee)
+                    .min(cap);
+                GasPricing::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: priority_fee.min(max_fee_per_gas),
+                }
             }
+            _ => {
+                let gas_price = (legacy_gas_price as f64 * gas_price_multiplier) as u128;
+                GasPricing::Legacy {
+                    gas_price: gas_price.min(cap),
+                }
+            }
+        }
+    }
+
+    /// Raise every fee by 12.5% so nodes accept the result as a replacement
+    /// (geth requires at least a 10% bump on both fee fields)
+    pub fn bumped(&self) -> Self {
+        let bump = |fee: u128| fee.saturating_add(fee.div_ceil(8));
+        match *self {
+            GasPricing::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => GasPricing::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+            GasPricing::Legacy { gas_price } => GasPricing::Legacy {
+                gas_price: bump(gas_price),
+            },
+        }
+    }
+
+    pub fn apply(&self, request: TransactionRequest) -> TransactionRequest {
+        match *self {
+            GasPricing::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => request
+                .with_max_fee_per_gas(max_fee_per_gas)
+                .with_max_priority_fee_per_gas(max_priority_fee_per_gas),
+            GasPricing::Legacy { gas_price } => request.with_gas_price(gas_price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        advance_status, collect_mint_signatures, eth_tx_hash, mock_validators, pending_mint, stored_signatures,
+        test_configs, validator_configs, MockEthereumRpc, MockSolanaRpc, MockValidator, TEST_ETH_KEY,
+        TEST_ETH_RECIPIENT,
+    };
+    use alloy::{node_bindings::Anvil, sol_types::SolCall};
+    use bridge_vault::state::BridgeStatus;
+    use std::sync::atomic::Ordering;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    async fn submitter_with_validators(
+        db: &Database,
+        validators: &[MockValidator],
+        threshold: usize,
+    ) -> TransactionSubmitter {
+        let (solana, ethereum, mut relayer) = test_configs("http://127.0.0.1:8899");
+        relayer.signature_threshold = threshold;
+        let client = ValidatorClient::new(validator_configs(validators));
+        TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), client).unwrap()
+    }
+
+    /// Submitter talking to `rpc`, signing with the test key
+    fn submitter_on(rpc: &MockEthereumRpc, db: &Database, dry_run: bool) -> TransactionSubmitter {
+        let (solana, ethereum, mut relayer) = test_configs(rpc.url());
+        relayer.dry_run = dry_run;
+        let mut submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![]))
+                .unwrap();
+        submitter.set_ethereum_signer(TEST_ETH_KEY).unwrap();
+        submitter
+    }
+
+    #[tokio::test]
+    async fn test_collection_resumes_after_validator_outage() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true, false]).await;
+        let submitter = submitter_with_validators(&db, &validators, 3).await;
+
+        // First pass: the third validator is down, the two signatures we got are kept
+        let tx = pending_mint(&db, 11).await;
+        let err = submitter.process_transaction(&tx).await.unwrap_err();
+        assert!(matches!(err, RelayerError::InsufficientSignatures { expected: 3, got: 2 }));
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 11).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(stored_signatures(&tx).len(), 2);
+
+        // Second pass: only the missing validator is asked again
+        validators[2].up.store(true, Ordering::SeqCst);
+        submitter.process_transaction(&tx).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 11).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        let signers: Vec<_> = stored_signatures(&tx).into_iter().map(|s| s.validator_address).collect();
+        assert_eq!(
+            signers,
+            validators.iter().map(|v| v.config.eth_address.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(validators[0].hits(), 1);
+        assert_eq!(validators[1].hits(), 1);
+        assert_eq!(validators[2].hits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_collection_resumes_after_restart() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, false]).await;
+        let tx = pending_mint(&db, 20).await;
+        assert!(tx.collection_started_at.is_none());
+
+        // Collection starts, gets one of two signatures, then the relayer goes down
+        {
+            let submitter = submitter_with_validators(&db, &validators, 2).await;
+            assert!(submitter.process_transaction(&tx).await.is_err());
         }
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 20).await.unwrap().unwrap();
+        let started_at = tx.collection_started_at.expect("collection start is recorded");
+        assert_eq!(stored_signatures(&tx).len(), 1);
+
+        // Not stalled yet under the default threshold
+        let submitter = submitter_with_validators(&db, &validators, 2).await;
+        assert_eq!(submitter.resume_stalled_collections().await.unwrap(), 0);
+
+        // After a restart with the validator back, collection picks up where it left off
+        validators[1].up.store(true, Ordering::SeqCst);
+        let (solana, ethereum, mut relayer) = test_configs("http://127.0.0.1:8899");
+        relayer.signature_threshold = 2;
+        relayer.collection_stall_secs = 0;
+        let client = ValidatorClient::new(validator_configs(&validators));
+        let restarted = TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), client).unwrap();
+        assert_eq!(restarted.resume_stalled_collections().await.unwrap(), 1);
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 20).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(stored_signatures(&tx).len(), 2);
+        assert_eq!(tx.collection_started_at, Some(started_at));
+        assert_eq!(validators[0].hits(), 1, "the signature from before the crash is reused");
+        assert_eq!(validators[1].hits(), 2);
+        assert_eq!(restarted.resume_stalled_collections().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_collection_stops_at_threshold() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true, true]).await;
+        let submitter = submitter_with_validators(&db, &validators, 2).await;
+
+        let tx = pending_mint(&db, 12).await;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 12).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(stored_signatures(&tx).len(), 2);
+        assert_eq!(validators[2].hits(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_collection_prefers_target_over_threshold() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true, true, true, true]).await;
+        let submitter_with_target = |wait_secs| {
+            let (solana, ethereum, mut relayer) = test_configs("http://127.0.0.1:8899");
+            relayer.signature_threshold = 2;
+            relayer.target_signatures = Some(4);
+            relayer.target_signatures_wait_secs = wait_secs;
+            let client = ValidatorClient::new(validator_configs(&validators));
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), client).unwrap()
+        };
+
+        // Everyone is up: collection goes past the threshold and stops at the target
+        let submitter = submitter_with_target(60);
+        let tx = pending_mint(&db, 16).await;
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 16).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(stored_signatures(&tx).len(), 4);
+        assert_eq!(validators[4].hits(), 0);
+
+        // Two validators down: three signatures meet the threshold, but within the wait
+        // the transfer stays pending for the target
+        validators[2].up.store(false, Ordering::SeqCst);
+        validators[3].up.store(false, Ordering::SeqCst);
+        let tx = pending_mint(&db, 17).await;
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 17).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert_eq!(stored_signatures(&tx).len(), 3);
+
+        // One comes back before the wait is over and the target is reached
+        validators[2].up.store(true, Ordering::SeqCst);
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 17).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(stored_signatures(&tx).len(), 4);
+
+        // Once the wait is over the threshold is enough
+        let tx = pending_mint(&db, 18).await;
+        validators[2].up.store(false, Ordering::SeqCst);
+        submitter_with_target(0).process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 18).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(stored_signatures(&tx).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_collection_without_validator_endpoints_is_a_config_error() {
+        let db = Database::new_in_memory().await.unwrap();
+        let mut validators = validator_configs(&mock_validators(&[true, true]).await);
+        for validator in &mut validators {
+            validator.endpoint = None;
+        }
+        let (solana, ethereum, relayer) = test_configs("http://127.0.0.1:8899");
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(validators)).unwrap();
+
+        let tx = pending_mint(&db, 15).await;
+        let err = submitter.process_transaction(&tx).await.unwrap_err();
+        assert!(
+            matches!(err, RelayerError::ConfigError(ref e) if e.starts_with("no validator endpoints configured")),
+            "{:?}",
+            err
+        );
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 15).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Pending);
+        assert!(tx.signatures.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_out_of_bounds_amounts_fail_before_collection() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true]).await;
+        let (solana, mut ethereum, mut relayer) = test_configs("http://127.0.0.1:8899");
+        ethereum.min_transfer_amount = Some(500);
+        ethereum.max_transfer_amount = Some(5_000);
+        relayer.signature_threshold = 2;
+        let client = ValidatorClient::new(validator_configs(&validators));
+        let submitter = TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), client).unwrap();
+
+        for (nonce, amount) in [(16, 499), (17, 5_001), (18, 500), (19, 5_000)] {
+            db.create_transaction(
+                nonce,
+                Chain::Solana,
+                Chain::Ethereum,
+                &crate::testing::sol_tx_signature(nonce),
+                "5XqZXqZXqZ",
+                TEST_ETH_RECIPIENT,
+                amount,
+                0,
+            )
+            .await
+            .unwrap();
+            let tx = db.get_transaction_by_nonce(Chain::Solana, nonce).await.unwrap().unwrap();
+            submitter.process_transaction(&tx).await.unwrap();
+        }
+
+        let below = db.get_transaction_by_nonce(Chain::Solana, 16).await.unwrap().unwrap();
+        assert_eq!(below.status, TransactionStatus::Failed);
+        assert_eq!(
+            below.error_message.as_deref(),
+            Some("Amount 499 is below the Solana -> Ethereum minimum of 500")
+        );
+        let above = db.get_transaction_by_nonce(Chain::Solana, 17).await.unwrap().unwrap();
+        assert_eq!(above.status, TransactionStatus::Failed);
+        assert_eq!(
+            above.error_message.as_deref(),
+            Some("Amount 5001 is above the Solana -> Ethereum maximum of 5000")
+        );
+
+        // The bounds themselves are allowed; only those two were signed
+        for nonce in [18, 19] {
+            let tx = db.get_transaction_by_nonce(Chain::Solana, nonce).await.unwrap().unwrap();
+            assert_eq!(tx.status, TransactionStatus::SignaturesCollected, "nonce {}", nonce);
+        }
+        assert_eq!(validators[0].hits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_transfer_is_not_collected() {
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true]).await;
+        let submitter = submitter_with_validators(&db, &validators, 2).await;
+        let now = chrono::Utc::now().timestamp();
+
+        for (nonce, expires_at) in [(13, now - 1), (14, now + 3_600)] {
+            db.create_expiring_transaction(
+                nonce,
+                Chain::Solana,
+                Chain::Ethereum,
+                &crate::testing::sol_tx_signature(nonce),
+                "5XqZXqZXqZ",
+                crate::testing::TEST_ETH_RECIPIENT,
+                1_000,
+                0,
+                Some(expires_at),
+            )
+            .await
+            .unwrap();
+        }
+
+        let expired = db.get_transaction_by_nonce(Chain::Solana, 13).await.unwrap().unwrap();
+        submitter.process_transaction(&expired).await.unwrap();
+        let expired = db.get_transaction_by_nonce(Chain::Solana, 13).await.unwrap().unwrap();
+        assert_eq!(expired.status, TransactionStatus::Failed);
+        assert!(expired.error_message.unwrap().starts_with("Expired at"));
+        assert_eq!(validators[0].hits(), 0);
+
+        let live = db.get_transaction_by_nonce(Chain::Solana, 14).await.unwrap().unwrap();
+        submitter.process_transaction(&live).await.unwrap();
+        let live = db.get_transaction_by_nonce(Chain::Solana, 14).await.unwrap().unwrap();
+        assert_eq!(live.status, TransactionStatus::SignaturesCollected);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_transfer_walks_from_pending_to_confirmed() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let validators = mock_validators(&[true, true]).await;
+        let (solana, ethereum, mut relayer) = test_configs(rpc.url());
+        relayer.dry_run = false;
+        let client = ValidatorClient::new(validator_configs(&validators));
+        let mut submitter = TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), client).unwrap();
+        submitter.set_ethereum_signer(TEST_ETH_KEY).unwrap();
+
+        let mut tx = pending_mint(&db, 41).await;
+        let mut statuses = vec![tx.status];
+        for _ in 0..3 {
+            submitter.process_transaction(&tx).await.unwrap();
+            tx = db.get_transaction_by_nonce(Chain::Solana, 41).await.unwrap().unwrap();
+            statuses.push(tx.status);
+        }
+        // The mint is mined straight away but needs 12 confirmations
+        assert_eq!(
+            statuses,
+            vec![
+                TransactionStatus::Pending,
+                TransactionStatus::SignaturesCollected,
+                TransactionStatus::Submitted,
+                TransactionStatus::Submitted,
+            ]
+        );
+        assert!(rpc.sent_transaction());
+        let mint_hash = tx.to_tx_hash.clone().unwrap();
+
+        rpc.chain().latest_block += 11;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 41).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+        assert_eq!(tx.to_tx_hash, Some(mint_hash));
+        assert_eq!(stored_signatures(&tx).len(), 2);
+        assert!(tx.error_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_broadcast() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let submitter = submitter_on(&rpc, &db, true);
+
+        let tx = pending_mint(&db, 9).await;
+        let tx = collect_mint_signatures(&db, &tx, 1).await;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        assert!(rpc.calls().iter().any(|m| m == "eth_call"));
+        assert!(!rpc.sent_transaction());
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 9).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert!(tx.to_tx_hash.is_none());
+        assert!(tx.error_message.unwrap().starts_with("dry run: "));
+    }
+
+    #[tokio::test]
+    async fn test_gas_above_ceiling_defers_submission() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let mut submitter = submitter_on(&rpc, &db, false);
+        submitter.ethereum_config.max_gas_price_gwei = Some(50.0);
+        rpc.chain().base_fee = 80 * GWEI;
+
+        let tx = pending_mint(&db, 17).await;
+        let tx = collect_mint_signatures(&db, &tx, 1).await;
+        let err = submitter.process_transaction(&tx).await.unwrap_err();
+        assert!(matches!(err, RelayerError::GasPriceAboveCeiling { ceiling_gwei, .. } if ceiling_gwei == 50.0));
+        assert!(!rpc.sent_transaction());
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 17).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert!(tx.to_tx_hash.is_none());
+        assert!(tx.error_message.unwrap().contains("ceiling"));
+
+        // Once fees come back down the same transfer goes out on the next pass
+        rpc.chain().base_fee = 20 * GWEI;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        assert!(rpc.sent_transaction());
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 17).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+        assert!(tx.error_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resubmission_after_crash_is_skipped() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let submitter = submitter_on(&rpc, &db, false);
+
+        // The mint went out, then the relayer died before recording it
+        rpc.chain().nonce_processed = true;
+        let tx = pending_mint(&db, 31).await;
+        let tx = collect_mint_signatures(&db, &tx, 1).await;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 31).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+        assert!(tx.to_tx_hash.is_none());
+
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 31).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+        assert_eq!(tx.error_message.as_deref(), Some(ALREADY_PROCESSED_NOTE));
+
+        assert!(!rpc.sent_transaction());
+        assert!(!rpc.calls().iter().any(|m| m == "eth_getTransactionCount"));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_submission_is_rebroadcast_after_timeout() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let submitter = submitter_on(&rpc, &db, false);
+        let sends = || rpc.calls().iter().filter(|m| *m == "eth_sendRawTransaction").count();
+
+        let tx = pending_mint(&db, 51).await;
+        let tx = collect_mint_signatures(&db, &tx, 1).await;
+        submitter.process_transaction(&tx).await.unwrap();
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 51).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+        assert!(tx.submitted_at.is_some());
+        assert_eq!(tx.submission_account_nonce, Some(0));
+        assert_eq!(tx.rebroadcasts, 0);
+        let first_hash = tx.to_tx_hash.clone().unwrap();
+
+        // The mint falls out of the mempool: no receipt, nonce never processed
+        rpc.chain().receipts.clear();
+        submitter.process_transaction(&tx).await.unwrap();
+        assert_eq!(sends(), 1);
+
+        let mut stale = tx.clone();
+        stale.submitted_at = Some(chrono::Utc::now() - chrono::Duration::seconds(601));
+        submitter.process_transaction(&stale).await.unwrap();
+        assert_eq!(sends(), 2);
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 51).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+        assert_ne!(tx.to_tx_hash.as_deref(), Some(first_hash.as_str()));
+        // A replacement at the same account nonce, not a second mint
+        assert_eq!(tx.submission_account_nonce, Some(0));
+        assert_eq!(rpc.calls().iter().filter(|m| *m == "eth_getTransactionCount").count(), 1);
+        assert_eq!(tx.rebroadcasts, 1);
+        assert!(tx.submitted_at.unwrap() > stale.submitted_at.unwrap());
+
+        // Out of re-broadcasts, it is failed for an operator to requeue
+        rpc.chain().receipts.clear();
+        let mut stale = tx.clone();
+        stale.submitted_at = Some(chrono::Utc::now() - chrono::Duration::seconds(601));
+        stale.rebroadcasts = submitter.relayer_config.max_retries as i64;
+        submitter.process_transaction(&stale).await.unwrap();
+        assert_eq!(sends(), 2);
+
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 51).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Failed);
+        assert!(tx.error_message.unwrap().contains("re-broadcasts"));
+    }
+
+    #[test]
+    fn test_mainnet_request_carries_eip1559_fields() {
+        let pricing = GasPricing::compute(1, Some(30 * GWEI), 0, 1.2, 2 * GWEI, 200 * GWEI);
+        assert_eq!(
+            pricing,
+            GasPricing::Eip1559 {
+                max_fee_per_gas: 62 * GWEI,
+                max_priority_fee_per_gas: 2 * GWEI,
+            }
+        );
+
+        let request = pricing.apply(TransactionRequest::default());
+        assert_eq!(request.max_fee_per_gas, Some(62 * GWEI));
+        assert_eq!(request.max_priority_fee_per_gas, Some(2 * GWEI));
+        assert_eq!(request.gas_price, None);
+    }
+
+    #[test]
+    fn test_max_fee_is_capped() {
+        let pricing = GasPricing::compute(1, Some(150 * GWEI), 0, 1.2, 2 * GWEI, 200 * GWEI);
+        assert_eq!(
+            pricing,
+            GasPricing::Eip1559 {
+                max_fee_per_gas: 200 * GWEI,
+                max_priority_fee_per_gas: 2 * GWEI,
+            }
+        );
+    }
+
+    #[test]
+    fn test_replacement_bumps_fees() {
+        let pricing = GasPricing::Eip1559 {
+            max_fee_per_gas: 80 * GWEI,
+            max_priority_fee_per_gas: 2 * GWEI,
+        };
+        assert_eq!(
+            pricing.bumped(),
+            GasPricing::Eip1559 {
+                max_fee_per_gas: 90 * GWEI,
+                max_priority_fee_per_gas: 2_250_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_legacy_chain_uses_gas_price() {
+        let pricing = GasPricing::compute(30, Some(30 * GWEI), 10 * GWEI, 1.5, 2 * GWEI, 200 * GWEI);
+        assert_eq!(pricing, GasPricing::Legacy { gas_price: 15 * GWEI });
+
+        let request = pricing.apply(TransactionRequest::default());
+        assert_eq!(request.gas_price, Some(15 * GWEI));
+        assert_eq!(request.max_fee_per_gas, None);
+    }
+
+    #[test]
+    fn test_describe_solana_error_names_bridge_error() {
+        let err = ClientError::from(TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(BridgeError::ThresholdNotMet as u32),
+        ));
+        let message = describe_solana_error(&err);
+        assert!(message.contains("ThresholdNotMet"), "{}", message);
+        assert!(message.contains("instruction 1"));
+
+        let unknown = ClientError::from(TransactionError::InstructionError(0, InstructionError::Custom(9_999)));
+        assert!(describe_solana_error(&unknown).contains("custom program error 9999"));
+    }
+
+    #[test]
+    fn test_mint_reverts_map_to_actionable_errors() {
+        let revert = |reason: &str| Revert { reason: reason.to_string() }.abi_encode();
+
+        assert!(matches!(
+            mint_revert_error(&SolanaBridge::EnforcedPause {}.abi_encode(), 7),
+            RelayerError::BridgePaused
+        ));
+        assert!(matches!(
+            mint_revert_error(&revert("Nonce already used"), 7),
+            RelayerError::TransactionAlreadyProcessed(7)
+        ));
+        assert!(matches!(mint_revert_error(&revert("Invalid signatures"), 7), RelayerError::InvalidSignature(_)));
+        assert!(matches!(mint_revert_error(&revert("Only bridge can mint"), 7), RelayerError::ConfigError(_)));
+
+        let err = mint_revert_error(&revert("Amount must be > 0"), 7);
+        assert_eq!(err.to_string(), "Destination contract reverted: Amount must be > 0");
+        let overflow = Panic { code: U256::from(0x11) }.abi_encode();
+        assert!(matches!(mint_revert_error(&overflow, 7), RelayerError::ContractReverted(_)));
+        let err = mint_revert_error(&[0xde, 0xad, 0xbe, 0xef], 7);
+        assert!(err.to_string().contains("0xdeadbeef"), "{}", err);
+    }
+
+    /// Init code for a stand-in bridge that answers `processedNonces` with false and reverts
+    /// every other call with `EnforcedPause()`, as a paused `SolanaBridge` does
+    fn paused_bridge_init_code() -> Vec<u8> {
+        // selector = calldata[0..4]; jump to the return below when it is `processedNonces`
+        let mut runtime = vec![0x60, 0x00, 0x35, 0x60, 0xe0, 0x1c, 0x63];
+        runtime.extend(SolanaBridge::processedNoncesCall::SELECTOR);
+        runtime.extend([0x14, 0x60, 0x1f, 0x57]);
+        // revert(EnforcedPause.selector)
+        runtime.push(0x63);
+        runtime.extend(SolanaBridge::EnforcedPause::SELECTOR);
+        runtime.extend([0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, 0x60, 0x04, 0x60, 0x00, 0xfd]);
+        // return a zero word
+        runtime.extend([0x5b, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+
+        // Copy the runtime code after this 11-byte prefix into memory and return it
+        let mut code = vec![0x60, runtime.len() as u8, 0x80, 0x60, 0x0b, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3];
+        code.extend(runtime);
+        code
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil from Foundry on PATH"]
+    async fn test_paused_bridge_revert_is_recorded_on_the_transfer() {
+        let anvil = Anvil::new().spawn();
+        let key = anvil.keys()[0].clone();
+        let deployer = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(PrivateKeySigner::from(key.clone())))
+            .on_http(anvil.endpoint_url());
+        let deploy = TransactionRequest::default().with_deploy_code(paused_bridge_init_code());
+        let receipt = deployer.send_transaction(deploy).await.unwrap().get_receipt().await.unwrap();
+        let bridge = receipt.contract_address.expect("deployment creates a contract");
+
+        let db = Database::new_in_memory().await.unwrap();
+        let (solana, mut ethereum, mut relayer) = test_configs(&anvil.endpoint());
+        ethereum.chain_id = anvil.chain_id();
+        ethereum.bridge_contract = bridge.to_string();
+        relayer.dry_run = false;
+        let mut submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+        submitter.set_ethereum_signer(&hex::encode(key.to_bytes())).unwrap();
+
+        let tx = pending_mint(&db, 30).await;
+        let tx = collect_mint_signatures(&db, &tx, 2).await;
+        let err = submitter.process_transaction(&tx).await.unwrap_err();
+        assert!(matches!(err, RelayerError::BridgePaused), "{:?}", err);
+
+        // Left to be retried once the bridge is unpaused, with the reason on the row
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 30).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert!(tx.to_tx_hash.is_none());
+        assert_eq!(tx.error_message.as_deref(), Some("Destination bridge is paused"));
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_waits_for_depth_and_demotes_reorged_revert() {
+        let rpc = MockEthereumRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let submitter = submitter_on(&rpc, &db, true);
+
+        let mint_hash = B256::repeat_byte(0x42);
+        rpc.chain().receipts.insert(mint_hash, (100, true));
+        let tx = pending_mint(&db, 21).await;
+        advance_status(&db, tx.id, TransactionStatus::Submitted, Some(&mint_hash.to_string())).await;
+
+        // Mined, but 1 of 12 confirmations
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 21).await.unwrap().unwrap();
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 21).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+
+        rpc.chain().latest_block = 111;
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 21).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+        assert_eq!(tx.to_tx_hash, Some(mint_hash.to_string()));
+
+        // Still fine on re-verification
+        submitter.reverify_confirmed().await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 21).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+
+        // A reorg re-includes the mint in another block, where it reverts
+        {
+            let mut chain = rpc.chain();
+            chain.latest_block = 112;
+            chain.receipts.insert(mint_hash, (102, false));
+        }
+        submitter.reverify_confirmed().await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Solana, 21).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Failed);
+        assert!(tx.error_message.unwrap().starts_with("reverted after confirmation"));
+    }
+
+    /// Ethereum-to-Solana transfer row for `nonce`, paying out to `user`
+    async fn pending_unlock(db: &Database, nonce: u64, user: &Pubkey) -> RelayerTransaction {
+        db.create_transaction(
+            nonce,
+            Chain::Ethereum,
+            Chain::Solana,
+            &eth_tx_hash(nonce),
+            TEST_ETH_RECIPIENT,
+            &user.to_string(),
+            1_000,
+            0,
+        )
+        .await
+        .unwrap();
+        db.get_transaction_by_nonce(Chain::Ethereum, nonce).await.unwrap().unwrap()
+    }
+
+    /// Serve `user`'s `UserBridgeState` for `tx` from `rpc`, with `unlocked` as given
+    /// Store a `BridgeConfig` using `signature_scheme` and return its address
+    fn set_bridge_config(rpc: &MockSolanaRpc, signature_scheme: u8) -> Pubkey {
+        let config = BridgeConfig {
+            admin: Pubkey::new_unique(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: vec![],
+            validator_threshold: 1,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme,
+            chain_fee_basis_points: [BridgeConfig::CHAIN_FEE_UNSET; BridgeConfig::CHAIN_FEE_SLOTS],
+        };
+        let address = Pubkey::new_unique();
+        let mut data = borsh::to_vec(&config).unwrap();
+        data.resize(BridgeConfig::LEN, 0);
+        rpc.chain().accounts.insert(address, data);
+        address
+    }
+
+    fn set_user_bridge_state(
+        rpc: &MockSolanaRpc,
+        submitter: &TransactionSubmitter,
+        tx: &RelayerTransaction,
+        unlocked: bool,
+    ) {
+        let user = Pubkey::from_str(&tx.recipient).unwrap();
+        let program_id = Pubkey::from_str(&submitter.solana_config.bridge_program_id).unwrap();
+        let state = UserBridgeState {
+            user,
+            locked_amount: tx.amount as u64,
+            token_mint: Pubkey::new_unique(),
+            destination_chain: 1,
+            destination_address: [0; 32],
+            status: if unlocked { BridgeStatus::Completed } else { BridgeStatus::Pending },
+            nonce: tx.nonce as u64,
+            timestamp: 0,
+            unlocked,
+            expiry: i64::MAX,
+            remaining_amount: if unlocked { 0 } else { tx.amount as u64 },
+        };
+        rpc.chain().accounts.insert(
+            user_bridge_state_address(&program_id, &user, tx.nonce as u64),
+            borsh::to_vec(&state).unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unlock_targets_the_configured_program_id() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (mut solana, ethereum, relayer) = test_configs(rpc.url());
+        // A localnet deployment under an id other than the compiled-in one
+        let program_id = Pubkey::new_unique();
+        assert_ne!(program_id, bridge_vault::ID);
+        solana.bridge_program_id = program_id.to_string();
+        solana.bridge_config_account = Some(set_bridge_config(&rpc, SIGNATURE_SCHEME_ED25519).to_string());
+        solana.token_mint = Some(Pubkey::new_unique().to_string());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+
+        let user = Pubkey::new_unique();
+        let tx = pending_unlock(&db, 61, &user).await;
+        let signature = ValidatorSignature {
+            validator_address: Pubkey::new_unique().to_string(),
+            signature: ChainSignature::Ed25519([7; 64]),
+            signed_at: chrono::Utc::now(),
+        };
+        let transaction = submitter
+            .build_solana_unlock_transaction(&tx, &[signature], &Keypair::new())
+            .await
+            .unwrap();
+
+        let message = &transaction.message;
+        let unlock = &message.instructions[1];
+        assert_eq!(message.account_keys[unlock.program_id_index as usize], program_id);
+        assert!(message.account_keys.contains(&user_bridge_state_address(&program_id, &user, 61)));
+    }
+
+    #[tokio::test]
+    async fn test_unlock_verifies_the_message_validators_signed() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (mut solana, ethereum, relayer) = test_configs(rpc.url());
+        let mint = Pubkey::new_unique();
+        solana.bridge_config_account = Some(set_bridge_config(&rpc, SIGNATURE_SCHEME_ED25519).to_string());
+        solana.token_mint = Some(mint.to_string());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+
+        let user = Pubkey::new_unique();
+        let tx = pending_unlock(&db, 62, &user).await;
+        let request =
+            submitter.validator_client.solana_unlock_request(&user, &mint, 1_000, 62, SIGNATURE_SCHEME_ED25519);
+        let message_hash: [u8; 32] = hex::decode(request.message_hash.trim_start_matches("0x"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(message_hash, create_unlock_message(62, &user, &mint, 1_000));
+
+        let validator = Keypair::new();
+        let signature = ValidatorSignature {
+            validator_address: validator.pubkey().to_string(),
+            signature: ChainSignature::Ed25519(validator.sign_message(&message_hash).into()),
+            signed_at: chrono::Utc::now(),
+        };
+        let transaction = submitter
+            .build_solana_unlock_transaction(&tx, &[signature], &Keypair::new())
+            .await
+            .unwrap();
+
+        // The Ed25519Program instruction carries the message it checks the signature against
+        let verify = &transaction.message.instructions[0];
+        assert!(verify.data.windows(32).any(|window| window == message_hash));
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_unlock_carries_compact_signatures() {
+        use crate::validator_client::ValidatorService;
+        use solana_sdk::{keccak, secp256k1_recover::secp256k1_recover};
+
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (mut solana, ethereum, relayer) = test_configs(rpc.url());
+        let mint = Pubkey::new_unique();
+        solana.bridge_config_account = Some(set_bridge_config(&rpc, SIGNATURE_SCHEME_SECP256K1).to_string());
+        solana.token_mint = Some(mint.to_string());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+
+        let user = Pubkey::new_unique();
+        let tx = pending_unlock(&db, 63, &user).await;
+        let message_hash = create_unlock_message(63, &user, &mint, 1_000);
+        let validator = PrivateKeySigner::from_str(TEST_ETH_KEY).unwrap();
+        let service = ValidatorService::new(validator.clone(), Keypair::new());
+        let signed = service.sign_for_solana_secp256k1(message_hash).await.unwrap();
+        let signature = ValidatorSignature {
+            validator_address: checksum_address(&validator.address()),
+            signature: ChainSignature::parse(Chain::Solana, SIGNATURE_SCHEME_SECP256K1, &signed).unwrap(),
+            signed_at: chrono::Utc::now(),
+        };
+        let transaction = submitter
+            .build_solana_unlock_transaction(&tx, &[signature], &Keypair::new())
+            .await
+            .unwrap();
+
+        // No Ed25519Program instruction: the unlock is the only one, and the program
+        // recovers the validator's address from the compact signature it carries
+        assert_eq!(transaction.message.instructions.len(), 1);
+        let BridgeInstruction::UnlockTokens { nonce, signatures } =
+            BridgeInstruction::unpack(&transaction.message.instructions[0].data).unwrap()
+        else {
+            panic!("Expected an unlock instruction");
+        };
+        assert_eq!(nonce, 63);
+        let mut compact = signatures[0];
+        let recovery_id = compact[32] >> 7;
+        compact[32] &= 0x7f;
+        let key = secp256k1_recover(&message_hash, recovery_id, &compact).unwrap();
+        assert_eq!(&keccak::hash(&key.to_bytes()).0[12..], validator.address().as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_solana_confirmation_waits_for_confirm_commitment() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (mut solana, ethereum, relayer) = test_configs(rpc.url());
+        solana.monitor_commitment = Some("confirmed".to_string());
+        solana.confirm_commitment = Some("finalized".to_string());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+        let tx = pending_unlock(&db, 31, &Pubkey::new_unique()).await;
+        set_user_bridge_state(&rpc, &submitter, &tx, true);
+
+        let unlock = solana_sdk::signature::Signature::new_unique();
+        let unlock_hash = unlock.to_string();
+        assert_eq!(submitter.check_solana_confirmation(&tx, &unlock_hash).await.unwrap(), Confirmation::Pending);
+
+        // Confirmed is enough for the monitor, but not for the submitter
+        rpc.chain().statuses.insert(unlock, "confirmed".to_string());
+        assert_eq!(submitter.check_solana_confirmation(&tx, &unlock_hash).await.unwrap(), Confirmation::Pending);
+
+        rpc.chain().statuses.insert(unlock, "finalized".to_string());
+        assert_eq!(submitter.check_solana_confirmation(&tx, &unlock_hash).await.unwrap(), Confirmation::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_solana_success_without_unlock_is_not_confirmed() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (solana, ethereum, relayer) = test_configs(rpc.url());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+
+        let noop = solana_sdk::signature::Signature::new_unique();
+        let unlocked = solana_sdk::signature::Signature::new_unique();
+        rpc.chain().statuses.insert(noop, "finalized".to_string());
+        rpc.chain().statuses.insert(unlocked, "finalized".to_string());
+
+        // Landed, but the lock's state account was never created
+        let missing = pending_unlock(&db, 32, &Pubkey::new_unique()).await;
+        assert!(matches!(
+            submitter.check_solana_confirmation(&missing, &noop.to_string()).await.unwrap(),
+            Confirmation::Reverted(_)
+        ));
+
+        // Landed, but left the lock in place
+        let still_locked = pending_unlock(&db, 33, &Pubkey::new_unique()).await;
+        set_user_bridge_state(&rpc, &submitter, &still_locked, false);
+        assert!(matches!(
+            submitter.check_solana_confirmation(&still_locked, &noop.to_string()).await.unwrap(),
+            Confirmation::Reverted(_)
+        ));
+
+        // A genuine unlock flips the flag
+        set_user_bridge_state(&rpc, &submitter, &still_locked, true);
+        assert_eq!(
+            submitter.check_solana_confirmation(&still_locked, &unlocked.to_string()).await.unwrap(),
+            Confirmation::Confirmed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rolled_back_solana_unlock_is_queued_again() {
+        let rpc = MockSolanaRpc::start().await;
+        let db = Database::new_in_memory().await.unwrap();
+        let (solana, ethereum, relayer) = test_configs(rpc.url());
+        let submitter =
+            TransactionSubmitter::new(solana, ethereum, relayer, db.clone(), ValidatorClient::new(vec![])).unwrap();
+        let tx = pending_unlock(&db, 34, &Pubkey::new_unique()).await;
+        set_user_bridge_state(&rpc, &submitter, &tx, true);
+
+        let unlock = solana_sdk::signature::Signature::new_unique();
+        advance_status(&db, tx.id, TransactionStatus::Submitted, Some(&unlock.to_string())).await;
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 34).await.unwrap().unwrap();
+
+        // Not rooted yet, so not confirmed by default
+        rpc.chain().statuses.insert(unlock, "confirmed".to_string());
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 34).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Submitted);
+
+        rpc.chain().statuses.insert(unlock, "finalized".to_string());
+        submitter.process_transaction(&tx).await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 34).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+
+        // Still there on re-verification
+        submitter.reverify_confirmed().await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 34).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::Confirmed);
+
+        // The slot is rolled back: the unlock is gone and the lock is back in place
+        rpc.chain().statuses.remove(&unlock);
+        set_user_bridge_state(&rpc, &submitter, &tx, false);
+        submitter.reverify_confirmed().await.unwrap();
+        let tx = db.get_transaction_by_nonce(Chain::Ethereum, 34).await.unwrap().unwrap();
+        assert_eq!(tx.status, TransactionStatus::SignaturesCollected);
+        assert_eq!(tx.to_tx_hash, None);
+        assert!(tx.error_message.unwrap().contains("rolled back after confirmation"));
+    }
+}
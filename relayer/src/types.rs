@@ -1,7 +1,33 @@
+use crate::amount::Amount;
+use crate::error::{RelayerError, Result};
+use alloy::primitives::Address;
+use bridge_vault::state::{SIGNATURE_SCHEME_ED25519, SIGNATURE_SCHEME_SECP256K1};
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use solana_sdk::signature::Signature;
+use std::io;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+/// EIP-55 mixed-case form of an Ethereum address. Every address the relayer
+/// stores or hands to a validator goes through this so the strings agree.
+pub fn checksum_address(address: &Address) -> String {
+    address.to_checksum(None)
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    sqlx::Type,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "PascalCase")]
 pub enum Chain {
     Solana,
@@ -19,6 +45,36 @@ impl std::fmt::Display for Chain {
     }
 }
 
+impl Chain {
+    /// Canonical spelling of a transaction id on this chain, so the same transaction is
+    /// always stored the same way: `0x` + lowercase hex for Ethereum, base58 for Solana.
+    /// Ids that are malformed for the chain are rejected.
+    pub fn normalize_tx_hash(self, tx_hash: &str) -> Result<String> {
+        let tx_hash = tx_hash.trim();
+        match self {
+            Chain::Ethereum => {
+                let digits = tx_hash
+                    .strip_prefix("0x")
+                    .or_else(|| tx_hash.strip_prefix("0X"))
+                    .unwrap_or(tx_hash);
+                if digits.len() != 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(RelayerError::ParseError(format!("Invalid Ethereum transaction hash {:?}", tx_hash)));
+                }
+                Ok(format!("0x{}", digits.to_ascii_lowercase()))
+            }
+            Chain::Solana => Signature::from_str(tx_hash)
+                .map(|signature| signature.to_string())
+                .map_err(|e| RelayerError::ParseError(format!("Invalid Solana signature {:?}: {}", tx_hash, e))),
+            Chain::Sui => {
+                if tx_hash.is_empty() || tx_hash.contains(char::is_whitespace) {
+                    return Err(RelayerError::ParseError(format!("Invalid Sui digest {:?}", tx_hash)));
+                }
+                Ok(tx_hash.to_string())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BridgeEvent {
     TokensLocked {
@@ -26,9 +82,14 @@ pub enum BridgeEvent {
         to_chain: Chain,
         sender: String,
         recipient: String,
+        /// Net of `fee`, i.e. what the recipient receives
         amount: u64,
+        /// Bridge fee the program kept when the tokens were locked
+        fee: u64,
         nonce: u64,
         tx_hash: String,
+        /// Unix time the source chain stops accepting the unlock, if it has one
+        expires_at: Option<i64>,
     },
     TokensBurned {
         from_chain: Chain,
@@ -79,6 +140,8 @@ pub enum TransactionStatus {
     Submitted,
     Confirmed,
     Failed,
+    /// The user reclaimed a Solana lock with `RefundLock` before it was bridged
+    Refunded,
 }
 
 impl std::fmt::Display for TransactionStatus {
@@ -89,10 +152,54 @@ impl std::fmt::Display for TransactionStatus {
             TransactionStatus::Submitted => write!(f, "Submitted"),
             TransactionStatus::Confirmed => write!(f, "Confirmed"),
             TransactionStatus::Failed => write!(f, "Failed"),
+            TransactionStatus::Refunded => write!(f, "Refunded"),
+        }
+    }
+}
+
+impl TransactionStatus {
+    /// No further status change is expected without operator action
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Confirmed | TransactionStatus::Failed | TransactionStatus::Refunded
+        )
+    }
+
+    /// Whether a transfer in this status may be moved to `next`. Transfers move one step
+    /// at a time along Pending -> SignaturesCollected -> Submitted -> Confirmed, and
+    /// anything but a refunded transfer can fail.
+    pub fn can_transition_to(&self, next: TransactionStatus) -> bool {
+        use TransactionStatus::*;
+
+        match (*self, next) {
+            (Refunded, _) => false,
+            (_, Failed) => true,
+            // The source chain gave the tokens back, whatever the relayer had recorded;
+            // keeping that visible is what lets reconciliation and alerts see it
+            (_, Refunded) => true,
+            // Same step again, with a new note or error
+            (Pending, Pending) | (SignaturesCollected, SignaturesCollected) | (Submitted, Submitted) => true,
+            (Pending, SignaturesCollected) => true,
+            (SignaturesCollected, Submitted) => true,
+            (Submitted, Confirmed) => true,
+            // The destination no longer has a submission, so it is queued again
+            (Submitted, SignaturesCollected) => true,
+            // A Solana rollback undid the unlock after it was confirmed
+            (Confirmed, SignaturesCollected) => true,
+            _ => false,
         }
     }
 }
 
+/// Published by `Database` whenever a transfer's status is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusUpdate {
+    pub from_chain: Chain,
+    pub nonce: u64,
+    pub status: TransactionStatus,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct RelayerTransaction {
     pub id: i64,
@@ -103,17 +210,329 @@ pub struct RelayerTransaction {
     pub to_tx_hash: Option<String>,
     pub sender: String,
     pub recipient: String,
+    /// Net amount delivered to the recipient
     pub amount: i64,
+    /// Amount the sender put in, before the bridge fee
+    pub gross_amount: i64,
+    pub fee_amount: i64,
     pub status: TransactionStatus,
     pub signatures: Option<String>,
     pub error_message: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Unix time after which the source chain only allows a refund (Solana locks)
+    pub expires_at: Option<i64>,
+    /// Ethereum bridge contract that emitted the burn; `None` for other sources
+    pub source_contract: Option<String>,
+    /// When the relayer first asked validators to sign this transfer
+    pub collection_started_at: Option<DateTime<Utc>>,
+    /// `amount` with the decimals of the source chain's token when it was recorded;
+    /// `None` when none were configured (see `TokenDecimals::net_amount`)
+    pub net_amount: Option<Amount>,
+    /// When the current destination transaction was broadcast
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// Ethereum sender nonce of the current destination transaction
+    pub submission_account_nonce: Option<i64>,
+    /// Times the submission was re-broadcast after going unconfirmed
+    pub rebroadcasts: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl RelayerTransaction {
+    /// Whether the lock behind this transfer can no longer be delivered at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ValidatorSignature {
     pub validator_address: String,
-    pub signature: String,
+    pub signature: ChainSignature,
     pub signed_at: DateTime<Utc>,
 }
+
+/// A validator signature in the scheme its destination chain checks.
+///
+/// Serialized as 0x-hex of `to_bytes()`; the length, plus a tag byte on compact
+/// secp256k1, tells the schemes apart, so stored signature sets keep their format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainSignature {
+    /// secp256k1 signature checked by the Ethereum bridge contract
+    Ecdsa { r: [u8; 32], s: [u8; 32], v: u8 },
+    /// Ed25519 signature checked by the Solana program
+    Ed25519([u8; 64]),
+    /// EIP-2098 compact secp256k1 signature checked by the Solana program under
+    /// `SIGNATURE_SCHEME_SECP256K1`
+    Secp256k1([u8; 64]),
+}
+
+/// Trailing byte that marks a 65-byte encoding as compact secp256k1; never a valid recovery id
+const SECP256K1_COMPACT_TAG: u8 = 0xff;
+
+impl ChainSignature {
+    /// Parse a hex signature meant for `chain`, rejecting the wrong length or a bad recovery id.
+    /// On Solana the kind must also match the bridge's `signature_scheme`.
+    pub fn parse(chain: Chain, signature_scheme: u8, hex_signature: &str) -> Result<Self> {
+        let signature = Self::from_hex(hex_signature)?;
+        let accepted = match signature {
+            ChainSignature::Ecdsa { .. } => chain == Chain::Ethereum,
+            ChainSignature::Ed25519(_) => chain == Chain::Solana && signature_scheme == SIGNATURE_SCHEME_ED25519,
+            ChainSignature::Secp256k1(_) => chain == Chain::Solana && signature_scheme == SIGNATURE_SCHEME_SECP256K1,
+        };
+        if !accepted {
+            return Err(RelayerError::InvalidSignature(format!(
+                "{} signature cannot be used on {} with signature scheme {}",
+                signature.kind(),
+                chain,
+                signature_scheme
+            )));
+        }
+        Ok(signature)
+    }
+
+    /// Compact form of a secp256k1 `r || s || v` signature: `r || s` with the recovery id in
+    /// the top bit of `s`. High-s signatures have no compact form and are rejected.
+    pub fn compact_secp256k1(r: [u8; 32], s: [u8; 32], v: u8) -> Result<Self> {
+        if s[0] & 0x80 != 0 {
+            return Err(RelayerError::InvalidSignature(
+                "High-s secp256k1 signature has no compact form".to_string(),
+            ));
+        }
+        let mut compact = [0u8; 64];
+        compact[..32].copy_from_slice(&r);
+        compact[32..].copy_from_slice(&s);
+        compact[32] |= (v % 27) << 7;
+        Ok(ChainSignature::Secp256k1(compact))
+    }
+
+    fn from_hex(hex_signature: &str) -> Result<Self> {
+        let bytes = hex::decode(hex_signature.trim_start_matches("0x"))
+            .map_err(|e| RelayerError::InvalidSignature(format!("Invalid signature hex: {}", e)))?;
+        Self::from_bytes(bytes)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        match bytes.len() {
+            65 if bytes[64] == SECP256K1_COMPACT_TAG => Ok(ChainSignature::Secp256k1(bytes[..64].try_into().unwrap())),
+            65 => {
+                let v = bytes[64];
+                if !matches!(v, 0 | 1 | 27 | 28) {
+                    return Err(RelayerError::InvalidSignature(format!("Invalid recovery id {}", v)));
+                }
+                Ok(ChainSignature::Ecdsa {
+                    r: bytes[..32].try_into().unwrap(),
+                    s: bytes[32..64].try_into().unwrap(),
+                    v,
+                })
+            }
+            64 => Ok(ChainSignature::Ed25519(bytes.try_into().unwrap())),
+            len => Err(RelayerError::InvalidSignature(format!(
+                "Expected a 65-byte secp256k1 or 64-byte Ed25519 signature, got {} bytes",
+                len
+            ))),
+        }
+    }
+
+    /// Chain whose verifier accepts this kind of signature
+    pub fn chain(&self) -> Chain {
+        match self {
+            ChainSignature::Ecdsa { .. } => Chain::Ethereum,
+            ChainSignature::Ed25519(_) | ChainSignature::Secp256k1(_) => Chain::Solana,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ChainSignature::Ecdsa { .. } => "ECDSA",
+            ChainSignature::Ed25519(_) => "Ed25519",
+            ChainSignature::Secp256k1(_) => "Compact secp256k1",
+        }
+    }
+
+    /// `r || s || v` for ECDSA, the raw 64 bytes for Ed25519, and the compact
+    /// bytes followed by a tag byte for compact secp256k1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            ChainSignature::Ecdsa { r, s, v } => [&r[..], &s[..], &[*v]].concat(),
+            ChainSignature::Ed25519(bytes) => bytes.to_vec(),
+            ChainSignature::Secp256k1(compact) => [&compact[..], &[SECP256K1_COMPACT_TAG]].concat(),
+        }
+    }
+}
+
+impl std::fmt::Display for ChainSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl Serialize for ChainSignature {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainSignature {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let hex_signature = String::deserialize(deserializer)?;
+        Self::from_hex(&hex_signature).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Borsh form is `to_bytes()` as a length-prefixed byte vector, mirroring the hex encoding
+impl BorshSerialize for ChainSignature {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.to_bytes().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for ChainSignature {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let bytes = Vec::<u8>::deserialize_reader(reader)?;
+        Self::from_bytes(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+/// `signed_at` travels as milliseconds since the epoch
+impl BorshSerialize for ValidatorSignature {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.validator_address.serialize(writer)?;
+        self.signature.serialize(writer)?;
+        self.signed_at.timestamp_millis().serialize(writer)
+    }
+}
+
+impl BorshDeserialize for ValidatorSignature {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let validator_address = String::deserialize_reader(reader)?;
+        let signature = ChainSignature::deserialize_reader(reader)?;
+        let millis = i64::deserialize_reader(reader)?;
+        let signed_at = DateTime::from_timestamp_millis(millis).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("signed_at {} out of range", millis))
+        })?;
+
+        Ok(ValidatorSignature {
+            validator_address,
+            signature,
+            signed_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_address_matches_eip55_vectors() {
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = Address::from_str(&expected.to_lowercase()).unwrap();
+            assert_eq!(checksum_address(&address), expected);
+        }
+    }
+
+    #[test]
+    fn test_legal_status_transitions() {
+        use TransactionStatus::*;
+
+        for (from, to) in [
+            (Pending, SignaturesCollected),
+            (SignaturesCollected, Submitted),
+            (Submitted, Confirmed),
+            (SignaturesCollected, SignaturesCollected),
+            (Submitted, SignaturesCollected),
+            (Confirmed, SignaturesCollected),
+            (Pending, Failed),
+            (Submitted, Failed),
+            (Confirmed, Failed),
+            (Pending, Refunded),
+            (Failed, Refunded),
+        ] {
+            assert!(from.can_transition_to(to), "{} -> {} should be allowed", from, to);
+        }
+    }
+
+    #[test]
+    fn test_illegal_status_transitions() {
+        use TransactionStatus::*;
+
+        for (from, to) in [
+            (Confirmed, Pending),
+            (Confirmed, Submitted),
+            (Confirmed, Confirmed),
+            (SignaturesCollected, Pending),
+            (Submitted, Pending),
+            (Failed, Pending),
+            (Failed, Confirmed),
+            (Pending, Submitted),
+            (Pending, Confirmed),
+            (SignaturesCollected, Confirmed),
+            (Refunded, Pending),
+            (Refunded, Confirmed),
+            (Refunded, Refunded),
+            (Refunded, Failed),
+        ] {
+            assert!(!from.can_transition_to(to), "{} -> {} should be rejected", from, to);
+        }
+    }
+
+    #[test]
+    fn test_chain_signature_round_trips_and_rejects_malformed() {
+        let ecdsa = ChainSignature::Ecdsa { r: [1; 32], s: [2; 32], v: 27 };
+        let ed25519 = ChainSignature::Ed25519([3; 64]);
+        let secp256k1 = ChainSignature::Secp256k1([4; 64]);
+
+        for (chain, scheme, signature) in [
+            (Chain::Ethereum, SIGNATURE_SCHEME_ED25519, ecdsa),
+            (Chain::Solana, SIGNATURE_SCHEME_ED25519, ed25519),
+            (Chain::Solana, SIGNATURE_SCHEME_SECP256K1, secp256k1),
+        ] {
+            let hex_signature = signature.to_string();
+            assert_eq!(ChainSignature::parse(chain, scheme, &hex_signature).unwrap(), signature);
+            assert_eq!(signature.chain(), chain);
+
+            let json = serde_json::to_string(&signature).unwrap();
+            assert_eq!(json, format!("\"{}\"", hex_signature));
+            assert_eq!(serde_json::from_str::<ChainSignature>(&json).unwrap(), signature);
+        }
+        assert_eq!(ecdsa.to_bytes().len(), 65);
+        assert_eq!(ecdsa.to_bytes()[64], 27);
+
+        // Truncated
+        let truncated = format!("0x{}", hex::encode(&ecdsa.to_bytes()[..63]));
+        assert!(ChainSignature::parse(Chain::Ethereum, SIGNATURE_SCHEME_ED25519, &truncated).is_err());
+        assert!(serde_json::from_str::<ChainSignature>(&format!("\"{}\"", truncated)).is_err());
+        assert!(ChainSignature::parse(Chain::Ethereum, SIGNATURE_SCHEME_ED25519, "0x").is_err());
+        // Not hex
+        assert!(ChainSignature::parse(Chain::Solana, SIGNATURE_SCHEME_ED25519, &"zz".repeat(64)).is_err());
+        // Bad recovery id
+        let bad_recovery_id = format!("0x{}", "11".repeat(65));
+        assert!(ChainSignature::parse(Chain::Ethereum, SIGNATURE_SCHEME_ED25519, &bad_recovery_id).is_err());
+        // Right scheme, wrong chain
+        assert!(ChainSignature::parse(Chain::Solana, SIGNATURE_SCHEME_ED25519, &ecdsa.to_string()).is_err());
+        assert!(ChainSignature::parse(Chain::Ethereum, SIGNATURE_SCHEME_ED25519, &ed25519.to_string()).is_err());
+        // Right chain, wrong signature scheme
+        assert!(ChainSignature::parse(Chain::Solana, SIGNATURE_SCHEME_SECP256K1, &ed25519.to_string()).is_err());
+        assert!(ChainSignature::parse(Chain::Solana, SIGNATURE_SCHEME_ED25519, &secp256k1.to_string()).is_err());
+    }
+
+    #[test]
+    fn test_compact_secp256k1_folds_recovery_id_into_s() {
+        let compact = |v| match ChainSignature::compact_secp256k1([1; 32], [2; 32], v).unwrap() {
+            ChainSignature::Secp256k1(compact) => compact,
+            other => panic!("expected a compact signature, got {:?}", other),
+        };
+
+        assert_eq!(compact(27)[32], 0x02);
+        assert_eq!(compact(0)[32], 0x02);
+        assert_eq!(compact(28)[32], 0x82);
+        assert_eq!(compact(1)[32], 0x82);
+        assert_eq!(compact(27)[..32], [1; 32]);
+        assert!(ChainSignature::compact_secp256k1([1; 32], [0x80; 32], 27).is_err());
+    }
+}
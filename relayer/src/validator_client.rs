@@ -1,137 +1,555 @@
 use crate::{
-    config::ValidatorConfig,
+    amount::TokenDecimals,
+    config::{ValidatorAuthScheme, ValidatorConfig},
     error::{RelayerError, Result},
-    types::{Chain, ValidatorSignature},
+    ethereum_monitor::SolanaBridge,
+    failover::{EthereumRpc, SolanaRpc},
+    retry::{with_timeout, DEFAULT_RPC_TIMEOUT},
+    routing::undeliverable,
+    solana_monitor::SolanaMonitor,
+    types::{checksum_address, BridgeEvent, Chain, ChainSignature, ValidatorSignature},
+    wire::{self, WireFormat, BORSH_CONTENT_TYPE},
+};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::sol_types::SolEvent;
+use alloy::signers::{local::PrivateKeySigner, Signature as AlloySignature, Signer};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use bridge_vault::{
+    processor::create_unlock_message,
+    state::{BridgeConfig, SIGNATURE_SCHEME_ED25519, SIGNATURE_SCHEME_SECP256K1},
 };
-use alloy::primitives::{Address, Bytes, U256};
-use alloy::signers::{Signature as AlloySignature, Signer};
 use chrono::Utc;
 use secp256k1::ecdsa::Signature as Secp256k1Signature;
-use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer as _},
+};
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
 use std::str::FromStr;
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How long a single validator gets to answer a signature request
+const VALIDATOR_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Consecutive failed requests after which a validator is skipped
+pub const DEFAULT_VALIDATOR_FAILURE_THRESHOLD: u32 = 3;
+/// How long a failing validator is skipped before it gets another try
+pub const DEFAULT_VALIDATOR_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Body POSTed to a validator's `/sign-ethereum` or `/sign-solana` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SignatureRequest {
+    pub chain: Chain,
+    pub nonce: u64,
+    /// 0x-prefixed hex of the 32-byte message hash
+    pub message_hash: String,
+    /// Transaction on the source chain the validator checks before signing
+    #[serde(default)]
+    pub source_tx_hash: String,
+    /// Bridge `signature_scheme` a Solana unlock is signed under; unused for Ethereum
+    #[serde(default)]
+    pub signature_scheme: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureResponse {
+    pub signature: String,
+}
+
+/// Body POSTed to a validator's `/sign-batch` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BatchSignatureRequest {
+    pub requests: Vec<SignatureRequest>,
+}
+
+/// Signatures keyed by the `message_hash` they sign; requests the validator
+/// refused are left out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSignatureResponse {
+    pub signatures: HashMap<String, String>,
+}
+
+/// [`BatchSignatureResponse`] in the Borsh wire format, one `(message_hash, signature)` per signed request
+#[derive(Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct BorshBatchSignatureResponse {
+    pub signatures: Vec<(String, ValidatorSignature)>,
+}
+
+impl From<BorshBatchSignatureResponse> for BatchSignatureResponse {
+    fn from(response: BorshBatchSignatureResponse) -> Self {
+        BatchSignatureResponse {
+            signatures: response
+                .signatures
+                .into_iter()
+                .map(|(message_hash, signature)| (message_hash, signature.signature.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Header used to present an `ApiKey` token
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header name and value carrying `token` under `scheme`
+fn auth_header(scheme: ValidatorAuthScheme, token: &str) -> (&'static str, String) {
+    match scheme {
+        ValidatorAuthScheme::Bearer => ("authorization", format!("Bearer {}", token)),
+        ValidatorAuthScheme::ApiKey => (API_KEY_HEADER, token.to_string()),
+    }
+}
+
+/// Differences between the relayer's `[[validators]]` and `BridgeConfig.validators`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidatorSetMismatch {
+    /// Configured validators whose `sol_public_key` is missing or not on-chain, as `name (key)`
+    pub unknown: Vec<String>,
+    /// On-chain validators no configured validator has as its `sol_public_key`
+    pub unconfigured: Vec<Pubkey>,
+}
+
+pub fn compare_validator_sets(configured: &[ValidatorConfig], on_chain: &[Pubkey]) -> ValidatorSetMismatch {
+    let configured_keys: HashSet<Pubkey> = configured
+        .iter()
+        .filter_map(|v| Pubkey::from_str(&v.sol_public_key).ok())
+        .collect();
+
+    ValidatorSetMismatch {
+        unknown: configured
+            .iter()
+            .filter(|v| Pubkey::from_str(&v.sol_public_key).map_or(true, |key| !on_chain.contains(&key)))
+            .map(|v| format!("{} ({})", v.name, v.sol_public_key))
+            .collect(),
+        unconfigured: on_chain.iter().filter(|key| !configured_keys.contains(key)).copied().collect(),
+    }
+}
 
-// TODO: Implement actual HTTP requests to validator endpoints
-// TODO: Implement ECDSA and Ed25519 signing
+/// Failure tracking for one validator. Once `failure_threshold` requests in a row
+/// fail the circuit opens and the validator is skipped until `open_until`; the
+/// next request after that either closes it again or reopens it.
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
 
 pub struct ValidatorClient {
     validators: Vec<ValidatorConfig>,
+    http: reqwest::Client,
+    /// Keyed by validator name
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+    /// Validators that answered `/sign-batch` with 404/405, asked per item from then on
+    batch_unsupported: Mutex<HashSet<String>>,
+    wire_format: WireFormat,
+    /// Validators that answered a Borsh body with 415, sent JSON from then on
+    borsh_unsupported: Mutex<HashSet<String>>,
 }
 
 impl ValidatorClient {
     pub fn new(validators: Vec<ValidatorConfig>) -> Self {
-        Self { validators }
+        let http = reqwest::Client::builder()
+            .timeout(VALIDATOR_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            validators,
+            http,
+            breakers: Mutex::new(HashMap::new()),
+            failure_threshold: DEFAULT_VALIDATOR_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_VALIDATOR_COOLDOWN,
+            batch_unsupported: Mutex::new(HashSet::new()),
+            wire_format: WireFormat::default(),
+            borsh_unsupported: Mutex::new(HashSet::new()),
+        }
     }
 
-    /// Request signatures from validators for a Solana -> Ethereum transfer
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Encode signature requests as `format`; validators that don't accept it get JSON
+    pub fn with_wire_format(mut self, format: WireFormat) -> Self {
+        self.wire_format = format;
+        self
+    }
+
+    /// Compare the configured validators with `BridgeConfig.validators` at `bridge_config`.
+    /// A configured `sol_public_key` the program doesn't know is an error, since signatures
+    /// collected from it would never verify; on-chain validators missing from the config are
+    /// only logged, as the threshold may still be reachable without them.
+    pub async fn verify_against_chain(&self, rpc: &SolanaRpc, bridge_config: Pubkey) -> Result<()> {
+        let data = with_timeout(
+            DEFAULT_RPC_TIMEOUT,
+            "Solana get_account_data",
+            rpc.call(move |client| async move { client.get_account_data(&bridge_config).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to get bridge config: {}", e)))?;
+
+        let config = BridgeConfig::load(&data)
+            .map_err(|e| RelayerError::ParseError(format!("Invalid bridge config data: {}", e)))?;
+
+        let mismatch = compare_validator_sets(&self.validators, &config.validators);
+        for key in &mismatch.unconfigured {
+            warn!("On-chain validator {} is not in the relayer config", key);
+        }
+        if !mismatch.unknown.is_empty() {
+            return Err(RelayerError::ConfigError(format!(
+                "Validators not in the on-chain set of {}: {}",
+                bridge_config,
+                mismatch.unknown.join(", ")
+            )));
+        }
+
+        info!(
+            "Validator config matches on-chain set ({} configured, {} on-chain)",
+            self.validators.len(),
+            config.validators.len()
+        );
+        Ok(())
+    }
+
+    /// Whether `validator` is currently skipped after repeated failures
+    pub fn is_circuit_open(&self, validator: &ValidatorConfig) -> bool {
+        let breakers = self.breakers.lock().unwrap();
+        breakers
+            .get(&validator.name)
+            .and_then(|breaker| breaker.open_until)
+            .is_some_and(|open_until| Instant::now() < open_until)
+    }
+
+    fn record_success(&self, validator: &ValidatorConfig) {
+        let mut breakers = self.breakers.lock().unwrap();
+        if let Some(breaker) = breakers.remove(&validator.name) {
+            if breaker.open_until.is_some() {
+                info!("Validator {} recovered, circuit closed", validator.name);
+            }
+        }
+    }
+
+    fn record_failure(&self, validator: &ValidatorConfig) {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(validator.name.clone()).or_default();
+        breaker.consecutive_failures += 1;
+
+        if breaker.consecutive_failures >= self.failure_threshold {
+            if breaker.open_until.is_none() {
+                warn!(
+                    "Validator {} failed {} times in a row, circuit open for {:?}",
+                    validator.name, breaker.consecutive_failures, self.cooldown
+                );
+            }
+            breaker.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Signature request for a Solana -> Ethereum transfer
     /// This creates the message that needs to be signed for minting on Ethereum
-    pub async fn collect_signatures_for_ethereum_mint(
+    pub fn ethereum_mint_request(
         &self,
         recipient: Address,
         amount: U256,
         nonce: u64,
         solana_sender: &str,
-    ) -> Result<Vec<ValidatorSignature>> {
-        info!(
-            "Collecting signatures for Ethereum mint: recipient={}, amount={}, nonce={}",
-            recipient, amount, nonce
-        );
-
+    ) -> SignatureRequest {
         // Create the message hash that validators will sign
         // This should match the hash creation in the Ethereum smart contract
-        let message_hash = self.create_ethereum_message_hash(recipient, amount, nonce, solana_sender);
+        let message_hash = Self::create_ethereum_message_hash(recipient, amount, nonce, solana_sender);
 
         debug!("Message hash: 0x{}", hex::encode(&message_hash));
 
-        let mut signatures = Vec::new();
+        SignatureRequest {
+            chain: Chain::Ethereum,
+            nonce,
+            message_hash: format!("0x{}", hex::encode(message_hash)),
+            source_tx_hash: String::new(),
+            signature_scheme: SIGNATURE_SCHEME_ED25519,
+        }
+    }
 
-        for validator in &self.validators {
-            if validator.endpoint.is_none() {
-                debug!("Skipping validator {} (no endpoint configured)", validator.name);
-                continue;
+    /// Signature request for an Ethereum -> Solana transfer: the program's own unlock
+    /// message, so the signatures verify against the `UnlockTokens` that carries them.
+    /// `signature_scheme` is the bridge's, and picks the key each validator signs with.
+    pub fn solana_unlock_request(
+        &self,
+        recipient: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        nonce: u64,
+        signature_scheme: u8,
+    ) -> SignatureRequest {
+        let message_hash = create_unlock_message(nonce, recipient, mint, amount);
+
+        debug!("Message hash: 0x{}", hex::encode(&message_hash));
+
+        SignatureRequest {
+            chain: Chain::Solana,
+            nonce,
+            message_hash: format!("0x{}", hex::encode(message_hash)),
+            source_tx_hash: String::new(),
+            signature_scheme,
+        }
+    }
+
+    /// Whether any validator has an endpoint to ask for signatures
+    pub fn has_endpoints(&self) -> bool {
+        self.validators.iter().any(|validator| validator.endpoint.is_some())
+    }
+
+    /// Validators that have an endpoint, haven't signed yet and aren't cooling
+    /// down after repeated failures, so a retry only asks the ones worth asking
+    pub fn outstanding<'a>(
+        &'a self,
+        request: &SignatureRequest,
+        collected: &[ValidatorSignature],
+    ) -> Vec<&'a ValidatorConfig> {
+        self.validators
+            .iter()
+            .filter(|validator| {
+                if validator.endpoint.is_none() {
+                    debug!("Skipping validator {} (no endpoint configured)", validator.name);
+                    return false;
+                }
+                if self.is_circuit_open(validator) {
+                    debug!("Skipping validator {} (circuit open)", validator.name);
+                    return false;
+                }
+                let address = Self::signer_address(validator, request);
+                !collected.iter().any(|sig| sig.validator_address == address)
+            })
+            .collect()
+    }
+
+    /// Ask one validator to sign `request`, feeding the result into its circuit breaker
+    pub async fn request_signature(
+        &self,
+        validator: &ValidatorConfig,
+        request: &SignatureRequest,
+    ) -> Result<ValidatorSignature> {
+        let result = self.fetch_signature(validator, request).await;
+        match result {
+            Ok(_) => self.record_success(validator),
+            Err(_) => self.record_failure(validator),
+        }
+        result
+    }
+
+    /// Ask one validator to sign several transfers in a single `/sign-batch` round trip.
+    ///
+    /// Returns one result per entry of `requests`, in order. Validators that don't
+    /// serve `/sign-batch` are remembered and asked one request at a time instead.
+    pub async fn collect_signatures_batch(
+        &self,
+        validator: &ValidatorConfig,
+        requests: &[SignatureRequest],
+    ) -> Vec<Result<ValidatorSignature>> {
+        if requests.is_empty() {
+            return Vec::new();
+        }
+
+        let supports_batch = !self.batch_unsupported.lock().unwrap().contains(&validator.name);
+        if supports_batch {
+            match self.fetch_signature_batch(validator, requests).await {
+                Ok(Some(signatures)) => {
+                    self.record_success(validator);
+                    return requests
+                        .iter()
+                        .map(|request| Self::batch_entry(validator, request, &signatures))
+                        .collect();
+                }
+                Ok(None) => {
+                    debug!("Validator {} does not support batching, asking per transfer", validator.name);
+                    self.batch_unsupported.lock().unwrap().insert(validator.name.clone());
+                }
+                Err(e) => {
+                    self.record_failure(validator);
+                    let message = e.to_string();
+                    return requests
+                        .iter()
+                        .map(|_| Err(RelayerError::NetworkError(message.clone())))
+                        .collect();
+                }
             }
+        }
 
-            info!(
-                "Would request signature from validator {} at endpoint {:?}",
-                validator.name, validator.endpoint
-            );
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            results.push(self.request_signature(validator, request).await);
+        }
+        results
+    }
 
-            let signature = ValidatorSignature {
-                validator_address: validator.eth_address.clone(),
-                signature: format!("0x{}", hex::encode(&message_hash)),
-                signed_at: Utc::now(),
-            };
+    /// POST `requests` to `/sign-batch`; `None` when the validator doesn't serve it
+    async fn fetch_signature_batch(
+        &self,
+        validator: &ValidatorConfig,
+        requests: &[SignatureRequest],
+    ) -> Result<Option<BatchSignatureResponse>> {
+        let body = BatchSignatureRequest {
+            requests: requests.to_vec(),
+        };
+        let (format, response) = self.send(validator, "sign-batch", &body).await?;
 
-            signatures.push(signature);
+        if matches!(response.status().as_u16(), 404 | 405) {
+            return Ok(None);
         }
 
-        if signatures.is_empty() {
-            return Err(RelayerError::InsufficientSignatures {
-                expected: self.validators.len(),
-                got: 0,
-            });
+        let response = response
+            .error_for_status()
+            .map_err(|e| RelayerError::NetworkError(format!("Validator {}: {}", validator.name, e)))?;
+        let response = match format {
+            WireFormat::Json => response.json::<BatchSignatureResponse>().await.map_err(|e| e.to_string()),
+            WireFormat::Borsh => Self::read_borsh::<BorshBatchSignatureResponse>(response).await.map(Into::into),
         }
+        .map_err(|e| RelayerError::ParseError(format!("Validator {}: {}", validator.name, e)))?;
+        Ok(Some(response))
+    }
 
-        info!("Collected {} signatures", signatures.len());
-        Ok(signatures)
+    /// Pick `request`'s signature out of a batched response
+    fn batch_entry(
+        validator: &ValidatorConfig,
+        request: &SignatureRequest,
+        response: &BatchSignatureResponse,
+    ) -> Result<ValidatorSignature> {
+        let signature = response.signatures.get(&request.message_hash).ok_or_else(|| {
+            RelayerError::InvalidSignature(format!(
+                "Validator {} did not sign nonce {}",
+                validator.name, request.nonce
+            ))
+        })?;
+        Self::validator_signature(validator, request, signature)
     }
 
-    /// Request signatures from validators for an Ethereum -> Solana transfer
-    /// This creates the message that needs to be signed for unlocking on Solana
-    pub async fn collect_signatures_for_solana_unlock(
+    async fn fetch_signature(
         &self,
-        recipient: &str,
-        amount: u64,
-        nonce: u64,
-        ethereum_sender: &str,
-    ) -> Result<Vec<ValidatorSignature>> {
-        info!(
-            "Collecting signatures for Solana unlock: recipient={}, amount={}, nonce={}",
-            recipient, amount, nonce
-        );
+        validator: &ValidatorConfig,
+        request: &SignatureRequest,
+    ) -> Result<ValidatorSignature> {
+        let path = match request.chain {
+            Chain::Ethereum => "sign-ethereum",
+            Chain::Solana => "sign-solana",
+            Chain::Sui => return Err(undeliverable(Chain::Sui)),
+        };
 
-        // Create the message hash that validators will sign
-        let message_hash = self.create_solana_message_hash(recipient, amount, nonce, ethereum_sender);
+        let (format, response) = self.send(validator, path, request).await?;
+        let response = response
+            .error_for_status()
+            .map_err(|e| RelayerError::NetworkError(format!("Validator {}: {}", validator.name, e)))?;
+        let signature = match format {
+            WireFormat::Json => response
+                .json::<SignatureResponse>()
+                .await
+                .map(|response| response.signature)
+                .map_err(|e| e.to_string()),
+            WireFormat::Borsh => Self::read_borsh::<ValidatorSignature>(response)
+                .await
+                .map(|signed| signed.signature.to_string()),
+        }
+        .map_err(|e| RelayerError::ParseError(format!("Validator {}: {}", validator.name, e)))?;
 
-        debug!("Message hash: 0x{}", hex::encode(&message_hash));
+        // The signer is whoever the config says this validator is, not what it claims
+        Self::validator_signature(validator, request, &signature)
+    }
 
-        let mut signatures = Vec::new();
+    /// POST `body` to `path`, in the configured wire format when the validator accepts it
+    async fn send<B: Serialize + BorshSerialize>(
+        &self,
+        validator: &ValidatorConfig,
+        path: &str,
+        body: &B,
+    ) -> Result<(WireFormat, reqwest::Response)> {
+        let network = |e: reqwest::Error| RelayerError::NetworkError(format!("Validator {}: {}", validator.name, e));
 
-        for validator in &self.validators {
-            if validator.endpoint.is_none() {
-                debug!("Skipping validator {} (no endpoint configured)", validator.name);
-                continue;
+        let borsh_refused = self.borsh_unsupported.lock().unwrap().contains(&validator.name);
+        if self.wire_format == WireFormat::Borsh && !borsh_refused {
+            let response = self
+                .post(validator, path)?
+                .header(reqwest::header::CONTENT_TYPE, BORSH_CONTENT_TYPE)
+                .body(wire::encode(body)?)
+                .send()
+                .await
+                .map_err(network)?;
+            if response.status().as_u16() != 415 {
+                return Ok((WireFormat::Borsh, response));
             }
+            debug!("Validator {} does not accept Borsh, using JSON", validator.name);
+            self.borsh_unsupported.lock().unwrap().insert(validator.name.clone());
+        }
 
-            info!(
-                "Would request signature from validator {} at endpoint {:?}",
-                validator.name, validator.endpoint
-            );
+        let response = self.post(validator, path)?.json(body).send().await.map_err(network)?;
+        Ok((WireFormat::Json, response))
+    }
 
-            let signature = ValidatorSignature {
-                validator_address: validator.sol_public_key.clone(),
-                signature: format!("0x{}", hex::encode(&message_hash)),
-                signed_at: Utc::now(),
-            };
+    async fn read_borsh<T: BorshDeserialize>(response: reqwest::Response) -> std::result::Result<T, String> {
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        wire::decode(&bytes).map_err(|e| e.to_string())
+    }
 
-            signatures.push(signature);
-        }
+    /// Authenticated POST to `path` on `validator`'s endpoint
+    fn post(&self, validator: &ValidatorConfig, path: &str) -> Result<reqwest::RequestBuilder> {
+        let endpoint = validator.endpoint.as_deref().ok_or_else(|| {
+            RelayerError::ConfigError(format!("Validator {} has no endpoint", validator.name))
+        })?;
+        let url = format!("{}/{}", endpoint.trim_end_matches('/'), path);
 
-        if signatures.is_empty() {
-            return Err(RelayerError::InsufficientSignatures {
-                expected: self.validators.len(),
-                got: 0,
-            });
+        info!("Requesting signature from validator {} at {}", validator.name, url);
+
+        let mut http_request = self.http.post(&url);
+        if let Some(ref token) = validator.auth_token {
+            let (name, value) = auth_header(validator.auth_scheme, token);
+            http_request = http_request.header(name, value);
         }
+        Ok(http_request)
+    }
 
-        info!("Collected {} signatures", signatures.len());
-        Ok(signatures)
+    fn validator_signature(
+        validator: &ValidatorConfig,
+        request: &SignatureRequest,
+        signature: &str,
+    ) -> Result<ValidatorSignature> {
+        let signature = ChainSignature::parse(request.chain, request.signature_scheme, signature).map_err(|e| {
+            RelayerError::InvalidSignature(format!("Validator {}: {}", validator.name, e))
+        })?;
+
+        Ok(ValidatorSignature {
+            validator_address: Self::signer_address(validator, request).to_string(),
+            signature,
+            signed_at: Utc::now(),
+        })
+    }
+
+    /// Key the destination chain will check the validator's signature against: its
+    /// Ethereum address unless the Solana program verifies Ed25519
+    fn signer_address<'a>(validator: &'a ValidatorConfig, request: &SignatureRequest) -> &'a str {
+        match (request.chain, request.signature_scheme) {
+            (Chain::Solana, SIGNATURE_SCHEME_ED25519) => &validator.sol_public_key,
+            _ => &validator.eth_address,
+        }
     }
 
     /// Create the message hash for Ethereum smart contract verification
     /// This must match the hash creation in the SolanaBridge contract
     fn create_ethereum_message_hash(
-        &self,
         recipient: Address,
         amount: U256,
         nonce: u64,
@@ -161,84 +579,1463 @@ impl ValidatorClient {
 
         result.into()
     }
+}
 
-    /// Create the message hash for Solana program verification
-    fn create_solana_message_hash(
-        &self,
-        recipient: &str,
-        amount: u64,
-        nonce: u64,
-        ethereum_sender: &str,
-    ) -> [u8; 32] {
-        // For Solana, we use SHA256
-        let mut hasher = Sha256::new();
+/// Result of checking one validator signature against a transfer, without any chain access
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureCheck {
+    /// Message the validator should have signed, as the relayer requests it
+    pub message_hash: [u8; 32],
+    /// Address recovered from an Ethereum signature, or the Solana key it was checked against
+    pub signer: String,
+    pub valid: bool,
+}
 
-        // Encode the data
-        hasher.update(recipient.as_bytes());
-        hasher.update(&amount.to_le_bytes());
-        hasher.update(&nonce.to_le_bytes());
-        hasher.update(ethereum_sender.as_bytes());
+/// Rebuild the message a validator signs for a transfer delivered on `chain` and check that
+/// `validator` signed it: by EIP-191 recovery for Ethereum, Ed25519 verification for Solana.
+///
+/// `recipient` and `sender` are the destination and source addresses as the relayer records
+/// them. A Solana unlock message is the program's, which names the token `mint` instead of
+/// the sender, so `mint` is required for Solana and unused for Ethereum.
+pub fn verify_transfer_signature(
+    chain: Chain,
+    nonce: u64,
+    recipient: &str,
+    amount: u64,
+    sender: &str,
+    mint: Option<&str>,
+    validator: &str,
+    signature: &str,
+) -> Result<SignatureCheck> {
+    match (chain, ChainSignature::parse(chain, SIGNATURE_SCHEME_ED25519, signature)?) {
+        (Chain::Ethereum, signature) => {
+            let recipient = Address::from_str(recipient)
+                .map_err(|e| RelayerError::ParseError(format!("Invalid recipient address: {}", e)))?;
+            let validator = Address::from_str(validator)
+                .map_err(|e| RelayerError::ParseError(format!("Invalid validator address: {}", e)))?;
+            let message_hash =
+                ValidatorClient::create_ethereum_message_hash(recipient, U256::from(amount), nonce, sender);
+            let recovered = AlloySignature::try_from(signature.to_bytes().as_slice())
+                .and_then(|signature| signature.recover_address_from_msg(message_hash))
+                .map_err(|e| RelayerError::InvalidSignature(format!("Cannot recover signer: {}", e)))?;
+            Ok(SignatureCheck {
+                message_hash,
+                signer: checksum_address(&recovered),
+                valid: recovered == validator,
+            })
+        }
+        (Chain::Solana, ChainSignature::Ed25519(bytes)) => {
+            let validator = Pubkey::from_str(validator)
+                .map_err(|e| RelayerError::ParseError(format!("Invalid validator pubkey: {}", e)))?;
+            let recipient = Pubkey::from_str(recipient)
+                .map_err(|e| RelayerError::ParseError(format!("Invalid recipient pubkey: {}", e)))?;
+            let mint = mint
+                .ok_or_else(|| RelayerError::ParseError("A Solana unlock signature needs the token mint".to_string()))
+                .and_then(|mint| {
+                    Pubkey::from_str(mint).map_err(|e| RelayerError::ParseError(format!("Invalid mint: {}", e)))
+                })?;
+            let message_hash = create_unlock_message(nonce, &recipient, &mint, amount);
+            Ok(SignatureCheck {
+                message_hash,
+                signer: validator.to_string(),
+                valid: Signature::from(bytes).verify(validator.as_ref(), &message_hash),
+            })
+        }
+        _ => Err(undeliverable(chain)),
+    }
+}
 
-        let result = hasher.finalize();
-        result.into()
+/// `(chain, nonce)` pairs a validator has already signed.
+///
+/// With a `path`, each pair is appended to that file as a JSON line before the
+/// signature is handed out, and reloaded on startup, so a restart doesn't reopen
+/// a nonce to a second signature.
+#[derive(Debug, Default)]
+pub struct SignedNonces {
+    path: Option<PathBuf>,
+    signed: HashSet<(Chain, u64)>,
+}
+
+impl SignedNonces {
+    /// Load the pairs recorded at `path`, creating the file on first write
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut signed = HashSet::new();
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                    signed.insert(serde_json::from_str::<(Chain, u64)>(line)?);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(RelayerError::ConfigError(format!(
+                    "Failed to read signed nonces {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        }
+
+        Ok(Self {
+            path: Some(path),
+            signed,
+        })
+    }
+
+    pub fn contains(&self, chain: Chain, nonce: u64) -> bool {
+        self.signed.contains(&(chain, nonce))
+    }
+
+    /// Record `(chain, nonce)`, failing if it was already signed
+    fn insert(&mut self, chain: Chain, nonce: u64) -> Result<()> {
+        if self.contains(chain, nonce) {
+            return Err(RelayerError::TransactionAlreadyProcessed(nonce));
+        }
+
+        if let Some(ref path) = self.path {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| {
+                    writeln!(file, "{}", serde_json::json!([chain, nonce]))?;
+                    Ok(file)
+                })
+                .map_err(|e| {
+                    RelayerError::Unknown(format!("Failed to record signed nonce {}: {}", nonce, e))
+                })?;
+            file.sync_data()
+                .map_err(|e| RelayerError::Unknown(format!("Failed to sync {}: {}", path.display(), e)))?;
+        }
+
+        self.signed.insert((chain, nonce));
+        Ok(())
     }
 }
 
 /// Validator service - this would run separately on each validator node
 /// It signs messages after verifying the source chain transaction
 pub struct ValidatorService {
-    eth_private_key: String,
-    sol_private_key: String,
+    eth_signer: PrivateKeySigner,
+    sol_keypair: Keypair,
+    /// Token every signing request must carry; `None` accepts any caller
+    auth: Option<(ValidatorAuthScheme, String)>,
+    /// Refuses a second signature for the same nonce, even to a misbehaving relayer
+    signed: Mutex<SignedNonces>,
+    /// Endpoint and bridge contract burns are checked against
+    ethereum: Option<(EthereumRpc, Address)>,
+    /// Endpoint and bridge program locks are checked against
+    solana: Option<(SolanaRpc, Pubkey)>,
+    /// Token mint Ethereum -> Solana unlocks release, which the unlock message names
+    unlock_mint: Option<Pubkey>,
+    /// Limit for each source-chain lookup
+    rpc_timeout: Duration,
+    /// Scaling the relayer applies to amounts, so requests are checked against what it sends
+    token_decimals: TokenDecimals,
 }
 
 impl ValidatorService {
-    pub fn new(eth_private_key: String, sol_private_key: String) -> Self {
+    pub fn new(eth_signer: PrivateKeySigner, sol_keypair: Keypair) -> Self {
         Self {
-            eth_private_key,
-            sol_private_key,
+            eth_signer,
+            sol_keypair,
+            auth: None,
+            signed: Mutex::new(SignedNonces::default()),
+            ethereum: None,
+            solana: None,
+            unlock_mint: None,
+            rpc_timeout: DEFAULT_RPC_TIMEOUT,
+            token_decimals: TokenDecimals::default(),
         }
     }
 
+    /// Check Ethereum -> Solana requests against `TokensBurned` events emitted by `bridge_contract`
+    pub fn with_ethereum(mut self, rpc: EthereumRpc, bridge_contract: Address) -> Self {
+        self.ethereum = Some((rpc, bridge_contract));
+        self
+    }
+
+    /// Check Solana -> Ethereum requests against `TokensLocked` events logged by `program_id`
+    pub fn with_solana(mut self, rpc: SolanaRpc, program_id: Pubkey) -> Self {
+        self.solana = Some((rpc, program_id));
+        self
+    }
+
+    /// Sign Ethereum -> Solana unlocks of `mint`, the relayer's `SOLANA_TOKEN_MINT`
+    pub fn with_unlock_mint(mut self, mint: Pubkey) -> Self {
+        self.unlock_mint = Some(mint);
+        self
+    }
+
+    /// Give up on a source-chain lookup after `timeout` instead of `DEFAULT_RPC_TIMEOUT`
+    pub fn with_rpc_timeout(mut self, timeout: Duration) -> Self {
+        self.rpc_timeout = timeout;
+        self
+    }
+
+    /// Expect source amounts rescaled by `decimals`, as the relayer configured with them sends
+    pub fn with_token_decimals(mut self, decimals: TokenDecimals) -> Self {
+        self.token_decimals = decimals;
+        self
+    }
+
+    /// Use a persisted signed-nonce set (see `SignedNonces::open`)
+    pub fn with_signed_nonces(mut self, signed: SignedNonces) -> Self {
+        self.signed = Mutex::new(signed);
+        self
+    }
+
+    /// Only accept signing requests that present `token` under `scheme`
+    pub fn with_auth(mut self, scheme: ValidatorAuthScheme, token: String) -> Self {
+        self.auth = Some((scheme, token));
+        self
+    }
+
+    /// Whether `headers` carry the configured token
+    pub fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let Some((scheme, ref token)) = self.auth else {
+            return true;
+        };
+        let (name, expected) = auth_header(scheme, token);
+        headers
+            .get(name)
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), expected.as_bytes()))
+    }
+
+    /// HTTP API the relayer's `ValidatorClient` talks to
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/sign-ethereum", post(sign_handler))
+            .route("/sign-solana", post(sign_handler))
+            .route("/sign-batch", post(sign_batch_handler))
+            .route("/health", get(|| async { StatusCode::OK }))
+            .with_state(self)
+    }
+
+    /// Verify the source transaction behind `request` and sign its message hash,
+    /// at most once per `(chain, nonce)`
+    pub async fn sign_request(&self, request: &SignatureRequest) -> Result<String> {
+        if self.signed.lock().unwrap().contains(request.chain, request.nonce) {
+            warn!("Refusing to sign {:?} nonce {} again", request.chain, request.nonce);
+            return Err(RelayerError::TransactionAlreadyProcessed(request.nonce));
+        }
+
+        let message_hash: [u8; 32] = hex::decode(request.message_hash.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| RelayerError::ParseError(format!("Invalid message hash {}", request.message_hash)))?;
+        if request.chain == Chain::Solana && !BridgeConfig::supports_signature_scheme(request.signature_scheme) {
+            return Err(RelayerError::ParseError(format!(
+                "Unknown signature scheme {}",
+                request.signature_scheme
+            )));
+        }
+
+        // Signatures are for the destination chain; the event to check is on the other one
+        let verified = match request.chain {
+            Chain::Ethereum => {
+                self.verify_solana_transaction(&request.source_tx_hash, request.nonce, message_hash)
+                    .await?
+            }
+            Chain::Solana => {
+                self.verify_ethereum_transaction(&request.source_tx_hash, request.nonce, message_hash)
+                    .await?
+            }
+            Chain::Sui => return Err(undeliverable(Chain::Sui)),
+        };
+        if !verified {
+            return Err(RelayerError::SourceVerificationFailed(format!(
+                "{} does not match nonce {}",
+                request.source_tx_hash, request.nonce
+            )));
+        }
+
+        let signature = match (request.chain, request.signature_scheme) {
+            (Chain::Ethereum, _) => self.sign_for_ethereum(message_hash).await?,
+            (_, SIGNATURE_SCHEME_SECP256K1) => self.sign_for_solana_secp256k1(message_hash).await?,
+            _ => self.sign_for_solana(message_hash).await?,
+        };
+
+        // Recorded before the signature leaves, so a concurrent duplicate loses here
+        self.signed.lock().unwrap().insert(request.chain, request.nonce)?;
+
+        Ok(signature)
+    }
+
     /// Sign a message for Ethereum (ECDSA signature)
+    ///
+    /// Signs the EIP-191 prefixed hash, which is what `ValidatorRegistry` recovers
+    /// against (`toEthSignedMessageHash`). Returns the 65-byte `r || s || v`, 0x-hex.
     pub async fn sign_for_ethereum(&self, message_hash: [u8; 32]) -> Result<String> {
         info!("Signing message for Ethereum: 0x{}", hex::encode(&message_hash));
-        Ok(format!("0x{}", hex::encode(&message_hash)))
+        let signature = self
+            .eth_signer
+            .sign_message(&message_hash)
+            .await
+            .map_err(|e| RelayerError::InvalidSignature(format!("Ethereum signing failed: {}", e)))?;
+        Ok(format!("0x{}", hex::encode(signature.as_bytes())))
     }
 
-    /// Sign a message for Solana (Ed25519 signature)
+    /// Sign a message for Solana (Ed25519 signature). Returns the 64-byte signature, 0x-hex.
     pub async fn sign_for_solana(&self, message_hash: [u8; 32]) -> Result<String> {
         info!("Signing message for Solana: 0x{}", hex::encode(&message_hash));
-        Ok(format!("0x{}", hex::encode(&message_hash)))
+        let signature = self.sol_keypair.sign_message(&message_hash);
+        Ok(format!("0x{}", hex::encode(signature.as_ref())))
+    }
+
+    /// Sign a message for a Solana program using `SIGNATURE_SCHEME_SECP256K1`, with the
+    /// Ethereum key. The program recovers against the bare hash, so no EIP-191 prefix is
+    /// applied. Returns the compact signature in its `ChainSignature` encoding, 0x-hex.
+    pub async fn sign_for_solana_secp256k1(&self, message_hash: [u8; 32]) -> Result<String> {
+        info!("Signing message for Solana with secp256k1: 0x{}", hex::encode(&message_hash));
+        let signature = self
+            .eth_signer
+            .sign_hash(&B256::from(message_hash))
+            .await
+            .map_err(|e| RelayerError::InvalidSignature(format!("secp256k1 signing failed: {}", e)))?;
+        let bytes = signature.as_bytes();
+        let compact = ChainSignature::compact_secp256k1(
+            bytes[..32].try_into().unwrap(),
+            bytes[32..64].try_into().unwrap(),
+            bytes[64],
+        )?;
+        Ok(compact.to_string())
+    }
+
+    /// `signature` from [`Self::sign_request`] as a [`ValidatorSignature`] for the Borsh format
+    fn signed(
+        &self,
+        request: &SignatureRequest,
+        signature: &str,
+    ) -> std::result::Result<ValidatorSignature, StatusCode> {
+        let validator_address = match (request.chain, request.signature_scheme) {
+            (Chain::Solana, SIGNATURE_SCHEME_ED25519) => self.sol_public_key().to_string(),
+            _ => checksum_address(&self.eth_address()),
+        };
+        let signature = ChainSignature::parse(request.chain, request.signature_scheme, signature).map_err(|e| {
+            warn!("Produced an unusable signature: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        Ok(ValidatorSignature {
+            validator_address,
+            signature,
+            signed_at: Utc::now(),
+        })
+    }
+
+    /// Address this validator signs Ethereum messages with
+    pub fn eth_address(&self) -> Address {
+        self.eth_signer.address()
+    }
+
+    /// Public key this validator signs Solana messages with
+    pub fn sol_public_key(&self) -> Pubkey {
+        self.sol_keypair.pubkey()
     }
 
-    /// Verify a source transaction on Ethereum before signing
-    pub async fn verify_ethereum_transaction(&self, tx_hash: &str, _nonce: u64) -> Result<bool> {
+    /// Verify a source transaction on Ethereum before signing.
+    ///
+    /// True only if `tx_hash` succeeded and emitted a `TokensBurned` for `nonce` from
+    /// the bridge contract whose recipient and amount make the program's unlock message
+    /// `message_hash`.
+    pub async fn verify_ethereum_transaction(
+        &self,
+        tx_hash: &str,
+        nonce: u64,
+        message_hash: [u8; 32],
+    ) -> Result<bool> {
         info!("Verifying Ethereum transaction: {}", tx_hash);
+
+        let (rpc, bridge_contract) = self.ethereum.as_ref().ok_or_else(|| {
+            RelayerError::ConfigError("No Ethereum RPC configured to verify burns".to_string())
+        })?;
+        let mint = self
+            .unlock_mint
+            .ok_or_else(|| RelayerError::ConfigError("No Solana token mint configured to sign unlocks".to_string()))?;
+        let Ok(hash) = B256::from_str(tx_hash) else {
+            warn!("Malformed Ethereum transaction hash {}", tx_hash);
+            return Ok(false);
+        };
+
+        let receipt = with_timeout(
+            self.rpc_timeout,
+            "Ethereum get_transaction_receipt",
+            rpc.call(|provider| async move { provider.get_transaction_receipt(hash).await }),
+        )
+        .await?
+        .map_err(|e| RelayerError::EthereumRpcError(format!("Failed to fetch receipt {}: {}", tx_hash, e)))?;
+        let Some(receipt) = receipt else {
+            warn!("No receipt for {}", tx_hash);
+            return Ok(false);
+        };
+        if !receipt.status() {
+            warn!("Transaction {} reverted", tx_hash);
+            return Ok(false);
+        }
+
+        let burn = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == *bridge_contract)
+            .filter_map(|log| SolanaBridge::TokensBurned::decode_log(&log.inner, true).ok())
+            .map(|decoded| decoded.data)
+            .find(|event| event.nonce == nonce);
+        let Some(burn) = burn else {
+            warn!("Transaction {} has no TokensBurned event for nonce {}", tx_hash, nonce);
+            return Ok(false);
+        };
+        let Ok(amount) = u64::try_from(burn.amount) else {
+            warn!("Burn amount {} in {} overflows u64", burn.amount, tx_hash);
+            return Ok(false);
+        };
+        let burned = self.token_decimals.source_amount(amount, Chain::Ethereum, Chain::Solana);
+        let amount = match self.token_decimals.delivered(burned, Chain::Solana) {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!("Burn amount in {} cannot be unlocked on Solana: {}", tx_hash, e);
+                return Ok(false);
+            }
+        };
+
+        let Ok(recipient) = Pubkey::from_str(&burn.solanaAddress) else {
+            warn!("Burn {} names {} as recipient, which is not a Solana address", tx_hash, burn.solanaAddress);
+            return Ok(false);
+        };
+
+        let expected = create_unlock_message(nonce, &recipient, &mint, amount);
+        if expected != message_hash {
+            warn!(
+                "Burn {} (recipient {}, amount {}) does not match the requested message",
+                tx_hash, burn.solanaAddress, amount
+            );
+            return Ok(false);
+        }
+
         Ok(true)
     }
 
-    /// Verify a source transaction on Solana before signing
-    pub async fn verify_solana_transaction(&self, tx_hash: &str, _nonce: u64) -> Result<bool> {
+    /// Verify a source transaction on Solana before signing.
+    ///
+    /// True only if `tx_hash` succeeded and the bridge program logged a `TokensLocked`
+    /// for `nonce` whose recipient, amount and sender hash to `message_hash`.
+    pub async fn verify_solana_transaction(
+        &self,
+        tx_hash: &str,
+        nonce: u64,
+        message_hash: [u8; 32],
+    ) -> Result<bool> {
         info!("Verifying Solana transaction: {}", tx_hash);
-        Ok(true)
+
+        let (rpc, program_id) = self.solana.as_ref().ok_or_else(|| {
+            RelayerError::ConfigError("No Solana RPC configured to verify locks".to_string())
+        })?;
+        let Ok(signature) = Signature::from_str(tx_hash) else {
+            warn!("Malformed Solana transaction signature {}", tx_hash);
+            return Ok(false);
+        };
+
+        let tx = with_timeout(
+            self.rpc_timeout,
+            "Solana get_transaction",
+            rpc.call(|client| async move {
+                let config = RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(client.commitment()),
+                    max_supported_transaction_version: Some(0),
+                };
+                client.get_transaction_with_config(&signature, config).await
+            }),
+        )
+        .await?
+        .map_err(|e| RelayerError::SolanaRpcError(format!("Failed to fetch {}: {}", tx_hash, e)))?;
+
+        let Some(meta) = tx.transaction.meta else {
+            warn!("Transaction {} has no status metadata", tx_hash);
+            return Ok(false);
+        };
+        if meta.err.is_some() {
+            warn!("Transaction {} failed", tx_hash);
+            return Ok(false);
+        }
+        let logs: Option<Vec<String>> = meta.log_messages.into();
+
+        let logs = logs.unwrap_or_default();
+        Ok(lock_matches(&logs, program_id, &self.token_decimals, tx_hash, nonce, message_hash))
+    }
+}
+
+/// Whether the `TokensLocked` logged by `program_id` in `logs` is for `nonce` and
+/// hashes to `message_hash` as a mint request, its amount rescaled by `decimals`
+fn lock_matches(
+    logs: &[String],
+    program_id: &Pubkey,
+    decimals: &TokenDecimals,
+    tx_hash: &str,
+    nonce: u64,
+    message_hash: [u8; 32],
+) -> bool {
+    let event = match SolanaMonitor::parse_logs(&program_logs(logs, program_id), tx_hash.to_string()) {
+        Ok(Some(event)) => event,
+        Ok(None) => {
+            warn!("Transaction {} has no TokensLocked event from {}", tx_hash, program_id);
+            return false;
+        }
+        Err(e) => {
+            warn!("Failed to parse logs of {}: {}", tx_hash, e);
+            return false;
+        }
+    };
+    let BridgeEvent::TokensLocked { to_chain: Chain::Ethereum, sender, recipient, amount, nonce: locked_nonce, .. } = event
+    else {
+        warn!("Lock in {} is not bound for Ethereum", tx_hash);
+        return false;
+    };
+    if locked_nonce != nonce {
+        warn!("Lock in {} has nonce {}, expected {}", tx_hash, locked_nonce, nonce);
+        return false;
+    }
+    let Some(recipient) = ethereum_recipient(&recipient) else {
+        warn!("Lock in {} has a malformed Ethereum recipient {}", tx_hash, recipient);
+        return false;
+    };
+
+    let locked = decimals.source_amount(amount, Chain::Solana, Chain::Ethereum);
+    let amount = match decimals.delivered(locked, Chain::Ethereum) {
+        Ok(amount) => amount,
+        Err(e) => {
+            warn!("Lock amount in {} cannot be minted on Ethereum: {}", tx_hash, e);
+            return false;
+        }
+    };
+    let expected = ValidatorClient::create_ethereum_message_hash(recipient, U256::from(amount), nonce, &sender);
+    if expected != message_hash {
+        warn!(
+            "Lock {} (recipient {}, amount {}, sender {}) does not match the requested message",
+            tx_hash, recipient, amount, sender
+        );
+        return false;
     }
+
+    true
+}
+
+/// Log lines emitted while `program_id` was the executing program, so a lookalike
+/// event logged by another program in the same transaction (or by a CPI) is ignored
+fn program_logs(logs: &[String], program_id: &Pubkey) -> Vec<String> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut own = Vec::new();
+
+    for log in logs {
+        // Runtime lines look like "Program <id> invoke [1]" and "Program <id> success";
+        // program output is "Program log: ...", whose second word isn't a pubkey
+        let mut words = log.split_whitespace();
+        if words.next() == Some("Program") {
+            if let Some(id) = words.next().and_then(|id| Pubkey::from_str(id).ok()) {
+                match words.next() {
+                    Some("invoke") => stack.push(id),
+                    Some("success") | Some("failed:") => {
+                        stack.pop();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        }
+
+        if stack.last() == Some(program_id) {
+            own.push(log.clone());
+        }
+    }
+
+    own
+}
+
+/// Ethereum address from a lock's recipient: 20 bytes, or 32 with the address in the low 20
+fn ethereum_recipient(recipient: &str) -> Option<Address> {
+    let bytes = hex::decode(recipient.trim_start_matches("0x")).ok()?;
+    match bytes.len() {
+        20 => Some(Address::from_slice(&bytes)),
+        32 if bytes[..12].iter().all(|b| *b == 0) => Some(Address::from_slice(&bytes[12..])),
+        _ => None,
+    }
+}
+
+/// Compare without returning early, so response timing doesn't leak how much of a token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Reads and answers JSON or, with a Borsh `Content-Type`, the binary format
+async fn sign_handler(
+    State(service): State<Arc<ValidatorService>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> std::result::Result<Response, StatusCode> {
+    let format = WireFormat::of(&headers);
+    let request: SignatureRequest = format.decode(&body).map_err(|e| {
+        warn!("Unreadable signature request: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    if !service.is_authorized(&headers) {
+        warn!("Rejected unauthenticated signature request for nonce {}", request.nonce);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let signature = service.sign_request(&request).await.map_err(|e| {
+        warn!("Failed to sign nonce {}: {}", request.nonce, e);
+        match e {
+            RelayerError::TransactionAlreadyProcessed(_) => StatusCode::CONFLICT,
+            RelayerError::SourceVerificationFailed(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RelayerError::ParseError(_) | RelayerError::InvalidChain(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    })?;
+
+    match format {
+        WireFormat::Json => Ok(Json(SignatureResponse { signature }).into_response()),
+        WireFormat::Borsh => borsh_response(&service.signed(&request, &signature)?),
+    }
+}
+
+/// Sign every request in the batch that passes verification; refusals are
+/// logged and left out of the response
+async fn sign_batch_handler(
+    State(service): State<Arc<ValidatorService>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> std::result::Result<Response, StatusCode> {
+    let format = WireFormat::of(&headers);
+    let batch: BatchSignatureRequest = format.decode(&body).map_err(|e| {
+        warn!("Unreadable signature batch: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+    if !service.is_authorized(&headers) {
+        warn!("Rejected unauthenticated batch of {} signature requests", batch.requests.len());
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let mut signed = Vec::new();
+    for request in &batch.requests {
+        match service.sign_request(request).await {
+            Ok(signature) => signed.push((request, signature)),
+            Err(e) => warn!("Failed to sign nonce {}: {}", request.nonce, e),
+        }
+    }
+
+    match format {
+        WireFormat::Json => {
+            let signatures = signed
+                .into_iter()
+                .map(|(request, signature)| (request.message_hash.clone(), signature))
+                .collect();
+            Ok(Json(BatchSignatureResponse { signatures }).into_response())
+        }
+        WireFormat::Borsh => {
+            let signatures = signed
+                .into_iter()
+                .map(|(request, signature)| -> std::result::Result<_, StatusCode> {
+                    Ok((request.message_hash.clone(), service.signed(request, &signature)?))
+                })
+                .collect::<std::result::Result<_, _>>()?;
+            borsh_response(&BorshBatchSignatureResponse { signatures })
+        }
+    }
+}
+
+fn borsh_response<T: BorshSerialize>(value: &T) -> std::result::Result<Response, StatusCode> {
+    wire::borsh_response(value).map_err(|e| {
+        warn!("Failed to encode response: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::{json, Value};
+    use solana_program_test::{processor, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        program_pack::Pack,
+        transaction::Transaction,
+    };
+
+    const BRIDGE_CONTRACT: &str = "0xaBD6f99Fbb77051B28942abe3118bf4D8Ea9F2CA";
+
+    fn bridge_contract() -> Address {
+        Address::from_str(BRIDGE_CONTRACT).unwrap()
+    }
+
+    fn burner() -> Address {
+        Address::from_str("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0").unwrap()
+    }
+
+    /// Solana user the test burns release to
+    fn burn_recipient() -> Pubkey {
+        Pubkey::new_from_array([5; 32])
+    }
+
+    /// Mint the test validators sign unlocks of
+    fn unlock_mint() -> Pubkey {
+        Pubkey::new_from_array([6; 32])
+    }
+
+    fn burn_event(nonce: u64, amount: u64) -> SolanaBridge::TokensBurned {
+        SolanaBridge::TokensBurned {
+            sender: burner(),
+            amount: U256::from(amount),
+            solanaAddress: burn_recipient().to_string(),
+            nonce,
+        }
+    }
+
+    /// Receipt for `tx_hash` carrying `event` as emitted by `emitter`
+    fn burn_receipt(tx_hash: B256, emitter: Address, event: &SolanaBridge::TokensBurned, success: bool) -> Value {
+        let data = event.encode_log_data();
+        let block_hash = B256::repeat_byte(0xbb);
+        json!({
+            "type": "0x2",
+            "status": if success { "0x1" } else { "0x0" },
+            "cumulativeGasUsed": "0x5208",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "logs": [{
+                "address": emitter,
+                "topics": data.topics(),
+                "data": data.data,
+                "blockHash": block_hash,
+                "blockNumber": "0x10",
+                "transactionHash": tx_hash,
+                "transactionIndex": "0x0",
+                "logIndex": "0x0",
+                "removed": false
+            }],
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockHash": block_hash,
+            "blockNumber": "0x10",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x3b9aca00",
+            "from": burner(),
+            "to": emitter,
+            "contractAddress": null
+        })
+    }
+
+    /// JSON-RPC endpoint answering `eth_getTransactionReceipt` from `receipts`, keyed by tx hash
+    async fn mock_ethereum_rpc(receipts: Vec<(B256, Value)>) -> EthereumRpc {
+        let receipts = Arc::new(receipts);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let receipts = receipts.clone();
+                async move {
+                    let hash = body["params"][0].as_str().and_then(|h| B256::from_str(h).ok());
+                    let result = receipts
+                        .iter()
+                        .find(|(tx_hash, _)| Some(*tx_hash) == hash)
+                        .map(|(_, receipt)| receipt.clone())
+                        .unwrap_or(Value::Null);
+                    Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        EthereumRpc::ethereum(&[format!("http://{}", addr)]).unwrap()
+    }
+
+    /// Bridge config, token accounts and allowlist for a user about to lock `amount`
+    struct LockFixture {
+        program_id: Pubkey,
+        admin: Keypair,
+        user: Keypair,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        vault_token_account: Pubkey,
+        user_bridge_state: Pubkey,
+        bridge_config: Pubkey,
+    }
+
+    fn add_account(program_test: &mut ProgramTest, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        program_test.add_account(
+            address,
+            Account {
+                lamports: 1_000_000_000,
+                data,
+                owner,
+                ..Account::default()
+            },
+        );
+    }
+
+    fn setup_lock_fixture(program_test: &mut ProgramTest, program_id: Pubkey, amount: u64) -> LockFixture {
+        let admin = Keypair::new();
+        let user = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let bridge_config = Pubkey::new_unique();
+        let user_token_account = Pubkey::new_unique();
+        let vault_token_account = Pubkey::new_unique();
+        let (vault_pda, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", bridge_config.as_ref()], &program_id);
+        let (user_bridge_state, _) = Pubkey::find_program_address(
+            &[b"bridge", user.pubkey().as_ref(), &0u64.to_le_bytes()],
+            &program_id,
+        );
+
+        program_test.add_account(
+            user.pubkey(),
+            Account {
+                lamports: 1_000_000_000,
+                ..Account::default()
+            },
+        );
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: amount,
+            ..spl_token::state::Mint::default()
+        }
+        .pack_into_slice(&mut data);
+        add_account(program_test, mint, data, spl_token::id());
+        for (address, owner, balance) in [(user_token_account, user.pubkey(), amount), (vault_token_account, vault_pda, 0)] {
+            let mut data = vec![0u8; spl_token::state::Account::LEN];
+            spl_token::state::Account {
+                mint,
+                owner,
+                amount: balance,
+                state: spl_token::state::AccountState::Initialized,
+                ..spl_token::state::Account::default()
+            }
+            .pack_into_slice(&mut data);
+            add_account(program_test, address, data, spl_token::id());
+        }
+        let config = bridge_vault::BridgeConfig {
+            admin: admin.pubkey(),
+            vault_pda_bump: vault_bump,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: vec![Pubkey::new_unique()],
+            validator_threshold: 1,
+            supported_chains: bridge_vault::BridgeConfig::ALL_CHAINS,
+            transfer_ttl: bridge_vault::BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: bridge_vault::state::SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [
+                bridge_vault::BridgeConfig::CHAIN_FEE_UNSET;
+                bridge_vault::BridgeConfig::CHAIN_FEE_SLOTS
+            ],
+        };
+        add_account(program_test, bridge_config, borsh::to_vec(&config).unwrap(), program_id);
+
+        LockFixture {
+            program_id,
+            admin,
+            user,
+            mint,
+            user_token_account,
+            vault_token_account,
+            user_bridge_state,
+            bridge_config,
+        }
+    }
+
+    /// Unlock request for a burn of 1000 by `burner()`, as the relayer would build it
+    fn unlock_request(nonce: u64, tx_hash: B256) -> SignatureRequest {
+        let mut request = ValidatorClient::new(vec![]).solana_unlock_request(
+            &burn_recipient(),
+            &unlock_mint(),
+            1_000,
+            nonce,
+            SIGNATURE_SCHEME_ED25519,
+        );
+        request.source_tx_hash = tx_hash.to_string();
+        request
+    }
+
+    /// Validator that can see one successful burn of 1000 with `nonce` at `tx_hash`
+    async fn validator_with_burn(nonce: u64, tx_hash: B256) -> ValidatorService {
+        let rpc = mock_ethereum_rpc(vec![(
+            tx_hash,
+            burn_receipt(tx_hash, bridge_contract(), &burn_event(nonce, 1_000), true),
+        )])
+        .await;
+        ValidatorService::new(PrivateKeySigner::random(), Keypair::new())
+            .with_ethereum(rpc, bridge_contract())
+            .with_unlock_mint(unlock_mint())
+    }
 
     #[test]
     fn test_message_hash_creation() {
-        let client = ValidatorClient::new(vec![]);
-
         // Test Ethereum message hash
         let recipient = Address::from_str("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0").unwrap();
         let amount = U256::from(1000000u64);
         let nonce = 1;
         let sender = "SoLXxX123";
 
-        let hash = client.create_ethereum_message_hash(recipient, amount, nonce, sender);
+        let hash = ValidatorClient::create_ethereum_message_hash(recipient, amount, nonce, sender);
         assert_eq!(hash.len(), 32);
     }
+
+    #[tokio::test]
+    async fn test_transfer_signatures_verify_offline() {
+        let service = ValidatorService::new(PrivateKeySigner::random(), Keypair::new());
+        let eth_validator = checksum_address(&service.eth_address());
+        let sol_validator = service.sol_public_key().to_string();
+        let solana_user = Pubkey::new_unique().to_string();
+        let eth_user = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0";
+
+        // Solana -> Ethereum mint
+        let mint_hash = ValidatorClient::create_ethereum_message_hash(
+            Address::from_str(eth_user).unwrap(),
+            U256::from(1_000u64),
+            7,
+            &solana_user,
+        );
+        let mint_signature = service.sign_for_ethereum(mint_hash).await.unwrap();
+        let check = verify_transfer_signature(
+            Chain::Ethereum,
+            7,
+            eth_user,
+            1_000,
+            &solana_user,
+            None,
+            &eth_validator,
+            &mint_signature,
+        )
+        .unwrap();
+        assert_eq!(
+            check,
+            SignatureCheck {
+                message_hash: mint_hash,
+                signer: eth_validator.clone(),
+                valid: true,
+            }
+        );
+        // Any other amount recovers some other address
+        let tampered = verify_transfer_signature(
+            Chain::Ethereum,
+            7,
+            eth_user,
+            1_001,
+            &solana_user,
+            None,
+            &eth_validator,
+            &mint_signature,
+        )
+        .unwrap();
+        assert!(!tampered.valid);
+        assert_ne!(tampered.signer, eth_validator);
+
+        // Ethereum -> Solana unlock, over the program's own unlock message
+        let mint = Pubkey::new_unique();
+        let unlock_hash = create_unlock_message(8, &Pubkey::from_str(&solana_user).unwrap(), &mint, 1_000);
+        let unlock_signature = service.sign_for_solana(unlock_hash).await.unwrap();
+        let mint = mint.to_string();
+        let check = verify_transfer_signature(
+            Chain::Solana,
+            8,
+            &solana_user,
+            1_000,
+            eth_user,
+            Some(&mint),
+            &sol_validator,
+            &unlock_signature,
+        )
+        .unwrap();
+        assert_eq!(
+            check,
+            SignatureCheck {
+                message_hash: unlock_hash,
+                signer: sol_validator.clone(),
+                valid: true,
+            }
+        );
+        let tampered = verify_transfer_signature(
+            Chain::Solana,
+            9,
+            &solana_user,
+            1_000,
+            eth_user,
+            Some(&mint),
+            &sol_validator,
+            &unlock_signature,
+        )
+        .unwrap();
+        assert!(!tampered.valid);
+        let other_mint = Pubkey::new_unique().to_string();
+        let tampered = verify_transfer_signature(
+            Chain::Solana,
+            8,
+            &solana_user,
+            1_000,
+            eth_user,
+            Some(&other_mint),
+            &sol_validator,
+            &unlock_signature,
+        )
+        .unwrap();
+        assert!(!tampered.valid);
+
+        // A signature in the other chain's scheme is rejected outright, as is an unlock without its mint
+        let verify_unlock = |mint: Option<&str>, signature: &str| {
+            verify_transfer_signature(Chain::Solana, 8, &solana_user, 1_000, eth_user, mint, &sol_validator, signature)
+        };
+        assert!(verify_unlock(Some(&mint), &mint_signature).is_err());
+        assert!(verify_unlock(None, &unlock_signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failing_validator_is_skipped_until_cooldown() {
+        // Nothing listens here, so every request fails
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let validator = ValidatorConfig {
+            name: "down".to_string(),
+            eth_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0".to_string(),
+            sol_public_key: "5XqZ".to_string(),
+            endpoint: Some(format!("http://{}", addr)),
+            auth_token: None,
+            auth_scheme: ValidatorAuthScheme::Bearer,
+        };
+        let cooldown = Duration::from_millis(200);
+        let client = ValidatorClient::new(vec![validator.clone()]).with_circuit_breaker(2, cooldown);
+        let (user, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let request = client.solana_unlock_request(&user, &mint, 1_000, 1, SIGNATURE_SCHEME_ED25519);
+
+        assert!(client.request_signature(&validator, &request).await.is_err());
+        assert_eq!(client.outstanding(&request, &[]).len(), 1, "one failure keeps the circuit closed");
+
+        assert!(client.request_signature(&validator, &request).await.is_err());
+        assert!(client.is_circuit_open(&validator));
+        assert!(client.outstanding(&request, &[]).is_empty());
+
+        tokio::time::sleep(cooldown + Duration::from_millis(50)).await;
+        assert!(!client.is_circuit_open(&validator));
+        assert_eq!(client.outstanding(&request, &[]).len(), 1);
+
+        // Still down: the trial request reopens the circuit straight away
+        assert!(client.request_signature(&validator, &request).await.is_err());
+        assert!(client.is_circuit_open(&validator));
+    }
+
+    #[tokio::test]
+    async fn test_batch_signs_three_transfers_in_one_request() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let batches = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route(
+            "/sign-batch",
+            post({
+                let batches = batches.clone();
+                move |Json(batch): Json<BatchSignatureRequest>| async move {
+                    batches.fetch_add(1, Ordering::SeqCst);
+                    // Sign in reverse so the client has to match by key, not position
+                    let signatures = batch
+                        .requests
+                        .iter()
+                        .rev()
+                        .map(|request| {
+                            let signature = ChainSignature::Ecdsa { r: [request.nonce as u8; 32], s: [1; 32], v: 27 };
+                            (request.message_hash.clone(), signature.to_string())
+                        })
+                        .collect();
+                    Json(BatchSignatureResponse { signatures })
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let validator = ValidatorConfig {
+            name: "batched".to_string(),
+            eth_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0".to_string(),
+            sol_public_key: "5XqZ".to_string(),
+            endpoint: Some(format!("http://{}", addr)),
+            auth_token: None,
+            auth_scheme: ValidatorAuthScheme::Bearer,
+        };
+        let client = ValidatorClient::new(vec![validator.clone()]);
+        let requests: Vec<SignatureRequest> = (1..=3)
+            .map(|nonce| client.ethereum_mint_request(burner(), U256::from(1_000 * nonce), nonce, "5XqZ"))
+            .collect();
+
+        let results = client.collect_signatures_batch(&validator, &requests).await;
+
+        assert_eq!(batches.load(Ordering::SeqCst), 1);
+        assert_eq!(results.len(), 3);
+        for (nonce, result) in (1..=3u8).zip(results) {
+            let signature = result.unwrap();
+            assert_eq!(signature.validator_address, validator.eth_address);
+            assert_eq!(signature.signature, ChainSignature::Ecdsa { r: [nonce; 32], s: [1; 32], v: 27 });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_falls_back_to_single_requests() {
+        // Only serves /sign-ethereum and /sign-solana
+        let legacy = crate::testing::MockValidator::start("legacy", "0x0000000000000000000000000000000000000001", true).await;
+        let client = ValidatorClient::new(vec![legacy.config.clone()]);
+        let requests: Vec<SignatureRequest> = (1..=3)
+            .map(|nonce| client.ethereum_mint_request(burner(), U256::from(1_000), nonce, "5XqZ"))
+            .collect();
+
+        let results = client.collect_signatures_batch(&legacy.config, &requests).await;
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(legacy.hits(), 3);
+        assert!(client.batch_unsupported.lock().unwrap().contains("legacy"));
+
+        // Remembered: the next batch goes straight to single requests
+        let results = client.collect_signatures_batch(&legacy.config, &requests[..1]).await;
+        assert!(results[0].is_ok());
+        assert_eq!(legacy.hits(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_borsh_wire_format_with_json_fallback() {
+        let burn_tx = B256::repeat_byte(3);
+        let service = Arc::new(validator_with_burn(3, burn_tx).await);
+        let sol_public_key = service.sol_public_key();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, service.router()).await.unwrap() });
+
+        let validator = ValidatorConfig {
+            name: "binary".to_string(),
+            eth_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0".to_string(),
+            sol_public_key: sol_public_key.to_string(),
+            endpoint: Some(format!("http://{}", addr)),
+            auth_token: None,
+            auth_scheme: ValidatorAuthScheme::Bearer,
+        };
+        let legacy = crate::testing::MockValidator::start("legacy", "0x0000000000000000000000000000000000000001", true).await;
+        let client = ValidatorClient::new(vec![]).with_wire_format(WireFormat::Borsh);
+
+        let results = client.collect_signatures_batch(&validator, &[unlock_request(3, burn_tx)]).await;
+        let signature = results.into_iter().next().unwrap().unwrap();
+        assert_eq!(signature.validator_address, sol_public_key.to_string());
+        assert_eq!(signature.signature.chain(), Chain::Solana);
+        assert!(!client.borsh_unsupported.lock().unwrap().contains("binary"));
+
+        // A JSON-only validator refuses the binary body and is asked again in JSON
+        let request = client.ethereum_mint_request(burner(), U256::from(1_000), 1, "5XqZ");
+        client.request_signature(&legacy.config, &request).await.unwrap();
+        assert_eq!(legacy.hits(), 1);
+        assert!(client.borsh_unsupported.lock().unwrap().contains("legacy"));
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_unlock_is_signed_with_the_ethereum_key() {
+        let burn_tx = B256::repeat_byte(4);
+        let service = Arc::new(validator_with_burn(4, burn_tx).await);
+        let eth_address = service.eth_address();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, service.router()).await.unwrap() });
+
+        let validator = ValidatorConfig {
+            name: "secp256k1".to_string(),
+            eth_address: checksum_address(&eth_address),
+            sol_public_key: Pubkey::new_unique().to_string(),
+            endpoint: Some(format!("http://{}", addr)),
+            auth_token: None,
+            auth_scheme: ValidatorAuthScheme::Bearer,
+        };
+        let client = ValidatorClient::new(vec![validator.clone()]).with_wire_format(WireFormat::Borsh);
+        let mut request = unlock_request(4, burn_tx);
+        request.signature_scheme = SIGNATURE_SCHEME_SECP256K1;
+
+        let signature = client.request_signature(&validator, &request).await.unwrap();
+        assert_eq!(signature.validator_address, validator.eth_address);
+        assert!(client.outstanding(&request, &[signature.clone()]).is_empty());
+
+        // The program recovers the Ethereum address from the bare unlock message
+        let ChainSignature::Secp256k1(compact) = signature.signature else {
+            panic!("expected a compact secp256k1 signature, got {:?}", signature.signature);
+        };
+        let mut expanded = [0u8; 65];
+        expanded[..64].copy_from_slice(&compact);
+        expanded[32] &= 0x7f;
+        expanded[64] = 27 + (compact[32] >> 7);
+        let message_hash = create_unlock_message(4, &burn_recipient(), &unlock_mint(), 1_000);
+        let recovered = AlloySignature::try_from(expanded.as_slice())
+            .unwrap()
+            .recover_address_from_prehash(&B256::from(message_hash))
+            .unwrap();
+        assert_eq!(recovered, eth_address);
+    }
+
+    #[tokio::test]
+    async fn test_verify_against_chain_detects_mismatched_validators() {
+        let on_chain: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let config = BridgeConfig {
+            admin: Pubkey::new_unique(),
+            vault_pda_bump: 255,
+            relayer_authority: Pubkey::new_unique(),
+            fee_basis_points: 0,
+            is_paused: false,
+            total_locked: 0,
+            nonce: 0,
+            validators: on_chain.clone(),
+            validator_threshold: 2,
+            supported_chains: BridgeConfig::ALL_CHAINS,
+            transfer_ttl: BridgeConfig::DEFAULT_TRANSFER_TTL,
+            validator_fee_bps: 0,
+            fees_per_validator: 0,
+            relayer_authorities: vec![],
+            max_total_locked: 0,
+            signature_scheme: bridge_vault::state::SIGNATURE_SCHEME_ED25519,
+            chain_fee_basis_points: [
+                bridge_vault::BridgeConfig::CHAIN_FEE_UNSET;
+                bridge_vault::BridgeConfig::CHAIN_FEE_SLOTS
+            ],
+        };
+        let bridge_config = Pubkey::new_unique();
+        let rpc = crate::testing::MockSolanaRpc::start().await;
+        let mut data = borsh::to_vec(&config).unwrap();
+        data.resize(BridgeConfig::LEN, 0);
+        rpc.chain().accounts.insert(bridge_config, data);
+        let solana = SolanaRpc::solana(
+            &[rpc.url().to_string()],
+            solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+        )
+        .unwrap();
+
+        let configured = |keys: &[String]| -> Vec<ValidatorConfig> {
+            keys.iter()
+                .enumerate()
+                .map(|(i, key)| ValidatorConfig {
+                    name: format!("Validator{}", i + 1),
+                    eth_address: format!("0x{:040x}", i + 1),
+                    sol_public_key: key.clone(),
+                    endpoint: None,
+                    auth_token: None,
+                    auth_scheme: ValidatorAuthScheme::Bearer,
+                })
+                .collect()
+        };
+
+        // Same set, in a different order
+        let matching: Vec<String> = on_chain.iter().rev().map(|key| key.to_string()).collect();
+        let client = ValidatorClient::new(configured(&matching));
+        client.verify_against_chain(&solana, bridge_config).await.unwrap();
+
+        // A subset is allowed, the missing validator is only warned about
+        let client = ValidatorClient::new(configured(&matching[..2]));
+        client.verify_against_chain(&solana, bridge_config).await.unwrap();
+        assert_eq!(
+            compare_validator_sets(&configured(&matching[..2]), &on_chain).unconfigured,
+            vec![on_chain[0]]
+        );
+
+        // A key the program doesn't know is rejected
+        let stray = Pubkey::new_unique().to_string();
+        let mismatched = vec![matching[0].clone(), stray.clone(), "not-a-key".to_string()];
+        let client = ValidatorClient::new(configured(&mismatched));
+        let err = client.verify_against_chain(&solana, bridge_config).await.unwrap_err();
+        assert!(matches!(err, RelayerError::ConfigError(_)));
+        let message = err.to_string();
+        assert!(message.contains(&format!("Validator2 ({})", stray)), "{}", message);
+        assert!(message.contains("Validator3 (not-a-key)"), "{}", message);
+        assert!(!message.contains("Validator1"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_signature_request_requires_auth_token() {
+        for scheme in [ValidatorAuthScheme::Bearer, ValidatorAuthScheme::ApiKey] {
+            let burn_tx = B256::repeat_byte(1);
+            let service = validator_with_burn(1, burn_tx)
+                .await
+                .with_auth(scheme, "s3cret".to_string());
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                axum::serve(listener, Arc::new(service).router()).await.unwrap();
+            });
+
+            let mut validator = ValidatorConfig {
+                name: "authed".to_string(),
+                eth_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0".to_string(),
+                sol_public_key: "5XqZ".to_string(),
+                endpoint: Some(format!("http://{}", addr)),
+                auth_token: None,
+                auth_scheme: scheme,
+            };
+            let client = ValidatorClient::new(vec![]);
+            let request = unlock_request(1, burn_tx);
+
+            let refused = client.request_signature(&validator, &request).await;
+            assert!(
+                matches!(refused, Err(RelayerError::NetworkError(ref e)) if e.contains("401")),
+                "{:?}: {:?}",
+                scheme,
+                refused
+            );
+
+            validator.auth_token = Some("wrong".to_string());
+            assert!(client.request_signature(&validator, &request).await.is_err());
+
+            validator.auth_token = Some("s3cret".to_string());
+            let signature = client.request_signature(&validator, &request).await.unwrap();
+            assert_eq!(signature.validator_address, "5XqZ");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validator_refuses_to_sign_nonce_twice() {
+        let path = std::env::temp_dir().join(format!("signed-nonces-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let burn_tx = B256::repeat_byte(5);
+        let request = unlock_request(5, burn_tx);
+
+        let service = validator_with_burn(5, burn_tx)
+            .await
+            .with_signed_nonces(SignedNonces::open(&path).unwrap());
+        service.sign_request(&request).await.unwrap();
+        assert!(matches!(
+            service.sign_request(&request).await,
+            Err(RelayerError::TransactionAlreadyProcessed(5))
+        ));
+
+        // Another chain's nonce 5 is a different transfer
+        assert!(!service.signed.lock().unwrap().contains(Chain::Ethereum, 5));
+
+        // A restarted validator still remembers
+        let restarted = validator_with_burn(5, burn_tx)
+            .await
+            .with_signed_nonces(SignedNonces::open(&path).unwrap());
+        assert!(matches!(
+            restarted.sign_request(&request).await,
+            Err(RelayerError::TransactionAlreadyProcessed(5))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_ethereum_transaction_checks_burn_event() {
+        let burn_tx = B256::repeat_byte(7);
+        let reverted_tx = B256::repeat_byte(8);
+        let foreign_tx = B256::repeat_byte(9);
+        let rpc = mock_ethereum_rpc(vec![
+            (burn_tx, burn_receipt(burn_tx, bridge_contract(), &burn_event(7, 1_000), true)),
+            (reverted_tx, burn_receipt(reverted_tx, bridge_contract(), &burn_event(8, 1_000), false)),
+            // Same event, but from some other contract
+            (foreign_tx, burn_receipt(foreign_tx, burner(), &burn_event(9, 1_000), true)),
+        ])
+        .await;
+        let service = ValidatorService::new(PrivateKeySigner::random(), Keypair::new())
+            .with_ethereum(rpc, bridge_contract())
+            .with_unlock_mint(unlock_mint());
+
+        let hash_for =
+            |nonce: u64, amount: u64| create_unlock_message(nonce, &burn_recipient(), &unlock_mint(), amount);
+        let verify = |tx: B256, nonce: u64, hash: [u8; 32]| {
+            let service = &service;
+            async move { service.verify_ethereum_transaction(&tx.to_string(), nonce, hash).await.unwrap() }
+        };
+
+        assert!(verify(burn_tx, 7, hash_for(7, 1_000)).await);
+        assert!(!verify(burn_tx, 7, hash_for(7, 2_000)).await, "amount mismatch");
+        let other_recipient = create_unlock_message(7, &Pubkey::new_unique(), &unlock_mint(), 1_000);
+        assert!(!verify(burn_tx, 7, other_recipient).await, "recipient mismatch");
+        let other_mint = create_unlock_message(7, &burn_recipient(), &Pubkey::new_unique(), 1_000);
+        assert!(!verify(burn_tx, 7, other_mint).await, "mint mismatch");
+        assert!(!verify(burn_tx, 6, hash_for(6, 1_000)).await, "no burn for that nonce");
+        assert!(!verify(reverted_tx, 8, hash_for(8, 1_000)).await, "reverted");
+        assert!(!verify(foreign_tx, 9, hash_for(9, 1_000)).await, "not the bridge");
+        assert!(!verify(B256::repeat_byte(0xee), 7, hash_for(7, 1_000)).await, "unknown tx");
+    }
+
+    #[tokio::test]
+    async fn test_verify_solana_lock_from_program_logs() {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new(
+            "bridge_vault",
+            program_id,
+            processor!(bridge_vault::process_instruction),
+        );
+        let fixture = setup_lock_fixture(&mut program_test, program_id, 5_000);
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let allow_ix = bridge_vault::BridgeInstruction::create_add_allowed_mint_instruction(
+            &program_id,
+            &fixture.admin.pubkey(),
+            &fixture.bridge_config,
+            &fixture.mint,
+        );
+        let mut allow_tx = Transaction::new_with_payer(&[allow_ix], Some(&payer.pubkey()));
+        allow_tx.sign(&[&payer, &fixture.admin], recent_blockhash);
+        banks_client.process_transaction(allow_tx).await.unwrap();
+
+        let recipient = burner();
+        let mut destination = [0u8; 32];
+        destination[12..].copy_from_slice(recipient.as_slice());
+        let lock_ix = bridge_vault::BridgeInstruction::create_lock_tokens_instruction(
+            &fixture.program_id,
+            &fixture.user.pubkey(),
+            &fixture.user_token_account,
+            &fixture.vault_token_account,
+            &fixture.user_bridge_state,
+            &fixture.bridge_config,
+            &fixture.mint,
+            5_000,
+            1,
+            destination,
+        );
+        let mut lock_tx = Transaction::new_with_payer(&[lock_ix], Some(&payer.pubkey()));
+        lock_tx.sign(&[&payer, &fixture.user], recent_blockhash);
+        let result = banks_client.process_transaction_with_metadata(lock_tx).await.unwrap();
+        result.result.unwrap();
+        let logs = result.metadata.unwrap().log_messages;
+
+        let sender = fixture.user.pubkey().to_string();
+        let mint_hash = |amount: u64, nonce: u64| {
+            ValidatorClient::create_ethereum_message_hash(recipient, U256::from(amount), nonce, &sender)
+        };
+
+        let unscaled = TokenDecimals::default();
+        assert!(lock_matches(&logs, &program_id, &unscaled, "lock", 0, mint_hash(5_000, 0)));
+        assert!(!lock_matches(&logs, &program_id, &unscaled, "lock", 0, mint_hash(50_000, 0)), "amount mismatch");
+        assert!(!lock_matches(&logs, &program_id, &unscaled, "lock", 1, mint_hash(5_000, 1)), "wrong nonce");
+        assert!(
+            !lock_matches(&logs, &Pubkey::new_unique(), &unscaled, "lock", 0, mint_hash(5_000, 0)),
+            "logged by some other program"
+        );
+
+        // With the mint's 6 decimals and an 18-decimal wrapped token, the mint carries 10^12 times as much
+        let scaled = TokenDecimals {
+            solana: Some(6),
+            ethereum: Some(18),
+        };
+        assert!(lock_matches(&logs, &program_id, &scaled, "lock", 0, mint_hash(5_000_000_000_000_000, 0)));
+        assert!(!lock_matches(&logs, &program_id, &scaled, "lock", 0, mint_hash(5_000, 0)), "unscaled amount");
+
+        // A lookalike event printed by a different program doesn't count
+        let impostor = Pubkey::new_unique();
+        let forged: Vec<String> = std::iter::once(format!("Program {} invoke [1]", impostor))
+            .chain(logs.iter().filter(|log| log.starts_with("Program log:")).cloned())
+            .chain(std::iter::once(format!("Program {} success", impostor)))
+            .collect();
+        assert!(!lock_matches(&forged, &program_id, &unscaled, "forged", 0, mint_hash(5_000, 0)));
+    }
 }
@@ -0,0 +1,179 @@
+//! Compact binary encoding for validator signature requests.
+//!
+//! JSON is the default. A `ValidatorClient` built `with_wire_format(WireFormat::Borsh)` sends
+//! bodies as a little-endian `u32` length followed by their Borsh encoding, tagged with
+//! [`BORSH_CONTENT_TYPE`]. `ValidatorService` answers in the format the request arrived in; a
+//! validator that only speaks JSON rejects the body with `415` and the client falls back.
+
+use crate::error::{RelayerError, Result};
+use axum::{
+    http::{header::CONTENT_TYPE, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const BORSH_CONTENT_TYPE: &str = "application/x-borsh";
+
+/// Encoding of signature request and response bodies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    #[default]
+    Json,
+    /// Length-prefixed Borsh, see [`encode`]
+    Borsh,
+}
+
+impl WireFormat {
+    /// Format of a request body, from its `Content-Type`
+    pub fn of(headers: &HeaderMap) -> Self {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default();
+        if content_type.trim().eq_ignore_ascii_case(BORSH_CONTENT_TYPE) {
+            Self::Borsh
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Decode a request body sent in this format
+    pub fn decode<T: DeserializeOwned + BorshDeserialize>(self, body: &[u8]) -> Result<T> {
+        match self {
+            Self::Json => {
+                serde_json::from_slice(body).map_err(|e| RelayerError::ParseError(format!("Invalid JSON body: {}", e)))
+            }
+            Self::Borsh => decode(body),
+        }
+    }
+}
+
+impl FromStr for WireFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "borsh" => Ok(Self::Borsh),
+            other => Err(format!("Unknown validator wire format: {}", other)),
+        }
+    }
+}
+
+/// `value` as a little-endian `u32` byte length followed by its Borsh encoding
+pub fn encode<T: BorshSerialize>(value: &T) -> Result<Vec<u8>> {
+    let body =
+        borsh::to_vec(value).map_err(|e| RelayerError::ParseError(format!("Borsh encoding failed: {}", e)))?;
+    let len = u32::try_from(body.len())
+        .map_err(|_| RelayerError::ParseError(format!("{}-byte body is too large to frame", body.len())))?;
+
+    let mut framed = Vec::with_capacity(4 + body.len());
+    framed.extend_from_slice(&len.to_le_bytes());
+    framed.extend_from_slice(&body);
+    Ok(framed)
+}
+
+/// Inverse of [`encode`]; the length prefix must cover exactly the rest of `bytes`
+pub fn decode<T: BorshDeserialize>(bytes: &[u8]) -> Result<T> {
+    if bytes.len() < 4 {
+        return Err(RelayerError::ParseError(format!("{}-byte frame has no length prefix", bytes.len())));
+    }
+    let (prefix, body) = bytes.split_at(4);
+    let len = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+    if len != body.len() {
+        return Err(RelayerError::ParseError(format!(
+            "Frame announces {} bytes but carries {}",
+            len,
+            body.len()
+        )));
+    }
+
+    borsh::from_slice(body).map_err(|e| RelayerError::ParseError(format!("Invalid Borsh body: {}", e)))
+}
+
+/// `value` framed by [`encode`] as an HTTP response body
+pub fn borsh_response<T: BorshSerialize>(value: &T) -> Result<Response> {
+    Ok(([(CONTENT_TYPE, BORSH_CONTENT_TYPE)], encode(value)?).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Chain, ChainSignature, ValidatorSignature};
+    use crate::validator_client::{BatchSignatureRequest, BorshBatchSignatureResponse, SignatureRequest};
+    use chrono::DateTime;
+
+    #[test]
+    fn test_batch_round_trips_in_borsh() {
+        let requests: Vec<SignatureRequest> = (1..=3)
+            .map(|nonce| SignatureRequest {
+                chain: if nonce % 2 == 0 { Chain::Solana } else { Chain::Ethereum },
+                nonce,
+                message_hash: format!("0x{}", hex::encode([nonce as u8; 32])),
+                source_tx_hash: format!("source_tx_{}", nonce),
+            })
+            .collect();
+        let batch = BatchSignatureRequest { requests };
+
+        let framed = encode(&batch).unwrap();
+        assert_eq!(u32::from_le_bytes(framed[..4].try_into().unwrap()) as usize, framed.len() - 4);
+        assert!(framed.len() < serde_json::to_vec(&batch).unwrap().len());
+
+        let decoded: BatchSignatureRequest = decode(&framed).unwrap();
+        assert_eq!(decoded.requests.len(), 3);
+        for (decoded, sent) in decoded.requests.iter().zip(&batch.requests) {
+            assert_eq!(decoded.chain, sent.chain);
+            assert_eq!(decoded.nonce, sent.nonce);
+            assert_eq!(decoded.message_hash, sent.message_hash);
+            assert_eq!(decoded.source_tx_hash, sent.source_tx_hash);
+        }
+
+        let signed_at = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let response = BorshBatchSignatureResponse {
+            signatures: batch
+                .requests
+                .iter()
+                .map(|request| {
+                    let signature = match request.chain {
+                        Chain::Ethereum => ChainSignature::Ecdsa { r: [1; 32], s: [2; 32], v: 27 },
+                        _ => ChainSignature::Ed25519([3; 64]),
+                    };
+                    let signature = ValidatorSignature {
+                        validator_address: format!("validator-{}", request.nonce),
+                        signature,
+                        signed_at,
+                    };
+                    (request.message_hash.clone(), signature)
+                })
+                .collect(),
+        };
+        let decoded: BorshBatchSignatureResponse = decode(&encode(&response).unwrap()).unwrap();
+        assert_eq!(decoded, response);
+
+        // Truncated or padded frames are rejected rather than partially read
+        assert!(decode::<BatchSignatureRequest>(&framed[..framed.len() - 1]).is_err());
+        let mut padded = framed.clone();
+        padded.push(0);
+        assert!(decode::<BatchSignatureRequest>(&padded).is_err());
+        assert!(decode::<BatchSignatureRequest>(&framed[..3]).is_err());
+    }
+
+    #[test]
+    fn test_wire_format_from_content_type() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(WireFormat::of(&headers), WireFormat::Json);
+
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        assert_eq!(WireFormat::of(&headers), WireFormat::Json);
+
+        headers.insert(CONTENT_TYPE, "Application/X-Borsh; v=1".parse().unwrap());
+        assert_eq!(WireFormat::of(&headers), WireFormat::Borsh);
+
+        assert_eq!("borsh".parse::<WireFormat>().unwrap(), WireFormat::Borsh);
+        assert!("protobuf".parse::<WireFormat>().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use anyhow::{anyhow, Context, Result};
+use relayer::{
+    amount::TokenDecimals,
+    config::{split_url_list, ValidatorAuthScheme},
+    failover::{EthereumRpc, SolanaRpc},
+    validator_client::{SignedNonces, ValidatorService},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::read_keypair_file};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Settings for one validator node
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    pub listen_addr: String,
+    /// 0x-hex secp256k1 key used for Ethereum mint signatures, and for unlock
+    /// signatures when the bridge's `signature_scheme` is secp256k1
+    pub eth_private_key: String,
+    /// Solana JSON keypair file used for Ed25519 unlock signatures
+    pub sol_keypair_path: String,
+    /// Token the relayer must present; `None` leaves the endpoint open
+    pub auth_token: Option<String>,
+    pub auth_scheme: ValidatorAuthScheme,
+    /// Where signed `(chain, nonce)` pairs are recorded
+    pub signed_nonces_path: String,
+    pub solana_rpc_urls: Vec<String>,
+    pub solana_commitment: String,
+    pub solana_bridge_program_id: String,
+    /// Mint unlocks release; must match the relayer's `SOLANA_TOKEN_MINT`
+    pub solana_token_mint: String,
+    pub ethereum_rpc_urls: Vec<String>,
+    pub ethereum_bridge_contract: String,
+    /// Must match the relayer's `SOLANA_TOKEN_DECIMALS` / `ETHEREUM_TOKEN_DECIMALS`
+    pub token_decimals: TokenDecimals,
+}
+
+impl NodeConfig {
+    pub fn from_env() -> Result<Self> {
+        dotenv::dotenv().ok();
+
+        Ok(Self {
+            listen_addr: std::env::var("VALIDATOR_LISTEN_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string()),
+            eth_private_key: std::env::var("VALIDATOR_ETH_PRIVATE_KEY")
+                .context("VALIDATOR_ETH_PRIVATE_KEY must be set")?,
+            sol_keypair_path: std::env::var("VALIDATOR_SOL_KEYPAIR_PATH")
+                .context("VALIDATOR_SOL_KEYPAIR_PATH must be set")?,
+            auth_token: std::env::var("VALIDATOR_AUTH_TOKEN").ok(),
+            auth_scheme: std::env::var("VALIDATOR_AUTH_SCHEME")
+                .unwrap_or_else(|_| "bearer".to_string())
+                .parse::<ValidatorAuthScheme>()
+                .map_err(|e| anyhow!("Invalid VALIDATOR_AUTH_SCHEME: {}", e))?,
+            signed_nonces_path: std::env::var("VALIDATOR_SIGNED_NONCES_PATH")
+                .unwrap_or_else(|_| "signed-nonces.jsonl".to_string()),
+            solana_rpc_urls: split_url_list(
+                &std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| "https://api.devnet.solana.com".to_string()),
+            ),
+            solana_commitment: std::env::var("SOLANA_COMMITMENT").unwrap_or_else(|_| "finalized".to_string()),
+            solana_bridge_program_id: std::env::var("SOLANA_BRIDGE_PROGRAM_ID")
+                .unwrap_or_else(|_| "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH".to_string()),
+            solana_token_mint: std::env::var("SOLANA_TOKEN_MINT").context("SOLANA_TOKEN_MINT must be set")?,
+            ethereum_rpc_urls: split_url_list(
+                &std::env::var("ETHEREUM_RPC_URL").context("ETHEREUM_RPC_URL must be set")?,
+            ),
+            ethereum_bridge_contract: std::env::var("ETHEREUM_BRIDGE_CONTRACT")
+                .context("ETHEREUM_BRIDGE_CONTRACT must be set")?,
+            token_decimals: TokenDecimals {
+                solana: optional_env("SOLANA_TOKEN_DECIMALS")?,
+                ethereum: optional_env("ETHEREUM_TOKEN_DECIMALS")?,
+            },
+        })
+    }
+}
+
+/// Parse env var `name` if it is set
+fn optional_env<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    std::env::var(name)
+        .ok()
+        .map(|value| value.parse().map_err(|e| anyhow!("Invalid {}: {}", name, e)))
+        .transpose()
+}
+
+/// Load the node's keys and wire up source-chain verification
+pub fn build_service(config: &NodeConfig) -> Result<ValidatorService> {
+    let eth_signer = PrivateKeySigner::from_str(&config.eth_private_key)
+        .map_err(|e| anyhow!("Invalid VALIDATOR_ETH_PRIVATE_KEY: {}", e))?;
+    let sol_keypair = read_keypair_file(&config.sol_keypair_path)
+        .map_err(|e| anyhow!("Failed to read Solana keypair {}: {}", config.sol_keypair_path, e))?;
+
+    let commitment = CommitmentConfig::from_str(&config.solana_commitment)
+        .map_err(|e| anyhow!("Invalid commitment: {}", e))?;
+    let program_id = Pubkey::from_str(&config.solana_bridge_program_id)
+        .map_err(|e| anyhow!("Invalid program ID: {}", e))?;
+    let token_mint = Pubkey::from_str(&config.solana_token_mint)
+        .map_err(|e| anyhow!("Invalid token mint: {}", e))?;
+    let bridge_contract = Address::from_str(&config.ethereum_bridge_contract)
+        .map_err(|e| anyhow!("Invalid bridge contract address: {}", e))?;
+
+    let mut service = ValidatorService::new(eth_signer, sol_keypair)
+        .with_signed_nonces(SignedNonces::open(&config.signed_nonces_path)?)
+        .with_solana(SolanaRpc::solana(&config.solana_rpc_urls, commitment)?, program_id)
+        .with_ethereum(EthereumRpc::ethereum(&config.ethereum_rpc_urls)?, bridge_contract)
+        .with_unlock_mint(token_mint)
+        .with_token_decimals(config.token_decimals);
+    if let Some(ref token) = config.auth_token {
+        service = service.with_auth(config.auth_scheme, token.clone());
+    }
+
+    Ok(service)
+}
+
+/// Serve the signing API on `listener` until the process stops
+pub async fn serve(listener: TcpListener, service: ValidatorService) -> Result<()> {
+    axum::serve(listener, Arc::new(service).router()).await?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+use validator::{build_service, serve, NodeConfig};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    info!("Bridge validator node starting...");
+    info!("Version: {}", env!("CARGO_PKG_VERSION"));
+
+    let config = NodeConfig::from_env()?;
+    let service = build_service(&config)?;
+    info!("Ethereum signer: {}", service.eth_address());
+    info!("Solana signer: {}", service.sol_public_key());
+    if config.auth_token.is_none() {
+        warn!("VALIDATOR_AUTH_TOKEN not set, signing endpoints accept any caller");
+    }
+
+    let listener = TcpListener::bind(&config.listen_addr).await?;
+    info!("Listening on {}", config.listen_addr);
+
+    tokio::select! {
+        result = serve(listener, service) => result?,
+        _ = tokio::signal::ctrl_c() => info!("Shutdown signal received..."),
+    }
+
+    info!("Validator stopped");
+    Ok(())
+}
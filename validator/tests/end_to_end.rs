@@ -0,0 +1,145 @@
+use alloy::{
+    primitives::{Address, B256, U256},
+    signers::local::PrivateKeySigner,
+    sol_types::SolEvent,
+};
+use axum::{routing::post, Json, Router};
+use bridge_vault::state::SIGNATURE_SCHEME_ED25519;
+use relayer::{
+    config::{ValidatorAuthScheme, ValidatorConfig},
+    ethereum_monitor::SolanaBridge,
+    validator_client::ValidatorClient,
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair, Signature},
+};
+use std::str::FromStr;
+use tokio::net::TcpListener;
+use validator::{build_service, serve, NodeConfig};
+
+const BRIDGE_CONTRACT: &str = "0xaBD6f99Fbb77051B28942abe3118bf4D8Ea9F2CA";
+
+/// Ethereum JSON-RPC endpoint that knows one successful burn: `tx_hash` emitting `event`
+async fn mock_ethereum_rpc(tx_hash: B256, event: SolanaBridge::TokensBurned) -> String {
+    let bridge = Address::from_str(BRIDGE_CONTRACT).unwrap();
+    let data = event.encode_log_data();
+    let block_hash = B256::repeat_byte(0xbb);
+    let receipt = json!({
+        "type": "0x2",
+        "status": "0x1",
+        "cumulativeGasUsed": "0x5208",
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "logs": [{
+            "address": bridge,
+            "topics": data.topics(),
+            "data": data.data,
+            "blockHash": block_hash,
+            "blockNumber": "0x10",
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false
+        }],
+        "transactionHash": tx_hash,
+        "transactionIndex": "0x0",
+        "blockHash": block_hash,
+        "blockNumber": "0x10",
+        "gasUsed": "0x5208",
+        "effectiveGasPrice": "0x3b9aca00",
+        "from": event.sender,
+        "to": bridge,
+        "contractAddress": null
+    });
+
+    let app = Router::new().route(
+        "/",
+        post(move |Json(body): Json<Value>| {
+            let receipt = receipt.clone();
+            async move {
+                let result = match body["params"][0].as_str().and_then(|h| B256::from_str(h).ok()) {
+                    Some(hash) if hash == tx_hash => receipt,
+                    _ => Value::Null,
+                };
+                Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+            }
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_client_collects_signature_from_validator_node() {
+    let sender = Address::from_str("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0").unwrap();
+    let user = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let burn_tx = B256::repeat_byte(3);
+    let rpc_url = mock_ethereum_rpc(
+        burn_tx,
+        SolanaBridge::TokensBurned {
+            sender,
+            amount: U256::from(1_000u64),
+            solanaAddress: user.to_string(),
+            nonce: 3,
+        },
+    )
+    .await;
+
+    let dir = std::env::temp_dir().join(format!("validator-e2e-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let keypair_path = dir.join("validator.json");
+    write_keypair_file(&Keypair::new(), &keypair_path).unwrap();
+
+    let config = NodeConfig {
+        listen_addr: "127.0.0.1:0".to_string(),
+        eth_private_key: PrivateKeySigner::random().to_bytes().to_string(),
+        sol_keypair_path: keypair_path.to_string_lossy().into_owned(),
+        auth_token: Some("node-token".to_string()),
+        auth_scheme: ValidatorAuthScheme::Bearer,
+        signed_nonces_path: dir.join("signed.jsonl").to_string_lossy().into_owned(),
+        // Not contacted: this transfer comes from Ethereum
+        solana_rpc_urls: vec!["http://127.0.0.1:8899".to_string()],
+        solana_commitment: "confirmed".to_string(),
+        solana_bridge_program_id: "7DazfS5hDxNJMJcxs1uKk3yoob7cbPLBFMXA3iRotjRH".to_string(),
+        solana_token_mint: mint.to_string(),
+        ethereum_rpc_urls: vec![rpc_url],
+        ethereum_bridge_contract: BRIDGE_CONTRACT.to_string(),
+        token_decimals: Default::default(),
+    };
+    let service = build_service(&config).unwrap();
+    let sol_public_key = service.sol_public_key();
+
+    let listener = TcpListener::bind(&config.listen_addr).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, service));
+
+    let validator = ValidatorConfig {
+        name: "node".to_string(),
+        eth_address: format!("{:?}", Address::ZERO),
+        sol_public_key: sol_public_key.to_string(),
+        endpoint: Some(format!("http://{}", addr)),
+        auth_token: config.auth_token.clone(),
+        auth_scheme: ValidatorAuthScheme::Bearer,
+    };
+    let client = ValidatorClient::new(vec![validator.clone()]);
+    let mut request = client.solana_unlock_request(&user, &mint, 1_000, 3, SIGNATURE_SCHEME_ED25519);
+    request.source_tx_hash = burn_tx.to_string();
+
+    let collected = client.request_signature(&validator, &request).await.unwrap();
+    assert_eq!(collected.validator_address, sol_public_key.to_string());
+
+    let signature = Signature::try_from(collected.signature.to_bytes()).unwrap();
+    let message_hash = hex::decode(request.message_hash.trim_start_matches("0x")).unwrap();
+    assert!(signature.verify(sol_public_key.as_ref(), &message_hash));
+
+    // The node has now signed nonce 3 and won't do it again
+    assert!(client.request_signature(&validator, &request).await.is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}